@@ -165,6 +165,68 @@ impl CircuitWrapper {
         operations
     }
 
+    /// Render the Circuit as quantikz LaTeX source.
+    ///
+    /// Args:
+    ///     qubit_labels (Optional[dict[int, str]]): Custom labels of the qubits, qubits without label are labelled by their index.
+    ///     show_qubit_labels (Optional[bool]): Whether qubit labels are drawn. Defaults to True.
+    ///     group_decomposition_blocks (Optional[bool]): Whether decomposition blocks are drawn as labelled boxes. Defaults to True.
+    ///     show_pragmas (Optional[bool]): Whether PRAGMA operations are drawn. Defaults to False.
+    ///
+    /// Returns:
+    ///     str: The LaTeX source of the Circuit using the quantikz package.
+    ///
+    /// Raises:
+    ///     RuntimeError: Circuit cannot be rendered.
+    pub fn to_quantikz(
+        &self,
+        qubit_labels: Option<std::collections::HashMap<usize, String>>,
+        show_qubit_labels: Option<bool>,
+        group_decomposition_blocks: Option<bool>,
+        show_pragmas: Option<bool>,
+    ) -> PyResult<String> {
+        let options = render_options(
+            qubit_labels,
+            show_qubit_labels,
+            group_decomposition_blocks,
+            show_pragmas,
+        );
+        roqoqo::render::circuit_to_quantikz(&self.internal, &options).map_err(|err| {
+            PyRuntimeError::new_err(format!("Circuit cannot be rendered: {:?}", err))
+        })
+    }
+
+    /// Render the Circuit as an SVG image.
+    ///
+    /// Args:
+    ///     qubit_labels (Optional[dict[int, str]]): Custom labels of the qubits, qubits without label are labelled by their index.
+    ///     show_qubit_labels (Optional[bool]): Whether qubit labels are drawn. Defaults to True.
+    ///     group_decomposition_blocks (Optional[bool]): Whether decomposition blocks are drawn as labelled boxes. Defaults to True.
+    ///     show_pragmas (Optional[bool]): Whether PRAGMA operations are drawn. Defaults to False.
+    ///
+    /// Returns:
+    ///     str: The SVG document of the Circuit.
+    ///
+    /// Raises:
+    ///     RuntimeError: Circuit cannot be rendered.
+    pub fn to_svg(
+        &self,
+        qubit_labels: Option<std::collections::HashMap<usize, String>>,
+        show_qubit_labels: Option<bool>,
+        group_decomposition_blocks: Option<bool>,
+        show_pragmas: Option<bool>,
+    ) -> PyResult<String> {
+        let options = render_options(
+            qubit_labels,
+            show_qubit_labels,
+            group_decomposition_blocks,
+            show_pragmas,
+        );
+        roqoqo::render::circuit_to_svg(&self.internal, &options).map_err(|err| {
+            PyRuntimeError::new_err(format!("Circuit cannot be rendered: {:?}", err))
+        })
+    }
+
     /// Return a copy of the Circuit (copy here produces a deepcopy).
     ///
     /// Returns:
@@ -254,6 +316,49 @@ impl CircuitWrapper {
         Ok(serialized)
     }
 
+    /// Return the JSON schema of the serialized Circuit including the roqoqo version fields.
+    ///
+    /// Returns:
+    ///     str: The JSON schema of Circuit.
+    #[allow(unused_variables)]
+    #[classmethod]
+    pub fn json_schema(cls: &PyType) -> String {
+        roqoqo::schema::json_schema::<roqoqo::Circuit>()
+    }
+
+    /// Validate the json representation of a Circuit against the JSON schema of Circuit.
+    ///
+    /// Args:
+    ///     input (str): The serialized Circuit in json form.
+    ///
+    /// Raises:
+    ///     ValueError: Input does not match the JSON schema, the message contains the path of the offending value.
+    #[allow(unused_variables)]
+    #[classmethod]
+    pub fn validate_json(cls: &PyType, json_string: &str) -> PyResult<()> {
+        roqoqo::schema::validate_json::<roqoqo::Circuit>(json_string)
+            .map_err(|err| PyValueError::new_err(format!("{}", err)))
+    }
+
+    /// Convert the json representation of a Circuit to a Circuit after validating it against the JSON schema.
+    ///
+    /// Args:
+    ///     input (str): The serialized Circuit in json form.
+    ///
+    /// Returns:
+    ///     Circuit: The deserialized Circuit.
+    ///
+    /// Raises:
+    ///     ValueError: Input does not match the JSON schema or cannot be deserialized to Circuit.
+    #[allow(unused_variables)]
+    #[classmethod]
+    pub fn from_json_validated(cls: &PyType, json_string: &str) -> PyResult<Self> {
+        Ok(Self {
+            internal: roqoqo::schema::from_json_validated::<roqoqo::Circuit>(json_string)
+                .map_err(|err| PyValueError::new_err(format!("{}", err)))?,
+        })
+    }
+
     #[allow(unused_variables)]
     #[classmethod]
     /// Convert the json representation of a Circuit to a Circuit.
@@ -605,6 +710,29 @@ impl PyMappingProtocol for CircuitWrapper {
     }
 }
 
+/// Create [roqoqo::render::RenderOptions] from the optional python arguments of the render methods.
+fn render_options(
+    qubit_labels: Option<std::collections::HashMap<usize, String>>,
+    show_qubit_labels: Option<bool>,
+    group_decomposition_blocks: Option<bool>,
+    show_pragmas: Option<bool>,
+) -> roqoqo::render::RenderOptions {
+    let mut options = roqoqo::render::RenderOptions::new();
+    if let Some(labels) = qubit_labels {
+        options.qubit_labels = labels;
+    }
+    if let Some(show) = show_qubit_labels {
+        options.show_qubit_labels = show;
+    }
+    if let Some(group) = group_decomposition_blocks {
+        options.group_decomposition_blocks = group;
+    }
+    if let Some(show) = show_pragmas {
+        options.show_pragmas = show;
+    }
+    options
+}
+
 /// Convert generic python object to [roqoqo::Circuit].
 ///
 /// Fallible conversion of generic python object to [roqoqo::Circuit].
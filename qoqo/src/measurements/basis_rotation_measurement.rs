@@ -12,14 +12,20 @@
 
 //! Qoqo basis rotation measurement.
 
-use super::BasisRotationInputWrapper;
+use super::measurement_auxiliary_data_input::readout_mitigation_from_python;
+use super::{
+    complex_expectation_values_to_python, expectation_values_with_errors_to_python,
+    extract_bit_registers, BasisRotationInputWrapper, PyComplexExpectationValues,
+    PyExpectationValuesWithErrors,
+};
 use crate::CircuitWrapper;
 use bincode::serialize;
+use numpy::PyReadonlyArray2;
 use pyo3::exceptions::{PyRuntimeError, PyValueError};
 use pyo3::prelude::*;
 use pyo3::types::PyByteArray;
 use pyo3::types::PyType;
-use roqoqo::measurements::BasisRotation;
+use roqoqo::measurements::{BasisRotation, BasisRotationBuilder, PauliGrouping};
 use roqoqo::prelude::*;
 use roqoqo::registers::{BitOutputRegister, ComplexOutputRegister, FloatOutputRegister};
 use roqoqo::Circuit;
@@ -69,6 +75,12 @@ impl BasisRotationWrapper {
     ///     input_bit_registers (dict[str, Union[list[list[int]], list[list[bool]]]]): The classical bit registers with the register name as key
     ///     float_registers (dict[str, list[list[float]]): The classical float registers as a dictionary with the register name as key
     ///     complex_registers (dict[str, list[list[complex]]): The classical complex registers as a dictionary with the register name as key
+    ///     confusion_matrices (Optional[dict[int, np.ndarray]]): The 2x2 confusion matrices of single readout bits used for readout error mitigation.
+    ///                                                           Overrides the readout mitigation of the measurement input when given.
+    ///     correlated_confusion_matrices (Optional[list[tuple[list[int], np.ndarray]]]): The confusion matrices of groups of readout bits with correlated readout errors.
+    ///     mitigation_method (Optional[str]): The correction method, "TensoredInversion" (default) or "IterativeBayesianUnfolding".
+    ///     max_iterations (Optional[int]): The maximal number of iterations of iterative Bayesian unfolding. Defaults to 100.
+    ///     tolerance (Optional[float]): The convergence tolerance of iterative Bayesian unfolding. Defaults to 1e-6.
     ///
     /// Returns:
     ///     Optional[dict[str, float]]: The evaluated measurement.
@@ -76,11 +88,18 @@ impl BasisRotationWrapper {
     /// Raises:
     ///     RuntimeError: Unexpected repetition of key in bit_register.
     ///     RuntimeError: Error evaluating basis rotation measurement.
+    ///     ValueError: Unknown readout mitigation method.
+    #[allow(clippy::too_many_arguments)]
     pub fn evaluate(
         &mut self,
         input_bit_registers: Py<PyAny>,
         float_registers: HashMap<String, FloatOutputRegister>,
         complex_registers: HashMap<String, ComplexOutputRegister>,
+        confusion_matrices: Option<HashMap<usize, PyReadonlyArray2<f64>>>,
+        correlated_confusion_matrices: Option<Vec<(Vec<usize>, PyReadonlyArray2<f64>)>>,
+        mitigation_method: Option<String>,
+        max_iterations: Option<usize>,
+        tolerance: Option<f64>,
     ) -> PyResult<Option<HashMap<String, f64>>> {
         let mut bit_registers: HashMap<String, BitOutputRegister> = HashMap::new();
         let bit_registers_bool: PyResult<HashMap<String, Vec<Vec<bool>>>> =
@@ -111,7 +130,19 @@ impl BasisRotationWrapper {
                 // }
             }
         }
-        self.internal
+        let mut measurement = self.internal.clone();
+        if confusion_matrices.is_some() || correlated_confusion_matrices.is_some() {
+            measurement
+                .input
+                .set_readout_mitigation(Some(readout_mitigation_from_python(
+                    confusion_matrices.unwrap_or_default(),
+                    correlated_confusion_matrices,
+                    mitigation_method,
+                    max_iterations,
+                    tolerance,
+                )?));
+        }
+        measurement
             .evaluate(bit_registers, float_registers, complex_registers)
             .map_err(|x| {
                 PyRuntimeError::new_err(format!(
@@ -121,6 +152,71 @@ impl BasisRotationWrapper {
             })
     }
 
+    /// Execute the basis rotation measurement and estimate the statistical errors of the expectation values.
+    ///
+    /// The errors are the shot noise of the readouts, estimated from the single shot results (at least two shots per register).
+    /// Readout error mitigation of the measurement input is applied.
+    ///
+    /// Args:
+    ///     input_bit_registers (dict[str, Union[list[list[int]], list[list[bool]]]]): The classical bit registers with the register name as key
+    ///     float_registers (dict[str, list[list[float]]): The classical float registers as a dictionary with the register name as key
+    ///     complex_registers (dict[str, list[list[complex]]): The classical complex registers as a dictionary with the register name as key
+    ///
+    /// Returns:
+    ///     Optional[tuple[dict[str, tuple[float, float]], np.ndarray]]: The mean and standard error of each expectation value and the covariance matrix of the Pauli products.
+    ///
+    /// Raises:
+    ///     RuntimeError: Error evaluating basis rotation measurement.
+    pub fn evaluate_with_errors(
+        &self,
+        input_bit_registers: Py<PyAny>,
+        float_registers: HashMap<String, FloatOutputRegister>,
+        complex_registers: HashMap<String, ComplexOutputRegister>,
+    ) -> PyResult<Option<PyExpectationValuesWithErrors>> {
+        let bit_registers = extract_bit_registers(&input_bit_registers)?;
+        self.internal
+            .evaluate_with_errors(bit_registers, float_registers, complex_registers)
+            .map(|results| results.map(expectation_values_with_errors_to_python))
+            .map_err(|x| {
+                PyRuntimeError::new_err(format!(
+                    "Error evaluating basis rotation measurement with errors {:?}",
+                    x
+                ))
+            })
+    }
+
+    /// Execute the basis rotation measurement and return complex expectation values.
+    ///
+    /// The real parts of the complex expectation values are the values returned by `evaluate`.
+    ///
+    /// Args:
+    ///     input_bit_registers (dict[str, Union[list[list[int]], list[list[bool]]]]): The classical bit registers with the register name as key
+    ///     float_registers (dict[str, list[list[float]]): The classical float registers as a dictionary with the register name as key
+    ///     complex_registers (dict[str, list[list[complex]]): The classical complex registers as a dictionary with the register name as key
+    ///
+    /// Returns:
+    ///     Optional[tuple[dict[str, complex], dict[str, np.ndarray], dict[str, np.ndarray]]]: The complex expectation values and the vectors and matrices of expectation values defined in the measurement input.
+    ///
+    /// Raises:
+    ///     RuntimeError: Error evaluating basis rotation measurement.
+    pub fn evaluate_complex(
+        &self,
+        input_bit_registers: Py<PyAny>,
+        float_registers: HashMap<String, FloatOutputRegister>,
+        complex_registers: HashMap<String, ComplexOutputRegister>,
+    ) -> PyResult<Option<PyComplexExpectationValues>> {
+        let bit_registers = extract_bit_registers(&input_bit_registers)?;
+        self.internal
+            .evaluate_complex(bit_registers, float_registers, complex_registers)
+            .map(|results| results.map(complex_expectation_values_to_python))
+            .map_err(|x| {
+                PyRuntimeError::new_err(format!(
+                    "Error evaluating basis rotation measurement with complex expectation values {:?}",
+                    x
+                ))
+            })
+    }
+
     /// Return the collection of quantum circuits for the separate basis rotations.
     ///
     /// Returns:
@@ -212,6 +308,111 @@ impl BasisRotationWrapper {
             .map_err(|_| PyRuntimeError::new_err("Unexpected error serializing BasisRotation"))
     }
 
+    /// Return the JSON schema of the serialized BasisRotation including the roqoqo version fields.
+    ///
+    /// Returns:
+    ///     str: The JSON schema of BasisRotation.
+    #[allow(unused_variables)]
+    #[classmethod]
+    pub fn json_schema(cls: &PyType) -> String {
+        roqoqo::schema::json_schema::<roqoqo::measurements::BasisRotation>()
+    }
+
+    /// Validate the json representation of a BasisRotation against the JSON schema of BasisRotation.
+    ///
+    /// Args:
+    ///     input (str): The serialized BasisRotation in json form.
+    ///
+    /// Raises:
+    ///     ValueError: Input does not match the JSON schema, the message contains the path of the offending value.
+    #[allow(unused_variables)]
+    #[classmethod]
+    pub fn validate_json(cls: &PyType, json_string: &str) -> PyResult<()> {
+        roqoqo::schema::validate_json::<roqoqo::measurements::BasisRotation>(json_string)
+            .map_err(|err| PyValueError::new_err(format!("{}", err)))
+    }
+
+    /// Convert the json representation of a BasisRotation to a BasisRotation after validating it against the JSON schema.
+    ///
+    /// Args:
+    ///     input (str): The serialized BasisRotation in json form.
+    ///
+    /// Returns:
+    ///     BasisRotation: The deserialized BasisRotation.
+    ///
+    /// Raises:
+    ///     ValueError: Input does not match the JSON schema or cannot be deserialized to BasisRotation.
+    #[allow(unused_variables)]
+    #[classmethod]
+    pub fn from_json_validated(cls: &PyType, json_string: &str) -> PyResult<Self> {
+        Ok(Self {
+            internal: roqoqo::schema::from_json_validated::<roqoqo::measurements::BasisRotation>(
+                json_string,
+            )
+            .map_err(|err| PyValueError::new_err(format!("{}", err)))?,
+        })
+    }
+
+    /// Create a BasisRotation measurement from operators given as sums of Pauli products.
+    ///
+    /// The Pauli products of all operators are grouped into sets of commuting products
+    /// and one basis rotation circuit is created for each set.
+    ///
+    /// Args:
+    ///     operators (dict[str, dict[str, float]]): The operators with the name of the expectation value as key.
+    ///                                              Each operator maps Pauli products like "0X1Z" to their coefficients.
+    ///     number_qubits (int): The number of qubits in the measurement.
+    ///     number_measurements (int): The number of projective measurements of each basis rotation circuit.
+    ///     use_flipped_measurement (Optional[bool]): Whether measurement errors are symmetrized with flipped measurements. Defaults to False.
+    ///     constant_circuit (Optional[Circuit]): The constant Circuit that is executed before each basis rotation circuit.
+    ///     grouping (Optional[str]): The grouping of Pauli products, "QubitWise" (default) or "GeneralCommuting".
+    ///                               "GeneralCommuting" uses Clifford circuits built from CNOT, Hadamard and SGate gates.
+    ///
+    /// Returns:
+    ///     BasisRotation: The BasisRotation measuring the operators.
+    ///
+    /// Raises:
+    ///     ValueError: Unknown grouping.
+    ///     ValueError: Operators could not be converted to a BasisRotation measurement.
+    #[allow(unused_variables)]
+    #[classmethod]
+    pub fn from_pauli_sums(
+        cls: &PyType,
+        operators: HashMap<String, HashMap<String, f64>>,
+        number_qubits: usize,
+        number_measurements: usize,
+        use_flipped_measurement: Option<bool>,
+        constant_circuit: Option<CircuitWrapper>,
+        grouping: Option<String>,
+    ) -> PyResult<Self> {
+        let grouping = match grouping.as_deref() {
+            None | Some("QubitWise") => PauliGrouping::QubitWise,
+            Some("GeneralCommuting") => PauliGrouping::GeneralCommuting,
+            Some(name) => return Err(PyValueError::new_err(format!("Unknown grouping {}", name))),
+        };
+        let mut builder = BasisRotationBuilder::new(number_qubits, number_measurements)
+            .with_flipped_measurement(use_flipped_measurement.unwrap_or(false))
+            .with_constant_circuit(constant_circuit.map(|c| c.internal))
+            .with_grouping(grouping);
+        let mut names: Vec<&String> = operators.keys().collect();
+        names.sort();
+        for name in names {
+            builder
+                .add_operator(name.clone(), &operators[name])
+                .map_err(|x| {
+                    PyValueError::new_err(format!("Invalid operator {}: {:?}", name, x))
+                })?;
+        }
+        Ok(Self {
+            internal: builder.build().map_err(|x| {
+                PyValueError::new_err(format!(
+                    "Operators could not be converted to a BasisRotation measurement {:?}",
+                    x
+                ))
+            })?,
+        })
+    }
+
     /// Deserialize the BasisRotation from json form using the [serde_json] crate.
     ///
     /// Returns:
@@ -12,7 +12,11 @@
 
 //! Qoqo cheated measurement
 
-use super::CheatedInputWrapper;
+use super::{
+    complex_expectation_values_to_python, expectation_values_with_errors_to_python,
+    extract_bit_registers, CheatedInputWrapper, PyComplexExpectationValues,
+    PyExpectationValuesWithErrors,
+};
 use crate::CircuitWrapper;
 use bincode::serialize;
 use pyo3::exceptions::{PyRuntimeError, PyValueError};
@@ -118,6 +122,71 @@ impl CheatedWrapper {
             })
     }
 
+    /// Execute the cheated measurement and estimate the statistical errors of the expectation values.
+    ///
+    /// Each entry of a complex register is treated as an independent trajectory of a stochastic simulation.
+    /// The covariance of Pauli products is empty for the cheated measurement.
+    ///
+    /// Args:
+    ///     input_bit_registers (dict[str, Union[list[list[int]], list[list[bool]]]]): The classical bit registers with the register name as key
+    ///     float_registers (dict[str, list[list[float]]): The classical float registers as a dictionary with the register name as key
+    ///     complex_registers (dict[str, list[list[complex]]): The classical complex registers as a dictionary with the register name as key
+    ///
+    /// Returns:
+    ///     Optional[tuple[dict[str, tuple[float, float]], np.ndarray]]: The mean and standard error of each expectation value and the covariance matrix of the Pauli products.
+    ///
+    /// Raises:
+    ///     RuntimeError: Error evaluating cheated measurement.
+    pub fn evaluate_with_errors(
+        &self,
+        input_bit_registers: Py<PyAny>,
+        float_registers: HashMap<String, FloatOutputRegister>,
+        complex_registers: HashMap<String, ComplexOutputRegister>,
+    ) -> PyResult<Option<PyExpectationValuesWithErrors>> {
+        let bit_registers = extract_bit_registers(&input_bit_registers)?;
+        self.internal
+            .evaluate_with_errors(bit_registers, float_registers, complex_registers)
+            .map(|results| results.map(expectation_values_with_errors_to_python))
+            .map_err(|x| {
+                PyRuntimeError::new_err(format!(
+                    "Error evaluating cheated measurement with errors {:?}",
+                    x
+                ))
+            })
+    }
+
+    /// Execute the cheated measurement and return complex expectation values.
+    ///
+    /// The real parts of the complex expectation values are the values returned by `evaluate`.
+    ///
+    /// Args:
+    ///     input_bit_registers (dict[str, Union[list[list[int]], list[list[bool]]]]): The classical bit registers with the register name as key
+    ///     float_registers (dict[str, list[list[float]]): The classical float registers as a dictionary with the register name as key
+    ///     complex_registers (dict[str, list[list[complex]]): The classical complex registers as a dictionary with the register name as key
+    ///
+    /// Returns:
+    ///     Optional[tuple[dict[str, complex], dict[str, np.ndarray], dict[str, np.ndarray]]]: The complex expectation values and the vectors and matrices of expectation values defined in the measurement input.
+    ///
+    /// Raises:
+    ///     RuntimeError: Error evaluating cheated measurement.
+    pub fn evaluate_complex(
+        &self,
+        input_bit_registers: Py<PyAny>,
+        float_registers: HashMap<String, FloatOutputRegister>,
+        complex_registers: HashMap<String, ComplexOutputRegister>,
+    ) -> PyResult<Option<PyComplexExpectationValues>> {
+        let bit_registers = extract_bit_registers(&input_bit_registers)?;
+        self.internal
+            .evaluate_complex(bit_registers, float_registers, complex_registers)
+            .map(|results| results.map(complex_expectation_values_to_python))
+            .map_err(|x| {
+                PyRuntimeError::new_err(format!(
+                    "Error evaluating cheated measurement with complex expectation values {:?}",
+                    x
+                ))
+            })
+    }
+
     /// Return the collection of quantum circuits for the separate cheated measurements.
     ///
     /// Returns:
@@ -211,6 +280,51 @@ impl CheatedWrapper {
             .map_err(|_| PyRuntimeError::new_err("Unexpected error serializing Cheated"))
     }
 
+    /// Return the JSON schema of the serialized Cheated including the roqoqo version fields.
+    ///
+    /// Returns:
+    ///     str: The JSON schema of Cheated.
+    #[allow(unused_variables)]
+    #[classmethod]
+    pub fn json_schema(cls: &PyType) -> String {
+        roqoqo::schema::json_schema::<roqoqo::measurements::Cheated>()
+    }
+
+    /// Validate the json representation of a Cheated against the JSON schema of Cheated.
+    ///
+    /// Args:
+    ///     input (str): The serialized Cheated in json form.
+    ///
+    /// Raises:
+    ///     ValueError: Input does not match the JSON schema, the message contains the path of the offending value.
+    #[allow(unused_variables)]
+    #[classmethod]
+    pub fn validate_json(cls: &PyType, json_string: &str) -> PyResult<()> {
+        roqoqo::schema::validate_json::<roqoqo::measurements::Cheated>(json_string)
+            .map_err(|err| PyValueError::new_err(format!("{}", err)))
+    }
+
+    /// Convert the json representation of a Cheated to a Cheated after validating it against the JSON schema.
+    ///
+    /// Args:
+    ///     input (str): The serialized Cheated in json form.
+    ///
+    /// Returns:
+    ///     Cheated: The deserialized Cheated.
+    ///
+    /// Raises:
+    ///     ValueError: Input does not match the JSON schema or cannot be deserialized to Cheated.
+    #[allow(unused_variables)]
+    #[classmethod]
+    pub fn from_json_validated(cls: &PyType, json_string: &str) -> PyResult<Self> {
+        Ok(Self {
+            internal: roqoqo::schema::from_json_validated::<roqoqo::measurements::Cheated>(
+                json_string,
+            )
+            .map_err(|err| PyValueError::new_err(format!("{}", err)))?,
+        })
+    }
+
     /// Deserialize the Cheated measurement from json form.
     ///
     /// Returns:
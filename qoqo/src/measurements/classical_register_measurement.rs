@@ -139,6 +139,52 @@ impl ClassicalRegisterWrapper {
             .map_err(|_| PyRuntimeError::new_err("Unexpected error serializing ClassicalRegister"))
     }
 
+    /// Return the JSON schema of the serialized ClassicalRegister including the roqoqo version fields.
+    ///
+    /// Returns:
+    ///     str: The JSON schema of ClassicalRegister.
+    #[allow(unused_variables)]
+    #[classmethod]
+    pub fn json_schema(cls: &PyType) -> String {
+        roqoqo::schema::json_schema::<roqoqo::measurements::ClassicalRegister>()
+    }
+
+    /// Validate the json representation of a ClassicalRegister against the JSON schema of ClassicalRegister.
+    ///
+    /// Args:
+    ///     input (str): The serialized ClassicalRegister in json form.
+    ///
+    /// Raises:
+    ///     ValueError: Input does not match the JSON schema, the message contains the path of the offending value.
+    #[allow(unused_variables)]
+    #[classmethod]
+    pub fn validate_json(cls: &PyType, json_string: &str) -> PyResult<()> {
+        roqoqo::schema::validate_json::<roqoqo::measurements::ClassicalRegister>(json_string)
+            .map_err(|err| PyValueError::new_err(format!("{}", err)))
+    }
+
+    /// Convert the json representation of a ClassicalRegister to a ClassicalRegister after validating it against the JSON schema.
+    ///
+    /// Args:
+    ///     input (str): The serialized ClassicalRegister in json form.
+    ///
+    /// Returns:
+    ///     ClassicalRegister: The deserialized ClassicalRegister.
+    ///
+    /// Raises:
+    ///     ValueError: Input does not match the JSON schema or cannot be deserialized to ClassicalRegister.
+    #[allow(unused_variables)]
+    #[classmethod]
+    pub fn from_json_validated(cls: &PyType, json_string: &str) -> PyResult<Self> {
+        Ok(Self {
+            internal:
+                roqoqo::schema::from_json_validated::<roqoqo::measurements::ClassicalRegister>(
+                    json_string,
+                )
+                .map_err(|err| PyValueError::new_err(format!("{}", err)))?,
+        })
+    }
+
     /// Deserialize the ClassicalRegister measurement from json form.
     ///
     /// Returns:
@@ -0,0 +1,289 @@
+// Copyright © 2021 HQS Quantum Simulations GmbH. All Rights Reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except
+// in compliance with the License. You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the
+// License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either
+// express or implied. See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Qoqo classical shadows measurement
+
+use super::{
+    complex_expectation_values_to_python, extract_bit_registers, ClassicalShadowsInputWrapper,
+    PyComplexExpectationValues,
+};
+use crate::CircuitWrapper;
+use bincode::serialize;
+use pyo3::exceptions::{PyRuntimeError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::PyByteArray;
+use pyo3::types::PyType;
+use roqoqo::measurements::ClassicalShadows;
+use roqoqo::prelude::*;
+use roqoqo::registers::{ComplexOutputRegister, FloatOutputRegister};
+use std::collections::HashMap;
+
+#[pyclass(name = "ClassicalShadows", module = "qoqo.measurements")]
+#[derive(Clone, Debug)]
+/// Collected information for executing a classical shadows measurement.
+///
+/// Each circuit measures all qubits in a randomly drawn single qubit basis
+/// and the expectation values of many Pauli products are estimated from the same measurement record.
+pub struct ClassicalShadowsWrapper {
+    /// Internal storage of [roqoqo::ClassicalShadows].
+    pub internal: ClassicalShadows,
+}
+
+#[pymethods]
+impl ClassicalShadowsWrapper {
+    /// Create a new ClassicalShadows measurement with randomized measurement bases.
+    ///
+    /// Circuit i measures into the readout register "ro_i".
+    ///
+    /// Args:
+    ///     constant_circuit (Optional[Circuit]): The constant Circuit that is executed before each Circuit in circuits.
+    ///     input (ClassicalShadowsInput): The additional input information required for measurement.
+    ///     number_bases (int): The number of randomized measurement bases (circuits).
+    ///     number_measurements (int): The number of projective measurements in each basis.
+    ///     seed (int): The seed of the random number generator drawing the bases.
+    ///
+    /// Returns:
+    ///     self: The ClassicalShadows containing the new classical shadows measurement.
+    #[new]
+    pub fn new(
+        constant_circuit: Option<CircuitWrapper>,
+        input: ClassicalShadowsInputWrapper,
+        number_bases: usize,
+        number_measurements: usize,
+        seed: u64,
+    ) -> Self {
+        Self {
+            internal: ClassicalShadows::new(
+                constant_circuit.map(|c| c.internal),
+                input.internal,
+                number_bases,
+                number_measurements,
+                seed,
+            ),
+        }
+    }
+
+    /// Execute the classical shadows measurement.
+    ///
+    /// Args:
+    ///     input_bit_registers (dict[str, Union[list[list[int]], list[list[bool]]]]): The classical bit registers with the register name as key
+    ///     float_registers (dict[str, list[list[float]]): The classical float registers as a dictionary with the register name as key
+    ///     complex_registers (dict[str, list[list[complex]]): The classical complex registers as a dictionary with the register name as key
+    ///
+    /// Returns:
+    ///     Optional[dict[str, float]]: The evaluated measurement.
+    ///
+    /// Raises:
+    ///     RuntimeError: Error evaluating classical shadows measurement.
+    pub fn evaluate(
+        &self,
+        input_bit_registers: Py<PyAny>,
+        float_registers: HashMap<String, FloatOutputRegister>,
+        complex_registers: HashMap<String, ComplexOutputRegister>,
+    ) -> PyResult<Option<HashMap<String, f64>>> {
+        let bit_registers = extract_bit_registers(&input_bit_registers)?;
+        self.internal
+            .evaluate(bit_registers, float_registers, complex_registers)
+            .map_err(|x| {
+                PyRuntimeError::new_err(format!(
+                    "Error evaluating classical shadows measurement {:?}",
+                    x
+                ))
+            })
+    }
+
+    /// Execute the classical shadows measurement and return complex expectation values.
+    ///
+    /// The real parts of the complex expectation values are the values returned by `evaluate`.
+    ///
+    /// Args:
+    ///     input_bit_registers (dict[str, Union[list[list[int]], list[list[bool]]]]): The classical bit registers with the register name as key
+    ///     float_registers (dict[str, list[list[float]]): The classical float registers as a dictionary with the register name as key
+    ///     complex_registers (dict[str, list[list[complex]]): The classical complex registers as a dictionary with the register name as key
+    ///
+    /// Returns:
+    ///     Optional[tuple[dict[str, complex], dict[str, np.ndarray], dict[str, np.ndarray]]]: The complex expectation values and the vectors and matrices of expectation values defined in the measurement input.
+    ///
+    /// Raises:
+    ///     RuntimeError: Error evaluating classical shadows measurement.
+    pub fn evaluate_complex(
+        &self,
+        input_bit_registers: Py<PyAny>,
+        float_registers: HashMap<String, FloatOutputRegister>,
+        complex_registers: HashMap<String, ComplexOutputRegister>,
+    ) -> PyResult<Option<PyComplexExpectationValues>> {
+        let bit_registers = extract_bit_registers(&input_bit_registers)?;
+        self.internal
+            .evaluate_complex(bit_registers, float_registers, complex_registers)
+            .map(|results| results.map(complex_expectation_values_to_python))
+            .map_err(|x| {
+                PyRuntimeError::new_err(format!(
+                    "Error evaluating classical shadows measurement with complex expectation values {:?}",
+                    x
+                ))
+            })
+    }
+
+    /// Returns the collection of quantum circuits measuring in the randomized bases.
+    ///
+    /// Returns:
+    ///     list[Circuit]: The quantum circuits.
+    pub fn circuits(&self) -> Vec<CircuitWrapper> {
+        self.internal
+            .circuits()
+            .map(|c| CircuitWrapper {
+                internal: c.clone(),
+            })
+            .collect()
+    }
+
+    /// Returns constant circuit that is executed before any Circuit in circuits.
+    ///
+    /// Returns:
+    ///     Optional[Circuit]: The constant Circuit (None if not defined).
+    pub fn constant_circuit(&self) -> Option<CircuitWrapper> {
+        self.internal
+            .constant_circuit()
+            .clone()
+            .map(|c| CircuitWrapper { internal: c })
+    }
+
+    /// Returns the measurement input data defining how to construct expectation values from measurements.
+    ///
+    /// Returns:
+    ///     ClassicalShadowsInput: The measurment input of ClassicalShadows.
+    pub fn input(&self) -> ClassicalShadowsInputWrapper {
+        let input = self.internal.input.clone();
+        ClassicalShadowsInputWrapper { internal: input }
+    }
+
+    /// Returns the type of the measurement in string form.
+    ///
+    /// Returns:
+    ///    str: The type of the measurement.
+    pub fn measurement_type(&self) -> &'static str {
+        "ClassicalShadows"
+    }
+
+    /// Returns clone of Measurement with symbolic parameters replaced
+    ///
+    /// Args:
+    ///     substituted_parameters (dict[str, float]): The dictionary containing the substitutions to use in the Circuit.
+    pub fn substitute_parameters(
+        &self,
+        substituted_parameters: HashMap<String, f64>,
+    ) -> PyResult<Self> {
+        Ok(Self {
+            internal: self
+                .internal
+                .substitute_parameters(substituted_parameters)
+                .map_err(|x| {
+                    PyRuntimeError::new_err(format!(
+                        "Error substituting symbolic parameters {:?}",
+                        x
+                    ))
+                })?,
+        })
+    }
+
+    /// Return the name of the measurement and the bincode representation of the Measurement using the [bincode] crate.
+    ///
+    /// Returns:
+    ///     (str, ByteArray): Name and serialized measurement (in [bincode] form).
+    ///
+    /// Raises:
+    ///     ValueError: Cannot serialize Measurement to bytes.
+    pub fn _internal_to_bincode(&self) -> PyResult<(&'static str, Py<PyByteArray>)> {
+        let serialized = serialize(&self.internal).map_err(|_| {
+            PyValueError::new_err("Cannot serialize ClassicalShadowsMeasurement to bytes")
+        })?;
+        let b: Py<PyByteArray> = Python::with_gil(|py| -> Py<PyByteArray> {
+            PyByteArray::new(py, &serialized[..]).into()
+        });
+        Ok(("ClassicalShadows", b))
+    }
+
+    /// Serializes the ClassicalShadows to json form using the [serde_json] crate.
+    ///
+    /// Returns:
+    ///     str: The serialized ClassicalShadows.
+    ///
+    /// Raises:
+    ///     RuntimeError: Unexpected error serializing ClassicalShadows.
+    pub fn to_json(&self) -> PyResult<String> {
+        serde_json::to_string(&self.internal)
+            .map_err(|_| PyRuntimeError::new_err("Unexpected error serializing ClassicalShadows"))
+    }
+
+    /// Return the JSON schema of the serialized ClassicalShadows including the roqoqo version fields.
+    ///
+    /// Returns:
+    ///     str: The JSON schema of ClassicalShadows.
+    #[allow(unused_variables)]
+    #[classmethod]
+    pub fn json_schema(cls: &PyType) -> String {
+        roqoqo::schema::json_schema::<roqoqo::measurements::ClassicalShadows>()
+    }
+
+    /// Validate the json representation of a ClassicalShadows against the JSON schema of ClassicalShadows.
+    ///
+    /// Args:
+    ///     input (str): The serialized ClassicalShadows in json form.
+    ///
+    /// Raises:
+    ///     ValueError: Input does not match the JSON schema, the message contains the path of the offending value.
+    #[allow(unused_variables)]
+    #[classmethod]
+    pub fn validate_json(cls: &PyType, json_string: &str) -> PyResult<()> {
+        roqoqo::schema::validate_json::<roqoqo::measurements::ClassicalShadows>(json_string)
+            .map_err(|err| PyValueError::new_err(format!("{}", err)))
+    }
+
+    /// Convert the json representation of a ClassicalShadows to a ClassicalShadows after validating it against the JSON schema.
+    ///
+    /// Args:
+    ///     input (str): The serialized ClassicalShadows in json form.
+    ///
+    /// Returns:
+    ///     ClassicalShadows: The deserialized ClassicalShadows.
+    ///
+    /// Raises:
+    ///     ValueError: Input does not match the JSON schema or cannot be deserialized to ClassicalShadows.
+    #[allow(unused_variables)]
+    #[classmethod]
+    pub fn from_json_validated(cls: &PyType, json_string: &str) -> PyResult<Self> {
+        Ok(Self {
+            internal:
+                roqoqo::schema::from_json_validated::<roqoqo::measurements::ClassicalShadows>(
+                    json_string,
+                )
+                .map_err(|err| PyValueError::new_err(format!("{}", err)))?,
+        })
+    }
+
+    /// Deserialize the ClassicalShadows from json form using the [serde_json] crate.
+    ///
+    /// Returns:
+    ///     ClassicalShadows: the deserialized ClassicalShadows.
+    ///
+    /// Raises:
+    ///     ValueError: Cannot deserialize string to ClassicalShadows.
+    #[allow(unused_variables)]
+    #[classmethod]
+    pub fn from_json(cls: &PyType, json_string: &str) -> PyResult<Self> {
+        Ok(Self {
+            internal: serde_json::from_str(json_string).map_err(|_| {
+                PyValueError::new_err("Cannot deserialize string to ClassicalShadows")
+            })?,
+        })
+    }
+}
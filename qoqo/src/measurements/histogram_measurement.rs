@@ -0,0 +1,255 @@
+// Copyright © 2021 HQS Quantum Simulations GmbH. All Rights Reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except
+// in compliance with the License. You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the
+// License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either
+// express or implied. See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Qoqo histogram measurement
+
+use super::{extract_bit_registers, HistogramInputWrapper};
+use crate::CircuitWrapper;
+use bincode::serialize;
+use pyo3::exceptions::{PyRuntimeError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::PyByteArray;
+use pyo3::types::PyType;
+use roqoqo::measurements::{Histogram, HistogramResult};
+use roqoqo::prelude::*;
+use roqoqo::registers::{ComplexOutputRegister, FloatOutputRegister};
+use roqoqo::Circuit;
+use std::collections::HashMap;
+
+/// Bitstring counts and marginal probabilities of a histogram measurement as python dictionaries.
+pub(crate) type PyHistogramResult = (
+    HashMap<String, HashMap<String, usize>>,
+    HashMap<String, HashMap<String, f64>>,
+);
+
+/// Converts the result of a histogram measurement to python types.
+fn histogram_result_to_python(result: HistogramResult) -> PyHistogramResult {
+    (result.counts, result.marginal_probabilities)
+}
+
+#[pyclass(name = "Histogram", module = "qoqo.measurements")]
+#[derive(Clone, Debug)]
+/// Collected information for executing a histogram measurement.
+///
+/// Evaluates the measured bit registers into histograms of bitstring counts
+/// and marginal probability distributions on selected qubits.
+/// In the bitstrings the k-th character is the value of the k-th readout bit.
+pub struct HistogramWrapper {
+    /// Internal storage of [roqoqo::Histogram].
+    pub internal: Histogram,
+}
+
+#[pymethods]
+impl HistogramWrapper {
+    /// Create a new Histogram measurement.
+    ///
+    /// Args:
+    ///     constant_circuit (Optional[Circuit]): The constant Circuit that is executed before each Circuit in circuits.
+    ///     circuits (list[Circuit]): The collection of quantum circuits executed for the measurement.
+    ///     input (HistogramInput): The additional input information required for measurement.
+    ///
+    /// Returns:
+    ///     self: The Histogram containing the new histogram measurement.
+    #[new]
+    pub fn new(
+        constant_circuit: Option<CircuitWrapper>,
+        circuits: Vec<CircuitWrapper>,
+        input: HistogramInputWrapper,
+    ) -> Self {
+        let new_circuits: Vec<Circuit> = circuits.into_iter().map(|c| c.internal).collect();
+        Self {
+            internal: Histogram {
+                constant_circuit: constant_circuit.map(|c| c.internal),
+                circuits: new_circuits,
+                input: input.internal,
+            },
+        }
+    }
+
+    /// Execute the histogram measurement.
+    ///
+    /// Args:
+    ///     input_bit_registers (dict[str, Union[list[list[int]], list[list[bool]]]]): The classical bit registers with the register name as key
+    ///     float_registers (dict[str, list[list[float]]): The classical float registers as a dictionary with the register name as key
+    ///     complex_registers (dict[str, list[list[complex]]): The classical complex registers as a dictionary with the register name as key
+    ///
+    /// Returns:
+    ///     tuple[dict[str, dict[str, int]], dict[str, dict[str, float]]]: The bitstring counts with the register name as key and the marginal probabilities with the marginal name as key.
+    ///
+    /// Raises:
+    ///     RuntimeError: Error evaluating histogram measurement.
+    pub fn evaluate(
+        &self,
+        input_bit_registers: Py<PyAny>,
+        float_registers: HashMap<String, FloatOutputRegister>,
+        complex_registers: HashMap<String, ComplexOutputRegister>,
+    ) -> PyResult<PyHistogramResult> {
+        let bit_registers = extract_bit_registers(&input_bit_registers)?;
+        self.internal
+            .evaluate(bit_registers, float_registers, complex_registers)
+            .map(histogram_result_to_python)
+            .map_err(|x| {
+                PyRuntimeError::new_err(format!("Error evaluating histogram measurement {:?}", x))
+            })
+    }
+
+    /// Returns the collection of quantum circuits that are measured.
+    ///
+    /// Returns:
+    ///     list[Circuit]: The quantum circuits.
+    pub fn circuits(&self) -> Vec<CircuitWrapper> {
+        self.internal
+            .circuits()
+            .map(|c| CircuitWrapper {
+                internal: c.clone(),
+            })
+            .collect()
+    }
+
+    /// Returns constant circuit that is executed before any Circuit in circuits.
+    ///
+    /// Returns:
+    ///     Optional[Circuit]: The constant Circuit (None if not defined).
+    pub fn constant_circuit(&self) -> Option<CircuitWrapper> {
+        self.internal
+            .constant_circuit()
+            .clone()
+            .map(|c| CircuitWrapper { internal: c })
+    }
+
+    /// Returns the measurement input data defining the evaluated marginal probabilities.
+    ///
+    /// Returns:
+    ///     HistogramInput: The measurment input of Histogram.
+    pub fn input(&self) -> HistogramInputWrapper {
+        let input = self.internal.input.clone();
+        HistogramInputWrapper { internal: input }
+    }
+
+    /// Returns the type of the measurement in string form.
+    ///
+    /// Returns:
+    ///    str: The type of the measurement.
+    pub fn measurement_type(&self) -> &'static str {
+        "Histogram"
+    }
+
+    /// Returns clone of Measurement with symbolic parameters replaced
+    ///
+    /// Args:
+    ///     substituted_parameters (dict[str, float]): The dictionary containing the substitutions to use in the Circuit.
+    pub fn substitute_parameters(
+        &self,
+        substituted_parameters: HashMap<String, f64>,
+    ) -> PyResult<Self> {
+        Ok(Self {
+            internal: self
+                .internal
+                .substitute_parameters(substituted_parameters)
+                .map_err(|x| {
+                    PyRuntimeError::new_err(format!(
+                        "Error substituting symbolic parameters {:?}",
+                        x
+                    ))
+                })?,
+        })
+    }
+
+    /// Return the name of the measurement and the bincode representation of the Measurement using the [bincode] crate.
+    ///
+    /// Returns:
+    ///     (str, ByteArray): Name and serialized measurement (in [bincode] form).
+    ///
+    /// Raises:
+    ///     ValueError: Cannot serialize Measurement to bytes.
+    pub fn _internal_to_bincode(&self) -> PyResult<(&'static str, Py<PyByteArray>)> {
+        let serialized = serialize(&self.internal)
+            .map_err(|_| PyValueError::new_err("Cannot serialize Histogram to bytes"))?;
+        let b: Py<PyByteArray> = Python::with_gil(|py| -> Py<PyByteArray> {
+            PyByteArray::new(py, &serialized[..]).into()
+        });
+        Ok(("Histogram", b))
+    }
+
+    /// Serializes the Histogram to json form using the [serde_json] crate.
+    ///
+    /// Returns:
+    ///     str: The serialized Histogram.
+    ///
+    /// Raises:
+    ///     RuntimeError: Unexpected error serializing Histogram.
+    pub fn to_json(&self) -> PyResult<String> {
+        serde_json::to_string(&self.internal)
+            .map_err(|_| PyRuntimeError::new_err("Unexpected error serializing Histogram"))
+    }
+
+    /// Return the JSON schema of the serialized Histogram including the roqoqo version fields.
+    ///
+    /// Returns:
+    ///     str: The JSON schema of Histogram.
+    #[allow(unused_variables)]
+    #[classmethod]
+    pub fn json_schema(cls: &PyType) -> String {
+        roqoqo::schema::json_schema::<roqoqo::measurements::Histogram>()
+    }
+
+    /// Validate the json representation of a Histogram against the JSON schema of Histogram.
+    ///
+    /// Args:
+    ///     input (str): The serialized Histogram in json form.
+    ///
+    /// Raises:
+    ///     ValueError: Input does not match the JSON schema, the message contains the path of the offending value.
+    #[allow(unused_variables)]
+    #[classmethod]
+    pub fn validate_json(cls: &PyType, json_string: &str) -> PyResult<()> {
+        roqoqo::schema::validate_json::<roqoqo::measurements::Histogram>(json_string)
+            .map_err(|err| PyValueError::new_err(format!("{}", err)))
+    }
+
+    /// Convert the json representation of a Histogram to a Histogram after validating it against the JSON schema.
+    ///
+    /// Args:
+    ///     input (str): The serialized Histogram in json form.
+    ///
+    /// Returns:
+    ///     Histogram: The deserialized Histogram.
+    ///
+    /// Raises:
+    ///     ValueError: Input does not match the JSON schema or cannot be deserialized to Histogram.
+    #[allow(unused_variables)]
+    #[classmethod]
+    pub fn from_json_validated(cls: &PyType, json_string: &str) -> PyResult<Self> {
+        Ok(Self {
+            internal: roqoqo::schema::from_json_validated::<roqoqo::measurements::Histogram>(
+                json_string,
+            )
+            .map_err(|err| PyValueError::new_err(format!("{}", err)))?,
+        })
+    }
+
+    /// Deserialize the Histogram from json form using the [serde_json] crate.
+    ///
+    /// Returns:
+    ///     Histogram: the deserialized Histogram.
+    ///
+    /// Raises:
+    ///     ValueError: Cannot deserialize string to Histogram.
+    #[allow(unused_variables)]
+    #[classmethod]
+    pub fn from_json(cls: &PyType, json_string: &str) -> PyResult<Self> {
+        Ok(Self {
+            internal: serde_json::from_str(json_string)
+                .map_err(|_| PyValueError::new_err("Cannot deserialize string to Histogram"))?,
+        })
+    }
+}
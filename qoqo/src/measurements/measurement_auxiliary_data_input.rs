@@ -13,10 +13,13 @@
 //! Qoqo measurement inputs
 
 use num_complex::Complex64;
-use pyo3::exceptions::PyRuntimeError;
+use numpy::PyReadonlyArray2;
+use pyo3::exceptions::{PyRuntimeError, PyValueError};
 use pyo3::prelude::*;
 use roqoqo::measurements::{
-    BasisRotationInput, CheatedBasisRotationInput, CheatedInput, PauliProductMask,
+    parse_pauli_product, BasisRotationInput, CheatedBasisRotationInput, CheatedInput,
+    ClassicalShadowsInput, HistogramInput, PauliProductMask, ReadoutMitigation,
+    ReadoutMitigationMethod,
 };
 use std::collections::HashMap;
 
@@ -119,6 +122,140 @@ impl BasisRotationInputWrapper {
                 PyRuntimeError::new_err(format!("Failed to add symbolic expectation value {:?}", x))
             })
     }
+
+    /// Add linear definition of complex expectation value to measurement input.
+    ///
+    /// Adds an expectation value that is defined by a linear combination
+    /// of expectation values of Pauli products with complex coefficients.
+    /// The `evaluate` method returns the real part, `evaluate_complex` the complex value.
+    ///
+    /// Args:
+    ///     name (str): The name of the expectation value.
+    ///     linear (dict[int, complex]): The linear combination of expectation values as a map between Pauli product index and complex coefficient.
+    ///
+    /// Raises:
+    ///     RuntimeError: Failed to add linear complex expectation value.
+    pub fn add_linear_complex_exp_val(
+        &mut self,
+        name: String,
+        linear: HashMap<usize, Complex64>,
+    ) -> PyResult<()> {
+        self.internal
+            .add_linear_complex_exp_val(name, linear)
+            .map_err(|x| {
+                PyRuntimeError::new_err(format!(
+                    "Failed to add linear complex expectation value {:?}",
+                    x
+                ))
+            })
+    }
+
+    /// Add a named vector of expectation values to measurement input.
+    ///
+    /// The vector is returned by the `evaluate_complex` method of the measurement.
+    ///
+    /// Args:
+    ///     name (str): The name of the vector.
+    ///     components (list[str]): The names of the expectation values forming the vector.
+    ///
+    /// Raises:
+    ///     RuntimeError: Failed to add expectation value vector.
+    pub fn add_exp_val_vector(&mut self, name: String, components: Vec<String>) -> PyResult<()> {
+        self.internal
+            .add_exp_val_vector(name, components)
+            .map_err(|x| {
+                PyRuntimeError::new_err(format!("Failed to add expectation value vector {:?}", x))
+            })
+    }
+
+    /// Add a named matrix of expectation values to measurement input.
+    ///
+    /// The matrix is returned by the `evaluate_complex` method of the measurement.
+    ///
+    /// Args:
+    ///     name (str): The name of the matrix.
+    ///     rows (list[list[str]]): The names of the expectation values forming the matrix as a list of rows.
+    ///
+    /// Raises:
+    ///     RuntimeError: Failed to add expectation value matrix.
+    pub fn add_exp_val_matrix(&mut self, name: String, rows: Vec<Vec<String>>) -> PyResult<()> {
+        self.internal.add_exp_val_matrix(name, rows).map_err(|x| {
+            PyRuntimeError::new_err(format!("Failed to add expectation value matrix {:?}", x))
+        })
+    }
+
+    /// Set the readout error mitigation applied when evaluating the measurement.
+    ///
+    /// Confusion matrices contain the probability to read out i when the qubit is in state j at position [i, j].
+    ///
+    /// Args:
+    ///     confusion_matrices (dict[int, np.ndarray]): The 2x2 confusion matrices of single readout bits.
+    ///     correlated_confusion_matrices (Optional[list[tuple[list[int], np.ndarray]]]): The confusion matrices of groups of readout bits with correlated readout errors.
+    ///     method (Optional[str]): The correction method, "TensoredInversion" (default) or "IterativeBayesianUnfolding".
+    ///     max_iterations (Optional[int]): The maximal number of iterations of iterative Bayesian unfolding. Defaults to 100.
+    ///     tolerance (Optional[float]): The convergence tolerance of iterative Bayesian unfolding. Defaults to 1e-6.
+    ///
+    /// Raises:
+    ///     ValueError: Unknown readout mitigation method.
+    ///     RuntimeError: Invalid confusion matrix.
+    pub fn set_readout_mitigation(
+        &mut self,
+        confusion_matrices: HashMap<usize, PyReadonlyArray2<f64>>,
+        correlated_confusion_matrices: Option<Vec<(Vec<usize>, PyReadonlyArray2<f64>)>>,
+        method: Option<String>,
+        max_iterations: Option<usize>,
+        tolerance: Option<f64>,
+    ) -> PyResult<()> {
+        let mitigation = readout_mitigation_from_python(
+            confusion_matrices,
+            correlated_confusion_matrices,
+            method,
+            max_iterations,
+            tolerance,
+        )?;
+        self.internal.set_readout_mitigation(Some(mitigation));
+        Ok(())
+    }
+
+    /// Remove the readout error mitigation of the measurement input.
+    pub fn remove_readout_mitigation(&mut self) {
+        self.internal.set_readout_mitigation(None);
+    }
+}
+
+/// Creates a [roqoqo::measurements::ReadoutMitigation] from the python arguments.
+pub(crate) fn readout_mitigation_from_python(
+    confusion_matrices: HashMap<usize, PyReadonlyArray2<f64>>,
+    correlated_confusion_matrices: Option<Vec<(Vec<usize>, PyReadonlyArray2<f64>)>>,
+    method: Option<String>,
+    max_iterations: Option<usize>,
+    tolerance: Option<f64>,
+) -> PyResult<ReadoutMitigation> {
+    let method = match method.as_deref() {
+        None | Some("TensoredInversion") => ReadoutMitigationMethod::TensoredInversion,
+        Some("IterativeBayesianUnfolding") => ReadoutMitigationMethod::IterativeBayesianUnfolding {
+            max_iterations: max_iterations.unwrap_or(100),
+            tolerance: tolerance.unwrap_or(1e-6),
+        },
+        Some(name) => {
+            return Err(PyValueError::new_err(format!(
+                "Unknown readout mitigation method {}",
+                name
+            )))
+        }
+    };
+    let mut mitigation = ReadoutMitigation::new(method);
+    for (qubit, matrix) in confusion_matrices {
+        mitigation
+            .add_single_qubit_confusion_matrix(qubit, matrix.as_array().to_owned())
+            .map_err(|x| PyRuntimeError::new_err(format!("Invalid confusion matrix {:?}", x)))?;
+    }
+    for (qubits, matrix) in correlated_confusion_matrices.unwrap_or_default() {
+        mitigation
+            .add_correlated_confusion_matrix(qubits, matrix.as_array().to_owned())
+            .map_err(|x| PyRuntimeError::new_err(format!("Invalid confusion matrix {:?}", x)))?;
+    }
+    Ok(mitigation)
 }
 
 #[pyclass(name = "CheatedBasisRotationInput", module = "qoqo.measurements")]
@@ -213,6 +350,67 @@ impl CheatedBasisRotationInputWrapper {
                 PyRuntimeError::new_err(format!("Failed to add symbolic expectation value {:?}", x))
             })
     }
+
+    /// Add linear definition of complex expectation value to measurement input.
+    ///
+    /// Adds an expectation value that is defined by a linear combination
+    /// of expectation values of Pauli products with complex coefficients.
+    /// The `evaluate` method returns the real part, `evaluate_complex` the complex value.
+    ///
+    /// Args:
+    ///     name (str): The name of the expectation value.
+    ///     linear (dict[int, complex]): The linear combination of expectation values as a map between Pauli product index and complex coefficient.
+    ///
+    /// Raises:
+    ///     RuntimeError: Failed to add linear complex expectation value.
+    pub fn add_linear_complex_exp_val(
+        &mut self,
+        name: String,
+        linear: HashMap<usize, Complex64>,
+    ) -> PyResult<()> {
+        self.internal
+            .add_linear_complex_exp_val(name, linear)
+            .map_err(|x| {
+                PyRuntimeError::new_err(format!(
+                    "Failed to add linear complex expectation value {:?}",
+                    x
+                ))
+            })
+    }
+
+    /// Add a named vector of expectation values to measurement input.
+    ///
+    /// The vector is returned by the `evaluate_complex` method of the measurement.
+    ///
+    /// Args:
+    ///     name (str): The name of the vector.
+    ///     components (list[str]): The names of the expectation values forming the vector.
+    ///
+    /// Raises:
+    ///     RuntimeError: Failed to add expectation value vector.
+    pub fn add_exp_val_vector(&mut self, name: String, components: Vec<String>) -> PyResult<()> {
+        self.internal
+            .add_exp_val_vector(name, components)
+            .map_err(|x| {
+                PyRuntimeError::new_err(format!("Failed to add expectation value vector {:?}", x))
+            })
+    }
+
+    /// Add a named matrix of expectation values to measurement input.
+    ///
+    /// The matrix is returned by the `evaluate_complex` method of the measurement.
+    ///
+    /// Args:
+    ///     name (str): The name of the matrix.
+    ///     rows (list[list[str]]): The names of the expectation values forming the matrix as a list of rows.
+    ///
+    /// Raises:
+    ///     RuntimeError: Failed to add expectation value matrix.
+    pub fn add_exp_val_matrix(&mut self, name: String, rows: Vec<Vec<String>>) -> PyResult<()> {
+        self.internal.add_exp_val_matrix(name, rows).map_err(|x| {
+            PyRuntimeError::new_err(format!("Failed to add expectation value matrix {:?}", x))
+        })
+    }
 }
 
 #[pyclass(name = "CheatedInput", module = "qoqo.measurements")]
@@ -272,4 +470,281 @@ impl CheatedInputWrapper {
                 ))
             })
     }
+
+    /// Add a named vector of expectation values to measurement input.
+    ///
+    /// The vector is returned by the `evaluate_complex` method of the measurement.
+    ///
+    /// Args:
+    ///     name (str): The name of the vector.
+    ///     components (list[str]): The names of the expectation values forming the vector.
+    ///
+    /// Raises:
+    ///     RuntimeError: Failed to add expectation value vector.
+    pub fn add_exp_val_vector(&mut self, name: String, components: Vec<String>) -> PyResult<()> {
+        self.internal
+            .add_exp_val_vector(name, components)
+            .map_err(|x| {
+                PyRuntimeError::new_err(format!("Failed to add expectation value vector {:?}", x))
+            })
+    }
+
+    /// Add a named matrix of expectation values to measurement input.
+    ///
+    /// The matrix is returned by the `evaluate_complex` method of the measurement.
+    ///
+    /// Args:
+    ///     name (str): The name of the matrix.
+    ///     rows (list[list[str]]): The names of the expectation values forming the matrix as a list of rows.
+    ///
+    /// Raises:
+    ///     RuntimeError: Failed to add expectation value matrix.
+    pub fn add_exp_val_matrix(&mut self, name: String, rows: Vec<Vec<String>>) -> PyResult<()> {
+        self.internal.add_exp_val_matrix(name, rows).map_err(|x| {
+            PyRuntimeError::new_err(format!("Failed to add expectation value matrix {:?}", x))
+        })
+    }
+}
+
+#[pyclass(name = "ClassicalShadowsInput", module = "qoqo.measurements")]
+#[derive(Clone, Debug)]
+/// Provides Necessary Information to run a [roqoqo::measurements::ClassicalShadows] measurement.
+pub struct ClassicalShadowsInputWrapper {
+    /// Internal storage of [roqoqo::ClassicalShadowsInput].
+    pub internal: ClassicalShadowsInput,
+}
+
+#[pymethods]
+impl ClassicalShadowsInputWrapper {
+    /// Create new ClassicalShadowsInput.
+    ///
+    /// The measurement bases are drawn when creating the ClassicalShadows measurement.
+    /// The Pauli products and measured_exp_vals start empty
+    /// and can be extended with [ClassicalShadowsInput::add_pauli_product],
+    /// [ClassicalShadowsInput::add_linear_exp_val] and [ClassicalShadowsInput::add_symbolic_exp_val].
+    ///
+    /// Args:
+    ///     number_qubits (int): The number of qubits in the ClassicalShadows measurement.
+    ///     number_batches (int): The number of batches used in the median-of-means estimation.
+    ///
+    /// Returns:
+    ///     self: The new instance of ClassicalShadowsInput.
+    #[new]
+    pub fn new(number_qubits: usize, number_batches: usize) -> Self {
+        Self {
+            internal: ClassicalShadowsInput::new(number_qubits, number_batches),
+        }
+    }
+
+    /// Add estimated Pauli product to ClassicalShadowsInput and returns index of Pauli product.
+    ///
+    /// When the pauli product is already in the measurement input the function only returns
+    /// its index.
+    ///
+    /// Args:
+    ///     pauli_product (str): The Pauli product, e.g. "0X1Z" for X on qubit 0 and Z on qubit 1.
+    ///
+    /// Returns:
+    ///     int: The index of the added Pauli product in the list of all Pauli products.
+    ///
+    /// Raises:
+    ///     RuntimeError: Failed to add pauli product.
+    pub fn add_pauli_product(&mut self, pauli_product: &str) -> PyResult<usize> {
+        parse_pauli_product(pauli_product)
+            .and_then(|pauli_string| self.internal.add_pauli_product(pauli_string))
+            .map_err(|x| PyRuntimeError::new_err(format!("Failed to add pauli product {:?}", x)))
+    }
+
+    /// Add linear definition of expectation value to measurement input.
+    ///
+    /// Adds an expectation value that is defined by a linear combination
+    /// of expectation values of Pauli products.
+    ///
+    /// Args:
+    ///     name (str): The name of the expectation value.
+    ///     linear (dict[int, float]): The linear combination of expectation values as a map between Pauli product index and coefficient.
+    ///
+    /// Raises:
+    ///     RuntimeError: Failed to add linear expectation value.
+    pub fn add_linear_exp_val(
+        &mut self,
+        name: String,
+        linear: HashMap<usize, f64>,
+    ) -> PyResult<()> {
+        self.internal.add_linear_exp_val(name, linear).map_err(|x| {
+            PyRuntimeError::new_err(format!("Failed to add linear expectation value {:?}", x))
+        })
+    }
+
+    /// Add symbolic definition of expectation value to measurement input.
+    ///
+    /// Adds an expectation value that is defined by a symbolic combination
+    /// of expectation values of Pauli products.
+    ///
+    /// Args:
+    ///     name (str): The name of the expectation value.
+    ///     symbolic (str): The symbolic expression for the expectation values
+    ///                     given by [qoqo_calculator::CalculatorFloat].
+    ///
+    /// Raises:
+    ///     RuntimeError: Failed to add symbolic expectation value.
+    ///
+    /// The i-th PauliProducts are hardcoded as variables pauli_product_i
+    /// in the string expression of CalculatorFloat.
+    pub fn add_symbolic_exp_val(&mut self, name: String, symbolic: String) -> PyResult<()> {
+        self.internal
+            .add_symbolic_exp_val(name, symbolic.into())
+            .map_err(|x| {
+                PyRuntimeError::new_err(format!("Failed to add symbolic expectation value {:?}", x))
+            })
+    }
+
+    /// Add linear definition of complex expectation value to measurement input.
+    ///
+    /// Adds an expectation value that is defined by a linear combination
+    /// of expectation values of Pauli products with complex coefficients.
+    /// The `evaluate` method returns the real part, `evaluate_complex` the complex value.
+    ///
+    /// Args:
+    ///     name (str): The name of the expectation value.
+    ///     linear (dict[int, complex]): The linear combination of expectation values as a map between Pauli product index and complex coefficient.
+    ///
+    /// Raises:
+    ///     RuntimeError: Failed to add linear complex expectation value.
+    pub fn add_linear_complex_exp_val(
+        &mut self,
+        name: String,
+        linear: HashMap<usize, Complex64>,
+    ) -> PyResult<()> {
+        self.internal
+            .add_linear_complex_exp_val(name, linear)
+            .map_err(|x| {
+                PyRuntimeError::new_err(format!(
+                    "Failed to add linear complex expectation value {:?}",
+                    x
+                ))
+            })
+    }
+
+    /// Add a named vector of expectation values to measurement input.
+    ///
+    /// The vector is returned by the `evaluate_complex` method of the measurement.
+    ///
+    /// Args:
+    ///     name (str): The name of the vector.
+    ///     components (list[str]): The names of the expectation values forming the vector.
+    ///
+    /// Raises:
+    ///     RuntimeError: Failed to add expectation value vector.
+    pub fn add_exp_val_vector(&mut self, name: String, components: Vec<String>) -> PyResult<()> {
+        self.internal
+            .add_exp_val_vector(name, components)
+            .map_err(|x| {
+                PyRuntimeError::new_err(format!("Failed to add expectation value vector {:?}", x))
+            })
+    }
+
+    /// Add a named matrix of expectation values to measurement input.
+    ///
+    /// The matrix is returned by the `evaluate_complex` method of the measurement.
+    ///
+    /// Args:
+    ///     name (str): The name of the matrix.
+    ///     rows (list[list[str]]): The names of the expectation values forming the matrix as a list of rows.
+    ///
+    /// Raises:
+    ///     RuntimeError: Failed to add expectation value matrix.
+    pub fn add_exp_val_matrix(&mut self, name: String, rows: Vec<Vec<String>>) -> PyResult<()> {
+        self.internal.add_exp_val_matrix(name, rows).map_err(|x| {
+            PyRuntimeError::new_err(format!("Failed to add expectation value matrix {:?}", x))
+        })
+    }
+}
+
+#[pyclass(name = "HistogramInput", module = "qoqo.measurements")]
+#[derive(Clone, Debug)]
+/// Provides Necessary Information to run a [roqoqo::measurements::Histogram] measurement.
+pub struct HistogramInputWrapper {
+    /// Internal storage of [roqoqo::HistogramInput].
+    pub internal: HistogramInput,
+}
+
+impl Default for HistogramInputWrapper {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[pymethods]
+impl HistogramInputWrapper {
+    /// Create new HistogramInput.
+    ///
+    /// The marginals start empty and can be extended with [HistogramInput::add_marginal].
+    ///
+    /// Returns:
+    ///     self: The new instance of HistogramInput.
+    #[new]
+    pub fn new() -> Self {
+        Self {
+            internal: HistogramInput::new(),
+        }
+    }
+
+    /// Add a marginal probability distribution to the measurement input.
+    ///
+    /// Args:
+    ///     name (str): The name of the marginal probability distribution.
+    ///     readout (str): The name of the readout register the distribution is evaluated from.
+    ///     qubits (list[int]): The measured qubits (readout bits) the distribution is defined on.
+    ///
+    /// Raises:
+    ///     RuntimeError: Failed to add marginal.
+    pub fn add_marginal(
+        &mut self,
+        name: String,
+        readout: String,
+        qubits: Vec<usize>,
+    ) -> PyResult<()> {
+        self.internal
+            .add_marginal(name, readout, qubits)
+            .map_err(|x| PyRuntimeError::new_err(format!("Failed to add marginal {:?}", x)))
+    }
+
+    /// Set the readout error mitigation applied to the marginal probability distributions.
+    ///
+    /// Confusion matrices contain the probability to read out i when the qubit is in state j at position [i, j].
+    ///
+    /// Args:
+    ///     confusion_matrices (dict[int, np.ndarray]): The 2x2 confusion matrices of single readout bits.
+    ///     correlated_confusion_matrices (Optional[list[tuple[list[int], np.ndarray]]]): The confusion matrices of groups of readout bits with correlated readout errors.
+    ///     method (Optional[str]): The correction method, "TensoredInversion" (default) or "IterativeBayesianUnfolding".
+    ///     max_iterations (Optional[int]): The maximal number of iterations of iterative Bayesian unfolding. Defaults to 100.
+    ///     tolerance (Optional[float]): The convergence tolerance of iterative Bayesian unfolding. Defaults to 1e-6.
+    ///
+    /// Raises:
+    ///     ValueError: Unknown readout mitigation method.
+    ///     RuntimeError: Invalid confusion matrix.
+    pub fn set_readout_mitigation(
+        &mut self,
+        confusion_matrices: HashMap<usize, PyReadonlyArray2<f64>>,
+        correlated_confusion_matrices: Option<Vec<(Vec<usize>, PyReadonlyArray2<f64>)>>,
+        method: Option<String>,
+        max_iterations: Option<usize>,
+        tolerance: Option<f64>,
+    ) -> PyResult<()> {
+        let mitigation = readout_mitigation_from_python(
+            confusion_matrices,
+            correlated_confusion_matrices,
+            method,
+            max_iterations,
+            tolerance,
+        )?;
+        self.internal.set_readout_mitigation(Some(mitigation));
+        Ok(())
+    }
+
+    /// Remove the readout error mitigation of the measurement input.
+    pub fn remove_readout_mitigation(&mut self) {
+        self.internal.set_readout_mitigation(None);
+    }
 }
@@ -12,10 +12,16 @@
 
 //! Qoqo measurements
 
+use num_complex::Complex64;
+use numpy::{PyArray1, PyArray2, ToPyArray};
 use pyo3::prelude::*;
+use roqoqo::measurements::{ComplexExpectationValues, ExpectationValuesWithErrors};
+use roqoqo::registers::BitOutputRegister;
+use std::collections::HashMap;
 mod measurement_auxiliary_data_input;
 pub use measurement_auxiliary_data_input::{
     BasisRotationInputWrapper, CheatedBasisRotationInputWrapper, CheatedInputWrapper,
+    ClassicalShadowsInputWrapper, HistogramInputWrapper,
 };
 mod basis_rotation_measurement;
 pub use basis_rotation_measurement::BasisRotationWrapper;
@@ -25,6 +31,10 @@ mod cheated_measurement;
 pub use cheated_measurement::CheatedWrapper;
 mod classical_register_measurement;
 pub use classical_register_measurement::ClassicalRegisterWrapper;
+mod classical_shadows_measurement;
+pub use classical_shadows_measurement::ClassicalShadowsWrapper;
+mod histogram_measurement;
+pub use histogram_measurement::HistogramWrapper;
 
 /// Measurements
 #[pymodule]
@@ -32,10 +42,89 @@ pub fn measurements(_py: Python, m: &PyModule) -> PyResult<()> {
     m.add_class::<BasisRotationInputWrapper>()?;
     m.add_class::<CheatedBasisRotationInputWrapper>()?;
     m.add_class::<CheatedInputWrapper>()?;
+    m.add_class::<ClassicalShadowsInputWrapper>()?;
+    m.add_class::<HistogramInputWrapper>()?;
     m.add_class::<BasisRotationWrapper>()?;
     m.add_class::<CheatedBasisRotationWrapper>()?;
     m.add_class::<CheatedWrapper>()?;
     m.add_class::<ClassicalRegisterWrapper>()?;
+    m.add_class::<ClassicalShadowsWrapper>()?;
+    m.add_class::<HistogramWrapper>()?;
 
     Ok(())
 }
+
+/// Extracts bit registers given either as lists of bools or as lists of integers.
+pub(crate) fn extract_bit_registers(
+    input_bit_registers: &Py<PyAny>,
+) -> PyResult<HashMap<String, BitOutputRegister>> {
+    Python::with_gil(|py| -> PyResult<HashMap<String, BitOutputRegister>> {
+        let input_bit_registers = input_bit_registers.as_ref(py);
+        if let Ok(bit_registers) =
+            input_bit_registers.extract::<HashMap<String, BitOutputRegister>>()
+        {
+            return Ok(bit_registers);
+        }
+        let tmp_bit_registers =
+            input_bit_registers.extract::<HashMap<String, Vec<Vec<usize>>>>()?;
+        Ok(tmp_bit_registers
+            .into_iter()
+            .map(|(name, output_reg)| {
+                (
+                    name,
+                    output_reg
+                        .into_iter()
+                        .map(|reg| reg.into_iter().map(|x| !matches!(x, 0)).collect())
+                        .collect(),
+                )
+            })
+            .collect())
+    })
+}
+
+/// Expectation values as (mean, standard error) tuples and covariance matrix of the Pauli products.
+pub(crate) type PyExpectationValuesWithErrors = (HashMap<String, (f64, f64)>, Py<PyArray2<f64>>);
+
+/// Converts expectation values with errors to python types.
+pub(crate) fn expectation_values_with_errors_to_python(
+    results: ExpectationValuesWithErrors,
+) -> PyExpectationValuesWithErrors {
+    Python::with_gil(|py| {
+        (
+            results
+                .expectation_values
+                .into_iter()
+                .map(|(name, value)| (name, (value.mean, value.standard_error)))
+                .collect(),
+            results.pauli_product_covariance.to_pyarray(py).to_owned(),
+        )
+    })
+}
+
+/// Complex expectation values and the named vectors and matrices of expectation values.
+pub(crate) type PyComplexExpectationValues = (
+    HashMap<String, Complex64>,
+    HashMap<String, Py<PyArray1<Complex64>>>,
+    HashMap<String, Py<PyArray2<Complex64>>>,
+);
+
+/// Converts complex expectation values to python types.
+pub(crate) fn complex_expectation_values_to_python(
+    results: ComplexExpectationValues,
+) -> PyComplexExpectationValues {
+    Python::with_gil(|py| {
+        (
+            results.expectation_values,
+            results
+                .vectors
+                .into_iter()
+                .map(|(name, vector)| (name, vector.to_pyarray(py).to_owned()))
+                .collect(),
+            results
+                .matrices
+                .into_iter()
+                .map(|(name, matrix)| (name, matrix.to_pyarray(py).to_owned()))
+                .collect(),
+        )
+    })
+}
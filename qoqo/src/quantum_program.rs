@@ -13,19 +13,27 @@
 use std::collections::HashMap;
 
 use crate::measurements::{
-    BasisRotationWrapper, CheatedBasisRotationWrapper, CheatedWrapper, ClassicalRegisterWrapper,
+    extract_bit_registers, BasisRotationWrapper, CheatedBasisRotationWrapper, CheatedWrapper,
+    ClassicalRegisterWrapper, ClassicalShadowsWrapper, HistogramWrapper,
 };
-use crate::{QoqoError, QOQO_VERSION};
+use crate::{QoqoError, RunResultWrapper, QOQO_VERSION};
 use bincode::{deserialize, serialize};
+use numpy::ToPyArray;
 use pyo3::exceptions::{PyRuntimeError, PyTypeError, PyValueError};
 use pyo3::prelude::*;
 use pyo3::types::PyByteArray;
+use pyo3::types::PyDict;
+use pyo3::types::PyTuple;
 use pyo3::types::PyType;
 use pyo3::PyObjectProtocol;
 use roqoqo::measurements;
 use roqoqo::measurements::Measure;
-use roqoqo::QuantumProgram;
+use roqoqo::registers::{ComplexOutputRegister, FloatOutputRegister};
 use roqoqo::ROQOQO_VERSION;
+use roqoqo::{
+    parameter_grid, OutputRegisters, ParameterSweepResult, QuantumProgram, RunResult, SymbolWarning,
+};
+use std::time::Instant;
 
 /// Represents a quantum program evaluating measurements based on a one or more free float parameters.
 ///
@@ -91,6 +99,22 @@ impl QuantumProgramWrapper {
                 },
             });
         }
+        if let Ok(try_downcast) = measurement.extract::<ClassicalShadowsWrapper>() {
+            return Ok(Self {
+                internal: QuantumProgram::ClassicalShadows {
+                    measurement: try_downcast.internal,
+                    input_parameter_names,
+                },
+            });
+        }
+        if let Ok(try_downcast) = measurement.extract::<HistogramWrapper>() {
+            return Ok(Self {
+                internal: QuantumProgram::Histogram {
+                    measurement: try_downcast.internal,
+                    input_parameter_names,
+                },
+            });
+        }
         // Everything that follows tries to extract the circuit when two separately
         // compiled python packages are involved
         let get_measurement_internal = measurement
@@ -116,6 +140,14 @@ impl QuantumProgramWrapper {
                 let measure: measurements::ClassicalRegister = deserialize(encoded).map_err(|_| PyTypeError::new_err("measurement is not of type Measurement. Are you using different versions of roqoqo?"))?;
                 Ok( Self{internal: QuantumProgram::ClassicalRegister{measurement: measure, input_parameter_names}})
             }
+            "ClassicalShadows" => {
+                let measure: measurements::ClassicalShadows = deserialize(encoded).map_err(|_| PyTypeError::new_err("measurement is not of type Measurement. Are you using different versions of roqoqo?"))?;
+                Ok( Self{internal: QuantumProgram::ClassicalShadows{measurement: measure, input_parameter_names}})
+            }
+            "Histogram" => {
+                let measure: measurements::Histogram = deserialize(encoded).map_err(|_| PyTypeError::new_err("measurement is not of type Measurement. Are you using different versions of roqoqo?"))?;
+                Ok( Self{internal: QuantumProgram::Histogram{measurement: measure, input_parameter_names}})
+            }
             _ => Err(PyTypeError::new_err("measurement is not of type Measurement. Are you using different versions of roqoqo?"))
         }
     }
@@ -124,7 +156,7 @@ impl QuantumProgramWrapper {
     ///
     /// Returns:
     ///     PyObject corresponding to the qoqo measurement type of the QuantumProgram,
-    ///     i.e. BasisRotation, CheatedBasisRotation, Cheated or ClassicalRegister.
+    ///     i.e. BasisRotation, CheatedBasisRotation, Cheated, ClassicalRegister, ClassicalShadows or Histogram.
     pub fn measurement(&self) -> PyObject {
         match self.internal.clone() {
             QuantumProgram::BasisRotation {
@@ -179,6 +211,32 @@ impl QuantumProgramWrapper {
                 .unwrap();
                 pyref.to_object(py)
             }),
+            QuantumProgram::ClassicalShadows {
+                measurement,
+                input_parameter_names: _,
+            } => Python::with_gil(|py| -> PyObject {
+                let pyref: Py<ClassicalShadowsWrapper> = Py::new(
+                    py,
+                    ClassicalShadowsWrapper {
+                        internal: measurement.clone(),
+                    },
+                )
+                .unwrap();
+                pyref.to_object(py)
+            }),
+            QuantumProgram::Histogram {
+                measurement,
+                input_parameter_names: _,
+            } => Python::with_gil(|py| -> PyObject {
+                let pyref: Py<HistogramWrapper> = Py::new(
+                    py,
+                    HistogramWrapper {
+                        internal: measurement.clone(),
+                    },
+                )
+                .unwrap();
+                pyref.to_object(py)
+            }),
         }
     }
 
@@ -204,6 +262,14 @@ impl QuantumProgramWrapper {
                 measurement: _,
                 input_parameter_names,
             } => input_parameter_names,
+            QuantumProgram::ClassicalShadows {
+                measurement: _,
+                input_parameter_names,
+            } => input_parameter_names,
+            QuantumProgram::Histogram {
+                measurement: _,
+                input_parameter_names,
+            } => input_parameter_names,
         }
     }
 
@@ -246,6 +312,17 @@ impl QuantumProgramWrapper {
                 Python::with_gil(|py| -> PyResult<Py<PyAny>> {
                     backend.call_method1(py, "run_measurement", (CheatedWrapper{internal: substituted_measurement}, ))
                 })            }
+            QuantumProgram::ClassicalShadows{measurement, input_parameter_names } => {
+                if parameters.len() != input_parameter_names.len() { return Err(PyValueError::new_err( format!("Wrong number of parameters {} parameters expected {} parameters given", input_parameter_names.len(), parameters.len())))};
+                let substituted_parameters: HashMap<String, f64> = input_parameter_names.iter().zip(parameters.iter()).map(|(key, value)| (key.clone(), *value)).collect();
+                let substituted_measurement = measurement.substitute_parameters(
+                    substituted_parameters
+                ).map_err(|err| PyRuntimeError::new_err(format!("Applying parameters failed {:?}", err)))?;
+                Python::with_gil(|py| -> PyResult<Py<PyAny>> {
+                    backend.call_method1(py, "run_measurement", (ClassicalShadowsWrapper{internal: substituted_measurement}, ))
+                })
+            }
+            QuantumProgram::Histogram{..} => Err(PyTypeError::new_err("A quantum programm returning histograms cannot be executed by `run` use `run_histogram` instead".to_string())),
             _ => Err(PyTypeError::new_err("A quantum programm returning classical registeres cannot be executed by `run` use `run_registers` instead".to_string()))
         }
     }
@@ -277,10 +354,277 @@ impl QuantumProgramWrapper {
                 Python::with_gil(|py| -> PyResult<Py<PyAny>> {
                     backend.call_method1(py, "run_measurement_registers", (ClassicalRegisterWrapper{internal: substituted_measurement}, ))
                 })           },
+            QuantumProgram::Histogram{measurement, input_parameter_names } => {
+                if parameters.len() != input_parameter_names.len() { return Err(PyValueError::new_err( format!("Wrong number of parameters {} parameters expected {} parameters given", input_parameter_names.len(), parameters.len())))};
+                let substituted_parameters: HashMap<String, f64> = input_parameter_names.iter().zip(parameters.iter()).map(|(key, value)| (key.clone(), *value)).collect();
+                let substituted_measurement = measurement.substitute_parameters(
+                    substituted_parameters
+                ).map_err(|err| PyRuntimeError::new_err(format!("Applying parameters failed {:?}", err)))?;
+                Python::with_gil(|py| -> PyResult<Py<PyAny>> {
+                    backend.call_method1(py, "run_measurement_registers", (HistogramWrapper{internal: substituted_measurement}, ))
+                })
+            },
             _ => Err(PyTypeError::new_err("A quantum programm returning expectation values cannot be executed by `run_registers` use `run` instead".to_string()))
         }
     }
 
+    /// Runs the QuantumProgram and returns bitstring histograms and marginal probabilities.
+    ///
+    /// Runs the quantum programm for a given set of parameters passed in the same order as the parameters
+    /// listed in `input_parameter_names`, runs the circuits with the `run_measurement_registers` method
+    /// of the backend and evaluates the registers with the Histogram measurement.
+    ///
+    /// Args:
+    ///     backend (Backend): The backend the program is executed on.
+    ///     parameters (Optional[List[float]): List of float  parameters of the function call in order of `input_parameter_names`
+    ///
+    /// Returns:
+    ///     tuple[dict[str, dict[str, int]], dict[str, dict[str, float]]]: The bitstring counts with the register name as key and the marginal probabilities with the marginal name as key.
+    pub fn run_histogram(
+        &self,
+        backend: Py<PyAny>,
+        parameters: Option<Vec<f64>>,
+    ) -> PyResult<Py<PyAny>> {
+        let parameters = parameters.unwrap_or_default();
+        match &self.internal{
+            QuantumProgram::Histogram{measurement, input_parameter_names } => {
+                if parameters.len() != input_parameter_names.len() { return Err(PyValueError::new_err( format!("Wrong number of parameters {} parameters expected {} parameters given", input_parameter_names.len(), parameters.len())))};
+                let substituted_parameters: HashMap<String, f64> = input_parameter_names.iter().zip(parameters.iter()).map(|(key, value)| (key.clone(), *value)).collect();
+                let substituted_measurement = measurement.substitute_parameters(
+                    substituted_parameters
+                ).map_err(|err| PyRuntimeError::new_err(format!("Applying parameters failed {:?}", err)))?;
+                Python::with_gil(|py| -> PyResult<Py<PyAny>> {
+                    let wrapper = Py::new(py, HistogramWrapper{internal: substituted_measurement})?;
+                    let registers = backend.call_method1(py, "run_measurement_registers", (wrapper.clone_ref(py), ))?;
+                    let registers: &PyTuple = registers.as_ref(py).downcast()?;
+                    wrapper.call_method1(py, "evaluate", registers)
+                })
+            },
+            _ => Err(PyTypeError::new_err("Only a quantum programm with a histogram measurement can be executed by `run_histogram`".to_string()))
+        }
+    }
+
+    /// Runs the QuantumProgram for a batch of parameter sets and returns the expectation values in a table.
+    ///
+    /// Each parameter set is passed in the same order as the parameters listed in `input_parameter_names`.
+    /// The parameter sets are run one after another with the `run` method of the QuantumProgram.
+    ///
+    /// Args:
+    ///     backend (Backend): The backend the program is executed on.
+    ///     parameter_sets (List[List[float]]): List of parameter sets in order of `input_parameter_names`.
+    ///
+    /// Returns:
+    ///     dict[str, Union[List[str], np.ndarray]]: The `parameter_names`, the `parameters` (one row for each set), the `expectation_value_names` and the `expectation_values` (one row for each set, NaN when a run returned no value).
+    ///
+    /// Raises:
+    ///     ValueError: A parameter set has the wrong number of parameters.
+    pub fn run_batch(
+        &self,
+        backend: Py<PyAny>,
+        parameter_sets: Vec<Vec<f64>>,
+    ) -> PyResult<Py<PyDict>> {
+        Python::with_gil(|py| -> PyResult<Py<PyDict>> {
+            let results = parameter_sets
+                .iter()
+                .map(|parameters| {
+                    self.run(backend.clone_ref(py), Some(parameters.clone()))?
+                        .extract::<Option<HashMap<String, f64>>>(py)
+                })
+                .collect::<PyResult<Vec<Option<HashMap<String, f64>>>>>()?;
+            let sweep = ParameterSweepResult::from_results(
+                self.input_parameter_names(),
+                &parameter_sets,
+                results,
+            )
+            .map_err(|err| PyValueError::new_err(format!("{:?}", err)))?;
+            let dict = PyDict::new(py);
+            dict.set_item("parameter_names", sweep.parameter_names)?;
+            dict.set_item("parameters", sweep.parameters.to_pyarray(py))?;
+            dict.set_item("expectation_value_names", sweep.expectation_value_names)?;
+            dict.set_item(
+                "expectation_values",
+                sweep.expectation_values.to_pyarray(py),
+            )?;
+            Ok(dict.into())
+        })
+    }
+
+    /// Runs the QuantumProgram on a grid of parameters and returns the expectation values in a table.
+    ///
+    /// The grid contains all combinations of the values of each parameter,
+    /// ordered like nested loops over the axes with the last axis in the innermost loop.
+    ///
+    /// Args:
+    ///     backend (Backend): The backend the program is executed on.
+    ///     axes (List[List[float]]): The values of each parameter in order of `input_parameter_names`.
+    ///
+    /// Returns:
+    ///     dict[str, Union[List[str], np.ndarray]]: The table of parameters and expectation values, see `run_batch`.
+    ///
+    /// Raises:
+    ///     ValueError: The number of axes does not match the number of parameters.
+    pub fn run_grid(&self, backend: Py<PyAny>, axes: Vec<Vec<f64>>) -> PyResult<Py<PyDict>> {
+        self.run_batch(backend, parameter_grid(&axes))
+    }
+
+    /// Runs the QuantumProgram and returns the expectation values together with information about the run.
+    ///
+    /// Runs the circuits with the `run_measurement_registers` method of the backend and evaluates the registers
+    /// with the measurement. The metadata of the backend is read from its `metadata` method if it has one.
+    ///
+    /// Args:
+    ///     backend (Backend): The backend the program is executed on.
+    ///     parameters (Optional[List[float]): List of float  parameters of the function call in order of `input_parameter_names`
+    ///     keep_registers (Optional[bool]): Whether the output registers are stored in the RunResult (default False).
+    ///
+    /// Returns:
+    ///     RunResult: The expectation values, the substituted parameters, the number of circuits and shots, the time of the run and the backend metadata.
+    ///
+    /// Raises:
+    ///     ValueError: Wrong number of parameters.
+    ///     TypeError: The QuantumProgram does not return expectation values.
+    pub fn run_with_metadata(
+        &self,
+        backend: Py<PyAny>,
+        parameters: Option<Vec<f64>>,
+        keep_registers: Option<bool>,
+    ) -> PyResult<RunResultWrapper> {
+        let parameters = parameters.unwrap_or_default();
+        let keep_registers = keep_registers.unwrap_or(false);
+        let input_parameter_names = self.input_parameter_names();
+        Python::with_gil(|py| -> PyResult<RunResultWrapper> {
+            match &self.internal {
+                QuantumProgram::BasisRotation { measurement, .. } => run_measurement_with_metadata(
+                    py,
+                    &backend,
+                    measurement,
+                    |internal| BasisRotationWrapper { internal }.into_py(py),
+                    input_parameter_names,
+                    parameters,
+                    keep_registers,
+                ),
+                QuantumProgram::CheatedBasisRotation { measurement, .. } => run_measurement_with_metadata(
+                    py,
+                    &backend,
+                    measurement,
+                    |internal| CheatedBasisRotationWrapper { internal }.into_py(py),
+                    input_parameter_names,
+                    parameters,
+                    keep_registers,
+                ),
+                QuantumProgram::Cheated { measurement, .. } => run_measurement_with_metadata(
+                    py,
+                    &backend,
+                    measurement,
+                    |internal| CheatedWrapper { internal }.into_py(py),
+                    input_parameter_names,
+                    parameters,
+                    keep_registers,
+                ),
+                QuantumProgram::ClassicalShadows { measurement, .. } => run_measurement_with_metadata(
+                    py,
+                    &backend,
+                    measurement,
+                    |internal| ClassicalShadowsWrapper { internal }.into_py(py),
+                    input_parameter_names,
+                    parameters,
+                    keep_registers,
+                ),
+                QuantumProgram::Histogram { .. } => Err(PyTypeError::new_err("A quantum programm returning histograms cannot be executed by `run_with_metadata` use `run_histogram` instead".to_string())),
+                _ => Err(PyTypeError::new_err("A quantum programm returning classical registeres cannot be executed by `run_with_metadata` use `run_registers` instead".to_string())),
+            }
+        })
+    }
+
+    /// Runs the QuantumProgram with parameters given by name and returns expectation values.
+    ///
+    /// Args:
+    ///     backend (Backend): The backend the program is executed on.
+    ///     parameters (dict[str, float]): The values of the input parameters with the name of the parameter as key.
+    ///
+    /// Returns:
+    ///     Optional[dict[str, float]]: The expectation values of the run, see `run`.
+    ///
+    /// Raises:
+    ///     ValueError: An input parameter is missing or an unknown name is given.
+    pub fn run_named(
+        &self,
+        backend: Py<PyAny>,
+        parameters: HashMap<String, f64>,
+    ) -> PyResult<Py<PyAny>> {
+        let values = self
+            .internal
+            .parameter_values(&parameters)
+            .map_err(|err| PyValueError::new_err(format!("{:?}", err)))?;
+        self.run(backend, Some(values))
+    }
+
+    /// Replaces a subset of the input parameters of the QuantumProgram with fixed values.
+    ///
+    /// The bound parameters are removed from `input_parameter_names` and set by InputSymbolic definitions
+    /// in all circuits of the measurement. The remaining input parameters keep their order.
+    ///
+    /// Args:
+    ///     parameters (dict[str, float]): The values of the bound input parameters with the name of the parameter as key.
+    ///
+    /// Returns:
+    ///     QuantumProgram: The QuantumProgram with the remaining input parameters.
+    ///
+    /// Raises:
+    ///     ValueError: A name is not an input parameter of the QuantumProgram.
+    pub fn bind_parameters(
+        &self,
+        parameters: HashMap<String, f64>,
+    ) -> PyResult<QuantumProgramWrapper> {
+        Ok(QuantumProgramWrapper {
+            internal: self
+                .internal
+                .bind_parameters(&parameters)
+                .map_err(|err| PyValueError::new_err(format!("{:?}", err)))?,
+        })
+    }
+
+    /// Returns the symbols that are used in the circuits of the measurement.
+    ///
+    /// Symbols set by InputSymbolic definitions are not included.
+    ///
+    /// Returns:
+    ///     List[str]: The sorted names of the free symbols.
+    ///
+    /// Raises:
+    ///     RuntimeError: A circuit cannot be substituted independent of the values of its symbols.
+    pub fn free_symbols(&self) -> PyResult<Vec<String>> {
+        Ok(self
+            .internal
+            .free_symbols()
+            .map_err(|err| PyRuntimeError::new_err(format!("{:?}", err)))?
+            .into_iter()
+            .collect())
+    }
+
+    /// Compares the input parameters of the QuantumProgram with the symbols used in its circuits.
+    ///
+    /// Returns:
+    ///     Tuple[List[str], List[str]]: The input parameters not used in any circuit and the symbols used in the circuits that are not input parameters.
+    ///
+    /// Raises:
+    ///     RuntimeError: A circuit cannot be substituted independent of the values of its symbols.
+    pub fn check_symbols(&self) -> PyResult<(Vec<String>, Vec<String>)> {
+        let warnings = self
+            .internal
+            .check_symbols()
+            .map_err(|err| PyRuntimeError::new_err(format!("{:?}", err)))?;
+        let mut unused: Vec<String> = Vec::new();
+        let mut undeclared: Vec<String> = Vec::new();
+        for warning in warnings {
+            match warning {
+                SymbolWarning::UnusedParameter(name) => unused.push(name),
+                SymbolWarning::UndeclaredSymbol(name) => undeclared.push(name),
+            }
+        }
+        Ok((unused, undeclared))
+    }
+
     /// Return a copy of the QuantumProgram (copy here produces a deepcopy).
     ///
     /// Returns:
@@ -371,6 +715,49 @@ impl QuantumProgramWrapper {
         Ok(serialized)
     }
 
+    /// Return the JSON schema of the serialized QuantumProgram including the roqoqo version fields.
+    ///
+    /// Returns:
+    ///     str: The JSON schema of QuantumProgram.
+    #[allow(unused_variables)]
+    #[classmethod]
+    pub fn json_schema(cls: &PyType) -> String {
+        roqoqo::schema::json_schema::<roqoqo::QuantumProgram>()
+    }
+
+    /// Validate the json representation of a QuantumProgram against the JSON schema of QuantumProgram.
+    ///
+    /// Args:
+    ///     input (str): The serialized QuantumProgram in json form.
+    ///
+    /// Raises:
+    ///     ValueError: Input does not match the JSON schema, the message contains the path of the offending value.
+    #[allow(unused_variables)]
+    #[classmethod]
+    pub fn validate_json(cls: &PyType, json_string: &str) -> PyResult<()> {
+        roqoqo::schema::validate_json::<roqoqo::QuantumProgram>(json_string)
+            .map_err(|err| PyValueError::new_err(format!("{}", err)))
+    }
+
+    /// Convert the json representation of a QuantumProgram to a QuantumProgram after validating it against the JSON schema.
+    ///
+    /// Args:
+    ///     input (str): The serialized QuantumProgram in json form.
+    ///
+    /// Returns:
+    ///     QuantumProgram: The deserialized QuantumProgram.
+    ///
+    /// Raises:
+    ///     ValueError: Input does not match the JSON schema or cannot be deserialized to QuantumProgram.
+    #[allow(unused_variables)]
+    #[classmethod]
+    pub fn from_json_validated(cls: &PyType, json_string: &str) -> PyResult<Self> {
+        Ok(Self {
+            internal: roqoqo::schema::from_json_validated::<roqoqo::QuantumProgram>(json_string)
+                .map_err(|err| PyValueError::new_err(format!("{}", err)))?,
+        })
+    }
+
     /// Convert the json representation of a QuantumProgram to a QuantumProgram.
     ///
     /// Args:
@@ -466,3 +853,74 @@ pub fn convert_into_quantum_program(input: &PyAny) -> Result<QuantumProgram, Qoq
         Err(QoqoError::VersionMismatch)
     }
 }
+
+/// Substitutes the parameters in a measurement, runs it with a python backend and returns the [RunResultWrapper].
+///
+/// The substituted measurement is passed to python wrapped by `wrap`.
+fn run_measurement_with_metadata<M, W>(
+    py: Python,
+    backend: &Py<PyAny>,
+    measurement: &M,
+    wrap: W,
+    parameter_names: Vec<String>,
+    parameters: Vec<f64>,
+    keep_registers: bool,
+) -> PyResult<RunResultWrapper>
+where
+    M: Measure + Clone,
+    W: FnOnce(M) -> Py<PyAny>,
+{
+    if parameters.len() != parameter_names.len() {
+        return Err(PyValueError::new_err(format!(
+            "Wrong number of parameters {} parameters expected {} parameters given",
+            parameter_names.len(),
+            parameters.len()
+        )));
+    };
+    let substituted_parameters: HashMap<String, f64> = parameter_names
+        .iter()
+        .zip(parameters.iter())
+        .map(|(key, value)| (key.clone(), *value))
+        .collect();
+    let measurement = measurement
+        .substitute_parameters(substituted_parameters)
+        .map_err(|err| PyRuntimeError::new_err(format!("Applying parameters failed {:?}", err)))?;
+    let wrapper = wrap(measurement.clone());
+    let start = Instant::now();
+    let registers =
+        backend.call_method1(py, "run_measurement_registers", (wrapper.clone_ref(py),))?;
+    let registers: &PyTuple = registers.as_ref(py).downcast()?;
+    let expectation_values = wrapper
+        .call_method1(py, "evaluate", registers)?
+        .extract::<Option<HashMap<String, f64>>>(py)?;
+    let elapsed_seconds = start.elapsed().as_secs_f64();
+    let registers = if keep_registers {
+        let (bit_registers, float_registers, complex_registers) = registers.extract::<(
+            Py<PyAny>,
+            HashMap<String, FloatOutputRegister>,
+            HashMap<String, ComplexOutputRegister>,
+        )>()?;
+        Some(OutputRegisters {
+            bit_registers: extract_bit_registers(&bit_registers)?,
+            float_registers,
+            complex_registers,
+        })
+    } else {
+        None
+    };
+    let backend_metadata = match backend.getattr(py, "metadata") {
+        Ok(metadata) => metadata.call0(py)?.extract::<HashMap<String, String>>(py)?,
+        Err(_) => HashMap::new(),
+    };
+    Ok(RunResultWrapper {
+        internal: RunResult::from_run(
+            &measurement,
+            parameter_names,
+            parameters,
+            expectation_values,
+            registers,
+            elapsed_seconds,
+            backend_metadata,
+        ),
+    })
+}
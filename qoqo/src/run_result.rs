@@ -0,0 +1,232 @@
+// Copyright © 2021 HQS Quantum Simulations GmbH. All Rights Reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except
+// in compliance with the License. You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the
+// License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either
+// express or implied. See the License for the specific language governing permissions and
+// limitations under the License.
+
+use bincode::{deserialize, serialize};
+use pyo3::exceptions::{PyTypeError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::PyByteArray;
+use pyo3::types::PyType;
+use pyo3::PyObjectProtocol;
+use roqoqo::registers::{BitOutputRegister, ComplexOutputRegister, FloatOutputRegister};
+use roqoqo::RunResult;
+use std::collections::HashMap;
+
+/// Expectation values of a single QuantumProgram run together with information about the run.
+///
+/// Returned by the `run_with_metadata` method of QuantumProgram.
+#[pyclass(name = "RunResult", module = "qoqo")]
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct RunResultWrapper {
+    /// Internal storage of [roqoqo::RunResult]
+    pub internal: RunResult,
+}
+
+#[pymethods]
+impl RunResultWrapper {
+    /// Return the expectation values of the run.
+    ///
+    /// Returns:
+    ///     Optional[dict[str, float]]: The expectation values with their name as key (None when the measurement did not return a result).
+    pub fn expectation_values(&self) -> Option<HashMap<String, f64>> {
+        self.internal.expectation_values.clone()
+    }
+
+    /// Return the output registers of the run.
+    ///
+    /// Returns:
+    ///     Optional[tuple[dict[str, list[list[bool]]], dict[str, list[list[float]]], dict[str, list[list[complex]]]]]: The bit, float and complex registers (None when they have not been kept).
+    #[allow(clippy::type_complexity)]
+    pub fn registers(
+        &self,
+    ) -> Option<(
+        HashMap<String, BitOutputRegister>,
+        HashMap<String, FloatOutputRegister>,
+        HashMap<String, ComplexOutputRegister>,
+    )> {
+        self.internal.registers.clone().map(|registers| {
+            (
+                registers.bit_registers,
+                registers.float_registers,
+                registers.complex_registers,
+            )
+        })
+    }
+
+    /// Return the names of the input parameters.
+    ///
+    /// Returns:
+    ///     List[str]: The names of the input parameters.
+    pub fn parameter_names(&self) -> Vec<String> {
+        self.internal.parameter_names.clone()
+    }
+
+    /// Return the values of the input parameters.
+    ///
+    /// Returns:
+    ///     List[float]: The values of the input parameters in order of `parameter_names`.
+    pub fn parameters(&self) -> Vec<f64> {
+        self.internal.parameters.clone()
+    }
+
+    /// Return the number of circuits run on the backend.
+    ///
+    /// Returns:
+    ///     int: The number of circuits.
+    pub fn number_circuits(&self) -> usize {
+        self.internal.number_circuits
+    }
+
+    /// Return the total number of shots of all circuits.
+    ///
+    /// Returns:
+    ///     int: The number of shots set by PragmaSetNumberOfMeasurements or PragmaRepeatedMeasurement.
+    pub fn total_shots(&self) -> usize {
+        self.internal.total_shots
+    }
+
+    /// Return the wall-clock time of the run.
+    ///
+    /// Returns:
+    ///     float: The time of running the circuits and evaluating the registers in seconds.
+    pub fn elapsed_seconds(&self) -> f64 {
+        self.internal.elapsed_seconds
+    }
+
+    /// Return the metadata supplied by the backend.
+    ///
+    /// Returns:
+    ///     dict[str, str]: The metadata returned by the `metadata` method of the backend (empty if the backend has none).
+    pub fn backend_metadata(&self) -> HashMap<String, String> {
+        self.internal.backend_metadata.clone()
+    }
+
+    /// Return a copy of the RunResult (copy here produces a deepcopy).
+    ///
+    /// Returns:
+    ///     RunResult: A deep copy of self.
+    pub fn __copy__(&self) -> RunResultWrapper {
+        self.clone()
+    }
+
+    /// Return a deep copy of the RunResult.
+    ///
+    /// Returns:
+    ///     RunResult: A deep copy of self.
+    pub fn __deepcopy__(&self, _memodict: Py<PyAny>) -> RunResultWrapper {
+        self.clone()
+    }
+
+    /// Return the bincode representation of the RunResult using the [bincode] crate.
+    ///
+    /// Returns:
+    ///     ByteArray: The serialized RunResult (in [bincode] form).
+    ///
+    /// Raises:
+    ///     ValueError: Cannot serialize RunResult to bytes.
+    pub fn to_bincode(&self) -> PyResult<Py<PyByteArray>> {
+        let serialized = serialize(&self.internal)
+            .map_err(|_| PyValueError::new_err("Cannot serialize RunResult to bytes"))?;
+        let b: Py<PyByteArray> = Python::with_gil(|py| -> Py<PyByteArray> {
+            PyByteArray::new(py, &serialized[..]).into()
+        });
+        Ok(b)
+    }
+
+    #[allow(unused_variables)]
+    #[classmethod]
+    /// Convert the bincode representation of the RunResult to a RunResult using the [bincode] crate.
+    ///
+    /// Args:
+    ///     input (ByteArray): The serialized RunResult (in [bincode] form).
+    ///
+    /// Returns:
+    ///     RunResult: The deserialized RunResult.
+    ///
+    /// Raises:
+    ///     TypeError: Input cannot be converted to byte array.
+    ///     ValueError: Input cannot be deserialized to RunResult.
+    pub fn from_bincode(cls: &PyType, input: &PyAny) -> PyResult<Self> {
+        let bytes = input
+            .extract::<Vec<u8>>()
+            .map_err(|_| PyTypeError::new_err("Input cannot be converted to byte array"))?;
+
+        Ok(Self {
+            internal: deserialize(&bytes[..])
+                .map_err(|_| PyValueError::new_err("Input cannot be deserialized to RunResult"))?,
+        })
+    }
+
+    /// Return the json representation of the RunResult.
+    ///
+    /// Returns:
+    ///     str: The serialized form of RunResult.
+    ///
+    /// Raises:
+    ///     ValueError: Cannot serialize RunResult to json.
+    fn to_json(&self) -> PyResult<String> {
+        let serialized = serde_json::to_string(&self.internal)
+            .map_err(|_| PyValueError::new_err("Cannot serialize RunResult to json"))?;
+        Ok(serialized)
+    }
+
+    /// Convert the json representation of a RunResult to a RunResult.
+    ///
+    /// Args:
+    ///     input (str): The serialized RunResult in json form.
+    ///
+    /// Returns:
+    ///     RunResult: The deserialized RunResult.
+    ///
+    /// Raises:
+    ///     ValueError: Input cannot be deserialized to RunResult.
+    #[allow(unused_variables)]
+    #[classmethod]
+    fn from_json(cls: &PyType, input: &str) -> PyResult<Self> {
+        Ok(Self {
+            internal: serde_json::from_str(input)
+                .map_err(|_| PyValueError::new_err("Input cannot be deserialized to RunResult"))?,
+        })
+    }
+}
+
+#[pyproto]
+impl PyObjectProtocol for RunResultWrapper {
+    /// Return the __richcmp__ magic method to perform rich comparison operations on RunResult.
+    ///
+    /// Args:
+    ///     other: The object to compare self to.
+    ///     op: Type of comparison.
+    ///
+    /// Returns:
+    ///     Whether the two RunResults compared evaluated to True or False
+    ///
+    /// Raises:
+    ///     NotImplementedError: Other comparison not implemented
+    fn __richcmp__(&self, other: Py<PyAny>, op: pyo3::class::basic::CompareOp) -> PyResult<bool> {
+        let other = Python::with_gil(|py| -> PyResult<RunResultWrapper> {
+            other.as_ref(py).extract::<RunResultWrapper>()
+        });
+        match op {
+            pyo3::class::basic::CompareOp::Eq => match other {
+                Ok(result) => Ok(self.internal == result.internal),
+                _ => Ok(false),
+            },
+            pyo3::class::basic::CompareOp::Ne => match other {
+                Ok(result) => Ok(self.internal != result.internal),
+                _ => Ok(true),
+            },
+            _ => Err(pyo3::exceptions::PyNotImplementedError::new_err(
+                "Other comparison not implemented",
+            )),
+        }
+    }
+}
@@ -219,6 +219,36 @@ fn test_get_operation_types() {
     })
 }
 
+/// Test to_quantikz and to_svg functions of Circuit
+#[test]
+fn test_render() {
+    pyo3::prepare_freethreaded_python();
+    Python::with_gil(|py| {
+        let circuit = new_circuit(py);
+        populate_circuit_rotatex(py, circuit, 0, 2);
+
+        let latex = String::extract(circuit.call_method0("to_quantikz").unwrap()).unwrap();
+        assert!(latex.starts_with("\\begin{quantikz}"));
+        assert!(latex.contains("\\gate{R_{x}(1.000)}"));
+        assert!(latex.contains("\\lstick{$q_{1}$}"));
+
+        let mut labels: HashMap<usize, String> = HashMap::new();
+        labels.insert(0, "a".to_string());
+        let latex = String::extract(
+            circuit
+                .call_method1("to_quantikz", (labels.clone(),))
+                .unwrap(),
+        )
+        .unwrap();
+        assert!(latex.contains("\\lstick{a}"));
+
+        let svg = String::extract(circuit.call_method1("to_svg", (labels,)).unwrap()).unwrap();
+        assert!(svg.starts_with("<svg"));
+        assert!(svg.contains("Rx(1.000)"));
+        assert!(svg.contains(">a</text>"));
+    })
+}
+
 /// Test copy and deepcopy functions of Circuit
 #[test]
 fn test_copy_deepcopy() {
@@ -354,6 +384,62 @@ fn test_value_error_bincode() {
     })
 }
 
+/// Test json_schema function of Circuit
+#[test]
+fn test_json_schema() {
+    pyo3::prepare_freethreaded_python();
+    Python::with_gil(|py| {
+        let circuit = new_circuit(py);
+        let schema = String::extract(circuit.call_method0("json_schema").unwrap()).unwrap();
+        assert!(schema.contains("_roqoqo_version"));
+        assert!(schema.contains("RotateX"));
+
+        // testing that 'json_schema' can be called directly on the class
+        let circuit_type = py.get_type::<CircuitWrapper>();
+        let schema_cls =
+            String::extract(circuit_type.call_method0("json_schema").unwrap()).unwrap();
+        assert_eq!(schema, schema_cls);
+        let schema_value: serde_json::Value = serde_json::from_str(&schema).unwrap();
+        assert!(schema_value.is_object());
+    })
+}
+
+/// Test validate_json and from_json_validated functions of Circuit
+#[test]
+fn test_json_validation() {
+    pyo3::prepare_freethreaded_python();
+    Python::with_gil(|py| {
+        let circuit = new_circuit(py);
+        populate_circuit_rotatex(py, circuit, 0, 3);
+        let circuit_type = py.get_type::<CircuitWrapper>();
+
+        let serialised = circuit.call_method0("to_json").unwrap();
+        assert!(circuit_type
+            .call_method1("validate_json", (serialised,))
+            .is_ok());
+        let deserialised = circuit_type
+            .call_method1("from_json_validated", (serialised,))
+            .unwrap();
+        let comparison =
+            bool::extract(deserialised.call_method1("__eq__", (circuit,)).unwrap()).unwrap();
+        assert!(comparison);
+
+        let mut invalid: serde_json::Value =
+            serde_json::from_str(&String::extract(serialised).unwrap()).unwrap();
+        invalid["operations"][0]["RotateX"]["qubit"] = serde_json::json!("zero");
+        let error = circuit_type
+            .call_method1("validate_json", (invalid.to_string(),))
+            .unwrap_err();
+        assert!(format!("{}", error).contains("/operations/0/RotateX/qubit"));
+        assert!(circuit_type
+            .call_method1("from_json_validated", (invalid.to_string(),))
+            .is_err());
+        assert!(circuit_type
+            .call_method1("validate_json", ("not json",))
+            .is_err());
+    })
+}
+
 /// Test to_ and from_json functions of Circuit
 #[test]
 fn test_to_from_json() {
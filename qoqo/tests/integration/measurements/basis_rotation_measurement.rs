@@ -13,6 +13,7 @@
 //! Integration test for public API of Basis rotation measurement
 
 use bincode::serialize;
+use numpy::PyArray2;
 use pyo3::prelude::*;
 use pyo3::Python;
 use qoqo::measurements::{BasisRotationInputWrapper, BasisRotationWrapper};
@@ -440,6 +441,239 @@ fn test_py03_evaluate_error0() {
     })
 }
 
+/// Test evaluate() function for BasisRotation measurement with readout error mitigation
+#[test]
+fn test_py03_evaluate_readout_mitigation() {
+    pyo3::prepare_freethreaded_python();
+
+    Python::with_gil(|py| {
+        let input_type = py.get_type::<BasisRotationInputWrapper>();
+        let input = input_type
+            .call1((1, false))
+            .unwrap()
+            .cast_as::<PyCell<BasisRotationInputWrapper>>()
+            .unwrap();
+        let _ = input
+            .call_method1("add_pauli_product", ("ro", vec![0]))
+            .unwrap();
+        let mut linear_map: HashMap<usize, f64> = HashMap::new();
+        linear_map.insert(0, 1.0);
+        let _ = input
+            .call_method1("add_linear_exp_val", ("z0".to_string(), linear_map))
+            .unwrap();
+
+        let circs: Vec<CircuitWrapper> = vec![CircuitWrapper::new()];
+        let br_type = py.get_type::<BasisRotationWrapper>();
+        let br = br_type
+            .call1((Some(CircuitWrapper::new()), circs, input))
+            .unwrap()
+            .cast_as::<PyCell<BasisRotationWrapper>>()
+            .unwrap();
+
+        // Qubit in state 1 with probability 0.75 is read out as 0 with probability 0.375
+        let mut register: BitOutputRegister = vec![vec![false]; 3];
+        register.extend(vec![vec![true]; 5]);
+        let mut measured_registers: HashMap<String, BitOutputRegister> = HashMap::new();
+        measured_registers.insert("ro".to_string(), register);
+        let input2: HashMap<String, FloatOutputRegister> = HashMap::new();
+        let input3: HashMap<String, ComplexOutputRegister> = HashMap::new();
+        let confusion_matrix = PyArray2::from_vec2(py, &[vec![0.9, 0.2], vec![0.1, 0.8]]).unwrap();
+        let mut confusion_matrices: HashMap<usize, &PyArray2<f64>> = HashMap::new();
+        confusion_matrices.insert(0, confusion_matrix);
+
+        let result = br
+            .call_method1(
+                "evaluate",
+                (measured_registers.clone(), input2.clone(), input3.clone()),
+            )
+            .unwrap();
+        let z0 = f64::extract(result.get_item("z0").unwrap()).unwrap();
+        assert!((z0 + 0.25).abs() < 1e-10);
+
+        for method in ["TensoredInversion", "IterativeBayesianUnfolding"] {
+            let result = br
+                .call_method1(
+                    "evaluate",
+                    (
+                        measured_registers.clone(),
+                        input2.clone(),
+                        input3.clone(),
+                        confusion_matrices.clone(),
+                        Option::<Vec<(Vec<usize>, &PyArray2<f64>)>>::None,
+                        method,
+                        1000,
+                        1e-12,
+                    ),
+                )
+                .unwrap();
+            let z0 = f64::extract(result.get_item("z0").unwrap()).unwrap();
+            assert!((z0 + 0.5).abs() < 1e-6);
+        }
+
+        let error = br.call_method1(
+            "evaluate",
+            (
+                measured_registers.clone(),
+                input2.clone(),
+                input3.clone(),
+                confusion_matrices.clone(),
+                Option::<Vec<(Vec<usize>, &PyArray2<f64>)>>::None,
+                "Unknown",
+            ),
+        );
+        assert!(error.is_err());
+
+        let input = br.call_method0("input").unwrap();
+        input
+            .call_method1("set_readout_mitigation", (confusion_matrices,))
+            .unwrap();
+        let br = br_type
+            .call1((
+                Some(CircuitWrapper::new()),
+                vec![CircuitWrapper::new()],
+                input,
+            ))
+            .unwrap();
+        let result = br
+            .call_method1("evaluate", (measured_registers, input2, input3))
+            .unwrap();
+        let z0 = f64::extract(result.get_item("z0").unwrap()).unwrap();
+        assert!((z0 + 0.5).abs() < 1e-10);
+    })
+}
+
+/// Test evaluate_with_errors() function for BasisRotation measurement
+#[test]
+fn test_py03_evaluate_with_errors() {
+    pyo3::prepare_freethreaded_python();
+
+    Python::with_gil(|py| {
+        let input_type = py.get_type::<BasisRotationInputWrapper>();
+        let input = input_type
+            .call1((1, false))
+            .unwrap()
+            .cast_as::<PyCell<BasisRotationInputWrapper>>()
+            .unwrap();
+        let _ = input
+            .call_method1("add_pauli_product", ("ro", vec![0]))
+            .unwrap();
+        let mut linear_map: HashMap<usize, f64> = HashMap::new();
+        linear_map.insert(0, 2.0);
+        let _ = input
+            .call_method1("add_linear_exp_val", ("z0".to_string(), linear_map))
+            .unwrap();
+
+        let circs: Vec<CircuitWrapper> = vec![CircuitWrapper::new()];
+        let br_type = py.get_type::<BasisRotationWrapper>();
+        let br = br_type
+            .call1((Some(CircuitWrapper::new()), circs, input))
+            .unwrap()
+            .cast_as::<PyCell<BasisRotationWrapper>>()
+            .unwrap();
+
+        let mut measured_registers: HashMap<String, Vec<Vec<usize>>> = HashMap::new();
+        measured_registers.insert("ro".to_string(), vec![vec![1], vec![1], vec![0], vec![0]]);
+        let input2: HashMap<String, FloatOutputRegister> = HashMap::new();
+        let input3: HashMap<String, ComplexOutputRegister> = HashMap::new();
+
+        let result = br
+            .call_method1("evaluate_with_errors", (measured_registers, input2, input3))
+            .unwrap();
+        let (expectation_values, covariance): (HashMap<String, (f64, f64)>, Vec<Vec<f64>>) = (
+            result.get_item(0).unwrap().extract().unwrap(),
+            result
+                .get_item(1)
+                .unwrap()
+                .call_method0("tolist")
+                .unwrap()
+                .extract()
+                .unwrap(),
+        );
+        let (mean, standard_error) = expectation_values["z0"];
+        assert!(mean.abs() < 1e-10);
+        assert!((standard_error - 2.0 / 3.0_f64.sqrt()).abs() < 1e-10);
+        assert!((covariance[0][0] - 1.0 / 3.0).abs() < 1e-10);
+    })
+}
+
+/// Test construction of BasisRotation from sums of Pauli products
+#[test]
+fn test_pyo3_from_pauli_sums() {
+    pyo3::prepare_freethreaded_python();
+
+    Python::with_gil(|py| {
+        let br_type = py.get_type::<BasisRotationWrapper>();
+        let mut hamiltonian: HashMap<String, f64> = HashMap::new();
+        hamiltonian.insert("0X1Z".to_string(), 0.5);
+        hamiltonian.insert("2Y".to_string(), -1.0);
+        hamiltonian.insert("0Z".to_string(), 0.3);
+        let mut operators: HashMap<String, HashMap<String, f64>> = HashMap::new();
+        operators.insert("energy".to_string(), hamiltonian);
+
+        let br = br_type
+            .call_method1("from_pauli_sums", (operators.clone(), 3, 100))
+            .unwrap()
+            .cast_as::<PyCell<BasisRotationWrapper>>()
+            .unwrap();
+        let circuits: Vec<CircuitWrapper> = br.call_method0("circuits").unwrap().extract().unwrap();
+        assert_eq!(circuits.len(), 2);
+
+        let br = br_type
+            .call_method1(
+                "from_pauli_sums",
+                (operators, 3, 100, true, Some(CircuitWrapper::new())),
+            )
+            .unwrap();
+        let circuits: Vec<CircuitWrapper> = br.call_method0("circuits").unwrap().extract().unwrap();
+        assert_eq!(circuits.len(), 4);
+
+        let mut bell: HashMap<String, HashMap<String, f64>> = HashMap::new();
+        bell.insert(
+            "bell".to_string(),
+            HashMap::from([
+                ("0X1X".to_string(), 1.0),
+                ("0Y1Y".to_string(), 1.0),
+                ("0Z1Z".to_string(), 1.0),
+            ]),
+        );
+        let br = br_type
+            .call_method1(
+                "from_pauli_sums",
+                (
+                    bell.clone(),
+                    2,
+                    100,
+                    false,
+                    Option::<CircuitWrapper>::None,
+                    "GeneralCommuting",
+                ),
+            )
+            .unwrap();
+        let circuits: Vec<CircuitWrapper> = br.call_method0("circuits").unwrap().extract().unwrap();
+        assert_eq!(circuits.len(), 1);
+        let result = br_type.call_method1(
+            "from_pauli_sums",
+            (
+                bell,
+                2,
+                100,
+                false,
+                Option::<CircuitWrapper>::None,
+                "Unknown",
+            ),
+        );
+        assert!(result.is_err());
+
+        let mut invalid: HashMap<String, HashMap<String, f64>> = HashMap::new();
+        invalid.insert(
+            "energy".to_string(),
+            HashMap::from([("5X".to_string(), 1.0)]),
+        );
+        let result = br_type.call_method1("from_pauli_sums", (invalid, 3, 100));
+        assert!(result.is_err());
+    })
+}
+
 /// Test copy
 #[test]
 fn test_pyo3_copy() {
@@ -527,10 +761,10 @@ fn test_pyo3_debug() {
         let br_clone = br_wrapper.clone();
         assert_eq!(format!("{:?}", br_wrapper), format!("{:?}", br_clone));
 
-        let debug_string = "RefCell { value: BasisRotationWrapper { internal: BasisRotation { constant_circuit: Some(Circuit { definitions: [], operations: [], _roqoqo_version: RoqoqoVersion }), circuits: [Circuit { definitions: [], operations: [], _roqoqo_version: RoqoqoVersion }], input: BasisRotationInput { pauli_product_qubit_masks: {\"ro\": {0: []}}, number_qubits: 3, number_pauli_products: 1, measured_exp_vals: {}, use_flipped_measurement: false } } } }";
+        let debug_string = "RefCell { value: BasisRotationWrapper { internal: BasisRotation { constant_circuit: Some(Circuit { definitions: [], operations: [], _roqoqo_version: RoqoqoVersion }), circuits: [Circuit { definitions: [], operations: [], _roqoqo_version: RoqoqoVersion }], input: BasisRotationInput { pauli_product_qubit_masks: {\"ro\": {0: []}}, number_qubits: 3, number_pauli_products: 1, measured_exp_vals: {}, use_flipped_measurement: false, readout_mitigation: None, exp_val_groups: {} } } } }";
         assert_eq!(format!("{:?}", br), debug_string);
 
-        let debug_input_string = "RefCell { value: BasisRotationInputWrapper { internal: BasisRotationInput { pauli_product_qubit_masks: {\"ro\": {0: []}}, number_qubits: 3, number_pauli_products: 1, measured_exp_vals: {}, use_flipped_measurement: false } } }";
+        let debug_input_string = "RefCell { value: BasisRotationInputWrapper { internal: BasisRotationInput { pauli_product_qubit_masks: {\"ro\": {0: []}}, number_qubits: 3, number_pauli_products: 1, measured_exp_vals: {}, use_flipped_measurement: false, readout_mitigation: None, exp_val_groups: {} } } }";
         assert_eq!(format!("{:?}", input), debug_input_string);
 
         let debug_input = &(*input);
@@ -799,3 +1033,52 @@ fn test_return_input() {
         assert_eq!(format!("{:?}", input_returned), format!("{:?}", input));
     })
 }
+
+/// Test json_schema, validate_json and from_json_validated functions
+#[test]
+fn test_json_schema_validation() {
+    pyo3::prepare_freethreaded_python();
+    Python::with_gil(|py| {
+        let input_type = py.get_type::<BasisRotationInputWrapper>();
+        let input = input_type
+            .call1((3, false))
+            .unwrap()
+            .cast_as::<PyCell<BasisRotationInputWrapper>>()
+            .unwrap();
+        let tmp_vec: Vec<usize> = vec![0];
+        let _ = input
+            .call_method1("add_pauli_product", ("ro", tmp_vec))
+            .unwrap();
+        let circs: Vec<CircuitWrapper> = vec![CircuitWrapper::new()];
+        let br = py
+            .get_type::<BasisRotationWrapper>()
+            .call1((Some(CircuitWrapper::new()), circs, input))
+            .unwrap()
+            .cast_as::<PyCell<BasisRotationWrapper>>()
+            .unwrap();
+
+        let br_type = py.get_type::<BasisRotationWrapper>();
+        let schema = String::extract(br_type.call_method0("json_schema").unwrap()).unwrap();
+        assert!(schema.contains("pauli_product_qubit_masks"));
+
+        let serialised = br.call_method0("to_json").unwrap();
+        assert!(br_type.call_method1("validate_json", (serialised,)).is_ok());
+        let deserialised = br_type
+            .call_method1("from_json_validated", (serialised,))
+            .unwrap()
+            .cast_as::<PyCell<BasisRotationWrapper>>()
+            .unwrap();
+        assert_eq!(format!("{:?}", br), format!("{:?}", deserialised));
+
+        let mut invalid: serde_json::Value =
+            serde_json::from_str(&String::extract(serialised).unwrap()).unwrap();
+        invalid["circuits"] = serde_json::json!(1);
+        let error = br_type
+            .call_method1("validate_json", (invalid.to_string(),))
+            .unwrap_err();
+        assert!(format!("{}", error).contains("/circuits"));
+        assert!(br_type
+            .call_method1("from_json_validated", (invalid.to_string(),))
+            .is_err());
+    })
+}
@@ -13,6 +13,7 @@
 //! Integration test for public API of Basis rotation measurement
 
 use bincode::serialize;
+use num_complex::Complex64;
 use pyo3::prelude::*;
 use pyo3::Python;
 use qoqo::measurements::{CheatedBasisRotationInputWrapper, CheatedBasisRotationWrapper};
@@ -124,6 +125,79 @@ fn test_py03_evaluate_bool() {
     })
 }
 
+/// Test evaluate_complex() function for CheatedBasisRotation measurement
+#[test]
+fn test_pyo3_evaluate_complex() {
+    pyo3::prepare_freethreaded_python();
+    Python::with_gil(|py| {
+        let input_type = py.get_type::<CheatedBasisRotationInputWrapper>();
+        let input = input_type
+            .call0()
+            .unwrap()
+            .cast_as::<PyCell<CheatedBasisRotationInputWrapper>>()
+            .unwrap();
+        let _ = input.call_method1("add_pauli_product", ("ro_x",)).unwrap();
+        let _ = input.call_method1("add_pauli_product", ("ro_y",)).unwrap();
+
+        let mut linear_map: HashMap<usize, Complex64> = HashMap::new();
+        linear_map.insert(0, Complex64::new(0.5, 0.0));
+        linear_map.insert(1, Complex64::new(0.0, 0.5));
+        input
+            .call_method1("add_linear_complex_exp_val", ("sigma_plus", linear_map))
+            .unwrap();
+        input
+            .call_method1("add_exp_val_vector", ("vector", vec!["sigma_plus"]))
+            .unwrap();
+        input
+            .call_method1("add_exp_val_matrix", ("matrix", vec![vec!["sigma_plus"]]))
+            .unwrap();
+        let error = input.call_method1("add_exp_val_vector", ("missing", vec!["missing"]));
+        assert!(error.is_err());
+
+        let circs: Vec<CircuitWrapper> = vec![CircuitWrapper::new()];
+        let br_type = py.get_type::<CheatedBasisRotationWrapper>();
+        let br = br_type
+            .call1((Some(CircuitWrapper::new()), circs, input))
+            .unwrap()
+            .cast_as::<PyCell<CheatedBasisRotationWrapper>>()
+            .unwrap();
+
+        let mut measured_registers: HashMap<String, FloatOutputRegister> = HashMap::new();
+        let _ = measured_registers.insert("ro_x".to_string(), vec![vec![0.4]]);
+        let _ = measured_registers.insert("ro_y".to_string(), vec![vec![0.2]]);
+        let bit_register: HashMap<String, BitOutputRegister> = HashMap::new();
+        let complex_register: HashMap<String, ComplexOutputRegister> = HashMap::new();
+
+        let result = br
+            .call_method1(
+                "evaluate_complex",
+                (
+                    bit_register.clone(),
+                    measured_registers.clone(),
+                    complex_register.clone(),
+                ),
+            )
+            .unwrap();
+        let exp_vals: HashMap<String, Complex64> = result.get_item(0).unwrap().extract().unwrap();
+        assert!((exp_vals["sigma_plus"] - Complex64::new(0.2, 0.1)).norm() < 1e-10);
+        let vectors: HashMap<String, Vec<Complex64>> =
+            result.get_item(1).unwrap().extract().unwrap();
+        assert_eq!(vectors["vector"].len(), 1);
+        let matrices: HashMap<String, Vec<Vec<Complex64>>> =
+            result.get_item(2).unwrap().extract().unwrap();
+        assert_eq!(matrices["matrix"].len(), 1);
+
+        let real = br
+            .call_method1(
+                "evaluate",
+                (bit_register, measured_registers, complex_register),
+            )
+            .unwrap();
+        let real_value = f64::extract(real.get_item("sigma_plus").unwrap()).unwrap();
+        assert!((real_value - 0.2).abs() < 1e-10);
+    })
+}
+
 /// Test evaluate() function for CheatedBasisRotation measurement with symbolic parameters
 #[test]
 fn test_evaluate_symbolic() {
@@ -302,11 +376,11 @@ fn test_pyo3_debug() {
         let br_clone = br_wrapper.clone();
         assert_eq!(format!("{:?}", br_wrapper), format!("{:?}", br_clone));
 
-        let debug_string = "RefCell { value: CheatedBasisRotationWrapper { internal: CheatedBasisRotation { constant_circuit: Some(Circuit { definitions: [], operations: [], _roqoqo_version: RoqoqoVersion }), circuits: [Circuit { definitions: [], operations: [], _roqoqo_version: RoqoqoVersion }], input: CheatedBasisRotationInput { measured_exp_vals: {}, pauli_product_keys: {\"ro\": 0} } } } }";
+        let debug_string = "RefCell { value: CheatedBasisRotationWrapper { internal: CheatedBasisRotation { constant_circuit: Some(Circuit { definitions: [], operations: [], _roqoqo_version: RoqoqoVersion }), circuits: [Circuit { definitions: [], operations: [], _roqoqo_version: RoqoqoVersion }], input: CheatedBasisRotationInput { measured_exp_vals: {}, pauli_product_keys: {\"ro\": 0}, exp_val_groups: {} } } } }";
         assert_eq!(format!("{:?}", br), debug_string);
 
         let debug_input = &(*input);
-        let debug_input_string = "RefCell { value: CheatedBasisRotationInputWrapper { internal: CheatedBasisRotationInput { measured_exp_vals: {}, pauli_product_keys: {\"ro\": 0} } } }";
+        let debug_input_string = "RefCell { value: CheatedBasisRotationInputWrapper { internal: CheatedBasisRotationInput { measured_exp_vals: {}, pauli_product_keys: {\"ro\": 0}, exp_val_groups: {} } } }";
         assert_eq!(format!("{:?}", input), debug_input_string);
         assert_eq!(
             CheatedBasisRotationInputWrapper::default().internal,
@@ -543,3 +617,49 @@ fn test_return_input() {
         assert_eq!(format!("{:?}", input_returned), format!("{:?}", input));
     })
 }
+
+/// Test json_schema, validate_json and from_json_validated functions
+#[test]
+fn test_json_schema_validation() {
+    pyo3::prepare_freethreaded_python();
+    Python::with_gil(|py| {
+        let input_type = py.get_type::<CheatedBasisRotationInputWrapper>();
+        let input = input_type
+            .call0()
+            .unwrap()
+            .cast_as::<PyCell<CheatedBasisRotationInputWrapper>>()
+            .unwrap();
+        let _ = input.call_method1("add_pauli_product", ("ro",)).unwrap();
+        let circs: Vec<CircuitWrapper> = vec![CircuitWrapper::new()];
+        let br = py
+            .get_type::<CheatedBasisRotationWrapper>()
+            .call1((Some(CircuitWrapper::new()), circs, input))
+            .unwrap()
+            .cast_as::<PyCell<CheatedBasisRotationWrapper>>()
+            .unwrap();
+
+        let br_type = py.get_type::<CheatedBasisRotationWrapper>();
+        let schema = String::extract(br_type.call_method0("json_schema").unwrap()).unwrap();
+        assert!(schema.contains("pauli_product_keys"));
+
+        let serialised = br.call_method0("to_json").unwrap();
+        assert!(br_type.call_method1("validate_json", (serialised,)).is_ok());
+        let deserialised = br_type
+            .call_method1("from_json_validated", (serialised,))
+            .unwrap()
+            .cast_as::<PyCell<CheatedBasisRotationWrapper>>()
+            .unwrap();
+        assert_eq!(format!("{:?}", br), format!("{:?}", deserialised));
+
+        let mut invalid: serde_json::Value =
+            serde_json::from_str(&String::extract(serialised).unwrap()).unwrap();
+        invalid["circuits"] = serde_json::json!(1);
+        let error = br_type
+            .call_method1("validate_json", (invalid.to_string(),))
+            .unwrap_err();
+        assert!(format!("{}", error).contains("/circuits"));
+        assert!(br_type
+            .call_method1("from_json_validated", (invalid.to_string(),))
+            .is_err());
+    })
+}
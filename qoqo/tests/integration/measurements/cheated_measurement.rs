@@ -291,10 +291,10 @@ fn test_pyo3_debug() {
         let br_clone = br_wrapper.clone();
         assert_eq!(format!("{:?}", br_wrapper), format!("{:?}", br_clone));
 
-        let debug_string = "RefCell { value: CheatedWrapper { internal: Cheated { constant_circuit: Some(Circuit { definitions: [], operations: [], _roqoqo_version: RoqoqoVersion }), circuits: [Circuit { definitions: [], operations: [], _roqoqo_version: RoqoqoVersion }], input: CheatedInput { measured_operators: {\"test_diagonal\": ([(0, 0, Complex { re: 1.0, im: 0.0 }), (0, 1, Complex { re: 0.0, im: 0.0 }), (1, 0, Complex { re: 0.0, im: 0.0 }), (1, 1, Complex { re: -1.0, im: 0.0 })], \"ro\")}, number_qubits: 3 } } } }";
+        let debug_string = "RefCell { value: CheatedWrapper { internal: Cheated { constant_circuit: Some(Circuit { definitions: [], operations: [], _roqoqo_version: RoqoqoVersion }), circuits: [Circuit { definitions: [], operations: [], _roqoqo_version: RoqoqoVersion }], input: CheatedInput { measured_operators: {\"test_diagonal\": ([(0, 0, Complex { re: 1.0, im: 0.0 }), (0, 1, Complex { re: 0.0, im: 0.0 }), (1, 0, Complex { re: 0.0, im: 0.0 }), (1, 1, Complex { re: -1.0, im: 0.0 })], \"ro\")}, number_qubits: 3, exp_val_groups: {} } } } }";
         assert_eq!(format!("{:?}", br), debug_string);
 
-        let debug_input_string = "RefCell { value: CheatedInputWrapper { internal: CheatedInput { measured_operators: {\"test_diagonal\": ([(0, 0, Complex { re: 1.0, im: 0.0 }), (0, 1, Complex { re: 0.0, im: 0.0 }), (1, 0, Complex { re: 0.0, im: 0.0 }), (1, 1, Complex { re: -1.0, im: 0.0 })], \"ro\")}, number_qubits: 3 } } }";
+        let debug_input_string = "RefCell { value: CheatedInputWrapper { internal: CheatedInput { measured_operators: {\"test_diagonal\": ([(0, 0, Complex { re: 1.0, im: 0.0 }), (0, 1, Complex { re: 0.0, im: 0.0 }), (1, 0, Complex { re: 0.0, im: 0.0 }), (1, 1, Complex { re: -1.0, im: 0.0 })], \"ro\")}, number_qubits: 3, exp_val_groups: {} } } }";
         assert_eq!(format!("{:?}", input), debug_input_string);
 
         let debug_input = &(*input);
@@ -577,3 +577,55 @@ fn test_return_input() {
         assert_eq!(format!("{:?}", input_returned), format!("{:?}", input));
     })
 }
+
+/// Test json_schema, validate_json and from_json_validated functions
+#[test]
+fn test_json_schema_validation() {
+    pyo3::prepare_freethreaded_python();
+    Python::with_gil(|py| {
+        let input_type = py.get_type::<CheatedInputWrapper>();
+        let input = input_type
+            .call1((1,))
+            .unwrap()
+            .cast_as::<PyCell<CheatedInputWrapper>>()
+            .unwrap();
+        let test_matrix = vec![
+            (0, 0, Complex64::new(1.0, 0.0)),
+            (1, 1, Complex64::new(-1.0, 0.0)),
+        ];
+        let _ = input
+            .call_method1("add_operator_exp_val", ("test_diagonal", test_matrix, "ro"))
+            .unwrap();
+        let circs: Vec<CircuitWrapper> = vec![CircuitWrapper::new()];
+        let br = py
+            .get_type::<CheatedWrapper>()
+            .call1((Some(CircuitWrapper::new()), circs, input))
+            .unwrap()
+            .cast_as::<PyCell<CheatedWrapper>>()
+            .unwrap();
+
+        let br_type = py.get_type::<CheatedWrapper>();
+        let schema = String::extract(br_type.call_method0("json_schema").unwrap()).unwrap();
+        assert!(schema.contains("measured_operators"));
+
+        let serialised = br.call_method0("to_json").unwrap();
+        assert!(br_type.call_method1("validate_json", (serialised,)).is_ok());
+        let deserialised = br_type
+            .call_method1("from_json_validated", (serialised,))
+            .unwrap()
+            .cast_as::<PyCell<CheatedWrapper>>()
+            .unwrap();
+        assert_eq!(format!("{:?}", br), format!("{:?}", deserialised));
+
+        let mut invalid: serde_json::Value =
+            serde_json::from_str(&String::extract(serialised).unwrap()).unwrap();
+        invalid["circuits"] = serde_json::json!(1);
+        let error = br_type
+            .call_method1("validate_json", (invalid.to_string(),))
+            .unwrap_err();
+        assert!(format!("{}", error).contains("/circuits"));
+        assert!(br_type
+            .call_method1("from_json_validated", (invalid.to_string(),))
+            .is_err());
+    })
+}
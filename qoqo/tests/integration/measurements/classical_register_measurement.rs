@@ -247,3 +247,42 @@ fn test_measurement_type() {
         assert_eq!(measurement_type.to_string(), "ClassicalRegister");
     })
 }
+
+/// Test json_schema, validate_json and from_json_validated functions
+#[test]
+fn test_json_schema_validation() {
+    pyo3::prepare_freethreaded_python();
+    Python::with_gil(|py| {
+        let circs: Vec<CircuitWrapper> = vec![CircuitWrapper::new()];
+        let br = py
+            .get_type::<ClassicalRegisterWrapper>()
+            .call1((Some(CircuitWrapper::new()), circs))
+            .unwrap()
+            .cast_as::<PyCell<ClassicalRegisterWrapper>>()
+            .unwrap();
+
+        let br_type = py.get_type::<ClassicalRegisterWrapper>();
+        let schema = String::extract(br_type.call_method0("json_schema").unwrap()).unwrap();
+        assert!(schema.contains("constant_circuit"));
+
+        let serialised = br.call_method0("to_json").unwrap();
+        assert!(br_type.call_method1("validate_json", (serialised,)).is_ok());
+        let deserialised = br_type
+            .call_method1("from_json_validated", (serialised,))
+            .unwrap()
+            .cast_as::<PyCell<ClassicalRegisterWrapper>>()
+            .unwrap();
+        assert_eq!(format!("{:?}", br), format!("{:?}", deserialised));
+
+        let mut invalid: serde_json::Value =
+            serde_json::from_str(&String::extract(serialised).unwrap()).unwrap();
+        invalid["circuits"] = serde_json::json!(1);
+        let error = br_type
+            .call_method1("validate_json", (invalid.to_string(),))
+            .unwrap_err();
+        assert!(format!("{}", error).contains("/circuits"));
+        assert!(br_type
+            .call_method1("from_json_validated", (invalid.to_string(),))
+            .is_err());
+    })
+}
@@ -0,0 +1,109 @@
+// Copyright © 2021 HQS Quantum Simulations GmbH. All Rights Reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except
+// in compliance with the License. You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the
+// License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either
+// express or implied. See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Integration test for public API of the classical shadows measurement
+
+use pyo3::prelude::*;
+use pyo3::Python;
+use qoqo::measurements::{ClassicalShadowsInputWrapper, ClassicalShadowsWrapper};
+use qoqo::{CircuitWrapper, QuantumProgramWrapper};
+use std::collections::HashMap;
+
+/// Test creating the randomized circuits of the ClassicalShadows measurement
+#[test]
+fn test_pyo3_circuits() {
+    pyo3::prepare_freethreaded_python();
+    Python::with_gil(|py| {
+        let input_type = py.get_type::<ClassicalShadowsInputWrapper>();
+        let input = input_type
+            .call1((2, 1))
+            .unwrap()
+            .cast_as::<PyCell<ClassicalShadowsInputWrapper>>()
+            .unwrap();
+        let index: usize = input
+            .call_method1("add_pauli_product", ("0Z",))
+            .unwrap()
+            .extract()
+            .unwrap();
+        assert_eq!(index, 0);
+        let mut linear_map: HashMap<usize, f64> = HashMap::new();
+        linear_map.insert(0, 1.0);
+        input
+            .call_method1("add_linear_exp_val", ("single_z", linear_map))
+            .unwrap();
+        assert!(input.call_method1("add_pauli_product", ("2Z",)).is_err());
+
+        let shadows_type = py.get_type::<ClassicalShadowsWrapper>();
+        let shadows = shadows_type
+            .call1((Option::<CircuitWrapper>::None, input, 5, 10, 1))
+            .unwrap()
+            .cast_as::<PyCell<ClassicalShadowsWrapper>>()
+            .unwrap();
+        let circuits: Vec<CircuitWrapper> =
+            shadows.call_method0("circuits").unwrap().extract().unwrap();
+        assert_eq!(circuits.len(), 5);
+        let measurement_type: String = shadows
+            .call_method0("measurement_type")
+            .unwrap()
+            .extract()
+            .unwrap();
+        assert_eq!(measurement_type, "ClassicalShadows");
+
+        let program = QuantumProgramWrapper::new(shadows, vec![]);
+        assert!(program.is_ok());
+    })
+}
+
+/// Test json_schema, validate_json and from_json_validated functions
+#[test]
+fn test_json_schema_validation() {
+    pyo3::prepare_freethreaded_python();
+    Python::with_gil(|py| {
+        let input = py
+            .get_type::<ClassicalShadowsInputWrapper>()
+            .call1((2, 1))
+            .unwrap()
+            .cast_as::<PyCell<ClassicalShadowsInputWrapper>>()
+            .unwrap();
+        input.call_method1("add_pauli_product", ("0Z",)).unwrap();
+        let br = py
+            .get_type::<ClassicalShadowsWrapper>()
+            .call1((Option::<CircuitWrapper>::None, input, 3, 10, 1))
+            .unwrap()
+            .cast_as::<PyCell<ClassicalShadowsWrapper>>()
+            .unwrap();
+
+        let br_type = py.get_type::<ClassicalShadowsWrapper>();
+        let schema = String::extract(br_type.call_method0("json_schema").unwrap()).unwrap();
+        assert!(schema.contains("measurement_bases"));
+
+        let serialised = br.call_method0("to_json").unwrap();
+        assert!(br_type.call_method1("validate_json", (serialised,)).is_ok());
+        let deserialised = br_type
+            .call_method1("from_json_validated", (serialised,))
+            .unwrap()
+            .cast_as::<PyCell<ClassicalShadowsWrapper>>()
+            .unwrap();
+        assert_eq!(format!("{:?}", br), format!("{:?}", deserialised));
+
+        let mut invalid: serde_json::Value =
+            serde_json::from_str(&String::extract(serialised).unwrap()).unwrap();
+        invalid["circuits"] = serde_json::json!(1);
+        let error = br_type
+            .call_method1("validate_json", (invalid.to_string(),))
+            .unwrap_err();
+        assert!(format!("{}", error).contains("/circuits"));
+        assert!(br_type
+            .call_method1("from_json_validated", (invalid.to_string(),))
+            .is_err());
+    })
+}
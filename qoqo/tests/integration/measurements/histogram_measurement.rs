@@ -0,0 +1,144 @@
+// Copyright © 2021 HQS Quantum Simulations GmbH. All Rights Reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except
+// in compliance with the License. You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the
+// License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either
+// express or implied. See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Integration test for public API of the histogram measurement
+
+use pyo3::prelude::*;
+use pyo3::Python;
+use qoqo::measurements::{HistogramInputWrapper, HistogramWrapper};
+use qoqo::{CircuitWrapper, QuantumProgramWrapper};
+use roqoqo::registers::{BitOutputRegister, ComplexOutputRegister, FloatOutputRegister};
+use std::collections::HashMap;
+
+/// Test evaluating the Histogram measurement into python dictionaries
+#[test]
+fn test_pyo3_evaluate() {
+    pyo3::prepare_freethreaded_python();
+    Python::with_gil(|py| {
+        let input_type = py.get_type::<HistogramInputWrapper>();
+        let input = input_type
+            .call0()
+            .unwrap()
+            .cast_as::<PyCell<HistogramInputWrapper>>()
+            .unwrap();
+        input
+            .call_method1("add_marginal", ("first", "ro", vec![0]))
+            .unwrap();
+        assert!(input
+            .call_method1("add_marginal", ("first", "ro", vec![1]))
+            .is_err());
+
+        let histogram_type = py.get_type::<HistogramWrapper>();
+        let histogram = histogram_type
+            .call1((
+                Option::<CircuitWrapper>::None,
+                vec![CircuitWrapper::new()],
+                input,
+            ))
+            .unwrap()
+            .cast_as::<PyCell<HistogramWrapper>>()
+            .unwrap();
+        let measurement_type: String = histogram
+            .call_method0("measurement_type")
+            .unwrap()
+            .extract()
+            .unwrap();
+        assert_eq!(measurement_type, "Histogram");
+
+        let mut bit_registers: HashMap<String, BitOutputRegister> = HashMap::new();
+        bit_registers.insert(
+            "ro".to_string(),
+            vec![vec![true, false], vec![true, true], vec![false, false]],
+        );
+        let float_registers: HashMap<String, FloatOutputRegister> = HashMap::new();
+        let complex_registers: HashMap<String, ComplexOutputRegister> = HashMap::new();
+        let (counts, marginals): (
+            HashMap<String, HashMap<String, usize>>,
+            HashMap<String, HashMap<String, f64>>,
+        ) = histogram
+            .call_method1(
+                "evaluate",
+                (
+                    bit_registers,
+                    float_registers.clone(),
+                    complex_registers.clone(),
+                ),
+            )
+            .unwrap()
+            .extract()
+            .unwrap();
+        assert_eq!(counts["ro"]["10"], 1);
+        assert_eq!(counts["ro"]["11"], 1);
+        assert_eq!(counts["ro"]["00"], 1);
+        assert!((marginals["first"]["1"] - 2.0 / 3.0).abs() < 1e-10);
+        assert!((marginals["first"]["0"] - 1.0 / 3.0).abs() < 1e-10);
+
+        let missing: HashMap<String, BitOutputRegister> = HashMap::new();
+        assert!(histogram
+            .call_method1("evaluate", (missing, float_registers, complex_registers))
+            .is_err());
+
+        let program = QuantumProgramWrapper::new(histogram, vec![]);
+        assert!(program.is_ok());
+    })
+}
+
+/// Test json_schema, validate_json and from_json_validated functions
+#[test]
+fn test_json_schema_validation() {
+    pyo3::prepare_freethreaded_python();
+    Python::with_gil(|py| {
+        let input = py
+            .get_type::<HistogramInputWrapper>()
+            .call0()
+            .unwrap()
+            .cast_as::<PyCell<HistogramInputWrapper>>()
+            .unwrap();
+        input
+            .call_method1("add_marginal", ("first", "ro", vec![0]))
+            .unwrap();
+        let br = py
+            .get_type::<HistogramWrapper>()
+            .call1((
+                Option::<CircuitWrapper>::None,
+                vec![CircuitWrapper::new()],
+                input,
+            ))
+            .unwrap()
+            .cast_as::<PyCell<HistogramWrapper>>()
+            .unwrap();
+
+        let br_type = py.get_type::<HistogramWrapper>();
+        let schema = String::extract(br_type.call_method0("json_schema").unwrap()).unwrap();
+        assert!(schema.contains("marginals"));
+
+        let serialised = br.call_method0("to_json").unwrap();
+        assert!(br_type.call_method1("validate_json", (serialised,)).is_ok());
+        let deserialised = br_type
+            .call_method1("from_json_validated", (serialised,))
+            .unwrap()
+            .cast_as::<PyCell<HistogramWrapper>>()
+            .unwrap();
+        assert_eq!(format!("{:?}", br), format!("{:?}", deserialised));
+
+        let mut invalid: serde_json::Value =
+            serde_json::from_str(&String::extract(serialised).unwrap()).unwrap();
+        invalid["circuits"] = serde_json::json!(1);
+        let error = br_type
+            .call_method1("validate_json", (invalid.to_string(),))
+            .unwrap_err();
+        assert!(format!("{}", error).contains("/circuits"));
+        assert!(br_type
+            .call_method1("from_json_validated", (invalid.to_string(),))
+            .is_err());
+    })
+}
@@ -21,3 +21,9 @@ pub use cheated_measurement::*;
 
 mod classical_register_measurement;
 pub use classical_register_measurement::*;
+
+mod classical_shadows_measurement;
+pub use classical_shadows_measurement::*;
+
+mod histogram_measurement;
+pub use histogram_measurement::*;
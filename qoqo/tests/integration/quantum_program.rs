@@ -27,6 +27,7 @@ use roqoqo::measurements::{
 use roqoqo::operations::Operation;
 use roqoqo::operations::*;
 use roqoqo::{Circuit, QuantumProgram, ROQOQO_VERSION};
+use std::collections::HashMap;
 
 #[pyclass(name = "TestBackend", module = "qoqo")]
 #[derive(Debug, Clone, Copy)]
@@ -90,7 +91,7 @@ fn test_basic_traits() {
 
         assert_eq!(
             format!("{:?}", QuantumProgramWrapper::new(input, vec!["test".into()]).unwrap()),
-            "QuantumProgramWrapper { internal: CheatedBasisRotation { measurement: CheatedBasisRotation { constant_circuit: Some(Circuit { definitions: [], operations: [], _roqoqo_version: RoqoqoVersion }), circuits: [Circuit { definitions: [], operations: [], _roqoqo_version: RoqoqoVersion }, Circuit { definitions: [], operations: [RotateX(RotateX { qubit: 0, theta: Float(0.0) })], _roqoqo_version: RoqoqoVersion }], input: CheatedBasisRotationInput { measured_exp_vals: {}, pauli_product_keys: {\"ro\": 0} } }, input_parameter_names: [\"test\"] } }"
+            "QuantumProgramWrapper { internal: CheatedBasisRotation { measurement: CheatedBasisRotation { constant_circuit: Some(Circuit { definitions: [], operations: [], _roqoqo_version: RoqoqoVersion }), circuits: [Circuit { definitions: [], operations: [], _roqoqo_version: RoqoqoVersion }, Circuit { definitions: [], operations: [RotateX(RotateX { qubit: 0, theta: Float(0.0) })], _roqoqo_version: RoqoqoVersion }], input: CheatedBasisRotationInput { measured_exp_vals: {}, pauli_product_keys: {\"ro\": 0}, exp_val_groups: {} } }, input_parameter_names: [\"test\"] } }"
         );
     })
 }
@@ -538,6 +539,46 @@ fn test_to_from_json() {
     })
 }
 
+/// Test json_schema, validate_json and from_json_validated functions of QuantumProgram
+#[test]
+fn test_json_schema_validation() {
+    pyo3::prepare_freethreaded_python();
+    Python::with_gil(|py| {
+        let input = create_measurement(py);
+        let program_type = py.get_type::<QuantumProgramWrapper>();
+        let program = program_type
+            .call1((input, vec!["test".to_string()]))
+            .unwrap()
+            .cast_as::<PyCell<QuantumProgramWrapper>>()
+            .unwrap();
+        let schema = String::extract(program_type.call_method0("json_schema").unwrap()).unwrap();
+        assert!(schema.contains("CheatedBasisRotation"));
+        assert!(schema.contains("input_parameter_names"));
+
+        let serialised = program.call_method0("to_json").unwrap();
+        assert!(program_type
+            .call_method1("validate_json", (serialised,))
+            .is_ok());
+        let deserialised = program_type
+            .call_method1("from_json_validated", (serialised,))
+            .unwrap();
+        let comparison =
+            bool::extract(deserialised.call_method1("__eq__", (program,)).unwrap()).unwrap();
+        assert!(comparison);
+
+        let mut invalid: serde_json::Value =
+            serde_json::from_str(&String::extract(serialised).unwrap()).unwrap();
+        invalid["CheatedBasisRotation"]["input_parameter_names"] = serde_json::json!(1);
+        let error = program_type
+            .call_method1("validate_json", (invalid.to_string(),))
+            .unwrap_err();
+        assert!(format!("{}", error).contains("/CheatedBasisRotation/input_parameter_names"));
+        assert!(program_type
+            .call_method1("from_json_validated", (invalid.to_string(),))
+            .is_err());
+    })
+}
+
 /// Test the __richcmp__ function
 #[test]
 fn test_richcmp() {
@@ -826,3 +867,280 @@ fn test_input_parameter_names() {
         assert_eq!(params_returned.to_string(), "['test']".to_string());
     })
 }
+
+#[pyclass(name = "AngleBackend", module = "qoqo")]
+#[derive(Debug, Clone, Copy)]
+struct AngleBackend;
+
+#[pymethods]
+impl AngleBackend {
+    /// Returns the sum of the angles of the RotateX operations as expectation value "angle".
+    fn run_measurement(
+        &self,
+        measurement: CheatedBasisRotationWrapper,
+    ) -> PyResult<HashMap<String, f64>> {
+        let mut angle = 0.0;
+        for circuit in measurement.internal.circuits.iter() {
+            for operation in circuit.iter() {
+                if let Operation::RotateX(rotation) = operation {
+                    angle += *rotation.theta().float().unwrap();
+                }
+            }
+        }
+        let mut result: HashMap<String, f64> = HashMap::new();
+        result.insert("angle".to_string(), angle);
+        Ok(result)
+    }
+
+    /// Returns the sum of the angles of the RotateX operations as float register "angle".
+    #[allow(clippy::type_complexity)]
+    fn run_measurement_registers(
+        &self,
+        measurement: CheatedBasisRotationWrapper,
+    ) -> PyResult<(
+        HashMap<String, Vec<Vec<bool>>>,
+        HashMap<String, Vec<Vec<f64>>>,
+        HashMap<String, Vec<Vec<Complex64>>>,
+    )> {
+        let angles = self.run_measurement(measurement)?;
+        let mut float_registers: HashMap<String, Vec<Vec<f64>>> = HashMap::new();
+        float_registers.insert("angle".to_string(), vec![vec![angles["angle"]]]);
+        Ok((HashMap::new(), float_registers, HashMap::new()))
+    }
+
+    /// Returns the name of the backend.
+    fn metadata(&self) -> HashMap<String, String> {
+        let mut metadata: HashMap<String, String> = HashMap::new();
+        metadata.insert("name".to_string(), "angle".to_string());
+        metadata
+    }
+}
+
+fn create_angle_program() -> QuantumProgramWrapper {
+    let mut circuit = Circuit::new();
+    circuit += RotateX::new(0, "theta".into());
+    circuit += RotateX::new(0, "phi".into());
+    QuantumProgramWrapper {
+        internal: QuantumProgram::CheatedBasisRotation {
+            measurement: CheatedBasisRotation {
+                constant_circuit: None,
+                circuits: vec![circuit],
+                input: CheatedBasisRotationInput::new(),
+            },
+            input_parameter_names: vec!["theta".to_string(), "phi".to_string()],
+        },
+    }
+}
+
+/// Test run_batch and run_grid of QuantumProgram
+#[test]
+fn test_run_batch_grid() {
+    pyo3::prepare_freethreaded_python();
+    Python::with_gil(|py| {
+        let program = Py::new(py, create_angle_program()).unwrap();
+        let program = program.as_ref(py);
+
+        let result = program
+            .call_method1(
+                "run_batch",
+                (AngleBackend, vec![vec![0.1, 0.2], vec![1.0, 2.0]]),
+            )
+            .unwrap();
+        let parameter_names: Vec<String> = result
+            .get_item("parameter_names")
+            .unwrap()
+            .extract()
+            .unwrap();
+        assert_eq!(
+            parameter_names,
+            vec!["theta".to_string(), "phi".to_string()]
+        );
+        let parameters: Vec<Vec<f64>> = result
+            .get_item("parameters")
+            .unwrap()
+            .call_method0("tolist")
+            .unwrap()
+            .extract()
+            .unwrap();
+        assert_eq!(parameters, vec![vec![0.1, 0.2], vec![1.0, 2.0]]);
+        let names: Vec<String> = result
+            .get_item("expectation_value_names")
+            .unwrap()
+            .extract()
+            .unwrap();
+        assert_eq!(names, vec!["angle".to_string()]);
+        let values: Vec<Vec<f64>> = result
+            .get_item("expectation_values")
+            .unwrap()
+            .call_method0("tolist")
+            .unwrap()
+            .extract()
+            .unwrap();
+        assert!((values[0][0] - 0.3).abs() < 1e-12);
+        assert!((values[1][0] - 3.0).abs() < 1e-12);
+
+        let error = program.call_method1("run_batch", (AngleBackend, vec![vec![0.1]]));
+        assert!(error.is_err());
+
+        let result = program
+            .call_method1("run_grid", (AngleBackend, vec![vec![0.0, 1.0], vec![0.5]]))
+            .unwrap();
+        let parameters: Vec<Vec<f64>> = result
+            .get_item("parameters")
+            .unwrap()
+            .call_method0("tolist")
+            .unwrap()
+            .extract()
+            .unwrap();
+        assert_eq!(parameters, vec![vec![0.0, 0.5], vec![1.0, 0.5]]);
+        let values: Vec<Vec<f64>> = result
+            .get_item("expectation_values")
+            .unwrap()
+            .call_method0("tolist")
+            .unwrap()
+            .extract()
+            .unwrap();
+        assert_eq!(values, vec![vec![0.5], vec![1.5]]);
+    })
+}
+
+/// Test run_with_metadata of QuantumProgram and the returned RunResult
+#[test]
+fn test_run_with_metadata() {
+    pyo3::prepare_freethreaded_python();
+    Python::with_gil(|py| {
+        let mut input = CheatedBasisRotationInput::new();
+        let index = input.add_pauli_product("angle".to_string());
+        let mut linear: HashMap<usize, f64> = HashMap::new();
+        linear.insert(index, 1.0);
+        input
+            .add_linear_exp_val("angle".to_string(), linear)
+            .unwrap();
+        let mut program = create_angle_program();
+        if let QuantumProgram::CheatedBasisRotation { measurement, .. } = &mut program.internal {
+            measurement.input = input;
+        }
+        let program = Py::new(py, program).unwrap();
+        let program = program.as_ref(py);
+
+        let result = program
+            .call_method1("run_with_metadata", (AngleBackend, vec![0.1, 0.2]))
+            .unwrap();
+        let values: HashMap<String, f64> = result
+            .call_method0("expectation_values")
+            .unwrap()
+            .extract()
+            .unwrap();
+        assert!((values["angle"] - 0.3).abs() < 1e-12);
+        assert!(result.call_method0("registers").unwrap().is_none());
+        let parameters: Vec<f64> = result
+            .call_method0("parameters")
+            .unwrap()
+            .extract()
+            .unwrap();
+        assert_eq!(parameters, vec![0.1, 0.2]);
+        let number_circuits: usize = result
+            .call_method0("number_circuits")
+            .unwrap()
+            .extract()
+            .unwrap();
+        assert_eq!(number_circuits, 1);
+        let total_shots: usize = result
+            .call_method0("total_shots")
+            .unwrap()
+            .extract()
+            .unwrap();
+        assert_eq!(total_shots, 0);
+        let metadata: HashMap<String, String> = result
+            .call_method0("backend_metadata")
+            .unwrap()
+            .extract()
+            .unwrap();
+        assert_eq!(metadata["name"], "angle");
+
+        let result = program
+            .call_method1("run_with_metadata", (AngleBackend, vec![1.0, 2.0], true))
+            .unwrap();
+        let registers: (
+            HashMap<String, Vec<Vec<bool>>>,
+            HashMap<String, Vec<Vec<f64>>>,
+            HashMap<String, Vec<Vec<Complex64>>>,
+        ) = result.call_method0("registers").unwrap().extract().unwrap();
+        assert_eq!(registers.1["angle"], vec![vec![3.0]]);
+
+        let json: String = result.call_method0("to_json").unwrap().extract().unwrap();
+        let deserialized = result.call_method1("from_json", (json,)).unwrap();
+        assert!(deserialized
+            .call_method1("__eq__", (result,))
+            .unwrap()
+            .extract::<bool>()
+            .unwrap());
+        let serialized = result.call_method0("to_bincode").unwrap();
+        let deserialized = result.call_method1("from_bincode", (serialized,)).unwrap();
+        assert!(deserialized
+            .call_method1("__eq__", (result,))
+            .unwrap()
+            .extract::<bool>()
+            .unwrap());
+
+        let error = program.call_method1("run_with_metadata", (AngleBackend, vec![0.1]));
+        assert!(error.is_err());
+    })
+}
+
+/// Test run_named, bind_parameters, free_symbols and check_symbols of QuantumProgram
+#[test]
+fn test_named_and_bound_parameters() {
+    pyo3::prepare_freethreaded_python();
+    Python::with_gil(|py| {
+        let program = Py::new(py, create_angle_program()).unwrap();
+        let program = program.as_ref(py);
+
+        let mut parameters: HashMap<String, f64> = HashMap::new();
+        parameters.insert("phi".to_string(), 0.2);
+        parameters.insert("theta".to_string(), 0.1);
+        let values: HashMap<String, f64> = program
+            .call_method1("run_named", (AngleBackend, parameters.clone()))
+            .unwrap()
+            .extract()
+            .unwrap();
+        assert!((values["angle"] - 0.3).abs() < 1e-12);
+        parameters.insert("unknown".to_string(), 0.3);
+        let error = program.call_method1("run_named", (AngleBackend, parameters));
+        assert!(error.is_err());
+
+        let mut bound: HashMap<String, f64> = HashMap::new();
+        bound.insert("theta".to_string(), 0.1);
+        let partial = program
+            .call_method1("bind_parameters", (bound.clone(),))
+            .unwrap();
+        let names: Vec<String> = partial
+            .call_method0("input_parameter_names")
+            .unwrap()
+            .extract()
+            .unwrap();
+        assert_eq!(names, vec!["phi".to_string()]);
+        let values: HashMap<String, f64> = partial
+            .call_method1("run", (AngleBackend, vec![0.2]))
+            .unwrap()
+            .extract()
+            .unwrap();
+        assert!((values["angle"] - 0.3).abs() < 1e-12);
+        let error = partial.call_method1("bind_parameters", (bound,));
+        assert!(error.is_err());
+
+        let symbols: Vec<String> = partial
+            .call_method0("free_symbols")
+            .unwrap()
+            .extract()
+            .unwrap();
+        assert_eq!(symbols, vec!["phi".to_string()]);
+        let (unused, undeclared): (Vec<String>, Vec<String>) = partial
+            .call_method0("check_symbols")
+            .unwrap()
+            .extract()
+            .unwrap();
+        assert!(unused.is_empty());
+        assert!(undeclared.is_empty());
+    })
+}
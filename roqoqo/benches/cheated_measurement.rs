@@ -0,0 +1,146 @@
+// Copyright © 2021 HQS Quantum Simulations GmbH. All Rights Reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except
+// in compliance with the License. You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the
+// License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either
+// express or implied. See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Benchmarks of the evaluation of Cheated measurements
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use num_complex::Complex64;
+use roqoqo::measurements::{Cheated, CheatedInput, OperatorSparseVec, SparseOperatorCsr};
+use roqoqo::prelude::*;
+use roqoqo::registers::ComplexOutputRegister;
+use roqoqo::Circuit;
+use std::collections::HashMap;
+
+/// Returns the sparse matrix of a Pauli Z on `qubit` times a Pauli X on `qubit + 1`.
+fn pauli_operator(qubit: usize, number_qubits: usize) -> OperatorSparseVec {
+    let dimension = 2_usize.pow(number_qubits as u32);
+    (0..dimension)
+        .map(|row| {
+            let sign = if (row >> qubit) & 1 == 1 { -1.0 } else { 1.0 };
+            let column = row ^ (1 << ((qubit + 1) % number_qubits));
+            (row, column, Complex64::new(sign, 0.0))
+        })
+        .collect()
+}
+
+/// Returns a normalized state vector with all amplitudes non-zero.
+fn state_vector(number_qubits: usize) -> Vec<Complex64> {
+    let dimension = 2_usize.pow(number_qubits as u32);
+    let norm = 1.0 / (dimension as f64).sqrt();
+    (0..dimension)
+        .map(|index| Complex64::from_polar(norm, index as f64))
+        .collect()
+}
+
+/// Returns the pure state density matrix of [state_vector] flattened in row-major order.
+fn density_matrix(number_qubits: usize) -> Vec<Complex64> {
+    let vector = state_vector(number_qubits);
+    vector
+        .iter()
+        .flat_map(|row| vector.iter().map(move |column| row * column.conj()))
+        .collect()
+}
+
+fn cheated_measurement(number_qubits: usize, number_operators: usize) -> Cheated {
+    let mut input = CheatedInput::new(number_qubits);
+    for index in 0..number_operators {
+        input
+            .add_operator_exp_val(
+                format!("operator_{}", index),
+                pauli_operator(index % number_qubits, number_qubits),
+                "ro".to_string(),
+            )
+            .unwrap();
+    }
+    Cheated {
+        constant_circuit: None,
+        circuits: vec![Circuit::new()],
+        input,
+    }
+}
+
+fn bench_sparse_operator(c: &mut Criterion) {
+    let mut group = c.benchmark_group("sparse_operator");
+    for number_qubits in [8, 12, 16] {
+        let operator =
+            SparseOperatorCsr::from_triplets(&pauli_operator(0, number_qubits), number_qubits)
+                .unwrap();
+        let vector = state_vector(number_qubits);
+        group.bench_with_input(
+            BenchmarkId::new("vector_expectation_value", number_qubits),
+            &vector,
+            |b, vector| b.iter(|| operator.vector_expectation_value(black_box(vector))),
+        );
+    }
+    for number_qubits in [4, 6, 8] {
+        let operator =
+            SparseOperatorCsr::from_triplets(&pauli_operator(0, number_qubits), number_qubits)
+                .unwrap();
+        let matrix = density_matrix(number_qubits);
+        group.bench_with_input(
+            BenchmarkId::new("density_matrix_expectation_value", number_qubits),
+            &matrix,
+            |b, matrix| b.iter(|| operator.density_matrix_expectation_value(black_box(matrix))),
+        );
+    }
+    group.finish();
+}
+
+fn bench_cheated_evaluate(c: &mut Criterion) {
+    let mut group = c.benchmark_group("cheated_evaluate");
+    group.sample_size(20);
+    for (number_qubits, number_operators, number_states) in [(10, 50, 1), (10, 50, 20), (14, 20, 1)]
+    {
+        let measurement = cheated_measurement(number_qubits, number_operators);
+        let mut complex_registers: HashMap<String, ComplexOutputRegister> = HashMap::new();
+        complex_registers.insert(
+            "ro".to_string(),
+            vec![state_vector(number_qubits); number_states],
+        );
+        group.bench_with_input(
+            BenchmarkId::new(
+                "state_vector",
+                format!(
+                    "{}_qubits_{}_operators_{}_states",
+                    number_qubits, number_operators, number_states
+                ),
+            ),
+            &complex_registers,
+            |b, registers| {
+                b.iter(|| {
+                    measurement
+                        .evaluate(HashMap::new(), HashMap::new(), registers.clone())
+                        .unwrap()
+                })
+            },
+        );
+    }
+    let number_qubits = 6;
+    let measurement = cheated_measurement(number_qubits, 20);
+    let mut complex_registers: HashMap<String, ComplexOutputRegister> = HashMap::new();
+    complex_registers.insert("ro".to_string(), vec![density_matrix(number_qubits)]);
+    group.bench_with_input(
+        BenchmarkId::new("density_matrix", "6_qubits_20_operators_1_states"),
+        &complex_registers,
+        |b, registers| {
+            b.iter(|| {
+                measurement
+                    .evaluate(HashMap::new(), HashMap::new(), registers.clone())
+                    .unwrap()
+            })
+        },
+    );
+    group.finish();
+}
+
+criterion_group!(benches, bench_sparse_operator, bench_cheated_evaluate);
+criterion_main!(benches);
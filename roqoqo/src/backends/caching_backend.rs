@@ -0,0 +1,344 @@
+// Copyright © 2021 HQS Quantum Simulations GmbH. All Rights Reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except
+// in compliance with the License. You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the
+// License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either
+// express or implied. See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::{hash_operations, BackendCapabilities, EvaluatingBackend, RegisterResult};
+use crate::operations::Operation;
+use crate::registers::{BitOutputRegister, ComplexOutputRegister, FloatOutputRegister};
+use crate::{RoqoqoBackendError, RoqoqoError, ROQOQO_VERSION};
+use std::collections::{BTreeMap, HashMap};
+use std::io::{Read, Write};
+use std::sync::{Mutex, MutexGuard};
+
+/// Output registers of a single cached circuit run.
+type Registers = (
+    HashMap<String, BitOutputRegister>,
+    HashMap<String, FloatOutputRegister>,
+    HashMap<String, ComplexOutputRegister>,
+);
+
+/// Statistics of the cache of a [CachingBackend].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct CacheStatistics {
+    /// Number of circuit runs answered from the cache.
+    pub hits: usize,
+    /// Number of circuit runs executed by the wrapped backend.
+    pub misses: usize,
+    /// Number of results removed from the cache to stay within its capacity.
+    pub evictions: usize,
+    /// Number of results currently stored in the cache.
+    pub entries: usize,
+}
+
+/// Cached result of a single circuit run.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+struct CachedRun {
+    /// The operations of the run circuit.
+    operations: Vec<Operation>,
+    /// The seed of the run, if the circuit was run with a seed.
+    seed: Option<u64>,
+    /// The output registers of the run.
+    registers: Registers,
+}
+
+impl CachedRun {
+    /// Returns true if the cached run belongs to the operations and seed.
+    fn matches(&self, operations: &[&Operation], seed: Option<u64>) -> bool {
+        self.seed == seed
+            && self.operations.len() == operations.len()
+            && self
+                .operations
+                .iter()
+                .zip(operations.iter())
+                .all(|(cached, operation)| cached == *operation)
+    }
+}
+
+/// Serializable content of the cache of a [CachingBackend].
+#[derive(serde::Serialize, serde::Deserialize)]
+struct CacheFile {
+    /// The roqoqo version that wrote the cache.
+    roqoqo_version: String,
+    /// The cached runs ordered from least to most recently used.
+    entries: Vec<CachedRun>,
+}
+
+/// Returns the cache key of the operations of a circuit run with an optional seed.
+fn cache_key<'a>(
+    operations: impl Iterator<Item = &'a Operation>,
+    seed: Option<u64>,
+) -> Result<u128, RoqoqoError> {
+    let mut hasher = hash_operations(operations)?;
+    if let Some(seed) = seed {
+        hasher
+            .write_all(&seed.to_le_bytes())
+            .expect("Unexpectedly failed to hash seed");
+    }
+    Ok(hasher.hash)
+}
+
+/// Least-recently-used cache of circuit results.
+///
+/// The runs are stored under the hash of their operations and seed ([cache_key]).
+/// As different circuits can have the same hash, the operations and seed of a stored run are compared
+/// with the requested ones before the stored registers are returned.
+#[derive(Debug, Default)]
+struct LruCache {
+    /// The cached runs with the time of their last use.
+    entries: HashMap<u128, (u64, CachedRun)>,
+    /// The keys of the cached results ordered by the time of their last use.
+    usage: BTreeMap<u64, u128>,
+    /// Counter providing the time of use.
+    clock: u64,
+    statistics: CacheStatistics,
+}
+
+impl LruCache {
+    fn get(
+        &mut self,
+        key: u128,
+        operations: &[&Operation],
+        seed: Option<u64>,
+    ) -> Option<Registers> {
+        let (last_used, run) = self.entries.get_mut(&key)?;
+        if !run.matches(operations, seed) {
+            return None;
+        }
+        self.clock += 1;
+        self.usage.remove(last_used);
+        self.usage.insert(self.clock, key);
+        *last_used = self.clock;
+        Some(run.registers.clone())
+    }
+
+    fn insert(&mut self, key: u128, run: CachedRun, capacity: usize) {
+        if capacity == 0 {
+            return;
+        }
+        self.clock += 1;
+        if let Some((last_used, _)) = self.entries.insert(key, (self.clock, run)) {
+            self.usage.remove(&last_used);
+        }
+        self.usage.insert(self.clock, key);
+        while self.entries.len() > capacity {
+            if let Some((&oldest, &oldest_key)) = self.usage.iter().next() {
+                self.usage.remove(&oldest);
+                self.entries.remove(&oldest_key);
+                self.statistics.evictions += 1;
+            }
+        }
+        self.statistics.entries = self.entries.len();
+    }
+}
+
+/// Backend wrapper caching the results of circuit runs of an [EvaluatingBackend].
+///
+/// The operations of every run circuit (with all symbolic parameters substituted) are identified by
+/// [stable_hash_operations](super::stable_hash_operations) and stored together with the output registers.
+/// When an identical circuit is run again, the output registers of the first run
+/// are returned without running the wrapped backend. Runs with a seed
+/// ([EvaluatingBackend::run_circuit_iterator_with_seed]) are cached separately for each seed.
+/// Failed runs are not cached.
+///
+/// The cache holds at most `capacity` results and removes the least recently used result when it is full.
+/// The cache can be written to and read from files with [CachingBackend::write_cache] and
+/// [CachingBackend::read_cache].
+///
+/// Note that for backends sampling measurement results the cached samples are returned for repeated
+/// circuits instead of new samples.
+#[derive(Debug)]
+pub struct CachingBackend<B>
+where
+    B: EvaluatingBackend,
+{
+    backend: B,
+    capacity: usize,
+    cache: Mutex<LruCache>,
+}
+
+impl<B> CachingBackend<B>
+where
+    B: EvaluatingBackend,
+{
+    /// Creates a new CachingBackend wrapping a backend.
+    ///
+    /// # Arguments
+    ///
+    /// * `backend` - The backend running the circuits that are not cached.
+    /// * `capacity` - The maximal number of cached results.
+    ///
+    /// # Returns
+    ///
+    /// * `Self` - The new CachingBackend with an empty cache.
+    pub fn new(backend: B, capacity: usize) -> Self {
+        Self {
+            backend,
+            capacity,
+            cache: Mutex::new(LruCache::default()),
+        }
+    }
+
+    /// Returns a reference to the wrapped backend.
+    pub fn backend(&self) -> &B {
+        &self.backend
+    }
+
+    /// Returns the maximal number of cached results.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Returns the statistics of the cache.
+    pub fn statistics(&self) -> CacheStatistics {
+        self.lock_cache().statistics
+    }
+
+    /// Removes all results from the cache and resets the statistics.
+    pub fn clear(&self) {
+        *self.lock_cache() = LruCache::default();
+    }
+
+    /// Writes the cached results to a writer, e.g. a [std::fs::File].
+    ///
+    /// # Arguments
+    ///
+    /// * `writer` - The writer the cache is written to.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - The cache has been written.
+    /// * `Err([RoqoqoBackendError::RoqoqoError])` - The cache could not be serialized or written.
+    pub fn write_cache<W: Write>(&self, writer: W) -> Result<(), RoqoqoBackendError> {
+        let cache = self.lock_cache();
+        let cache_file = CacheFile {
+            roqoqo_version: ROQOQO_VERSION.to_string(),
+            entries: cache
+                .usage
+                .values()
+                .map(|key| cache.entries[key].1.clone())
+                .collect(),
+        };
+        bincode::serialize_into(writer, &cache_file).map_err(|err| {
+            RoqoqoError::SerializationError {
+                msg: format!("{:?}", err),
+            }
+        })?;
+        Ok(())
+    }
+
+    /// Reads cached results from a reader, e.g. a [std::fs::File], and adds them to the cache.
+    ///
+    /// Only caches written by the same roqoqo version are read, as the hashes of the circuits
+    /// depend on the encoding of the operations.
+    /// When the read results exceed the capacity, the least recently used results are removed.
+    ///
+    /// # Arguments
+    ///
+    /// * `reader` - The reader the cache is read from.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - The cache has been read.
+    /// * `Err([RoqoqoBackendError::RoqoqoError])` - The cache could not be read or has been written by a different roqoqo version.
+    pub fn read_cache<R: Read>(&self, reader: R) -> Result<(), RoqoqoBackendError> {
+        let cache_file: CacheFile =
+            bincode::deserialize_from(reader).map_err(|err| RoqoqoError::SerializationError {
+                msg: format!("{:?}", err),
+            })?;
+        if cache_file.roqoqo_version != ROQOQO_VERSION {
+            return Err(RoqoqoError::SerializationError {
+                msg: format!(
+                    "Cache has been written by roqoqo {} and cannot be read by roqoqo {}",
+                    cache_file.roqoqo_version, ROQOQO_VERSION
+                ),
+            }
+            .into());
+        }
+        let mut cache = self.lock_cache();
+        for run in cache_file.entries {
+            let key = cache_key(run.operations.iter(), run.seed)?;
+            cache.insert(key, run, self.capacity);
+        }
+        Ok(())
+    }
+
+    /// Returns the cached result of the operations and seed or runs the wrapped backend and caches its result.
+    fn cached_run(
+        &self,
+        operations: &[&Operation],
+        seed: Option<u64>,
+        run: impl FnOnce() -> RegisterResult,
+    ) -> RegisterResult {
+        let key = cache_key(operations.iter().copied(), seed)?;
+        {
+            let mut cache = self.lock_cache();
+            if let Some(registers) = cache.get(key, operations, seed) {
+                cache.statistics.hits += 1;
+                return Ok(registers);
+            }
+            cache.statistics.misses += 1;
+        }
+        let registers = run()?;
+        let cached_run = CachedRun {
+            operations: operations.iter().copied().cloned().collect(),
+            seed,
+            registers: registers.clone(),
+        };
+        self.lock_cache().insert(key, cached_run, self.capacity);
+        Ok(registers)
+    }
+
+    fn lock_cache(&self) -> MutexGuard<LruCache> {
+        self.cache
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+impl<B> EvaluatingBackend for CachingBackend<B>
+where
+    B: EvaluatingBackend,
+{
+    fn run_circuit_iterator<'a>(
+        &self,
+        circuit: impl Iterator<Item = &'a Operation>,
+    ) -> RegisterResult {
+        let operations: Vec<&Operation> = circuit.collect();
+        self.cached_run(&operations, None, || {
+            self.backend
+                .run_circuit_iterator(operations.iter().copied())
+        })
+    }
+
+    fn run_circuit_iterator_with_seed<'a>(
+        &self,
+        circuit: impl Iterator<Item = &'a Operation>,
+        seed: u64,
+    ) -> RegisterResult {
+        let operations: Vec<&Operation> = circuit.collect();
+        self.cached_run(&operations, Some(seed), || {
+            self.backend
+                .run_circuit_iterator_with_seed(operations.iter().copied(), seed)
+        })
+    }
+
+    fn supports_seed(&self) -> bool {
+        self.backend.supports_seed()
+    }
+
+    fn capabilities(&self) -> Option<BackendCapabilities> {
+        self.backend.capabilities()
+    }
+
+    fn metadata(&self) -> HashMap<String, String> {
+        self.backend.metadata()
+    }
+}
@@ -0,0 +1,342 @@
+// Copyright © 2021 HQS Quantum Simulations GmbH. All Rights Reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except
+// in compliance with the License. You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the
+// License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either
+// express or implied. See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::operations::{InvolveQubits, InvolvedQubits, Operate, Operation};
+use crate::Circuit;
+use std::collections::{HashMap, HashSet};
+use std::fmt::{Display, Formatter};
+
+/// hqslang names of the operations reading out the cheated state of a simulator.
+const CHEATED_READOUT_OPERATIONS: &[&str] = &[
+    "PragmaGetStateVector",
+    "PragmaGetDensityMatrix",
+    "PragmaGetOccupationProbability",
+    "PragmaGetPauliProduct",
+];
+
+/// hqslang names of the operations measuring qubits projectively.
+const PROJECTIVE_MEASUREMENT_OPERATIONS: &[&str] = &["MeasureQubit", "PragmaRepeatedMeasurement"];
+
+/// Description of the circuits a backend can run.
+///
+/// Returned by [crate::backends::EvaluatingBackend::capabilities] and used by
+/// [BackendCapabilities::check_circuit] and [crate::QuantumProgram::check_capabilities]
+/// to find all operations a backend cannot run before any circuit is executed.
+///
+/// An operation is supported when its hqslang name is in `supported_operations`
+/// or one of its tags is in `supported_tags`.
+/// [crate::operations::InputSymbolic] definitions are always supported, as they are resolved
+/// by roqoqo when the symbolic parameters are substituted.
+///
+/// # Example
+///
+/// ```
+/// use roqoqo::backends::BackendCapabilities;
+/// use roqoqo::operations::{MeasureQubit, PauliX, RotateZ};
+/// use roqoqo::Circuit;
+///
+/// let mut capabilities = BackendCapabilities::new();
+/// capabilities.supported_tags.insert("SingleQubitGateOperation".to_string());
+/// capabilities.supported_operations.insert("MeasureQubit".to_string());
+/// capabilities.max_qubits = Some(2);
+///
+/// let mut circuit = Circuit::new();
+/// circuit += PauliX::new(0);
+/// circuit += RotateZ::new(3, 0.1.into());
+/// circuit += MeasureQubit::new(0, "ro".to_string(), 0);
+/// let incompatibilities = capabilities.check_circuit(&circuit);
+/// assert_eq!(incompatibilities.len(), 1);
+/// assert_eq!(incompatibilities[0].index, 1);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "json_schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct BackendCapabilities {
+    /// hqslang names of the supported operations.
+    pub supported_operations: HashSet<String>,
+    /// Tags of the supported operations (e.g. "SingleQubitGateOperation").
+    pub supported_tags: HashSet<String>,
+    /// The maximal number of qubits of a circuit (None if not limited).
+    pub max_qubits: Option<usize>,
+    /// Whether the state of the backend can be read out with cheated operations like [crate::operations::PragmaGetStateVector].
+    pub cheated_readout: bool,
+    /// Whether measured qubits can be used by operations after the measurement.
+    pub mid_circuit_measurement: bool,
+    /// Whether operations can be conditioned on measured bits with [crate::operations::PragmaConditional].
+    pub conditional_operations: bool,
+    /// Whether noise can be applied with noise pragmas like [crate::operations::PragmaDamping].
+    pub noise_pragmas: bool,
+}
+
+/// Reason why a backend cannot run an operation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "json_schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub enum IncompatibilityReason {
+    /// The operation is not supported by the backend.
+    UnsupportedOperation,
+    /// The operation acts on a qubit beyond the maximal number of qubits of the backend.
+    QubitOutOfRange {
+        /// The largest qubit the operation acts on.
+        qubit: usize,
+        /// The maximal number of qubits of the backend.
+        max_qubits: usize,
+    },
+    /// The operation is a cheated readout and the backend does not support cheated readout.
+    CheatedReadout,
+    /// The operation measures qubits that are used afterwards and the backend does not support mid-circuit measurements.
+    MidCircuitMeasurement,
+    /// The operation is conditioned on a measured bit and the backend does not support conditional operations.
+    ConditionalOperation,
+    /// The operation is a noise pragma and the backend does not support noise pragmas.
+    NoisePragma,
+}
+
+/// Operation of a circuit that cannot be run by a backend.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "json_schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct Incompatibility {
+    /// The index of the circuit of a measurement the operation is part of
+    /// (None for the constant circuit of a measurement or a single checked circuit).
+    pub circuit: Option<usize>,
+    /// The index of the operation in its circuit.
+    ///
+    /// Operations in the circuit of a [crate::operations::PragmaConditional] have the index of the PragmaConditional.
+    pub index: usize,
+    /// The hqslang name of the operation.
+    pub hqslang: String,
+    /// The reason the backend cannot run the operation.
+    pub reason: IncompatibilityReason,
+}
+
+impl Display for Incompatibility {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self.circuit {
+            Some(circuit) => write!(
+                f,
+                "Operation {} at index {} of circuit {}: ",
+                self.hqslang, self.index, circuit
+            )?,
+            None => write!(f, "Operation {} at index {}: ", self.hqslang, self.index)?,
+        }
+        match self.reason {
+            IncompatibilityReason::UnsupportedOperation => {
+                write!(f, "operation is not supported by the backend")
+            }
+            IncompatibilityReason::QubitOutOfRange { qubit, max_qubits } => write!(
+                f,
+                "qubit {} exceeds the maximal number of qubits {} of the backend",
+                qubit, max_qubits
+            ),
+            IncompatibilityReason::CheatedReadout => {
+                write!(f, "backend does not support cheated readout")
+            }
+            IncompatibilityReason::MidCircuitMeasurement => {
+                write!(f, "backend does not support mid-circuit measurements")
+            }
+            IncompatibilityReason::ConditionalOperation => {
+                write!(f, "backend does not support conditional operations")
+            }
+            IncompatibilityReason::NoisePragma => {
+                write!(f, "backend does not support noise pragmas")
+            }
+        }
+    }
+}
+
+impl BackendCapabilities {
+    /// Creates new BackendCapabilities without any supported operations or features.
+    ///
+    /// # Returns
+    ///
+    /// * `Self` - The new BackendCapabilities.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns true when the hqslang name or one of the tags of the operation is supported.
+    ///
+    /// # Arguments
+    ///
+    /// * `operation` - The operation that is checked.
+    pub fn supports_operation(&self, operation: &Operation) -> bool {
+        self.supported_operations.contains(operation.hqslang())
+            || operation
+                .tags()
+                .iter()
+                .any(|tag| self.supported_tags.contains(*tag))
+    }
+
+    /// Returns all operations of a circuit the backend cannot run.
+    ///
+    /// # Arguments
+    ///
+    /// * `circuit` - The circuit that is checked.
+    ///
+    /// # Returns
+    ///
+    /// * `Vec<Incompatibility>` - The incompatible operations ordered by their index (empty if the backend can run the circuit).
+    pub fn check_circuit(&self, circuit: &Circuit) -> Vec<Incompatibility> {
+        self.check_circuits(&[(None, circuit)])
+    }
+
+    /// Returns all operations the backend cannot run when the circuits are run one after another.
+    ///
+    /// # Arguments
+    ///
+    /// * `circuits` - The circuits that are run together with the index reported in [Incompatibility::circuit].
+    pub(crate) fn check_circuits(
+        &self,
+        circuits: &[(Option<usize>, &Circuit)],
+    ) -> Vec<Incompatibility> {
+        let mut checker = CircuitChecker {
+            capabilities: self,
+            incompatibilities: Vec::new(),
+            measured_qubits: HashMap::new(),
+            measured_all: Vec::new(),
+        };
+        for (circuit_index, circuit) in circuits.iter() {
+            for (index, operation) in circuit.iter().enumerate() {
+                checker.check_operation(*circuit_index, index, operation);
+            }
+        }
+        let mut incompatibilities = checker.incompatibilities;
+        incompatibilities
+            .sort_by_key(|incompatibility| (incompatibility.circuit, incompatibility.index));
+        incompatibilities
+    }
+}
+
+/// Location of an operation given by the index of its circuit and its index in the circuit.
+type Location = (Option<usize>, usize, &'static str);
+
+/// Collects the incompatibilities of a sequence of operations.
+struct CircuitChecker<'a> {
+    capabilities: &'a BackendCapabilities,
+    incompatibilities: Vec<Incompatibility>,
+    /// Measurements of single qubits that have not been followed by an operation on the qubit.
+    measured_qubits: HashMap<usize, Vec<Location>>,
+    /// Measurements of all qubits that have not been followed by an operation on a qubit.
+    measured_all: Vec<Location>,
+}
+
+impl CircuitChecker<'_> {
+    fn check_operation(&mut self, circuit: Option<usize>, index: usize, operation: &Operation) {
+        let capabilities = self.capabilities;
+        let hqslang = operation.hqslang();
+        let tags = operation.tags();
+        let location: Location = (circuit, index, hqslang);
+        // Symbolic inputs are resolved by roqoqo when the parameters are substituted,
+        // e.g. the definitions added by [crate::QuantumProgram::bind_parameters]
+        if hqslang == "InputSymbolic" {
+            return;
+        }
+
+        let feature_reason = if CHEATED_READOUT_OPERATIONS.contains(&hqslang) {
+            Some((
+                capabilities.cheated_readout,
+                IncompatibilityReason::CheatedReadout,
+            ))
+        } else if hqslang == "PragmaConditional" {
+            Some((
+                capabilities.conditional_operations,
+                IncompatibilityReason::ConditionalOperation,
+            ))
+        } else if tags.contains(&"PragmaNoiseOperation") {
+            Some((
+                capabilities.noise_pragmas,
+                IncompatibilityReason::NoisePragma,
+            ))
+        } else {
+            None
+        };
+        match feature_reason {
+            Some((false, reason)) => self.push(location, reason),
+            Some((true, _)) => (),
+            None if !capabilities.supports_operation(operation) => {
+                self.push(location, IncompatibilityReason::UnsupportedOperation)
+            }
+            None => (),
+        }
+
+        let involved_qubits = operation.involved_qubits();
+        if let (Some(max_qubits), InvolvedQubits::Set(qubits)) =
+            (capabilities.max_qubits, &involved_qubits)
+        {
+            if let Some(qubit) = qubits.iter().copied().filter(|q| *q >= max_qubits).max() {
+                self.push(
+                    location,
+                    IncompatibilityReason::QubitOutOfRange { qubit, max_qubits },
+                );
+            }
+        }
+
+        if !capabilities.mid_circuit_measurement && !tags.contains(&"Measurement") {
+            self.check_mid_circuit_measurements(&involved_qubits);
+        }
+        if PROJECTIVE_MEASUREMENT_OPERATIONS.contains(&hqslang) {
+            match &involved_qubits {
+                InvolvedQubits::All => self.measured_all.push(location),
+                InvolvedQubits::Set(qubits) => {
+                    for qubit in qubits {
+                        self.measured_qubits
+                            .entry(*qubit)
+                            .or_default()
+                            .push(location);
+                    }
+                }
+                InvolvedQubits::None => (),
+            }
+        }
+
+        if let Operation::PragmaConditional(conditional) = operation {
+            for inner in conditional.circuit().iter() {
+                self.check_operation(circuit, index, inner);
+            }
+        }
+    }
+
+    /// Marks the measurements of qubits used by a later operation as mid-circuit measurements.
+    fn check_mid_circuit_measurements(&mut self, involved_qubits: &InvolvedQubits) {
+        let mut locations: Vec<Location> = match involved_qubits {
+            InvolvedQubits::None => return,
+            InvolvedQubits::All => self
+                .measured_qubits
+                .drain()
+                .flat_map(|(_, locations)| locations)
+                .collect(),
+            InvolvedQubits::Set(qubits) => qubits
+                .iter()
+                .flat_map(|qubit| self.measured_qubits.remove(qubit).unwrap_or_default())
+                .collect(),
+        };
+        locations.append(&mut self.measured_all);
+        for location in locations {
+            self.push(location, IncompatibilityReason::MidCircuitMeasurement);
+        }
+    }
+
+    /// Adds an incompatibility unless the same incompatibility has already been found.
+    fn push(&mut self, location: Location, reason: IncompatibilityReason) {
+        let (circuit, index, hqslang) = location;
+        let incompatibility = Incompatibility {
+            circuit,
+            index,
+            hqslang: hqslang.to_string(),
+            reason,
+        };
+        if !self.incompatibilities.contains(&incompatibility) {
+            self.incompatibilities.push(incompatibility);
+        }
+    }
+}
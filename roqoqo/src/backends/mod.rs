@@ -0,0 +1,726 @@
+// Copyright © 2021 HQS Quantum Simulations GmbH. All Rights Reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except
+// in compliance with the License. You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the
+// License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either
+// express or implied. See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Traits defining the standard form of roqoqo backends.
+//!
+//! roqoqo can be used to implement interfaces and backends to quantum hardware, quantum simulators and other software packages.
+//! While roqoqo does not require a certain design for general interfaces or backends,
+//! roqoqo::backends provides a trait for implementing backends that produce measurement results which can be evaluated to
+//! expectation values.
+//! This trait is supposed to be implemented for backends connecting to quantum simulators or to real quantum hardware devices.
+//! Backends submitting circuits to a queue (e.g. of a quantum hardware provider) can implement the job based [JobBackend] trait
+//! instead, which separates submitting a job from fetching its result.
+//! Any [EvaluatingBackend] can be used as a [JobBackend] with the in-process queue [LocalJobQueue].
+//! With the `serialize` feature the results of repeated circuits can be cached with `CachingBackend`,
+//! and circuit runs can be recorded with `RecordingBackend` and replayed in tests with `ReplayBackend`.
+//!
+//! Note: The following backends are implemented in roqoqo and supported by HQS Quantum Simulations GmbH.
+//!
+//! Evaluated backends:
+//! * `aqt`, ( <https://github.com/HQSquantumsimulations/qoqo_aqt> ),
+//! * `mock` ( <https://github.com/HQSquantumsimulations/qoqo_mock> ),
+//! * `quest` ( <https://github.com/HQSquantumsimulations/qoqo-quest> ).
+//!
+//! Other backends:
+//! * `qasm` ( <https://github.com/HQSquantumsimulations/qoqo_qasm> ).
+
+mod capabilities;
+pub use capabilities::*;
+#[cfg(feature = "serialize")]
+mod stable_hash;
+#[cfg(feature = "serialize")]
+pub use stable_hash::*;
+#[cfg(feature = "serialize")]
+mod caching_backend;
+#[cfg(feature = "serialize")]
+pub use caching_backend::*;
+#[cfg(feature = "serialize")]
+mod recording_backend;
+#[cfg(feature = "serialize")]
+pub use recording_backend::*;
+
+use std::collections::{HashMap, VecDeque};
+use std::time::Duration;
+
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+use crate::measurements::ClassicalRegister;
+use crate::operations::Operation;
+use crate::registers::{BitOutputRegister, ComplexOutputRegister, FloatOutputRegister};
+use crate::Circuit;
+use crate::{
+    measurements::{Measure, MeasureExpectationValues},
+    RoqoqoBackendError, RoqoqoError,
+};
+
+/// Result of functions running a full circuit and producing output registers.
+pub type RegisterResult = Result<
+    (
+        HashMap<String, BitOutputRegister>,
+        HashMap<String, FloatOutputRegister>,
+        HashMap<String, ComplexOutputRegister>,
+    ),
+    RoqoqoBackendError,
+>;
+
+/// Trait for Backends that can evaluate measurements to expectation values.
+pub trait EvaluatingBackend: Sized {
+    /// Runs a circuit with the backend.
+    ///
+    /// A circuit is passed to the backend and executed.
+    /// During execution values are written to and read from classical registers
+    /// ([crate::registers::BitRegister], [crate::registers::FloatRegister] and [crate::registers::ComplexRegister]).
+    /// To produce sufficient statistics for evaluating expectationg values,
+    /// circuits have to be run multiple times.
+    /// The results of each repetition are concatenated in OutputRegisters
+    /// ([crate::registers::BitOutputRegister], [crate::registers::FloatOutputRegister] and [crate::registers::ComplexOutputRegister]).  
+    ///
+    ///
+    /// # Arguments
+    ///
+    /// * `circuit` - The circuit that is run on the backend.
+    ///
+    /// # Returns
+    ///
+    /// `RegisterResult` - The output registers written by the evaluated circuits.
+    fn run_circuit(&self, circuit: &Circuit) -> RegisterResult {
+        self.run_circuit_iterator(circuit.iter())
+    }
+
+    /// Runs each operation obtained from an iterator over operations on the backend.
+    ///
+    /// An iterator over operations is passed to the backend and executed.
+    /// During execution values are written to and read from classical registers
+    /// ([crate::registers::BitRegister], [crate::registers::FloatRegister] and [crate::registers::ComplexRegister]).
+    /// To produce sufficient statistics for evaluating expectationg values,
+    /// circuits have to be run multiple times.
+    /// The results of each repetition are concatenated in OutputRegisters
+    /// ([crate::registers::BitOutputRegister], [crate::registers::FloatOutputRegister] and [crate::registers::ComplexOutputRegister]).  
+    ///
+    ///
+    /// # Arguments
+    ///
+    /// * `circuit` - The iterator over operations that is run on the backend (corresponds to a circuit).
+    ///
+    /// # Returns
+    ///
+    /// `RegisterResult` - The output registers written by the evaluated circuits.
+    fn run_circuit_iterator<'a>(
+        &self,
+        circuit: impl Iterator<Item = &'a Operation>,
+    ) -> RegisterResult;
+
+    /// Runs operations produced by a fallible iterator of owned operations on the backend.
+    ///
+    /// Execution path for operations read from a stream, e.g. [crate::streaming::CircuitStreamReader],
+    /// that stops at the first read error.
+    /// Backends applying the operations one after another should override this function and drop every
+    /// operation after applying it, so that streamed circuits are run with memory independent of their length.
+    /// As [EvaluatingBackend::run_circuit_iterator] borrows the operations for the whole run,
+    /// the default implementation collects all operations in memory before running them.
+    ///
+    /// # Arguments
+    ///
+    /// * `operations` - The iterator over operations that is run on the backend (corresponds to a circuit).
+    ///
+    /// # Returns
+    ///
+    /// `RegisterResult` - The output registers written by the evaluated circuits.
+    fn run_circuit_stream(
+        &self,
+        operations: impl Iterator<Item = Result<Operation, RoqoqoError>>,
+    ) -> RegisterResult {
+        let operations: Vec<Operation> = operations.collect::<Result<Vec<Operation>, _>>()?;
+        self.run_circuit_iterator(operations.iter())
+    }
+
+    /// Runs all circuits corresponding to one measurement with the backend.
+    ///
+    /// An expectation value measurement in general involves several circuits.
+    /// Each circuit is passes to the backend and executed separately.
+    /// During execution values are written to and read from classical registers
+    /// ([crate::registers::BitRegister], [crate::registers::FloatRegister] and [crate::registers::ComplexRegister]).
+    /// To produce sufficient statistics for evaluating expectationg values,
+    /// circuits have to be run multiple times.
+    /// The results of each repetition are concatenated in OutputRegisters
+    /// ([crate::registers::BitOutputRegister], [crate::registers::FloatOutputRegister] and [crate::registers::ComplexOutputRegister]).  
+    /// At the end all OutputRegisters are combined in a single HashMap for each type of register.
+    ///
+    /// # Arguments
+    ///
+    /// * `measurement` - The measurement that is run on the backend.
+    ///
+    /// # Returns
+    ///
+    /// `RegisterResult` - The output registers written by the evaluated measurement circuits.
+    fn run_measurement_registers<T>(&self, measurement: &T) -> RegisterResult
+    where
+        T: Measure,
+    {
+        merge_registers(measurement.circuits().map(
+            |circuit| match measurement.constant_circuit() {
+                Some(x) => self.run_circuit_iterator(x.iter().chain(circuit.iter())),
+                None => self.run_circuit_iterator(circuit.iter()),
+            },
+        ))
+    }
+
+    /// Runs each operation obtained from an iterator over operations on the backend with a fixed seed.
+    ///
+    /// The seed is used by backends with random processes (e.g. sampling of measurement results)
+    /// to make the output registers reproducible.
+    /// Seeding is best-effort: the default implementation ignores the seed and calls
+    /// [EvaluatingBackend::run_circuit_iterator].
+    /// Backends using random numbers should override this function and [EvaluatingBackend::supports_seed].
+    ///
+    /// # Arguments
+    ///
+    /// * `circuit` - The iterator over operations that is run on the backend (corresponds to a circuit).
+    /// * `seed` - The seed of the random number generation of the backend.
+    ///
+    /// # Returns
+    ///
+    /// `RegisterResult` - The output registers written by the evaluated circuits.
+    #[allow(unused_variables)]
+    fn run_circuit_iterator_with_seed<'a>(
+        &self,
+        circuit: impl Iterator<Item = &'a Operation>,
+        seed: u64,
+    ) -> RegisterResult {
+        self.run_circuit_iterator(circuit)
+    }
+
+    /// Returns true when the backend honors the seed of [EvaluatingBackend::run_circuit_iterator_with_seed].
+    ///
+    /// When the backend returns false, runs with a seed are only reproducible if the backend is deterministic.
+    /// The default implementation returns false.
+    fn supports_seed(&self) -> bool {
+        false
+    }
+
+    /// Runs all circuits corresponding to one measurement with the backend using a seed for each circuit.
+    ///
+    /// Each circuit is run with [EvaluatingBackend::run_circuit_iterator_with_seed] and a seed derived
+    /// from `seed` and the index of the circuit in the measurement.
+    /// The seed is ignored by backends that do not support seeds, see [EvaluatingBackend::supports_seed].
+    /// The output registers are combined in the order of the circuits.
+    /// Gives the same result as `run_measurement_registers_parallel` available with the `parallel` feature.
+    ///
+    /// # Arguments
+    ///
+    /// * `measurement` - The measurement that is run on the backend.
+    /// * `seed` - The seed from which the seeds of the circuits are derived.
+    ///
+    /// # Returns
+    ///
+    /// `RegisterResult` - The output registers written by the evaluated measurement circuits.
+    fn run_measurement_registers_seeded<T>(&self, measurement: &T, seed: u64) -> RegisterResult
+    where
+        T: Measure,
+    {
+        let constant_circuit = measurement.constant_circuit().as_ref();
+        merge_registers(measurement.circuits().enumerate().map(|(index, circuit)| {
+            run_seeded_circuit(self, constant_circuit, circuit, seed, index)
+        }))
+    }
+
+    /// Runs all circuits corresponding to one measurement in parallel with the backend.
+    ///
+    /// Requires the `parallel` feature. The circuits are run in parallel with rayon.
+    /// Each circuit is run with [EvaluatingBackend::run_circuit_iterator_with_seed] and a seed derived
+    /// from `seed` and the index of the circuit in the measurement.
+    /// The output registers are combined in the order of the circuits, so that the result does not depend
+    /// on the order of execution and is identical to [EvaluatingBackend::run_measurement_registers_seeded].
+    ///
+    /// # Arguments
+    ///
+    /// * `measurement` - The measurement that is run on the backend.
+    /// * `seed` - The seed from which the seeds of the circuits are derived.
+    ///
+    /// # Returns
+    ///
+    /// `RegisterResult` - The output registers written by the evaluated measurement circuits.
+    #[cfg(feature = "parallel")]
+    fn run_measurement_registers_parallel<T>(&self, measurement: &T, seed: u64) -> RegisterResult
+    where
+        T: Measure,
+        Self: Sync,
+    {
+        let constant_circuit = measurement.constant_circuit().as_ref();
+        let circuits: Vec<&Circuit> = measurement.circuits().collect();
+        let results: Vec<RegisterResult> = circuits
+            .par_iter()
+            .enumerate()
+            .map(|(index, circuit)| {
+                run_seeded_circuit(self, constant_circuit, circuit, seed, index)
+            })
+            .collect();
+        merge_registers(results.into_iter())
+    }
+
+    /// Evaluates expectation values of a measurement with the backend.
+    ///
+    /// # Arguments
+    ///
+    /// * `measurement` - The measurement that is run on the backend.
+    ///
+    /// # Returns
+    ///
+    /// `Ok(Option<HashMap<String, f64>>)` - The HashMap of measurement results.
+    /// `Err(RoqoqoBackendError)` - The measurement run failed.
+    fn run_measurement<T>(
+        &self,
+        measurement: &T,
+    ) -> Result<Option<HashMap<String, f64>>, RoqoqoBackendError>
+    where
+        T: MeasureExpectationValues,
+    {
+        let (bit_registers, float_registers, complex_registers) =
+            self.run_measurement_registers(measurement)?;
+        Ok(measurement.evaluate(bit_registers, float_registers, complex_registers)?)
+    }
+
+    /// Returns the capabilities of the backend.
+    ///
+    /// Used to check before running a circuit that the backend supports all its operations,
+    /// e.g. with [crate::QuantumProgram::check_capabilities].
+    ///
+    /// # Returns
+    ///
+    /// * `Some(BackendCapabilities)` - The capabilities of the backend.
+    /// * `None` - The backend does not describe its capabilities (default).
+    fn capabilities(&self) -> Option<BackendCapabilities> {
+        None
+    }
+
+    /// Returns metadata describing the backend.
+    ///
+    /// The metadata is stored in the [crate::RunResult] of [crate::QuantumProgram::run_with_metadata],
+    /// e.g. the name and version of a simulator or the device of a hardware provider.
+    ///
+    /// # Returns
+    ///
+    /// * `HashMap<String, String>` - The metadata of the backend (empty by default).
+    fn metadata(&self) -> HashMap<String, String> {
+        HashMap::new()
+    }
+}
+
+/// Allows passing a reference to a backend where a backend is expected, e.g. to [crate::QuantumProgram::run].
+///
+/// Keeps the state of backends like `CachingBackend` between runs.
+impl<B> EvaluatingBackend for &B
+where
+    B: EvaluatingBackend,
+{
+    fn run_circuit_iterator<'a>(
+        &self,
+        circuit: impl Iterator<Item = &'a Operation>,
+    ) -> RegisterResult {
+        (**self).run_circuit_iterator(circuit)
+    }
+
+    fn run_circuit_stream(
+        &self,
+        operations: impl Iterator<Item = Result<Operation, RoqoqoError>>,
+    ) -> RegisterResult {
+        (**self).run_circuit_stream(operations)
+    }
+
+    fn run_measurement_registers<T>(&self, measurement: &T) -> RegisterResult
+    where
+        T: Measure,
+    {
+        (**self).run_measurement_registers(measurement)
+    }
+
+    fn run_circuit_iterator_with_seed<'a>(
+        &self,
+        circuit: impl Iterator<Item = &'a Operation>,
+        seed: u64,
+    ) -> RegisterResult {
+        (**self).run_circuit_iterator_with_seed(circuit, seed)
+    }
+
+    fn supports_seed(&self) -> bool {
+        (**self).supports_seed()
+    }
+
+    fn capabilities(&self) -> Option<BackendCapabilities> {
+        (**self).capabilities()
+    }
+
+    fn metadata(&self) -> HashMap<String, String> {
+        (**self).metadata()
+    }
+}
+
+/// Runs one circuit of a measurement with the seed derived from the index of the circuit.
+fn run_seeded_circuit<B>(
+    backend: &B,
+    constant_circuit: Option<&Circuit>,
+    circuit: &Circuit,
+    seed: u64,
+    index: usize,
+) -> RegisterResult
+where
+    B: EvaluatingBackend,
+{
+    let circuit_seed = seed
+        ^ (index as u64)
+            .wrapping_add(1)
+            .wrapping_mul(0x9E37_79B9_7F4A_7C15);
+    match constant_circuit {
+        Some(x) => {
+            backend.run_circuit_iterator_with_seed(x.iter().chain(circuit.iter()), circuit_seed)
+        }
+        None => backend.run_circuit_iterator_with_seed(circuit.iter(), circuit_seed),
+    }
+}
+
+/// Combines the output registers of several circuits into a single HashMap for each type of register.
+///
+/// The entries of registers with the same name are concatenated in the order of the results.
+pub(crate) fn merge_registers(results: impl Iterator<Item = RegisterResult>) -> RegisterResult {
+    let mut bit_registers: HashMap<String, BitOutputRegister> = HashMap::new();
+    let mut float_registers: HashMap<String, FloatOutputRegister> = HashMap::new();
+    let mut complex_registers: HashMap<String, ComplexOutputRegister> = HashMap::new();
+
+    for result in results {
+        let (tmp_bit_reg, tmp_float_reg, tmp_complex_reg) = result?;
+        for (key, mut val) in tmp_bit_reg.into_iter() {
+            if let Some(x) = bit_registers.get_mut(&key) {
+                x.append(&mut val);
+            } else {
+                let _ = bit_registers.insert(key, val);
+            }
+        }
+        for (key, mut val) in tmp_float_reg.into_iter() {
+            if let Some(x) = float_registers.get_mut(&key) {
+                x.append(&mut val);
+            } else {
+                let _ = float_registers.insert(key, val);
+            }
+        }
+        for (key, mut val) in tmp_complex_reg.into_iter() {
+            if let Some(x) = complex_registers.get_mut(&key) {
+                x.append(&mut val);
+            } else {
+                let _ = complex_registers.insert(key, val);
+            }
+        }
+    }
+    Ok((bit_registers, float_registers, complex_registers))
+}
+
+/// Identifier of a job submitted to a [JobBackend].
+pub type JobId = usize;
+
+/// Status of a job submitted to a [JobBackend].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub enum JobStatus {
+    /// The job is waiting in the queue of the backend.
+    Queued,
+    /// The job is being executed.
+    Running,
+    /// The job has been executed successfully and its result can be fetched.
+    Completed,
+    /// The execution of the job failed and the error can be fetched.
+    Failed,
+    /// The job has been cancelled before it was executed.
+    Cancelled,
+}
+
+impl JobStatus {
+    /// Returns true when the job will not change its status anymore.
+    pub fn is_finished(&self) -> bool {
+        matches!(
+            self,
+            JobStatus::Completed | JobStatus::Failed | JobStatus::Cancelled
+        )
+    }
+}
+
+/// Trait for Backends that execute circuits as jobs in a queue.
+///
+/// In contrast to [EvaluatingBackend], running a circuit is split into submitting a job,
+/// polling its status and fetching the result once the job has finished.
+/// This corresponds to the queues of quantum hardware providers, where the execution of a job
+/// can start long after it has been submitted.
+pub trait JobBackend {
+    /// Submits a circuit to the backend.
+    ///
+    /// # Arguments
+    ///
+    /// * `circuit` - The circuit that is run on the backend.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(JobId)` - The identifier of the submitted job.
+    /// * `Err(RoqoqoBackendError)` - The job could not be submitted.
+    fn submit_circuit(&mut self, circuit: &Circuit) -> Result<JobId, RoqoqoBackendError>;
+
+    /// Submits all circuits corresponding to one measurement to the backend as a single job.
+    ///
+    /// The result of the job contains the output registers of all circuits combined
+    /// as returned by [EvaluatingBackend::run_measurement_registers].
+    ///
+    /// # Arguments
+    ///
+    /// * `measurement` - The measurement that is run on the backend.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(JobId)` - The identifier of the submitted job.
+    /// * `Err(RoqoqoBackendError)` - The job could not be submitted.
+    fn submit_measurement<T>(&mut self, measurement: &T) -> Result<JobId, RoqoqoBackendError>
+    where
+        T: Measure;
+
+    /// Returns the current status of a job.
+    ///
+    /// # Arguments
+    ///
+    /// * `job_id` - The identifier of the job.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(JobStatus)` - The status of the job.
+    /// * `Err(RoqoqoBackendError)` - The job is unknown to the backend or the status could not be obtained.
+    fn poll(&mut self, job_id: JobId) -> Result<JobStatus, RoqoqoBackendError>;
+
+    /// Cancels a job that has not finished yet.
+    ///
+    /// # Arguments
+    ///
+    /// * `job_id` - The identifier of the job.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - The job has been cancelled.
+    /// * `Err(RoqoqoBackendError)` - The job is unknown, has already finished or could not be cancelled.
+    fn cancel(&mut self, job_id: JobId) -> Result<(), RoqoqoBackendError>;
+
+    /// Fetches the result of a finished job.
+    ///
+    /// # Arguments
+    ///
+    /// * `job_id` - The identifier of the job.
+    ///
+    /// # Returns
+    ///
+    /// `RegisterResult` - The output registers written by the job or the error of a failed job.
+    /// Returns an error when the job has not finished, has been cancelled or is unknown.
+    fn fetch_result(&mut self, job_id: JobId) -> RegisterResult;
+
+    /// Waits until a job has finished and fetches its result.
+    ///
+    /// # Arguments
+    ///
+    /// * `job_id` - The identifier of the job.
+    /// * `poll_interval` - The time to wait between polling the status of the job.
+    ///
+    /// # Returns
+    ///
+    /// `RegisterResult` - The output registers written by the job or the error of a failed job.
+    fn wait_for_result(&mut self, job_id: JobId, poll_interval: Duration) -> RegisterResult {
+        while !self.poll(job_id)?.is_finished() {
+            std::thread::sleep(poll_interval);
+        }
+        self.fetch_result(job_id)
+    }
+
+    /// Returns the capabilities of the backend.
+    ///
+    /// Used to check before running a circuit that the backend supports all its operations,
+    /// e.g. with [crate::QuantumProgram::check_capabilities].
+    ///
+    /// # Returns
+    ///
+    /// * `Some(BackendCapabilities)` - The capabilities of the backend.
+    /// * `None` - The backend does not describe its capabilities (default).
+    fn capabilities(&self) -> Option<BackendCapabilities> {
+        None
+    }
+}
+
+/// In-process job queue executing jobs with an [EvaluatingBackend].
+///
+/// Adapter that allows using any [EvaluatingBackend] as a [JobBackend].
+/// Submitted jobs are stored in a first-in-first-out queue and executed one after another
+/// when [LocalJobQueue::process_next] is called or when a queued job is polled.
+/// Jobs that are still queued can be cancelled.
+///
+/// The result of a job is removed from the queue when it is fetched.
+#[derive(Debug)]
+pub struct LocalJobQueue<B>
+where
+    B: EvaluatingBackend,
+{
+    backend: B,
+    next_job_id: JobId,
+    queue: VecDeque<(JobId, ClassicalRegister)>,
+    statuses: HashMap<JobId, JobStatus>,
+    results: HashMap<JobId, RegisterResult>,
+}
+
+impl<B> LocalJobQueue<B>
+where
+    B: EvaluatingBackend,
+{
+    /// Creates a new LocalJobQueue executing jobs with a backend.
+    ///
+    /// # Arguments
+    ///
+    /// * `backend` - The backend executing the jobs.
+    ///
+    /// # Returns
+    ///
+    /// * `Self` - The new LocalJobQueue with an empty queue.
+    pub fn new(backend: B) -> Self {
+        Self {
+            backend,
+            next_job_id: 0,
+            queue: VecDeque::new(),
+            statuses: HashMap::new(),
+            results: HashMap::new(),
+        }
+    }
+
+    /// Returns a reference to the backend executing the jobs.
+    pub fn backend(&self) -> &B {
+        &self.backend
+    }
+
+    /// Returns the number of jobs waiting in the queue.
+    pub fn number_queued(&self) -> usize {
+        self.queue.len()
+    }
+
+    /// Executes the oldest job in the queue.
+    ///
+    /// # Returns
+    ///
+    /// * `Some(JobId)` - The identifier of the executed job.
+    /// * `None` - The queue is empty.
+    pub fn process_next(&mut self) -> Option<JobId> {
+        let (job_id, measurement) = self.queue.pop_front()?;
+        let result = self.backend.run_measurement_registers(&measurement);
+        let status = match result {
+            Ok(_) => JobStatus::Completed,
+            Err(_) => JobStatus::Failed,
+        };
+        self.statuses.insert(job_id, status);
+        self.results.insert(job_id, result);
+        Some(job_id)
+    }
+
+    /// Executes all jobs in the queue.
+    pub fn process_all(&mut self) {
+        while self.process_next().is_some() {}
+    }
+
+    fn enqueue(&mut self, measurement: ClassicalRegister) -> JobId {
+        let job_id = self.next_job_id;
+        self.next_job_id += 1;
+        self.queue.push_back((job_id, measurement));
+        self.statuses.insert(job_id, JobStatus::Queued);
+        job_id
+    }
+
+    fn status(&self, job_id: JobId) -> Result<JobStatus, RoqoqoBackendError> {
+        self.statuses
+            .get(&job_id)
+            .copied()
+            .ok_or_else(|| RoqoqoBackendError::GenericError {
+                msg: format!("Job {} is unknown to the job queue", job_id),
+            })
+    }
+}
+
+impl<B> JobBackend for LocalJobQueue<B>
+where
+    B: EvaluatingBackend,
+{
+    fn submit_circuit(&mut self, circuit: &Circuit) -> Result<JobId, RoqoqoBackendError> {
+        Ok(self.enqueue(ClassicalRegister {
+            constant_circuit: None,
+            circuits: vec![circuit.clone()],
+        }))
+    }
+
+    fn submit_measurement<T>(&mut self, measurement: &T) -> Result<JobId, RoqoqoBackendError>
+    where
+        T: Measure,
+    {
+        Ok(self.enqueue(ClassicalRegister {
+            constant_circuit: measurement.constant_circuit().clone(),
+            circuits: measurement.circuits().cloned().collect(),
+        }))
+    }
+
+    /// Returns the current status of a job.
+    ///
+    /// Polling a queued job executes all jobs in the queue up to and including the polled job.
+    fn poll(&mut self, job_id: JobId) -> Result<JobStatus, RoqoqoBackendError> {
+        if self.status(job_id)? == JobStatus::Queued {
+            while let Some(processed_id) = self.process_next() {
+                if processed_id == job_id {
+                    break;
+                }
+            }
+        }
+        self.status(job_id)
+    }
+
+    fn cancel(&mut self, job_id: JobId) -> Result<(), RoqoqoBackendError> {
+        match self.status(job_id)? {
+            JobStatus::Queued => {
+                self.queue.retain(|(queued_id, _)| *queued_id != job_id);
+                self.statuses.insert(job_id, JobStatus::Cancelled);
+                Ok(())
+            }
+            status => Err(RoqoqoBackendError::GenericError {
+                msg: format!(
+                    "Job {} cannot be cancelled with status {:?}",
+                    job_id, status
+                ),
+            }),
+        }
+    }
+
+    /// Fetches the result of a finished job.
+    ///
+    /// The job is removed from the queue when the result of a completed or failed job is fetched.
+    fn fetch_result(&mut self, job_id: JobId) -> RegisterResult {
+        match self.status(job_id)? {
+            JobStatus::Completed | JobStatus::Failed => {
+                self.statuses.remove(&job_id);
+                self.results.remove(&job_id).unwrap_or_else(|| {
+                    Err(RoqoqoBackendError::GenericError {
+                        msg: format!("Result of job {} is missing", job_id),
+                    })
+                })
+            }
+            status => Err(RoqoqoBackendError::GenericError {
+                msg: format!("Job {} has no result with status {:?}", job_id, status),
+            }),
+        }
+    }
+
+    /// Returns the capabilities of the wrapped backend.
+    fn capabilities(&self) -> Option<BackendCapabilities> {
+        self.backend.capabilities()
+    }
+}
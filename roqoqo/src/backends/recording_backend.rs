@@ -0,0 +1,272 @@
+// Copyright © 2021 HQS Quantum Simulations GmbH. All Rights Reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except
+// in compliance with the License. You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the
+// License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either
+// express or implied. See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::{BackendCapabilities, EvaluatingBackend, RegisterResult};
+use crate::operations::Operation;
+use crate::registers::{BitOutputRegister, ComplexOutputRegister, FloatOutputRegister};
+use crate::{Circuit, RoqoqoBackendError, RoqoqoError};
+use std::collections::HashMap;
+use std::io::{Read, Write};
+use std::sync::{Mutex, MutexGuard};
+
+/// A circuit run by a [RecordingBackend] together with the returned output registers.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[cfg_attr(feature = "json_schema", derive(schemars::JsonSchema))]
+pub struct RecordedRun {
+    /// The circuit that has been run.
+    pub circuit: Circuit,
+    /// The seed of the run when the circuit has been run with [EvaluatingBackend::run_circuit_iterator_with_seed].
+    pub seed: Option<u64>,
+    /// The returned bit registers with the register name as key.
+    pub bit_registers: HashMap<String, BitOutputRegister>,
+    /// The returned float registers with the register name as key.
+    pub float_registers: HashMap<String, FloatOutputRegister>,
+    /// The returned complex registers with the register name as key.
+    pub complex_registers: HashMap<String, ComplexOutputRegister>,
+}
+
+/// Backend wrapper recording the circuits run with an [EvaluatingBackend] and the returned registers.
+///
+/// The recorded runs can be written to a file with [RecordingBackend::write_recording]
+/// and served by a [ReplayBackend] in tests without running the wrapped backend.
+/// Failed runs are not recorded.
+#[derive(Debug)]
+pub struct RecordingBackend<B>
+where
+    B: EvaluatingBackend,
+{
+    backend: B,
+    recording: Mutex<Vec<RecordedRun>>,
+}
+
+impl<B> RecordingBackend<B>
+where
+    B: EvaluatingBackend,
+{
+    /// Creates a new RecordingBackend wrapping a backend.
+    ///
+    /// # Arguments
+    ///
+    /// * `backend` - The backend running the recorded circuits.
+    ///
+    /// # Returns
+    ///
+    /// * `Self` - The new RecordingBackend with an empty recording.
+    pub fn new(backend: B) -> Self {
+        Self {
+            backend,
+            recording: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Returns a reference to the wrapped backend.
+    pub fn backend(&self) -> &B {
+        &self.backend
+    }
+
+    /// Returns the recorded runs in the order of execution.
+    pub fn recording(&self) -> Vec<RecordedRun> {
+        self.lock_recording().clone()
+    }
+
+    /// Writes the recorded runs to a writer, e.g. a [std::fs::File].
+    ///
+    /// # Arguments
+    ///
+    /// * `writer` - The writer the recording is written to.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - The recording has been written.
+    /// * `Err([RoqoqoBackendError::RoqoqoError])` - The recording could not be serialized or written.
+    pub fn write_recording<W: Write>(&self, writer: W) -> Result<(), RoqoqoBackendError> {
+        bincode::serialize_into(writer, &*self.lock_recording()).map_err(|err| {
+            RoqoqoError::SerializationError {
+                msg: format!("{:?}", err),
+            }
+        })?;
+        Ok(())
+    }
+
+    /// Runs the operations with the wrapped backend and records the run.
+    fn record(
+        &self,
+        operations: Vec<&Operation>,
+        seed: Option<u64>,
+        run: impl FnOnce(Vec<&Operation>) -> RegisterResult,
+    ) -> RegisterResult {
+        let circuit: Circuit = operations.iter().copied().cloned().collect();
+        let (bit_registers, float_registers, complex_registers) = run(operations)?;
+        self.lock_recording().push(RecordedRun {
+            circuit,
+            seed,
+            bit_registers: bit_registers.clone(),
+            float_registers: float_registers.clone(),
+            complex_registers: complex_registers.clone(),
+        });
+        Ok((bit_registers, float_registers, complex_registers))
+    }
+
+    fn lock_recording(&self) -> MutexGuard<Vec<RecordedRun>> {
+        self.recording
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+impl<B> EvaluatingBackend for RecordingBackend<B>
+where
+    B: EvaluatingBackend,
+{
+    fn run_circuit_iterator<'a>(
+        &self,
+        circuit: impl Iterator<Item = &'a Operation>,
+    ) -> RegisterResult {
+        self.record(circuit.collect(), None, |operations| {
+            self.backend.run_circuit_iterator(operations.into_iter())
+        })
+    }
+
+    fn run_circuit_iterator_with_seed<'a>(
+        &self,
+        circuit: impl Iterator<Item = &'a Operation>,
+        seed: u64,
+    ) -> RegisterResult {
+        self.record(circuit.collect(), Some(seed), |operations| {
+            self.backend
+                .run_circuit_iterator_with_seed(operations.into_iter(), seed)
+        })
+    }
+
+    fn supports_seed(&self) -> bool {
+        self.backend.supports_seed()
+    }
+
+    fn capabilities(&self) -> Option<BackendCapabilities> {
+        self.backend.capabilities()
+    }
+
+    fn metadata(&self) -> HashMap<String, String> {
+        self.backend.metadata()
+    }
+}
+
+/// Backend serving the output registers recorded by a [RecordingBackend].
+///
+/// Every run circuit is matched against the recorded circuits by equality. Runs with a seed only match
+/// runs recorded with the same seed. When a circuit has been recorded several times, the recorded results
+/// are returned in the recorded order and the last one is repeated once all have been served.
+/// Running a circuit that has not been recorded returns an error.
+#[derive(Debug)]
+pub struct ReplayBackend {
+    recording: Vec<RecordedRun>,
+    /// Number of times the recorded runs of a circuit and seed have been served,
+    /// with the position of the first recorded run of the circuit and seed as key.
+    served: Mutex<HashMap<usize, usize>>,
+}
+
+impl ReplayBackend {
+    /// Creates a new ReplayBackend serving recorded runs.
+    ///
+    /// # Arguments
+    ///
+    /// * `recording` - The recorded runs, e.g. from [RecordingBackend::recording].
+    ///
+    /// # Returns
+    ///
+    /// * `Self` - The new ReplayBackend.
+    pub fn new(recording: Vec<RecordedRun>) -> Self {
+        Self {
+            recording,
+            served: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Creates a new ReplayBackend from a recording read from a reader, e.g. a [std::fs::File].
+    ///
+    /// # Arguments
+    ///
+    /// * `reader` - The reader the recording written by [RecordingBackend::write_recording] is read from.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Self)` - The new ReplayBackend.
+    /// * `Err([RoqoqoError::SerializationError])` - The recording could not be read.
+    pub fn from_reader<R: Read>(reader: R) -> Result<Self, RoqoqoError> {
+        let recording: Vec<RecordedRun> =
+            bincode::deserialize_from(reader).map_err(|err| RoqoqoError::SerializationError {
+                msg: format!("{:?}", err),
+            })?;
+        Ok(Self::new(recording))
+    }
+
+    /// Returns the recorded runs served by the backend.
+    pub fn recording(&self) -> &[RecordedRun] {
+        &self.recording
+    }
+
+    /// Returns the recorded registers of a circuit run.
+    fn replay<'a>(
+        &self,
+        circuit: impl Iterator<Item = &'a Operation>,
+        seed: Option<u64>,
+    ) -> RegisterResult {
+        let circuit: Circuit = circuit.cloned().collect();
+        let matching: Vec<(usize, &RecordedRun)> = self
+            .recording
+            .iter()
+            .enumerate()
+            .filter(|(_, run)| run.seed == seed && run.circuit == circuit)
+            .collect();
+        if matching.is_empty() {
+            return Err(RoqoqoBackendError::GenericError {
+                msg: format!(
+                    "Circuit with seed {:?} has not been recorded and cannot be replayed: {}",
+                    seed, circuit
+                ),
+            });
+        }
+        let mut served = self
+            .served
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        let count = served.entry(matching[0].0).or_insert(0);
+        let (_, run) = matching[(*count).min(matching.len() - 1)];
+        *count += 1;
+        Ok((
+            run.bit_registers.clone(),
+            run.float_registers.clone(),
+            run.complex_registers.clone(),
+        ))
+    }
+}
+
+impl EvaluatingBackend for ReplayBackend {
+    fn run_circuit_iterator<'a>(
+        &self,
+        circuit: impl Iterator<Item = &'a Operation>,
+    ) -> RegisterResult {
+        self.replay(circuit, None)
+    }
+
+    fn run_circuit_iterator_with_seed<'a>(
+        &self,
+        circuit: impl Iterator<Item = &'a Operation>,
+        seed: u64,
+    ) -> RegisterResult {
+        self.replay(circuit, Some(seed))
+    }
+
+    fn supports_seed(&self) -> bool {
+        true
+    }
+}
@@ -0,0 +1,478 @@
+// Copyright © 2021 HQS Quantum Simulations GmbH. All Rights Reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except
+// in compliance with the License. You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the
+// License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either
+// express or implied. See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::operations::Operation;
+use crate::RoqoqoError;
+use serde::ser::{self, Serialize};
+use std::fmt;
+use std::io::Write;
+
+const FNV_OFFSET_BASIS: u128 = 0x6c62_272e_07bb_0142_62b8_2175_6295_c58d;
+const FNV_PRIME: u128 = 0x0000_0000_0100_0000_0000_0000_0000_013b;
+
+/// Writer computing the 128 bit FNV-1a hash of the written bytes.
+pub(crate) struct StableHasher {
+    pub(crate) hash: u128,
+}
+
+impl Write for StableHasher {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        for byte in buf {
+            self.hash ^= u128::from(*byte);
+            self.hash = self.hash.wrapping_mul(FNV_PRIME);
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Returns a stable hash of a sequence of operations.
+///
+/// The hash is the 128 bit FNV-1a hash of a canonical binary encoding of the operations.
+/// The canonical encoding follows the serde serialization of the operations but writes the entries of maps
+/// (e.g. the `qubit_mapping` of [crate::operations::PragmaRepeatedMeasurement]) sorted by their keys,
+/// so that it does not depend on the iteration order of a [std::collections::HashMap].
+/// In contrast to [std::hash::Hash] the hash does not depend on the platform or on the run of the program,
+/// and can be used to identify circuits across program runs with the same roqoqo version.
+///
+/// # Arguments
+///
+/// * `operations` - The operations that are hashed.
+///
+/// # Returns
+///
+/// * `Ok(u128)` - The hash of the operations.
+/// * `Err([RoqoqoError::SerializationError])` - An operation could not be serialized.
+pub fn stable_hash_operations<'a>(
+    operations: impl Iterator<Item = &'a Operation>,
+) -> Result<u128, RoqoqoError> {
+    Ok(hash_operations(operations)?.hash)
+}
+
+/// Returns the hasher after hashing the canonical encoding of the operations.
+pub(crate) fn hash_operations<'a>(
+    operations: impl Iterator<Item = &'a Operation>,
+) -> Result<StableHasher, RoqoqoError> {
+    let mut hasher = StableHasher {
+        hash: FNV_OFFSET_BASIS,
+    };
+    for operation in operations {
+        let mut encoder = CanonicalEncoder::default();
+        operation
+            .serialize(&mut encoder)
+            .map_err(|err| RoqoqoError::SerializationError { msg: err.0 })?;
+        hasher
+            .write_all(&encoder.output)
+            .expect("Unexpectedly failed to hash operation");
+    }
+    Ok(hasher)
+}
+
+/// Error raised when a value cannot be encoded canonically.
+#[derive(Debug)]
+struct CanonicalEncodingError(String);
+
+impl fmt::Display for CanonicalEncodingError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl std::error::Error for CanonicalEncodingError {}
+
+impl ser::Error for CanonicalEncodingError {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        CanonicalEncodingError(msg.to_string())
+    }
+}
+
+/// Serde serializer writing a canonical binary encoding of a value.
+///
+/// Numbers are written in little endian byte order and strings, bytes and collections are followed by their length.
+/// The entries of maps are encoded separately and written sorted by the encoding of their keys.
+#[derive(Debug, Default)]
+struct CanonicalEncoder {
+    output: Vec<u8>,
+}
+
+impl CanonicalEncoder {
+    fn write_length(&mut self, length: usize) {
+        self.output
+            .extend_from_slice(&(length as u64).to_le_bytes());
+    }
+
+    fn write_variant(&mut self, variant_index: u32) {
+        self.output.extend_from_slice(&variant_index.to_le_bytes());
+    }
+}
+
+/// Encoder of sequences, tuples and structs, writing the number of elements after the elements.
+struct CompoundEncoder<'a> {
+    encoder: &'a mut CanonicalEncoder,
+    length: usize,
+}
+
+impl CompoundEncoder<'_> {
+    fn element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), CanonicalEncodingError> {
+        self.length += 1;
+        value.serialize(&mut *self.encoder)
+    }
+
+    fn finish(self) -> Result<(), CanonicalEncodingError> {
+        self.encoder.write_length(self.length);
+        Ok(())
+    }
+}
+
+/// Encoder of maps, collecting the encoded entries and writing them sorted by their keys.
+struct MapEncoder<'a> {
+    encoder: &'a mut CanonicalEncoder,
+    key: Option<Vec<u8>>,
+    entries: Vec<(Vec<u8>, Vec<u8>)>,
+}
+
+fn encode<T: ?Sized + Serialize>(value: &T) -> Result<Vec<u8>, CanonicalEncodingError> {
+    let mut encoder = CanonicalEncoder::default();
+    value.serialize(&mut encoder)?;
+    Ok(encoder.output)
+}
+
+impl<'a> ser::Serializer for &'a mut CanonicalEncoder {
+    type Ok = ();
+    type Error = CanonicalEncodingError;
+    type SerializeSeq = CompoundEncoder<'a>;
+    type SerializeTuple = CompoundEncoder<'a>;
+    type SerializeTupleStruct = CompoundEncoder<'a>;
+    type SerializeTupleVariant = CompoundEncoder<'a>;
+    type SerializeMap = MapEncoder<'a>;
+    type SerializeStruct = CompoundEncoder<'a>;
+    type SerializeStructVariant = CompoundEncoder<'a>;
+
+    fn serialize_bool(self, v: bool) -> Result<(), Self::Error> {
+        self.output.push(u8::from(v));
+        Ok(())
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<(), Self::Error> {
+        self.output.extend_from_slice(&v.to_le_bytes());
+        Ok(())
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<(), Self::Error> {
+        self.output.extend_from_slice(&v.to_le_bytes());
+        Ok(())
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<(), Self::Error> {
+        self.output.extend_from_slice(&v.to_le_bytes());
+        Ok(())
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<(), Self::Error> {
+        self.output.extend_from_slice(&v.to_le_bytes());
+        Ok(())
+    }
+
+    fn serialize_i128(self, v: i128) -> Result<(), Self::Error> {
+        self.output.extend_from_slice(&v.to_le_bytes());
+        Ok(())
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<(), Self::Error> {
+        self.output.push(v);
+        Ok(())
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<(), Self::Error> {
+        self.output.extend_from_slice(&v.to_le_bytes());
+        Ok(())
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<(), Self::Error> {
+        self.output.extend_from_slice(&v.to_le_bytes());
+        Ok(())
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<(), Self::Error> {
+        self.output.extend_from_slice(&v.to_le_bytes());
+        Ok(())
+    }
+
+    fn serialize_u128(self, v: u128) -> Result<(), Self::Error> {
+        self.output.extend_from_slice(&v.to_le_bytes());
+        Ok(())
+    }
+
+    fn serialize_f32(self, v: f32) -> Result<(), Self::Error> {
+        self.output.extend_from_slice(&v.to_le_bytes());
+        Ok(())
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<(), Self::Error> {
+        self.output.extend_from_slice(&v.to_le_bytes());
+        Ok(())
+    }
+
+    fn serialize_char(self, v: char) -> Result<(), Self::Error> {
+        self.output.extend_from_slice(&u32::from(v).to_le_bytes());
+        Ok(())
+    }
+
+    fn serialize_str(self, v: &str) -> Result<(), Self::Error> {
+        self.serialize_bytes(v.as_bytes())
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> Result<(), Self::Error> {
+        self.output.extend_from_slice(v);
+        self.write_length(v.len());
+        Ok(())
+    }
+
+    fn serialize_none(self) -> Result<(), Self::Error> {
+        self.output.push(0);
+        Ok(())
+    }
+
+    fn serialize_some<T: ?Sized + Serialize>(self, value: &T) -> Result<(), Self::Error> {
+        self.output.push(1);
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        variant_index: u32,
+        _variant: &'static str,
+    ) -> Result<(), Self::Error> {
+        self.write_variant(variant_index);
+        Ok(())
+    }
+
+    fn serialize_newtype_struct<T: ?Sized + Serialize>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<(), Self::Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: ?Sized + Serialize>(
+        self,
+        _name: &'static str,
+        variant_index: u32,
+        _variant: &'static str,
+        value: &T,
+    ) -> Result<(), Self::Error> {
+        self.write_variant(variant_index);
+        value.serialize(self)
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> Result<Self::SerializeSeq, Self::Error> {
+        Ok(CompoundEncoder {
+            encoder: self,
+            length: 0,
+        })
+    }
+
+    fn serialize_tuple(self, _len: usize) -> Result<Self::SerializeTuple, Self::Error> {
+        Ok(CompoundEncoder {
+            encoder: self,
+            length: 0,
+        })
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleStruct, Self::Error> {
+        Ok(CompoundEncoder {
+            encoder: self,
+            length: 0,
+        })
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleVariant, Self::Error> {
+        self.write_variant(variant_index);
+        Ok(CompoundEncoder {
+            encoder: self,
+            length: 0,
+        })
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap, Self::Error> {
+        Ok(MapEncoder {
+            encoder: self,
+            key: None,
+            entries: Vec::new(),
+        })
+    }
+
+    fn serialize_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStruct, Self::Error> {
+        Ok(CompoundEncoder {
+            encoder: self,
+            length: 0,
+        })
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStructVariant, Self::Error> {
+        self.write_variant(variant_index);
+        Ok(CompoundEncoder {
+            encoder: self,
+            length: 0,
+        })
+    }
+}
+
+impl ser::SerializeSeq for CompoundEncoder<'_> {
+    type Ok = ();
+    type Error = CanonicalEncodingError;
+
+    fn serialize_element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Self::Error> {
+        self.element(value)
+    }
+
+    fn end(self) -> Result<(), Self::Error> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeTuple for CompoundEncoder<'_> {
+    type Ok = ();
+    type Error = CanonicalEncodingError;
+
+    fn serialize_element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Self::Error> {
+        self.element(value)
+    }
+
+    fn end(self) -> Result<(), Self::Error> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeTupleStruct for CompoundEncoder<'_> {
+    type Ok = ();
+    type Error = CanonicalEncodingError;
+
+    fn serialize_field<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Self::Error> {
+        self.element(value)
+    }
+
+    fn end(self) -> Result<(), Self::Error> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeTupleVariant for CompoundEncoder<'_> {
+    type Ok = ();
+    type Error = CanonicalEncodingError;
+
+    fn serialize_field<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Self::Error> {
+        self.element(value)
+    }
+
+    fn end(self) -> Result<(), Self::Error> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeStruct for CompoundEncoder<'_> {
+    type Ok = ();
+    type Error = CanonicalEncodingError;
+
+    fn serialize_field<T: ?Sized + Serialize>(
+        &mut self,
+        _key: &'static str,
+        value: &T,
+    ) -> Result<(), Self::Error> {
+        self.element(value)
+    }
+
+    fn end(self) -> Result<(), Self::Error> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeStructVariant for CompoundEncoder<'_> {
+    type Ok = ();
+    type Error = CanonicalEncodingError;
+
+    fn serialize_field<T: ?Sized + Serialize>(
+        &mut self,
+        _key: &'static str,
+        value: &T,
+    ) -> Result<(), Self::Error> {
+        self.element(value)
+    }
+
+    fn end(self) -> Result<(), Self::Error> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeMap for MapEncoder<'_> {
+    type Ok = ();
+    type Error = CanonicalEncodingError;
+
+    fn serialize_key<T: ?Sized + Serialize>(&mut self, key: &T) -> Result<(), Self::Error> {
+        self.key = Some(encode(key)?);
+        Ok(())
+    }
+
+    fn serialize_value<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Self::Error> {
+        let key = self.key.take().ok_or_else(|| {
+            CanonicalEncodingError("Map value serialized without a key".to_string())
+        })?;
+        self.entries.push((key, encode(value)?));
+        Ok(())
+    }
+
+    fn end(mut self) -> Result<(), Self::Error> {
+        self.entries.sort();
+        let length = self.entries.len();
+        for (key, value) in self.entries {
+            self.encoder.output.extend_from_slice(&key);
+            self.encoder.write_length(key.len());
+            self.encoder.output.extend_from_slice(&value);
+            self.encoder.write_length(value.len());
+        }
+        self.encoder.write_length(length);
+        Ok(())
+    }
+}
@@ -10,6 +10,7 @@
 // express or implied. See the License for the specific language governing permissions and
 // limitations under the License.
 
+use crate::backends::{BackendCapabilities, Incompatibility};
 use crate::operations::{Define, InvolveQubits, InvolvedQubits, Operate, Operation, Substitute};
 #[cfg(feature = "overrotate")]
 use crate::operations::{Rotate, Rotation};
@@ -182,6 +183,20 @@ impl Circuit {
         self.definitions.iter().chain(self.operations.iter())
     }
 
+    /// Returns a stable hash of the Circuit.
+    ///
+    /// The hash does not depend on the platform or on the run of the program.
+    /// See [crate::backends::stable_hash_operations].
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(u128)` - The hash of the definitions and operations of the Circuit.
+    /// * `Err([RoqoqoError::SerializationError])` - An operation could not be serialized.
+    #[cfg(feature = "serialize")]
+    pub fn stable_hash(&self) -> Result<u128, RoqoqoError> {
+        crate::backends::stable_hash_operations(self.iter())
+    }
+
     /// Returns true if the Circuit contains symbolic variables.
     ///
     /// # Returns
@@ -337,6 +352,21 @@ impl Circuit {
         operations
     }
 
+    /// Returns all operations of the Circuit a backend with the given capabilities cannot run.
+    ///
+    /// See [BackendCapabilities::check_circuit].
+    ///
+    /// # Arguments
+    ///
+    /// * `capabilities` - The capabilities of the backend, e.g. from [crate::backends::EvaluatingBackend::capabilities].
+    ///
+    /// # Returns
+    ///
+    /// * `Vec<Incompatibility>` - The incompatible operations ordered by their index (empty if the backend can run the Circuit).
+    pub fn check_capabilities(&self, capabilities: &BackendCapabilities) -> Vec<Incompatibility> {
+        capabilities.check_circuit(self)
+    }
+
     /// Returns clone of the circuit with all Overrotation Pragmas applied.
     ///
     /// # Returns
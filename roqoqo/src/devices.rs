@@ -108,6 +108,25 @@ pub trait Device {
     /// * `None` - The qubit is not part of the device.
     fn qubit_decoherence_rates(&self, qubit: &usize) -> Option<Array2<f64>>;
 
+    /// Returns the readout confusion matrix of a qubit.
+    ///
+    /// The entry `[i, j]` of the confusion matrix is the probability to read out `i`
+    /// when the qubit is in state `j`.
+    /// Used to mitigate readout errors with [crate::measurements::ReadoutMitigation::from_device].
+    ///
+    /// # Arguments
+    ///
+    /// * `qubit` - The qubit for which the confusion matrix is returned.
+    ///
+    /// # Returns
+    ///
+    /// * `Some<Array2<f64>>` - The 2x2 confusion matrix.
+    /// * `None` - No readout error information is available for the qubit (default).
+    #[allow(unused_variables)]
+    fn readout_confusion_matrix(&self, qubit: &usize) -> Option<Array2<f64>> {
+        None
+    }
+
     /// Returns the number of qubits the device supports.
     fn number_qubits(&self) -> usize;
 
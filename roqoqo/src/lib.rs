@@ -202,6 +202,12 @@ pub enum RoqoqoError {
         /// Name of the expecataion value missing.
         name: String,
     },
+    /// Error adding a group of expectation values containing an undefined expectation value.
+    #[error("Expectation value {name} is not defined.")]
+    ExpValMissing {
+        /// Name of the missing expectation value.
+        name: String,
+    },
     /// Expected register is missing from the Output registers.
     #[error("OutputRegister {name} is missing.")]
     MissingRegister {
@@ -214,12 +220,38 @@ pub enum RoqoqoError {
         /// Error message.
         msg: String,
     },
+    /// Error occured in classical shadows measurement.
+    #[error("Error occured in classical shadows measurement. {msg}")]
+    ClassicalShadowsMeasurementError {
+        /// Error message.
+        msg: String,
+    },
+    /// Error occured in zero-noise extrapolation.
+    #[error("Error occured in zero-noise extrapolation. {msg}")]
+    ZeroNoiseExtrapolationError {
+        /// Error message.
+        msg: String,
+    },
+    /// Error occured in histogram measurement.
+    #[error("Error occured in histogram measurement. {msg}")]
+    HistogramMeasurementError {
+        /// Error message.
+        msg: String,
+    },
     /// Error serializing an internal roqoqo object
     #[error("An error occured serializing a roqoqo object: {msg} ")]
     SerializationError {
         /// Error message
         msg: String,
     },
+    /// Error when a JSON document does not match the JSON schema of a roqoqo object
+    #[error("JSON document does not match schema at {path}: {msg}")]
+    JsonValidationError {
+        /// JSON pointer to the value that does not match the schema
+        path: String,
+        /// Error message
+        msg: String,
+    },
     /// Generic error that does not fit in other error categories.
     #[error("An error occured in roqoqo: {msg} ")]
     GenericError {
@@ -306,6 +338,18 @@ pub use circuit::*;
 pub mod backends;
 pub mod devices;
 pub mod measurements;
+pub mod optimization;
+pub mod qir;
 mod quantum_program;
 pub mod registers;
-pub use quantum_program::QuantumProgram;
+pub mod render;
+#[cfg(feature = "json_schema")]
+pub mod schema;
+#[cfg(feature = "binary_format")]
+pub mod serialization;
+#[cfg(feature = "serialize")]
+pub mod streaming;
+pub use quantum_program::{
+    parameter_grid, OutputRegisters, ParameterSweepResult, QuantumProgram, QuantumProgramJob,
+    RunResult, SymbolWarning,
+};
@@ -0,0 +1,414 @@
+// Copyright © 2021 HQS Quantum Simulations GmbH. All Rights Reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except
+// in compliance with the License. You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the
+// License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either
+// express or implied. See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::statistical_errors::mean_and_gradient;
+use super::{BasisRotation, Measure, MeasureExpectationValues, MeasureExpectationValuesWithErrors};
+use crate::backends::EvaluatingBackend;
+use crate::operations::*;
+use crate::registers::{BitOutputRegister, ComplexOutputRegister, FloatOutputRegister};
+use crate::{Circuit, RoqoqoBackendError, RoqoqoError};
+use std::collections::HashMap;
+
+/// Default number of measurements of every circuit in the first round.
+const DEFAULT_INITIAL_SHOTS: usize = 100;
+/// Default number of measurements distributed over the circuits in every following round.
+const DEFAULT_SHOTS_PER_ROUND: usize = 1000;
+
+/// BasisRotation measurement that adapts the number of measurements to a target precision.
+///
+/// The number of measurements set with [crate::operations::PragmaRepeatedMeasurement]
+/// or [crate::operations::PragmaSetNumberOfMeasurements] in the circuits of the measurement is replaced:
+/// All circuits are first run with `initial_shots` measurements.
+/// In each following round `shots_per_round` measurements are distributed over the readout registers
+/// according to their contribution to the variance of the expectation values that have not reached the target precision.
+/// The measurement stops when the standard errors of all expectation values are below `target_standard_error`
+/// or when the total number of measurements reaches `shot_budget`.
+///
+/// Evaluating the measurement returns `Ok(None)` while more measurements are needed.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "json_schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct AdaptiveBasisRotation {
+    /// The BasisRotation measurement that is run.
+    pub measurement: BasisRotation,
+    /// The target standard error of all expectation values.
+    pub target_standard_error: f64,
+    /// The maximal total number of measurements summed over all readout registers.
+    pub shot_budget: usize,
+    /// The number of measurements of every circuit in the first round.
+    pub initial_shots: usize,
+    /// The number of measurements distributed over the readout registers in every following round.
+    pub shots_per_round: usize,
+}
+
+impl AdaptiveBasisRotation {
+    /// Creates a new AdaptiveBasisRotation.
+    ///
+    /// # Arguments
+    ///
+    /// * `measurement` - The BasisRotation measurement that is run.
+    /// * `target_standard_error` - The target standard error of all expectation values.
+    /// * `shot_budget` - The maximal total number of measurements summed over all readout registers.
+    ///
+    /// # Returns
+    ///
+    /// * `Self` - The new AdaptiveBasisRotation.
+    pub fn new(measurement: BasisRotation, target_standard_error: f64, shot_budget: usize) -> Self {
+        Self {
+            measurement,
+            target_standard_error,
+            shot_budget,
+            initial_shots: DEFAULT_INITIAL_SHOTS,
+            shots_per_round: DEFAULT_SHOTS_PER_ROUND,
+        }
+    }
+
+    /// Sets the number of measurements of every circuit in the first round.
+    ///
+    /// # Arguments
+    ///
+    /// * `initial_shots` - The number of measurements, at least two are needed to estimate the variance.
+    pub fn with_initial_shots(mut self, initial_shots: usize) -> Self {
+        self.initial_shots = initial_shots;
+        self
+    }
+
+    /// Sets the number of measurements distributed over the readout registers in every following round.
+    ///
+    /// # Arguments
+    ///
+    /// * `shots_per_round` - The number of measurements.
+    pub fn with_shots_per_round(mut self, shots_per_round: usize) -> Self {
+        self.shots_per_round = shots_per_round;
+        self
+    }
+
+    /// Returns the number of additional measurements for each readout register in the next round.
+    ///
+    /// The measurements are distributed to approach the allocation that minimizes the summed variance
+    /// of the expectation values above the target standard error,
+    /// where the number of measurements of a readout register is proportional to the square root of its
+    /// single shot variance contribution.
+    /// Flipped readout registers receive the same number of measurements as the corresponding readout register.
+    ///
+    /// # Arguments
+    ///
+    /// * `bit_registers` - The bit registers measured so far.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(HashMap<String, usize>)` - The additional measurements of each readout register (without the flipped extension).
+    /// * `Err(RoqoqoError)` - The statistics of the measured registers could not be evaluated.
+    pub fn shot_allocation(
+        &self,
+        bit_registers: &HashMap<String, BitOutputRegister>,
+    ) -> Result<HashMap<String, usize>, RoqoqoError> {
+        let (pauli_products, covariance) = self
+            .measurement
+            .pauli_product_statistics(bit_registers, true)?;
+        let covariance = covariance.ok_or_else(|| RoqoqoError::GenericError {
+            msg: "Covariance of the Pauli products could not be calculated".to_string(),
+        })?;
+        let input = &self.measurement.input;
+
+        let mut readouts: Vec<&String> = input.pauli_product_qubit_masks.keys().collect();
+        readouts.sort();
+        let mut variance_contributions: Vec<f64> = vec![0.0; readouts.len()];
+        for evaluation in input.measured_exp_vals.values() {
+            let (_, gradient) = mean_and_gradient(evaluation, &pauli_products, &covariance)?;
+            let variance = gradient.dot(&covariance.dot(&gradient));
+            if variance.sqrt() <= self.target_standard_error {
+                continue;
+            }
+            for (contribution, readout) in variance_contributions.iter_mut().zip(readouts.iter()) {
+                let indices: Vec<usize> = input.pauli_product_qubit_masks[*readout]
+                    .keys()
+                    .copied()
+                    .collect();
+                let mut readout_variance = 0.0;
+                for row in indices.iter() {
+                    for column in indices.iter() {
+                        readout_variance +=
+                            gradient[*row] * covariance[[*row, *column]] * gradient[*column];
+                    }
+                }
+                // Variance of the mean times the number of shots is the single shot variance
+                *contribution +=
+                    readout_variance * self.readout_shots(bit_registers, readout) as f64;
+            }
+        }
+
+        let cost_per_shot = if input.use_flipped_measurement { 2 } else { 1 };
+        let remaining = self
+            .shot_budget
+            .saturating_sub(self.total_shots(bit_registers));
+        let new_shots = self.shots_per_round.min(remaining / cost_per_shot);
+        let weights: Vec<f64> = variance_contributions.iter().map(|v| v.sqrt()).collect();
+        let total_weight: f64 = weights.iter().sum();
+        let mut allocation: HashMap<String, usize> = readouts
+            .iter()
+            .map(|readout| ((*readout).clone(), 0))
+            .collect();
+        if new_shots == 0 || total_weight == 0.0 {
+            return Ok(allocation);
+        }
+        // Shots needed to reach the optimal distribution of all shots after this round
+        let total_after_round = (new_shots
+            + readouts
+                .iter()
+                .map(|readout| self.readout_shots(bit_registers, readout))
+                .sum::<usize>()) as f64;
+        let mut deficits: Vec<f64> = weights
+            .iter()
+            .zip(readouts.iter())
+            .map(|(weight, readout)| {
+                (total_after_round * weight / total_weight
+                    - self.readout_shots(bit_registers, readout) as f64)
+                    .max(0.0)
+            })
+            .collect();
+        if deficits.iter().sum::<f64>() == 0.0 {
+            deficits = weights;
+        }
+        let total_deficit: f64 = deficits.iter().sum();
+        let exact: Vec<f64> = deficits
+            .iter()
+            .map(|deficit| new_shots as f64 * deficit / total_deficit)
+            .collect();
+        let mut assigned: Vec<usize> = exact.iter().map(|shots| shots.floor() as usize).collect();
+        // Distribute the shots lost to rounding by the size of the fractional parts
+        let mut order: Vec<usize> = (0..exact.len()).collect();
+        order.sort_by(|a, b| {
+            (exact[*b] - exact[*b].floor())
+                .partial_cmp(&(exact[*a] - exact[*a].floor()))
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+        let missing = new_shots - assigned.iter().sum::<usize>();
+        for index in order.into_iter().take(missing) {
+            assigned[index] += 1;
+        }
+        for (readout, shots) in readouts.iter().zip(assigned) {
+            allocation.insert((*readout).clone(), shots);
+        }
+        Ok(allocation)
+    }
+
+    /// Runs the measurement with adaptively allocated measurements on a backend.
+    ///
+    /// # Arguments
+    ///
+    /// * `backend` - The backend the circuits are run with.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Some(HashMap<String, f64>))` - The expectation values after reaching the target precision or the shot budget.
+    /// * `Ok(None)` - The evaluation of the measurement is incomplete.
+    /// * `Err(RoqoqoBackendError)` - Running or evaluating the measurement failed.
+    pub fn run<B>(&self, backend: &B) -> Result<Option<HashMap<String, f64>>, RoqoqoBackendError>
+    where
+        B: EvaluatingBackend,
+    {
+        let mut bit_registers: HashMap<String, BitOutputRegister> = HashMap::new();
+        let mut float_registers: HashMap<String, FloatOutputRegister> = HashMap::new();
+        let mut complex_registers: HashMap<String, ComplexOutputRegister> = HashMap::new();
+        let mut allocation: HashMap<String, usize> = self
+            .measurement
+            .input
+            .pauli_product_qubit_masks
+            .keys()
+            .map(|readout| (readout.clone(), self.initial_shots))
+            .collect();
+        loop {
+            for circuit in self.measurement.circuits.iter() {
+                let (circuit, number_measurements) = set_number_measurements(circuit, &allocation)?;
+                if number_measurements == 0 {
+                    continue;
+                }
+                let (tmp_bit_reg, tmp_float_reg, tmp_complex_reg) =
+                    match self.measurement.constant_circuit() {
+                        Some(x) => backend.run_circuit_iterator(x.iter().chain(circuit.iter()))?,
+                        None => backend.run_circuit_iterator(circuit.iter())?,
+                    };
+                append_registers(&mut bit_registers, tmp_bit_reg);
+                append_registers(&mut float_registers, tmp_float_reg);
+                append_registers(&mut complex_registers, tmp_complex_reg);
+            }
+            if let Some(result) = self.evaluate(
+                bit_registers.clone(),
+                float_registers.clone(),
+                complex_registers.clone(),
+            )? {
+                return Ok(Some(result));
+            }
+            allocation = self.shot_allocation(&bit_registers)?;
+            if allocation.values().all(|shots| *shots == 0) {
+                // No further measurement can reduce the variance
+                return Ok(self.measurement.evaluate(
+                    bit_registers,
+                    float_registers,
+                    complex_registers,
+                )?);
+            }
+        }
+    }
+
+    /// Returns the number of measurements of a readout register without the flipped extension.
+    fn readout_shots(
+        &self,
+        bit_registers: &HashMap<String, BitOutputRegister>,
+        readout: &str,
+    ) -> usize {
+        bit_registers
+            .get(readout)
+            .map_or(0, |register| register.len())
+    }
+
+    /// Returns the total number of measurements of all readout registers including flipped registers.
+    fn total_shots(&self, bit_registers: &HashMap<String, BitOutputRegister>) -> usize {
+        self.measurement
+            .input
+            .pauli_product_qubit_masks
+            .keys()
+            .map(|readout| {
+                self.readout_shots(bit_registers, readout)
+                    + self.readout_shots(bit_registers, &format!("{}_flipped", readout))
+            })
+            .sum()
+    }
+}
+
+impl Measure for AdaptiveBasisRotation {
+    /// Returns the circuits of the BasisRotation measurement.
+    fn circuits<'a>(&'a self) -> Box<dyn Iterator<Item = &'a Circuit> + 'a> {
+        self.measurement.circuits()
+    }
+
+    /// Returns the constant circuit of the BasisRotation measurement.
+    fn constant_circuit(&self) -> &Option<Circuit> {
+        self.measurement.constant_circuit()
+    }
+
+    /// Returns clone of the AdaptiveBasisRotation with symbolic parameters replaced.
+    fn substitute_parameters(
+        &self,
+        substituted_parameters: HashMap<String, f64>,
+    ) -> Result<Self, RoqoqoError> {
+        Ok(Self {
+            measurement: self
+                .measurement
+                .substitute_parameters(substituted_parameters)?,
+            ..self.clone()
+        })
+    }
+}
+
+impl MeasureExpectationValues for AdaptiveBasisRotation {
+    /// Evaluates the BasisRotation measurement when the target precision or the shot budget is reached.
+    ///
+    /// # Arguments
+    ///
+    /// * `bit_registers` - The classical bit registers as a HashMap with the register name as key.
+    /// * `float_registers` - The classical float registers as a HashMap with the register name as key.
+    /// * `complex_registers` - The classical complex registers as a HashMap with the register name as key.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Some(HashMap<String, f64>))` - The measurement has been evaluated successfully. The HashMap contains the measured expectation values.
+    /// * `Ok(None)` - The target precision has not been reached and the shot budget allows more measurements.
+    /// * `Err([RoqoqoError::BasisRotationMeasurementError])` - An error occured in basis rotation measurement.
+    fn evaluate(
+        &self,
+        bit_registers: HashMap<String, BitOutputRegister>,
+        float_registers: HashMap<String, FloatOutputRegister>,
+        complex_registers: HashMap<String, ComplexOutputRegister>,
+    ) -> Result<Option<HashMap<String, f64>>, RoqoqoError> {
+        let budget_reached = self.total_shots(&bit_registers) >= self.shot_budget;
+        let results = match self.measurement.evaluate_with_errors(
+            bit_registers,
+            float_registers,
+            complex_registers,
+        )? {
+            Some(results) => results,
+            None => return Ok(None),
+        };
+        let precision_reached = results
+            .expectation_values
+            .values()
+            .all(|value| value.standard_error <= self.target_standard_error);
+        if precision_reached || budget_reached {
+            Ok(Some(
+                results
+                    .expectation_values
+                    .into_iter()
+                    .map(|(name, value)| (name, value.mean))
+                    .collect(),
+            ))
+        } else {
+            Ok(None)
+        }
+    }
+}
+
+/// Returns a copy of the circuit with the number of measurements set for each readout register.
+///
+/// Flipped readout registers use the number of measurements of the corresponding readout register.
+/// Returns the largest number of measurements set in the circuit.
+fn set_number_measurements(
+    circuit: &Circuit,
+    allocation: &HashMap<String, usize>,
+) -> Result<(Circuit, usize), RoqoqoError> {
+    let shots = |readout: &String| -> usize {
+        let readout = readout.strip_suffix("_flipped").unwrap_or(readout);
+        allocation.get(readout).copied().unwrap_or(0)
+    };
+    let mut new_circuit = Circuit::new();
+    let mut number_measurements: Option<usize> = None;
+    for operation in circuit.iter() {
+        match operation {
+            Operation::PragmaRepeatedMeasurement(op) => {
+                let number = shots(op.readout());
+                number_measurements = Some(number_measurements.unwrap_or(0).max(number));
+                new_circuit += PragmaRepeatedMeasurement::new(
+                    op.readout().clone(),
+                    number,
+                    op.qubit_mapping().clone(),
+                );
+            }
+            Operation::PragmaSetNumberOfMeasurements(op) => {
+                let number = shots(op.readout());
+                number_measurements = Some(number_measurements.unwrap_or(0).max(number));
+                new_circuit += PragmaSetNumberOfMeasurements::new(number, op.readout().clone());
+            }
+            _ => new_circuit += operation.clone(),
+        }
+    }
+    match number_measurements {
+        Some(number) => Ok((new_circuit, number)),
+        None => Err(RoqoqoError::BasisRotationMeasurementError {
+            msg: "Circuit does not set the number of measurements with PragmaRepeatedMeasurement or PragmaSetNumberOfMeasurements".to_string(),
+        }),
+    }
+}
+
+/// Appends the registers of a single run to the accumulated registers.
+fn append_registers<T>(
+    registers: &mut HashMap<String, Vec<T>>,
+    new_registers: HashMap<String, Vec<T>>,
+) {
+    for (key, mut val) in new_registers.into_iter() {
+        if let Some(x) = registers.get_mut(&key) {
+            x.append(&mut val);
+        } else {
+            let _ = registers.insert(key, val);
+        }
+    }
+}
@@ -0,0 +1,593 @@
+// Copyright © 2021 HQS Quantum Simulations GmbH. All Rights Reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except
+// in compliance with the License. You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the
+// License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either
+// express or implied. See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::{BasisRotation, BasisRotationInput};
+use crate::operations::{
+    DefinitionBit, Hadamard, MeasureQubit, PauliX, PragmaRepeatedMeasurement,
+    PragmaSetNumberOfMeasurements, RotateX, SGate, CNOT,
+};
+use crate::{Circuit, RoqoqoError};
+use std::collections::{BTreeMap, HashMap};
+use std::f64::consts::FRAC_PI_2;
+
+/// Pauli product as a map from qubit to Pauli operator ('X', 'Y' or 'Z').
+pub type PauliString = BTreeMap<usize, char>;
+
+/// Strategy for grouping Pauli products that are measured with the same circuit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PauliGrouping {
+    /// Groups Pauli products that commute on every qubit.
+    ///
+    /// Each group is measured by rotating single qubits into the X, Y or Z basis.
+    QubitWise,
+    /// Groups Pauli products that commute.
+    ///
+    /// Each group is measured with a Clifford circuit built from [CNOT], [Hadamard] and [SGate]
+    /// gates that maps all Pauli products of the group to products of Z operators.
+    /// Needs fewer circuits than [PauliGrouping::QubitWise] at the cost of two-qubit gates.
+    GeneralCommuting,
+}
+
+/// Builds a [BasisRotation] measurement from operators given as sums of Pauli products.
+///
+/// Pauli products are written as strings of qubit indices followed by the Pauli operator
+/// acting on the qubit, e.g. `"0X1Z"` for `X_0 Z_1`. The empty string is the identity.
+/// The Pauli products of all operators are grouped into sets of commuting products
+/// according to the [PauliGrouping] (qubit-wise commuting by default).
+/// For each set a circuit is created that rotates the qubits into the common eigenbasis
+/// and measures all qubits into the readout register `ro_<index of set>`.
+///
+/// # Example
+///
+/// ```
+/// use roqoqo::measurements::BasisRotationBuilder;
+/// use std::collections::HashMap;
+///
+/// let mut hamiltonian: HashMap<String, f64> = HashMap::new();
+/// hamiltonian.insert("0X1Z".to_string(), 0.5);
+/// hamiltonian.insert("2Y".to_string(), -1.0);
+/// hamiltonian.insert("0Z".to_string(), 0.3);
+///
+/// let mut builder = BasisRotationBuilder::new(3, 1000);
+/// builder.add_operator("energy".to_string(), &hamiltonian).unwrap();
+/// let measurement = builder.build().unwrap();
+/// assert_eq!(measurement.circuits.len(), 2);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct BasisRotationBuilder {
+    /// Number of qubits that are measured.
+    number_qubits: usize,
+    /// Number of projective measurements of each circuit.
+    number_measurements: usize,
+    /// Whether the measurements are repeated with a final flip of all qubits.
+    use_flipped_measurement: bool,
+    /// Whether single qubits are measured with MeasureQubit instead of PragmaRepeatedMeasurement.
+    use_measure_qubit: bool,
+    /// Circuit executed before each basis rotation circuit.
+    constant_circuit: Option<Circuit>,
+    /// Strategy for grouping the Pauli products.
+    grouping: PauliGrouping,
+    /// Operators as sums of Pauli products with the name of the expectation value as key.
+    operators: Vec<(String, Vec<(PauliString, f64)>)>,
+}
+
+impl BasisRotationBuilder {
+    /// Creates new BasisRotationBuilder without operators.
+    ///
+    /// # Arguments
+    ///
+    /// * `number_qubits` - The number of qubits in the measurement.
+    /// * `number_measurements` - The number of projective measurements of each basis rotation circuit.
+    pub fn new(number_qubits: usize, number_measurements: usize) -> Self {
+        Self {
+            number_qubits,
+            number_measurements,
+            use_flipped_measurement: false,
+            use_measure_qubit: false,
+            constant_circuit: None,
+            grouping: PauliGrouping::QubitWise,
+            operators: Vec::new(),
+        }
+    }
+
+    /// Sets whether measurement errors are symmetrized with flipped measurements.
+    ///
+    /// For each basis rotation an additional circuit flipping all qubits before the readout
+    /// into `ro_<index of set>_flipped` is created.
+    pub fn with_flipped_measurement(mut self, use_flipped_measurement: bool) -> Self {
+        self.use_flipped_measurement = use_flipped_measurement;
+        self
+    }
+
+    /// Sets whether qubits are measured with [MeasureQubit] and [PragmaSetNumberOfMeasurements]
+    /// instead of a single [PragmaRepeatedMeasurement].
+    pub fn with_measure_qubit(mut self, use_measure_qubit: bool) -> Self {
+        self.use_measure_qubit = use_measure_qubit;
+        self
+    }
+
+    /// Sets the constant circuit executed before each basis rotation circuit, e.g. the state preparation.
+    pub fn with_constant_circuit(mut self, constant_circuit: Option<Circuit>) -> Self {
+        self.constant_circuit = constant_circuit;
+        self
+    }
+
+    /// Sets the strategy for grouping Pauli products into jointly measured sets.
+    pub fn with_grouping(mut self, grouping: PauliGrouping) -> Self {
+        self.grouping = grouping;
+        self
+    }
+
+    /// Adds an operator given as a sum of Pauli products whose expectation value is measured.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the expectation value.
+    /// * `operator` - The Pauli products (e.g. `"0X1Z"`) and their coefficients.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - The operator was added.
+    /// * `Err([RoqoqoError::ExpValUsedTwice])` - The name of the expectation value is already taken.
+    /// * `Err([RoqoqoError::BasisRotationMeasurementError])` - A Pauli product could not be parsed.
+    /// * `Err([RoqoqoError::PauliProductExceedsQubits])` - A Pauli product acts on a qubit outside the measured qubits.
+    pub fn add_operator(
+        &mut self,
+        name: String,
+        operator: &HashMap<String, f64>,
+    ) -> Result<(), RoqoqoError> {
+        if self.operators.iter().any(|(existing, _)| existing == &name) {
+            return Err(RoqoqoError::ExpValUsedTwice { name });
+        }
+        let mut terms: Vec<(PauliString, f64)> = Vec::with_capacity(operator.len());
+        for (pauli_product, coefficient) in operator.iter() {
+            let pauli_string = parse_pauli_product(pauli_product)?;
+            if let Some(qubit) = pauli_string
+                .keys()
+                .find(|qubit| **qubit >= self.number_qubits)
+            {
+                return Err(RoqoqoError::PauliProductExceedsQubits {
+                    pp_qubit: *qubit,
+                    number_qubits: self.number_qubits,
+                });
+            }
+            match terms
+                .iter_mut()
+                .find(|(existing, _)| existing == &pauli_string)
+            {
+                Some((_, existing_coefficient)) => *existing_coefficient += coefficient,
+                None => terms.push((pauli_string, *coefficient)),
+            }
+        }
+        self.operators.push((name, terms));
+        Ok(())
+    }
+
+    /// Builds the BasisRotation measurement.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(BasisRotation)` - The measurement with basis rotation circuits and measurement input.
+    /// * `Err(RoqoqoError)` - The measurement input could not be constructed.
+    pub fn build(self) -> Result<BasisRotation, RoqoqoError> {
+        let mut pauli_strings: Vec<&PauliString> = Vec::new();
+        for (_, terms) in self.operators.iter() {
+            for (pauli_string, _) in terms.iter() {
+                if !pauli_string.is_empty() && !pauli_strings.contains(&pauli_string) {
+                    pauli_strings.push(pauli_string);
+                }
+            }
+        }
+        let groups = match self.grouping {
+            PauliGrouping::QubitWise => group_qubit_wise_commuting(pauli_strings),
+            PauliGrouping::GeneralCommuting => {
+                group_general_commuting(pauli_strings, self.number_qubits)?
+            }
+        };
+
+        let mut input = BasisRotationInput::new(self.number_qubits, self.use_flipped_measurement);
+        let mut circuits: Vec<Circuit> = Vec::new();
+        for (index, group) in groups.iter().enumerate() {
+            let readout = format!("ro_{}", index);
+            circuits.push(self.basis_rotation_circuit(&group.rotation, &readout));
+            if self.use_flipped_measurement {
+                circuits.push(
+                    self.basis_rotation_circuit(&group.rotation, &format!("{}_flipped", readout)),
+                );
+            }
+        }
+        for (name, terms) in self.operators.iter() {
+            let mut linear: HashMap<usize, f64> = HashMap::new();
+            for (pauli_string, coefficient) in terms.iter() {
+                // The identity is constant and can be read from any register
+                let (group_index, mask, sign) = groups
+                    .iter()
+                    .enumerate()
+                    .find_map(|(index, group)| {
+                        group
+                            .pauli_products
+                            .iter()
+                            .find(|(member, _, _)| member == pauli_string)
+                            .map(|(_, mask, sign)| (index, mask.clone(), *sign))
+                    })
+                    .unwrap_or((0, Vec::new(), 1.0));
+                let pauli_product_index =
+                    input.add_pauli_product(format!("ro_{}", group_index), mask)?;
+                *linear.entry(pauli_product_index).or_insert(0.0) += sign * coefficient;
+            }
+            input.add_linear_exp_val(name.clone(), linear)?;
+        }
+        // Pauli products of the identity need a register even when nothing else is measured
+        if circuits.is_empty() && input.number_pauli_products > 0 {
+            circuits.push(self.basis_rotation_circuit(&Circuit::new(), "ro_0"));
+            if self.use_flipped_measurement {
+                circuits.push(self.basis_rotation_circuit(&Circuit::new(), "ro_0_flipped"));
+            }
+        }
+        Ok(BasisRotation {
+            constant_circuit: self.constant_circuit,
+            circuits,
+            input,
+        })
+    }
+
+    /// Creates the circuit measuring all qubits after the rotation into the computational basis.
+    fn basis_rotation_circuit(&self, rotation: &Circuit, readout: &str) -> Circuit {
+        let mut circuit = Circuit::new();
+        circuit += DefinitionBit::new(readout.to_string(), self.number_qubits, true);
+        circuit += rotation.clone();
+        if readout.ends_with("_flipped") {
+            for qubit in 0..self.number_qubits {
+                circuit += PauliX::new(qubit);
+            }
+        }
+        if self.use_measure_qubit {
+            for qubit in 0..self.number_qubits {
+                circuit += MeasureQubit::new(qubit, readout.to_string(), qubit);
+            }
+            circuit +=
+                PragmaSetNumberOfMeasurements::new(self.number_measurements, readout.to_string());
+        } else {
+            circuit +=
+                PragmaRepeatedMeasurement::new(readout.to_string(), self.number_measurements, None);
+        }
+        circuit
+    }
+}
+
+/// Parses a Pauli product like `"0X1Z"` into a map from qubits to Pauli operators.
+///
+/// # Arguments
+///
+/// * `pauli_product` - The Pauli product string, the empty string is the identity.
+///
+/// # Returns
+///
+/// * `Ok(PauliString)` - The parsed Pauli product.
+/// * `Err([RoqoqoError::BasisRotationMeasurementError])` - The string is not a valid Pauli product.
+pub fn parse_pauli_product(pauli_product: &str) -> Result<PauliString, RoqoqoError> {
+    let mut pauli_string = PauliString::new();
+    let mut qubit_digits = String::new();
+    for character in pauli_product.chars() {
+        match character {
+            '0'..='9' => qubit_digits.push(character),
+            'X' | 'Y' | 'Z' | 'I' => {
+                let qubit: usize = qubit_digits.parse().map_err(|_| {
+                    RoqoqoError::BasisRotationMeasurementError {
+                        msg: format!("Missing qubit index in Pauli product {}", pauli_product),
+                    }
+                })?;
+                qubit_digits.clear();
+                if character == 'I' {
+                    continue;
+                }
+                if pauli_string.insert(qubit, character).is_some() {
+                    return Err(RoqoqoError::BasisRotationMeasurementError {
+                        msg: format!(
+                            "Qubit {} appears twice in Pauli product {}",
+                            qubit, pauli_product
+                        ),
+                    });
+                }
+            }
+            _ => {
+                return Err(RoqoqoError::BasisRotationMeasurementError {
+                    msg: format!(
+                        "Unexpected character {} in Pauli product {}",
+                        character, pauli_product
+                    ),
+                })
+            }
+        }
+    }
+    if !qubit_digits.is_empty() {
+        return Err(RoqoqoError::BasisRotationMeasurementError {
+            msg: format!("Missing Pauli operator in Pauli product {}", pauli_product),
+        });
+    }
+    Ok(pauli_string)
+}
+
+/// Pauli products that are measured with the same basis rotation circuit.
+#[derive(Debug, Clone, PartialEq)]
+struct MeasurementGroup {
+    /// Gates rotating the common eigenbasis of the Pauli products into the computational basis.
+    rotation: Circuit,
+    /// The Pauli products with the qubits of the measured parity and the sign of the parity.
+    pauli_products: Vec<(PauliString, Vec<usize>, f64)>,
+}
+
+/// Groups Pauli products into sets of qubit-wise commuting products.
+///
+/// Uses a greedy first-fit assignment, starting with the Pauli products acting on the most qubits.
+fn group_qubit_wise_commuting(mut pauli_strings: Vec<&PauliString>) -> Vec<MeasurementGroup> {
+    pauli_strings.sort_by(|a, b| b.len().cmp(&a.len()).then_with(|| a.cmp(b)));
+    let mut groups: Vec<(PauliString, Vec<&PauliString>)> = Vec::new();
+    for pauli_string in pauli_strings {
+        let compatible = groups.iter_mut().find(|(basis, _)| {
+            pauli_string
+                .iter()
+                .all(|(qubit, pauli)| basis.get(qubit).map_or(true, |p| p == pauli))
+        });
+        match compatible {
+            Some((basis, members)) => {
+                basis.extend(pauli_string.iter().map(|(q, p)| (*q, *p)));
+                members.push(pauli_string);
+            }
+            None => groups.push((pauli_string.clone(), vec![pauli_string])),
+        }
+    }
+    groups
+        .into_iter()
+        .map(|(basis, members)| {
+            let mut rotation = Circuit::new();
+            for (qubit, pauli) in basis.iter() {
+                match pauli {
+                    'X' => rotation += Hadamard::new(*qubit),
+                    'Y' => rotation += RotateX::new(*qubit, FRAC_PI_2.into()),
+                    _ => (),
+                }
+            }
+            MeasurementGroup {
+                rotation,
+                pauli_products: members
+                    .into_iter()
+                    .map(|member| (member.clone(), member.keys().copied().collect(), 1.0))
+                    .collect(),
+            }
+        })
+        .collect()
+}
+
+/// Groups Pauli products into sets of commuting products.
+///
+/// Uses a greedy first-fit assignment, starting with the Pauli products acting on the most qubits,
+/// and synthesizes a Clifford circuit diagonalizing each set.
+fn group_general_commuting(
+    mut pauli_strings: Vec<&PauliString>,
+    number_qubits: usize,
+) -> Result<Vec<MeasurementGroup>, RoqoqoError> {
+    pauli_strings.sort_by(|a, b| b.len().cmp(&a.len()).then_with(|| a.cmp(b)));
+    let mut groups: Vec<Vec<(&PauliString, SymplecticPauli)>> = Vec::new();
+    for pauli_string in pauli_strings {
+        let symplectic = SymplecticPauli::new(pauli_string, number_qubits);
+        let compatible = groups.iter_mut().find(|members| {
+            members
+                .iter()
+                .all(|(_, member)| member.commutes_with(&symplectic))
+        });
+        match compatible {
+            Some(members) => members.push((pauli_string, symplectic)),
+            None => groups.push(vec![(pauli_string, symplectic)]),
+        }
+    }
+    groups
+        .into_iter()
+        .map(|members| {
+            let mut synthesis = CliffordSynthesis {
+                rotation: Circuit::new(),
+                generators: members.iter().map(|(_, p)| p.clone()).collect(),
+                pauli_products: members.iter().map(|(_, p)| p.clone()).collect(),
+            };
+            synthesis.diagonalize()?;
+            let mut pauli_products: Vec<(PauliString, Vec<usize>, f64)> =
+                Vec::with_capacity(members.len());
+            for ((member, _), diagonal) in members.iter().zip(synthesis.pauli_products.iter()) {
+                if diagonal.x.iter().any(|x| *x) {
+                    return Err(RoqoqoError::BasisRotationMeasurementError {
+                        msg: "Pauli products could not be diagonalized".to_string(),
+                    });
+                }
+                let mask: Vec<usize> = (0..number_qubits).filter(|q| diagonal.z[*q]).collect();
+                let sign = if diagonal.negative { -1.0 } else { 1.0 };
+                pauli_products.push(((*member).clone(), mask, sign));
+            }
+            Ok(MeasurementGroup {
+                rotation: synthesis.rotation,
+                pauli_products,
+            })
+        })
+        .collect()
+}
+
+/// Pauli product in the binary symplectic representation with a sign.
+///
+/// Qubit `q` carries I for `(x[q], z[q]) = (false, false)`, X for `(true, false)`,
+/// Z for `(false, true)` and Y for `(true, true)`.
+#[derive(Debug, Clone, PartialEq)]
+struct SymplecticPauli {
+    x: Vec<bool>,
+    z: Vec<bool>,
+    negative: bool,
+}
+
+impl SymplecticPauli {
+    fn new(pauli_string: &PauliString, number_qubits: usize) -> Self {
+        let mut x = vec![false; number_qubits];
+        let mut z = vec![false; number_qubits];
+        for (qubit, pauli) in pauli_string.iter() {
+            x[*qubit] = matches!(pauli, 'X' | 'Y');
+            z[*qubit] = matches!(pauli, 'Z' | 'Y');
+        }
+        Self {
+            x,
+            z,
+            negative: false,
+        }
+    }
+
+    fn commutes_with(&self, other: &Self) -> bool {
+        (0..self.x.len())
+            .filter(|q| (self.x[*q] && other.z[*q]) != (self.z[*q] && other.x[*q]))
+            .count()
+            % 2
+            == 0
+    }
+
+    fn is_identity(&self) -> bool {
+        !self.x.iter().chain(self.z.iter()).any(|bit| *bit)
+    }
+
+    /// Multiplies with another Pauli product ignoring the sign.
+    fn multiply_ignoring_sign(&mut self, other: &Self) {
+        for (x, other_x) in self.x.iter_mut().zip(other.x.iter()) {
+            *x ^= other_x;
+        }
+        for (z, other_z) in self.z.iter_mut().zip(other.z.iter()) {
+            *z ^= other_z;
+        }
+    }
+}
+
+/// Synthesis of a Clifford circuit mapping commuting Pauli products to products of Z operators.
+///
+/// Every gate is applied as a conjugation to an independent set of generators, which determines
+/// the next gate, and to the Pauli products of the group, which tracks their signs.
+struct CliffordSynthesis {
+    rotation: Circuit,
+    generators: Vec<SymplecticPauli>,
+    pauli_products: Vec<SymplecticPauli>,
+}
+
+impl CliffordSynthesis {
+    fn paulis_mut(&mut self) -> impl Iterator<Item = &mut SymplecticPauli> {
+        self.generators
+            .iter_mut()
+            .chain(self.pauli_products.iter_mut())
+    }
+
+    fn hadamard(&mut self, qubit: usize) {
+        for pauli in self.paulis_mut() {
+            pauli.negative ^= pauli.x[qubit] && pauli.z[qubit];
+            std::mem::swap(&mut pauli.x[qubit], &mut pauli.z[qubit]);
+        }
+        self.rotation += Hadamard::new(qubit);
+    }
+
+    fn s_gate(&mut self, qubit: usize) {
+        for pauli in self.paulis_mut() {
+            pauli.negative ^= pauli.x[qubit] && pauli.z[qubit];
+            pauli.z[qubit] ^= pauli.x[qubit];
+        }
+        self.rotation += SGate::new(qubit);
+    }
+
+    fn cnot(&mut self, control: usize, target: usize) {
+        for pauli in self.paulis_mut() {
+            pauli.negative ^=
+                pauli.x[control] && pauli.z[target] && (pauli.x[target] == pauli.z[control]);
+            pauli.x[target] ^= pauli.x[control];
+            pauli.z[control] ^= pauli.z[target];
+        }
+        self.rotation += CNOT::new(control, target);
+    }
+
+    fn controlled_z(&mut self, control: usize, target: usize) {
+        self.hadamard(target);
+        self.cnot(control, target);
+        self.hadamard(target);
+    }
+
+    /// Brings the X part of the generators into reduced row echelon form and removes dependent generators.
+    ///
+    /// Returns the pivot qubit of each generator with a non-zero X part, these generators come first.
+    fn reduce_x(&mut self) -> Vec<usize> {
+        let number_qubits = self.generators.first().map_or(0, |g| g.x.len());
+        let mut pivots: Vec<usize> = Vec::new();
+        for qubit in 0..number_qubits {
+            let row = pivots.len();
+            if let Some(pivot_row) =
+                (row..self.generators.len()).find(|r| self.generators[*r].x[qubit])
+            {
+                self.generators.swap(row, pivot_row);
+                let pivot = self.generators[row].clone();
+                for (index, generator) in self.generators.iter_mut().enumerate() {
+                    if index != row && generator.x[qubit] {
+                        generator.multiply_ignoring_sign(&pivot);
+                    }
+                }
+                pivots.push(qubit);
+            }
+        }
+        self.generators.retain(|generator| !generator.is_identity());
+        pivots
+    }
+
+    /// Applies gates until all generators, and with them all Pauli products, are products of Z operators.
+    fn diagonalize(&mut self) -> Result<(), RoqoqoError> {
+        let number_qubits = self.generators.first().map_or(0, |g| g.x.len());
+        // Hadamards until the X part of the independent generators has full rank
+        let mut pivots = self.reduce_x();
+        while let Some(generator) = self.generators.get(pivots.len()) {
+            let qubit = (0..number_qubits)
+                .find(|q| generator.z[*q] && !pivots.contains(q))
+                .ok_or_else(|| RoqoqoError::BasisRotationMeasurementError {
+                    msg: "Pauli products in a group do not commute".to_string(),
+                })?;
+            self.hadamard(qubit);
+            pivots = self.reduce_x();
+        }
+        let others: Vec<usize> = (0..number_qubits).filter(|q| !pivots.contains(q)).collect();
+        // CNOTs clearing the X part outside of the pivots
+        for (row, pivot) in pivots.iter().enumerate() {
+            for qubit in others.iter() {
+                if self.generators[row].x[*qubit] {
+                    self.cnot(*pivot, *qubit);
+                }
+            }
+        }
+        // Controlled-Z gates clearing the Z part outside of the pivots
+        for (row, pivot) in pivots.iter().enumerate() {
+            for qubit in others.iter() {
+                if self.generators[row].z[*qubit] {
+                    self.controlled_z(*pivot, *qubit);
+                }
+            }
+        }
+        // Controlled-Z gates clearing the symmetric Z part between pivots
+        for (row, pivot) in pivots.iter().enumerate() {
+            for other in pivots.iter().skip(row + 1) {
+                if self.generators[row].z[*other] {
+                    self.controlled_z(*pivot, *other);
+                }
+            }
+        }
+        // S gates turning Y into X and Hadamards turning X into Z on the pivots
+        for (row, pivot) in pivots.iter().enumerate() {
+            if self.generators[row].z[*pivot] {
+                self.s_gate(*pivot);
+            }
+            self.hadamard(*pivot);
+        }
+        Ok(())
+    }
+}
@@ -86,58 +86,43 @@ impl Measure for BasisRotation {
     }
 }
 
-impl MeasureExpectationValues for BasisRotation {
-    // TODO add optional device later for use with flipped measurement
-    #[allow(unused_variables)]
-    /// Executes the basis rotation measurement.
+impl BasisRotation {
+    /// Returns the measured expectation values of the Pauli products.
     ///
     /// # Arguments
     ///
     /// * `bit_registers` - The classical bit registers as a HashMap with the register name as key.
-    /// * `float_registers` - The classical float registers as a HashMap with the register name as key.
-    /// * `complex_registers` - The classical complex registers as a HashMap with the register name as key.
+    /// * `with_covariance` - Whether the covariance matrix of the Pauli products is calculated.
     ///
     /// # Returns
     ///
-    /// * `Ok(Some(HashMap<String, f64>))` - The measurement has been evaluated successfully. The HashMap contains the measured expectation values.
-    /// * `Ok(None)` - The measurement did not fail but is incomplete. A new round of measurements is needed
+    /// * `Ok((Array1<f64>, Option<Array2<f64>>))` - The expectation values of the Pauli products and their covariance matrix.
     /// * `Err([RoqoqoError::BasisRotationMeasurementError])` - An error occured in basis rotation measurement.
-    ///
-    fn evaluate(
+    pub(crate) fn pauli_product_statistics(
         &self,
-        bit_registers: HashMap<String, BitOutputRegister>,
-        float_registers: HashMap<String, FloatOutputRegister>,
-        complex_registers: HashMap<String, ComplexOutputRegister>,
-    ) -> Result<Option<HashMap<String, f64>>, RoqoqoError> {
-        // todo replace with actual input
-        let measurement_fidelities = vec![1.0; self.input.number_qubits];
-
-        // Setting up measurement correction factors for flipped measurement
-        let mut measurement_correction_factors: HashMap<String, Vec<f64>> = HashMap::new();
-        let flipped_and_extension: Vec<(bool, &'static str)>;
-        if self.input.use_flipped_measurement {
-            // helper vector to iterate over when evaluating the pauli products
-            flipped_and_extension = vec![(false, ""), (true, "_flipped")];
-            for (name, pauli_product_mask) in self.input.pauli_product_qubit_masks.iter() {
-                let mut measurement_correction_factor: Vec<f64> =
-                    (0..self.input.number_pauli_products)
-                        .into_iter()
-                        .map(|_| 1.0)
-                        .collect();
-                for (pp_index, indices) in pauli_product_mask.iter() {
-                    if !indices.is_empty() {
-                        for i in indices {
-                            measurement_correction_factor[*pp_index] *= measurement_fidelities[*i]
-                        }
-                    }
-                }
-                measurement_correction_factors.insert(name.clone(), measurement_correction_factor);
-            }
+        bit_registers: &HashMap<String, BitOutputRegister>,
+        with_covariance: bool,
+    ) -> Result<(Array1<f64>, Option<Array2<f64>>), RoqoqoError> {
+        let number_pauli_products = self.input.number_pauli_products;
+        // helper vector to iterate over when evaluating the pauli products
+        // Averaging between normal and flipped readout when flipped measurement is used
+        let (flipped_and_extension, weight): (Vec<(bool, &'static str)>, f64) =
+            if self.input.use_flipped_measurement {
+                (vec![(false, ""), (true, "_flipped")], 0.5)
+            } else {
+                (vec![(false, "")], 1.0)
+            };
+        let mut pauli_products: Array1<f64> = Array1::zeros(number_pauli_products);
+        let mut covariance: Option<Array2<f64>> = if with_covariance {
+            Some(Array2::zeros((
+                number_pauli_products,
+                number_pauli_products,
+            )))
         } else {
-            flipped_and_extension = vec![(false, "")];
-        }
-        let mut pauli_product_dict: HashMap<String, Array1<f64>> = HashMap::new();
+            None
+        };
         for (register_name, mask) in self.input.pauli_product_qubit_masks.iter() {
+            let indices: Vec<usize> = mask.keys().copied().collect();
             for (flip_measurement, extension) in flipped_and_extension.iter() {
                 let register = bit_registers
                     .get(&format!("{}{}", register_name.as_str(), extension))
@@ -148,14 +133,31 @@ impl MeasureExpectationValues for BasisRotation {
                             extension
                         ),
                     })?;
+                // Single shot values of the pauli products in the order of indices
                 let mut single_shot_pauli_products: Array2<f64> =
-                    Array2::zeros((register.len(), self.input.number_pauli_products));
-                for (index, mask_val) in mask.iter() {
+                    Array2::zeros((register.len(), indices.len()));
+                for (column_index, index) in indices.iter().enumerate() {
+                    let mask_val = &mask[index];
+                    let mut column = single_shot_pauli_products.column_mut(column_index);
+                    if let Some(mitigation) = &self.input.readout_mitigation {
+                        pauli_products[*index] += weight
+                            * mitigation.mitigated_pauli_product(
+                                register,
+                                mask_val,
+                                *flip_measurement,
+                            )?;
+                        if with_covariance {
+                            column.assign(&mitigation.single_shot_pauli_product(
+                                register,
+                                mask_val,
+                                *flip_measurement,
+                            )?);
+                        }
+                        continue;
+                    }
                     if mask_val.is_empty() {
-                        single_shot_pauli_products.column_mut(*index).fill(1.0);
+                        column.fill(1.0);
                     } else {
-                        // Accessing column of single_shot_pauli_products that corresponds to pauli product designated by index
-                        let mut column = single_shot_pauli_products.column_mut(*index);
                         // Iterate over all single shot readouts for all qubits and construct Pauli Product
                         for (row_index, values) in register.iter().enumerate() {
                             // Determine the value of the pauli product with the parity of the number of 0 and 1 measurements of the qubits
@@ -174,82 +176,153 @@ impl MeasureExpectationValues for BasisRotation {
                             };
                         }
                     }
+                    pauli_products[*index] += weight
+                        * column
+                            .mean()
+                            .ok_or(RoqoqoError::BasisRotationMeasurementError {
+                                msg: format!(
+                                    "Column {} out of index for sinlge_shot_pauli_products",
+                                    index
+                                ),
+                            })?;
                 }
-                let mut pauli_products_tmp: Array1<f64> =
-                    Array1::zeros(self.input.number_pauli_products);
-                for i in 0..self.input.number_pauli_products {
-                    pauli_products_tmp[i] = single_shot_pauli_products.column(i).mean().ok_or(
-                        RoqoqoError::BasisRotationMeasurementError {
-                            msg: format!(
-                                "Column {} out of index for sinlge_shot_pauli_products",
-                                i
-                            ),
-                        },
-                    )?;
+                if let Some(covariance) = covariance.as_mut() {
+                    // Pauli products measured in different registers are statistically independent
+                    let register_covariance =
+                        covariance_of_means(single_shot_pauli_products.view()).map_err(|err| {
+                            RoqoqoError::BasisRotationMeasurementError {
+                                msg: format!(
+                                    "bit register {}{}: {}",
+                                    register_name.as_str(),
+                                    extension,
+                                    err
+                                ),
+                            }
+                        })?;
+                    for (row, index_row) in indices.iter().enumerate() {
+                        for (column, index_column) in indices.iter().enumerate() {
+                            covariance[[*index_row, *index_column]] +=
+                                weight * weight * register_covariance[[row, column]];
+                        }
+                    }
                 }
-                pauli_product_dict.insert(
-                    format!("{}{}", register_name.as_str(), extension),
-                    pauli_products_tmp,
-                );
             }
         }
+        Ok((pauli_products, covariance))
+    }
+}
 
-        let mut pauli_products: Array1<f64> = Array1::zeros(self.input.number_pauli_products);
-        for (register_name, _) in self.input.pauli_product_qubit_masks.iter() {
-            if !register_name.ends_with("flipped") {
-                // Create temporary averaged vector of pauli_products
-                // Averaging between normal and flipped readout when flipped measurement is used
-                if self.input.use_flipped_measurement {
-                    let tmp_pauli_products = (&pauli_product_dict
-                        .get(register_name.as_str())
-                        .ok_or(RoqoqoError::BasisRotationMeasurementError {
-                            msg: format!("Register name {} not fount", register_name),
-                        })?
-                        .view()
-                        + &pauli_product_dict
-                            .get(format!("{}_flipped", register_name).as_str())
-                            .ok_or(RoqoqoError::BasisRotationMeasurementError {
-                                msg: format!("Register name {}_flipped not fount", register_name),
-                            })?
-                            .view())
-                        / 2.0;
-                    // reinserting in dict of pauli products
-                    pauli_products += &tmp_pauli_products.view();
-                } else {
-                    pauli_products += &pauli_product_dict
-                        .get(register_name.as_str())
-                        .ok_or(RoqoqoError::BasisRotationMeasurementError {
-                            msg: format!("Register name {} not fount", register_name),
-                        })?
-                        .view()
-                }
-            }
-        }
+impl MeasureExpectationValues for BasisRotation {
+    #[allow(unused_variables)]
+    /// Executes the basis rotation measurement.
+    ///
+    /// When the input contains a [ReadoutMitigation], the expectation values of the Pauli products
+    /// are corrected for readout errors.
+    ///
+    /// # Arguments
+    ///
+    /// * `bit_registers` - The classical bit registers as a HashMap with the register name as key.
+    /// * `float_registers` - The classical float registers as a HashMap with the register name as key.
+    /// * `complex_registers` - The classical complex registers as a HashMap with the register name as key.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Some(HashMap<String, f64>))` - The measurement has been evaluated successfully. The HashMap contains the measured expectation values.
+    /// * `Ok(None)` - The measurement did not fail but is incomplete. A new round of measurements is needed
+    /// * `Err([RoqoqoError::BasisRotationMeasurementError])` - An error occured in basis rotation measurement.
+    ///
+    fn evaluate(
+        &self,
+        bit_registers: HashMap<String, BitOutputRegister>,
+        float_registers: HashMap<String, FloatOutputRegister>,
+        complex_registers: HashMap<String, ComplexOutputRegister>,
+    ) -> Result<Option<HashMap<String, f64>>, RoqoqoError> {
+        let (pauli_products, _) = self.pauli_product_statistics(&bit_registers, false)?;
         // Evaluating expectation values
         let mut results: HashMap<String, f64> = HashMap::new();
 
         for (name, evaluation) in self.input.measured_exp_vals.iter() {
-            results.insert(
-                name.clone(),
-                match evaluation {
-                    PauliProductsToExpVal::Linear(hm) => {
-                        let mut value: f64 = 0.0;
-                        for (index, coefficient) in hm {
-                            value += pauli_products[*index] * coefficient;
-                        }
-                        value
-                    }
-                    PauliProductsToExpVal::Symbolic(x) => {
-                        let mut calculator = qoqo_calculator::Calculator::new();
-                        for (ind, p) in pauli_products.iter().enumerate() {
-                            calculator.set_variable(format!("pauli_product_{}", ind).as_str(), *p);
-                        }
-                        calculator.parse_get(x.clone())?
-                    }
-                },
-            );
+            results.insert(name.clone(), evaluation.complex_value(&pauli_products)?.re);
         }
 
         Ok(Some(results))
     }
+
+    /// Returns true if all expectation values are linear combinations of Pauli products and no readout mitigation is applied.
+    fn is_linear(&self) -> bool {
+        self.input.readout_mitigation.is_none()
+            && self
+                .input
+                .measured_exp_vals
+                .values()
+                .all(|exp_val| exp_val.is_linear())
+    }
+}
+
+impl MeasureComplexExpectationValues for BasisRotation {
+    /// Executes the basis rotation measurement and returns complex expectation values.
+    ///
+    /// # Arguments
+    ///
+    /// * `bit_registers` - The classical bit registers as a HashMap with the register name as key.
+    /// * `float_registers` - The classical float registers as a HashMap with the register name as key.
+    /// * `complex_registers` - The classical complex registers as a HashMap with the register name as key.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Some(ComplexExpectationValues))` - The measurement has been evaluated successfully.
+    /// * `Ok(None)` - The measurement did not fail but is incomplete. A new round of measurements is needed
+    /// * `Err([RoqoqoError::BasisRotationMeasurementError])` - An error occured in basis rotation measurement.
+    #[allow(unused_variables)]
+    fn evaluate_complex(
+        &self,
+        bit_registers: HashMap<String, BitOutputRegister>,
+        float_registers: HashMap<String, FloatOutputRegister>,
+        complex_registers: HashMap<String, ComplexOutputRegister>,
+    ) -> Result<Option<ComplexExpectationValues>, RoqoqoError> {
+        let (pauli_products, _) = self.pauli_product_statistics(&bit_registers, false)?;
+        Ok(Some(ComplexExpectationValues::from_groups(
+            complex_exp_vals_from_pauli_products(&self.input.measured_exp_vals, &pauli_products)?,
+            &self.input.exp_val_groups,
+        )?))
+    }
+}
+
+impl MeasureExpectationValuesWithErrors for BasisRotation {
+    #[allow(unused_variables)]
+    /// Executes the basis rotation measurement and estimates the shot noise of the expectation values.
+    ///
+    /// The covariance of the Pauli products is estimated from the single shot results of each readout register.
+    /// With iterative Bayesian unfolding the errors are linearized around the tensored inversion.
+    ///
+    /// # Arguments
+    ///
+    /// * `bit_registers` - The classical bit registers as a HashMap with the register name as key.
+    /// * `float_registers` - The classical float registers as a HashMap with the register name as key.
+    /// * `complex_registers` - The classical complex registers as a HashMap with the register name as key.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Some(ExpectationValuesWithErrors))` - The measurement has been evaluated successfully.
+    /// * `Ok(None)` - The measurement did not fail but is incomplete. A new round of measurements is needed
+    /// * `Err([RoqoqoError::BasisRotationMeasurementError])` - An error occured in basis rotation measurement, e.g. a register with less than two shots.
+    fn evaluate_with_errors(
+        &self,
+        bit_registers: HashMap<String, BitOutputRegister>,
+        float_registers: HashMap<String, FloatOutputRegister>,
+        complex_registers: HashMap<String, ComplexOutputRegister>,
+    ) -> Result<Option<ExpectationValuesWithErrors>, RoqoqoError> {
+        let (pauli_products, covariance) = self.pauli_product_statistics(&bit_registers, true)?;
+        let covariance = covariance.ok_or_else(|| RoqoqoError::GenericError {
+            msg: "Covariance of the Pauli products could not be calculated".to_string(),
+        })?;
+        Ok(Some(ExpectationValuesWithErrors {
+            expectation_values: propagate_errors(
+                &self.input.measured_exp_vals,
+                &pauli_products,
+                &covariance,
+            )?,
+            pauli_product_covariance: covariance,
+        }))
+    }
 }
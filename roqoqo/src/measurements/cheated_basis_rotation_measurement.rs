@@ -11,7 +11,7 @@
 // limitations under the License.
 
 use super::*;
-use ndarray::Array1;
+use ndarray::{Array1, Array2};
 #[cfg(feature = "serialize")]
 use serde::{Deserialize, Serialize};
 
@@ -88,6 +88,8 @@ impl Measure for CheatedBasisRotation {
 impl MeasureExpectationValues for CheatedBasisRotation {
     /// Executes the cheated basis rotation measurement
     ///
+    /// The Pauli products are the means over all rows of their float registers.
+    ///
     /// # Arguments
     ///
     /// * `bit_registers` - The classical bit registers as a HashMap with the register name as key
@@ -107,39 +109,128 @@ impl MeasureExpectationValues for CheatedBasisRotation {
         float_registers: HashMap<String, FloatOutputRegister>,
         complex_registers: HashMap<String, ComplexOutputRegister>,
     ) -> Result<Option<HashMap<String, f64>>, RoqoqoError> {
-        let mut pauli_products: Array1<f64> = Array1::zeros(self.input.pauli_product_keys.len());
-        for (register_name, register) in float_registers.iter() {
-            if let Some(index) = self.input.pauli_product_keys.get(register_name) {
-                pauli_products[*index] = register[0][0];
-            }
-        }
+        let pauli_products = self.pauli_product_values(&float_registers);
         // Evaluating expectation values
         let mut results: HashMap<String, f64> = HashMap::new();
 
         for (name, evaluation) in self.input.measured_exp_vals.iter() {
-            results.insert(
-                name.clone(),
-                match evaluation {
-                    PauliProductsToExpVal::Linear(hm) => {
-                        let mut value: f64 = 0.0;
-                        for (index, coefficient) in hm {
-                            value += pauli_products[*index] * coefficient;
-                        }
-                        value
-                    }
-                    PauliProductsToExpVal::Symbolic(x) => {
-                        let mut calculator = qoqo_calculator::Calculator::new();
-                        for (ind, p) in pauli_products.iter().enumerate() {
-                            dbg!(ind);
-                            dbg!(p);
-                            calculator.set_variable(format!("pauli_product_{}", ind).as_str(), *p);
-                        }
-                        calculator.parse_get(x.clone())?
-                    }
-                },
-            );
+            results.insert(name.clone(), evaluation.complex_value(&pauli_products)?.re);
         }
 
         Ok(Some(results))
     }
+
+    /// Returns true if all expectation values are linear combinations of Pauli products.
+    fn is_linear(&self) -> bool {
+        self.input
+            .measured_exp_vals
+            .values()
+            .all(|exp_val| exp_val.is_linear())
+    }
+}
+
+impl MeasureComplexExpectationValues for CheatedBasisRotation {
+    /// Executes the cheated basis rotation measurement and returns complex expectation values.
+    ///
+    /// # Arguments
+    ///
+    /// * `bit_registers` - The classical bit registers as a HashMap with the register name as key
+    /// * `float_registers` - The classical float registers as a HashMap with the register name as key
+    /// * `complex_registers` - The classical complex registers as a HashMap with the register name as key
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Some(ComplexExpectationValues))` - The measurement has been evaluated successfully
+    /// * `Ok(None)` - The measurement did not fail but is incomplete. A new round of measurements is needed
+    /// * `Err(RoqoqoError)` - Calculator parsing error.
+    #[allow(unused_variables)]
+    fn evaluate_complex(
+        &self,
+        bit_registers: HashMap<String, BitOutputRegister>,
+        float_registers: HashMap<String, FloatOutputRegister>,
+        complex_registers: HashMap<String, ComplexOutputRegister>,
+    ) -> Result<Option<ComplexExpectationValues>, RoqoqoError> {
+        let pauli_products = self.pauli_product_values(&float_registers);
+        Ok(Some(ComplexExpectationValues::from_groups(
+            complex_exp_vals_from_pauli_products(&self.input.measured_exp_vals, &pauli_products)?,
+            &self.input.exp_val_groups,
+        )?))
+    }
+}
+
+impl CheatedBasisRotation {
+    /// Returns the expectation values of the Pauli products read from the float registers.
+    ///
+    /// Each row of a float register is treated as an independent trajectory of a stochastic simulation
+    /// and the Pauli product is the mean over all trajectories.
+    fn pauli_product_values(
+        &self,
+        float_registers: &HashMap<String, FloatOutputRegister>,
+    ) -> Array1<f64> {
+        let mut pauli_products: Array1<f64> = Array1::zeros(self.input.pauli_product_keys.len());
+        for (register_name, register) in float_registers.iter() {
+            if let Some(index) = self.input.pauli_product_keys.get(register_name) {
+                if let Some(mean) = trajectories(register).mean() {
+                    pauli_products[*index] = mean;
+                }
+            }
+        }
+        pauli_products
+    }
+}
+
+impl MeasureExpectationValuesWithErrors for CheatedBasisRotation {
+    /// Executes the cheated basis rotation measurement and estimates the statistical errors.
+    ///
+    /// Each row of a float register is treated as an independent trajectory of a stochastic simulation
+    /// and the Pauli product is the mean over all trajectories.
+    /// The Pauli products of different registers are treated as statistically independent.
+    /// Registers with a single entry are exact and have no statistical error.
+    ///
+    /// # Arguments
+    ///
+    /// * `bit_registers` - The classical bit registers as a HashMap with the register name as key
+    /// * `float_registers` - The classical float registers as a HashMap with the register name as key
+    /// * `complex_registers` - The classical complex registers as a HashMap with the register name as key
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Some(ExpectationValuesWithErrors))` - The measurement has been evaluated successfully
+    /// * `Ok(None)` - The measurement did not fail but is incomplete. A new round of measurements is needed
+    /// * `Err(RoqoqoError)` - Calculator parsing error.
+    #[allow(unused_variables)]
+    fn evaluate_with_errors(
+        &self,
+        bit_registers: HashMap<String, BitOutputRegister>,
+        float_registers: HashMap<String, FloatOutputRegister>,
+        complex_registers: HashMap<String, ComplexOutputRegister>,
+    ) -> Result<Option<ExpectationValuesWithErrors>, RoqoqoError> {
+        let number_pauli_products = self.input.pauli_product_keys.len();
+        let pauli_products = self.pauli_product_values(&float_registers);
+        let mut covariance: Array2<f64> =
+            Array2::zeros((number_pauli_products, number_pauli_products));
+        for (register_name, register) in float_registers.iter() {
+            if let Some(index) = self.input.pauli_product_keys.get(register_name) {
+                if let Some(value) = mean_with_error(&trajectories(register)) {
+                    covariance[[*index, *index]] = value.standard_error.powi(2);
+                }
+            }
+        }
+        Ok(Some(ExpectationValuesWithErrors {
+            expectation_values: propagate_errors(
+                &self.input.measured_exp_vals,
+                &pauli_products,
+                &covariance,
+            )?,
+            pauli_product_covariance: covariance,
+        }))
+    }
+}
+
+/// Returns the first entry of every row of a float register.
+fn trajectories(register: &FloatOutputRegister) -> Array1<f64> {
+    register
+        .iter()
+        .filter_map(|values| values.first().copied())
+        .collect()
 }
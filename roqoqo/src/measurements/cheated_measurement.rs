@@ -10,14 +10,18 @@
 // express or implied. See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::measurements::{CheatedInput, Measure, MeasureExpectationValues};
+use crate::measurements::{
+    mean_with_error, CheatedInput, ComplexExpectationValues, ExpectationValuesWithErrors, Measure,
+    MeasureComplexExpectationValues, MeasureExpectationValues, MeasureExpectationValuesWithErrors,
+    SparseOperatorCsr,
+};
 use crate::registers::{BitOutputRegister, ComplexOutputRegister, FloatOutputRegister};
 use crate::Circuit;
 use crate::RoqoqoError;
-use ndarray::{Array1, ArrayView1, ArrayView2};
-//use ndarray::{Array2};
+use ndarray::{Array1, Array2};
 use num_complex::Complex64;
-//use sprs::{CsMat, TriMat};
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
 use std::collections::HashMap;
 
 /// Cheated measurement using state obtained from simulator backend.
@@ -108,6 +112,7 @@ impl MeasureExpectationValues for Cheated {
     /// * `Ok(None)` - The measurement did not fail but is incomplete. A new round of measurements is needed.
     /// * `Err([RoqoqoError::MissingRegister])` - The OutputRegister is missing.
     /// * `Err([RoqoqoError::MismatchedRegisterDimension])` - The dimension of register exceeds Hilbert space dimension of qubits.
+    /// * `Err([RoqoqoError::MismatchedOperatorDimension])` - The index of an operator exceeds Hilbert space dimension of qubits.
     #[allow(unused_variables)]
     fn evaluate(
         &self,
@@ -115,92 +120,187 @@ impl MeasureExpectationValues for Cheated {
         float_registers: HashMap<String, FloatOutputRegister>,
         complex_registers: HashMap<String, ComplexOutputRegister>,
     ) -> Result<Option<HashMap<String, f64>>, RoqoqoError> {
-        let dimension = 2_usize.pow(self.input.number_qubits as u32);
         // Evaluating expectation values
         let mut results: HashMap<String, f64> = HashMap::new();
-        for (name, (operator, readout)) in self.input.measured_operators.iter() {
-            // let row_inds: Vec<usize> = operator.iter().map(|(row, _, _)| row).cloned().collect();
-            // let col_inds: Vec<usize> = operator.iter().map(|(_, col, _)| col).cloned().collect();
-            // let data: Vec<Complex64> = operator.iter().map(|(_, _, val)| val).cloned().collect();
-            // let tmp_tri = TriMat::from_triplets((dimension, dimension), row_inds, col_inds, data);
-            // let matrix: CsMat<Complex64> = tmp_tri.to_csc();
-            let register_vec =
-                complex_registers
-                    .get(readout)
-                    .ok_or_else(|| RoqoqoError::MissingRegister {
-                        name: readout.clone(),
-                    })?;
-            let mut local_results: Array1<f64> = Array1::zeros(register_vec.len());
-            for (index, register) in register_vec.iter().enumerate() {
-                if register.len() == dimension {
-                    let vector: ArrayView1<Complex64> = ArrayView1::<Complex64>::from(register);
-                    // let complex_conj_vec: Vec<Complex64> =
-                    //     register.iter().map(|x| x.conj()).collect();
-                    // let complex_conj: ArrayView1<Complex64> =
-                    //     ArrayView1::<Complex64>::from(&complex_conj_vec);
-                    // let tmp_val: Complex64 = complex_conj.dot(&(&matrix * &vector));
-                    let tmp_val: Complex64 =
-                        sparse_matrix_vector_expectation_value(operator, &vector);
-                    local_results[index] = tmp_val.re;
-                } else if register.len() == dimension * dimension {
-                    let vector: ArrayView2<Complex64> =
-                        ArrayView2::<Complex64>::from_shape((dimension, dimension), register)
-                            .expect("Unexpected error reshaping array");
-                    // let complex_conj_vec: Vec<Complex64> =
-                    //     register.iter().map(|x| x.conj()).collect();
-                    // let complex_conj: Array2<Complex64> = Array2::<Complex64>::from_shape_vec(
-                    //     (dimension, dimension),
-                    //     complex_conj_vec,
-                    // )
-                    // .expect("Unexpected error reshaping array");
-                    // let tmp_val: Complex64 =
-                    //     ((complex_conj.t()).dot(&(&matrix * &vector))).diag().sum();
-                    let tmp_val =
-                        sparse_matrix_matrix_expectation_value(operator, &vector, dimension);
-                    local_results[index] = tmp_val.re;
-                } else {
-                    return Err(RoqoqoError::MismatchedRegisterDimension {
-                        dim: register.len(),
-                        number_qubits: self.input.number_qubits,
-                    });
-                }
-            }
-
+        for (name, local_results) in
+            self.complex_trajectory_expectation_values(&complex_registers)?
+        {
             results.insert(
-                name.clone(),
-                local_results.mean().expect(
+                name,
+                local_results.mapv(|value| value.re).mean().expect(
                     "Unexpectedly could not calculate mean of expectation values of register",
                 ),
             );
         }
         Ok(Some(results))
     }
+
+    /// Returns true, as the expectation values of operators are linear in the state.
+    fn is_linear(&self) -> bool {
+        true
+    }
 }
 
-#[inline]
-fn sparse_matrix_vector_expectation_value(
-    matrix: &[(usize, usize, Complex64)],
-    vector: &ArrayView1<Complex64>,
-) -> Complex64 {
-    let mut val: Complex64 = Complex64::new(0.0, 0.0);
-    for (index_i, index_j, value) in matrix {
-        val += vector[*index_i].conj() * value * vector[*index_j]
+impl MeasureExpectationValuesWithErrors for Cheated {
+    /// Executes the cheated measurement and estimates the statistical errors.
+    ///
+    /// Each entry of a complex register is treated as an independent trajectory of a stochastic simulation.
+    /// The standard error is the standard deviation of the trajectory expectation values divided by the
+    /// square root of the number of trajectories. A single trajectory is exact and has no statistical error.
+    ///
+    /// # Arguments
+    ///
+    /// * `bit_registers` - The classical bit registers as a HashMap with the register name as key.
+    /// * `float_registers` - The classical float registers as a HashMap with the register name as key.
+    /// * `complex_registers` - The classical complex registers as a HashMap with the register name as key.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Some(ExpectationValuesWithErrors))` - The measurement has been evaluated successfully. The covariance of Pauli products is empty.
+    /// * `Ok(None)` - The measurement did not fail but is incomplete. A new round of measurements is needed.
+    /// * `Err([RoqoqoError::MissingRegister])` - The OutputRegister is missing.
+    /// * `Err([RoqoqoError::MismatchedRegisterDimension])` - The dimension of register exceeds Hilbert space dimension of qubits.
+    /// * `Err([RoqoqoError::MismatchedOperatorDimension])` - The index of an operator exceeds Hilbert space dimension of qubits.
+    #[allow(unused_variables)]
+    fn evaluate_with_errors(
+        &self,
+        bit_registers: HashMap<String, BitOutputRegister>,
+        float_registers: HashMap<String, FloatOutputRegister>,
+        complex_registers: HashMap<String, ComplexOutputRegister>,
+    ) -> Result<Option<ExpectationValuesWithErrors>, RoqoqoError> {
+        let mut expectation_values = HashMap::new();
+        for (name, local_results) in
+            self.complex_trajectory_expectation_values(&complex_registers)?
+        {
+            expectation_values.insert(
+                name,
+                mean_with_error(&local_results.mapv(|value| value.re)).expect(
+                    "Unexpectedly could not calculate mean of expectation values of register",
+                ),
+            );
+        }
+        Ok(Some(ExpectationValuesWithErrors {
+            expectation_values,
+            pauli_product_covariance: Array2::zeros((0, 0)),
+        }))
     }
-    val
 }
 
-#[inline]
-fn sparse_matrix_matrix_expectation_value(
-    matrix: &[(usize, usize, Complex64)],
-    dense_matrix: &ArrayView2<Complex64>,
-    dimension: usize,
-) -> Complex64 {
-    let mut val: Complex64 = Complex64::new(0.0, 0.0);
-    for (index_j, index_k, value) in matrix {
-        for index_i in 0..dimension {
-            val +=
-                dense_matrix[(*index_j, index_i)].conj() * value * dense_matrix[(*index_k, index_i)]
+impl MeasureComplexExpectationValues for Cheated {
+    /// Executes the cheated measurement and returns complex expectation values.
+    ///
+    /// The expectation values of non-hermitian operators keep their imaginary part.
+    ///
+    /// # Arguments
+    ///
+    /// * `bit_registers` - The classical bit registers as a HashMap with the register name as key.
+    /// * `float_registers` - The classical float registers as a HashMap with the register name as key.
+    /// * `complex_registers` - The classical complex registers as a HashMap with the register name as key.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Some(ComplexExpectationValues))` - The measurement has been evaluated successfully.
+    /// * `Ok(None)` - The measurement did not fail but is incomplete. A new round of measurements is needed.
+    /// * `Err([RoqoqoError::MissingRegister])` - The OutputRegister is missing.
+    /// * `Err([RoqoqoError::MismatchedRegisterDimension])` - The dimension of register exceeds Hilbert space dimension of qubits.
+    /// * `Err([RoqoqoError::MismatchedOperatorDimension])` - The index of an operator exceeds Hilbert space dimension of qubits.
+    #[allow(unused_variables)]
+    fn evaluate_complex(
+        &self,
+        bit_registers: HashMap<String, BitOutputRegister>,
+        float_registers: HashMap<String, FloatOutputRegister>,
+        complex_registers: HashMap<String, ComplexOutputRegister>,
+    ) -> Result<Option<ComplexExpectationValues>, RoqoqoError> {
+        let mut results: HashMap<String, Complex64> = HashMap::new();
+        for (name, local_results) in
+            self.complex_trajectory_expectation_values(&complex_registers)?
+        {
+            results.insert(
+                name,
+                local_results.mean().expect(
+                    "Unexpectedly could not calculate mean of expectation values of register",
+                ),
+            );
         }
+        Ok(Some(ComplexExpectationValues::from_groups(
+            results,
+            &self.input.exp_val_groups,
+        )?))
     }
-    val
+}
+
+impl Cheated {
+    /// Returns the complex expectation values of all measured operators for every state in the complex registers.
+    ///
+    /// The operators are converted to [SparseOperatorCsr] once and all operators reading out
+    /// the same register are evaluated in a single pass over the states of the register.
+    /// With the `parallel` feature the states and operators are evaluated in parallel.
+    fn complex_trajectory_expectation_values(
+        &self,
+        complex_registers: &HashMap<String, ComplexOutputRegister>,
+    ) -> Result<HashMap<String, Array1<Complex64>>, RoqoqoError> {
+        let number_qubits = self.input.number_qubits;
+        let mut operators_by_readout: HashMap<&str, Vec<(&String, SparseOperatorCsr)>> =
+            HashMap::new();
+        for (name, (operator, readout)) in self.input.measured_operators.iter() {
+            operators_by_readout
+                .entry(readout.as_str())
+                .or_insert_with(Vec::new)
+                .push((
+                    name,
+                    SparseOperatorCsr::from_triplets(operator, number_qubits)?,
+                ));
+        }
+        let mut results: HashMap<String, Array1<Complex64>> = HashMap::new();
+        for (readout, operators) in operators_by_readout.iter() {
+            let register_vec =
+                complex_registers
+                    .get(*readout)
+                    .ok_or_else(|| RoqoqoError::MissingRegister {
+                        name: readout.to_string(),
+                    })?;
+            #[cfg(feature = "parallel")]
+            let states = register_vec.par_iter();
+            #[cfg(not(feature = "parallel"))]
+            let states = register_vec.iter();
+            let state_results: Vec<Vec<Complex64>> = states
+                .map(|state| state_expectation_values(operators, state, number_qubits))
+                .collect::<Result<Vec<Vec<Complex64>>, RoqoqoError>>()?;
+            for (operator_index, (name, _)) in operators.iter().enumerate() {
+                let local_results: Array1<Complex64> = state_results
+                    .iter()
+                    .map(|values| values[operator_index])
+                    .collect();
+                results.insert((*name).clone(), local_results);
+            }
+        }
+        Ok(results)
+    }
+}
+
+/// Returns the expectation values of a batch of operators for a state vector or flattened density matrix.
+fn state_expectation_values(
+    operators: &[(&String, SparseOperatorCsr)],
+    state: &[Complex64],
+    number_qubits: usize,
+) -> Result<Vec<Complex64>, RoqoqoError> {
+    let dimension = 2_usize.pow(number_qubits as u32);
+    let expectation_value: fn(&SparseOperatorCsr, &[Complex64]) -> Complex64 =
+        if state.len() == dimension {
+            SparseOperatorCsr::vector_expectation_value
+        } else if state.len() == dimension * dimension {
+            SparseOperatorCsr::density_matrix_expectation_value
+        } else {
+            return Err(RoqoqoError::MismatchedRegisterDimension {
+                dim: state.len(),
+                number_qubits,
+            });
+        };
+    #[cfg(feature = "parallel")]
+    let operator_iter = operators.par_iter();
+    #[cfg(not(feature = "parallel"))]
+    let operator_iter = operators.iter();
+    Ok(operator_iter
+        .map(|(_, operator)| expectation_value(operator, state))
+        .collect())
 }
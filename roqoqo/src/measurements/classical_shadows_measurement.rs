@@ -0,0 +1,347 @@
+// Copyright © 2021 HQS Quantum Simulations GmbH. All Rights Reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except
+// in compliance with the License. You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the
+// License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either
+// express or implied. See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::*;
+use crate::operations::{DefinitionBit, Hadamard, PragmaRepeatedMeasurement, RotateX};
+use ndarray::Array1;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+#[cfg(feature = "serialize")]
+use serde::{Deserialize, Serialize};
+use std::f64::consts::FRAC_PI_2;
+
+/// Collected information for executing a classical shadows measurement.
+///
+/// Each circuit measures all qubits in a randomly chosen single qubit basis.
+/// Measuring after a uniformly random single qubit Clifford gate is equivalent to measuring
+/// in a uniformly random X, Y or Z basis, so only the basis is drawn.
+/// The expectation values of many Pauli products are estimated from the same measurement record.
+///
+/// # Example
+///
+/// ```
+/// use roqoqo::measurements::{parse_pauli_product, ClassicalShadows, ClassicalShadowsInput};
+/// use std::collections::HashMap;
+///
+/// let mut input = ClassicalShadowsInput::new(2, 4);
+/// let index = input.add_pauli_product(parse_pauli_product("0X1X").unwrap()).unwrap();
+/// let mut linear: HashMap<usize, f64> = HashMap::new();
+/// linear.insert(index, 1.0);
+/// input.add_linear_exp_val("xx".to_string(), linear).unwrap();
+///
+/// let measurement = ClassicalShadows::new(None, input, 100, 10, 42);
+/// assert_eq!(measurement.circuits.len(), 100);
+/// ```
+#[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "json_schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+pub struct ClassicalShadows {
+    /// Constant Circuit that is executed before each Circuit in circuits.
+    pub constant_circuit: Option<Circuit>,
+    /// Collection of quantum circuits measuring in the randomized bases.
+    pub circuits: Vec<Circuit>,
+    /// Additional input information required for measurement.
+    pub input: ClassicalShadowsInput,
+}
+
+impl ClassicalShadows {
+    /// Creates a new ClassicalShadows measurement with randomized measurement bases.
+    ///
+    /// The basis of every qubit in every circuit is drawn uniformly from X, Y and Z.
+    /// Circuit `i` writes its measurement into the readout register `ro_<i>`.
+    /// The drawn bases are stored in the `measurement_bases` of the input,
+    /// replacing previously stored bases.
+    ///
+    /// # Arguments
+    ///
+    /// * `constant_circuit` - The constant Circuit that is executed before each Circuit in circuits.
+    /// * `input` - The measurement input defining the estimated expectation values.
+    /// * `number_bases` - The number of randomized measurement bases (circuits).
+    /// * `number_measurements` - The number of projective measurements in each basis.
+    /// * `seed` - The seed of the random number generator drawing the bases.
+    pub fn new(
+        constant_circuit: Option<Circuit>,
+        mut input: ClassicalShadowsInput,
+        number_bases: usize,
+        number_measurements: usize,
+        seed: u64,
+    ) -> Self {
+        let mut rng = StdRng::seed_from_u64(seed);
+        input.measurement_bases.clear();
+        let mut circuits: Vec<Circuit> = Vec::with_capacity(number_bases);
+        for index in 0..number_bases {
+            let readout = format!("ro_{}", index);
+            let basis: Vec<char> = (0..input.number_qubits)
+                .map(|_| ['X', 'Y', 'Z'][rng.gen_range(0..3)])
+                .collect();
+            let mut circuit = Circuit::new();
+            circuit += DefinitionBit::new(readout.clone(), input.number_qubits, true);
+            for (qubit, pauli) in basis.iter().enumerate() {
+                match pauli {
+                    'X' => circuit += Hadamard::new(qubit),
+                    'Y' => circuit += RotateX::new(qubit, FRAC_PI_2.into()),
+                    _ => (),
+                }
+            }
+            circuit += PragmaRepeatedMeasurement::new(readout.clone(), number_measurements, None);
+            circuits.push(circuit);
+            input.measurement_bases.insert(readout, basis);
+        }
+        Self {
+            constant_circuit,
+            circuits,
+            input,
+        }
+    }
+
+    /// Returns the median-of-means estimates of the Pauli products.
+    ///
+    /// A single shot in the basis `b` estimates the Pauli product `P` by
+    /// `3^|P| * (-1)^(parity of the qubits of P)` if `b` agrees with `P` on all qubits of `P` and by zero otherwise.
+    /// The shots of one basis are averaged, the bases are split into `number_batches` batches
+    /// and the estimate is the median of the batch means.
+    ///
+    /// # Arguments
+    ///
+    /// * `bit_registers` - The classical bit registers as a HashMap with the register name as key.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Array1<f64>)` - The estimated expectation values of the Pauli products.
+    /// * `Err([RoqoqoError::MissingRegister])` - The register of a measurement basis is missing.
+    /// * `Err([RoqoqoError::ClassicalShadowsMeasurementError])` - The registers do not contain enough measurements.
+    fn pauli_product_estimates(
+        &self,
+        bit_registers: &HashMap<String, BitOutputRegister>,
+    ) -> Result<Array1<f64>, RoqoqoError> {
+        let mut register_names: Vec<&String> = self.input.measurement_bases.keys().collect();
+        register_names.sort();
+        // Mean of the single shot estimates of every Pauli product for each basis
+        let mut basis_estimates: Vec<Vec<f64>> = Vec::with_capacity(register_names.len());
+        for register_name in register_names {
+            let register =
+                bit_registers
+                    .get(register_name)
+                    .ok_or_else(|| RoqoqoError::MissingRegister {
+                        name: register_name.clone(),
+                    })?;
+            if register.is_empty() {
+                return Err(RoqoqoError::ClassicalShadowsMeasurementError {
+                    msg: format!("bit register {} is empty", register_name),
+                });
+            }
+            if let Some(values) = register
+                .iter()
+                .find(|values| values.len() < self.input.number_qubits)
+            {
+                return Err(RoqoqoError::ClassicalShadowsMeasurementError {
+                    msg: format!(
+                        "bit register {} contains {} qubits, expected {}",
+                        register_name,
+                        values.len(),
+                        self.input.number_qubits
+                    ),
+                });
+            }
+            let basis = &self.input.measurement_bases[register_name];
+            basis_estimates.push(
+                self.input
+                    .pauli_products
+                    .iter()
+                    .map(|pauli_product| {
+                        register
+                            .iter()
+                            .map(|values| single_shot_estimate(pauli_product, basis, values))
+                            .sum::<f64>()
+                            / register.len() as f64
+                    })
+                    .collect(),
+            );
+        }
+        if basis_estimates.is_empty() {
+            return Err(RoqoqoError::ClassicalShadowsMeasurementError {
+                msg: "No measurement bases defined".to_string(),
+            });
+        }
+        let number_bases = basis_estimates.len();
+        let number_batches = self.input.number_batches.clamp(1, number_bases);
+        let mut pauli_products: Array1<f64> = Array1::zeros(self.input.pauli_products.len());
+        for (index, value) in pauli_products.iter_mut().enumerate() {
+            let batch_means: Vec<f64> = (0..number_batches)
+                .map(|batch| {
+                    let start = batch * number_bases / number_batches;
+                    let end = (batch + 1) * number_bases / number_batches;
+                    basis_estimates[start..end]
+                        .iter()
+                        .map(|estimates| estimates[index])
+                        .sum::<f64>()
+                        / (end - start) as f64
+                })
+                .collect();
+            *value = median(batch_means);
+        }
+        Ok(pauli_products)
+    }
+}
+
+/// Returns the estimate of a Pauli product from a single shot in the given basis.
+fn single_shot_estimate(pauli_product: &PauliString, basis: &[char], values: &[bool]) -> f64 {
+    let mut estimate = 1.0;
+    for (qubit, pauli) in pauli_product.iter() {
+        if basis[*qubit] != *pauli {
+            return 0.0;
+        }
+        estimate *= if values[*qubit] { -3.0 } else { 3.0 };
+    }
+    estimate
+}
+
+/// Returns the median of a non-empty list of values.
+fn median(mut values: Vec<f64>) -> f64 {
+    values.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+    let middle = values.len() / 2;
+    if values.len() % 2 == 0 {
+        (values[middle - 1] + values[middle]) / 2.0
+    } else {
+        values[middle]
+    }
+}
+
+impl Measure for ClassicalShadows {
+    /// Returns the constant Circuit that is executed before each Circuit in circuits.
+    ///
+    /// # Returns
+    ///
+    /// * `&Option<Circuit` - The constant Circuit (None if not defined).
+    fn constant_circuit(&self) -> &Option<Circuit> {
+        &self.constant_circuit
+    }
+
+    /// Returns iterator over circuits for measurement.
+    ///
+    /// # Returns
+    ///
+    /// * `Box<dyn Iterator<Item = &'a Circuit> + 'a>` - The quantum circuits.
+    fn circuits<'a>(&'a self) -> Box<dyn Iterator<Item = &'a Circuit> + 'a> {
+        Box::new(self.circuits.iter())
+    }
+
+    /// Returns clone of Measurement with symbolic parameters replaced.
+    ///
+    /// # Arguments
+    ///
+    /// * `substituted_parameters` - The HashMap containing the substitutions to use in the Circuit.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Self)` -  The Circuits with the parameters substituted.
+    /// * `Err(RoqoqoError)` - The subsitution failed.
+    fn substitute_parameters(
+        &self,
+        substituted_parameters: HashMap<String, f64>,
+    ) -> Result<Self, RoqoqoError> {
+        let mut calculator = qoqo_calculator::Calculator::new();
+        for (name, val) in substituted_parameters.iter() {
+            calculator.set_variable(name, *val)
+        }
+        let new_constant_circuit = match &self.constant_circuit {
+            None => None,
+            Some(c) => Some(c.substitute_parameters(&mut calculator)?),
+        };
+        let mut new_circuits = Vec::new();
+        for circ in self.circuits.iter() {
+            let mut calculator = qoqo_calculator::Calculator::new();
+            for (name, val) in substituted_parameters.iter() {
+                calculator.set_variable(name, *val)
+            }
+            new_circuits.push(circ.substitute_parameters(&mut calculator)?)
+        }
+        Ok(Self {
+            constant_circuit: new_constant_circuit,
+            circuits: new_circuits,
+            input: self.input.clone(),
+        })
+    }
+}
+
+impl MeasureExpectationValues for ClassicalShadows {
+    /// Executes the classical shadows measurement.
+    ///
+    /// # Arguments
+    ///
+    /// * `bit_registers` - The classical bit registers as a HashMap with the register name as key.
+    /// * `float_registers` - The classical float registers as a HashMap with the register name as key.
+    /// * `complex_registers` - The classical complex registers as a HashMap with the register name as key.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Some(HashMap<String, f64>))` - The measurement has been evaluated successfully. The HashMap contains the measured expectation values.
+    /// * `Ok(None)` - The measurement did not fail but is incomplete. A new round of measurements is needed.
+    /// * `Err(RoqoqoError)` - An error occured in the classical shadows measurement.
+    #[allow(unused_variables)]
+    fn evaluate(
+        &self,
+        bit_registers: HashMap<String, BitOutputRegister>,
+        float_registers: HashMap<String, FloatOutputRegister>,
+        complex_registers: HashMap<String, ComplexOutputRegister>,
+    ) -> Result<Option<HashMap<String, f64>>, RoqoqoError> {
+        let pauli_products = self.pauli_product_estimates(&bit_registers)?;
+        // Evaluating expectation values
+        let mut results: HashMap<String, f64> = HashMap::new();
+
+        for (name, evaluation) in self.input.measured_exp_vals.iter() {
+            results.insert(name.clone(), evaluation.complex_value(&pauli_products)?.re);
+        }
+
+        Ok(Some(results))
+    }
+
+    /// Returns true if all expectation values are linear combinations of Pauli products
+    /// and the Pauli products are estimated with a single batch (no median of means).
+    fn is_linear(&self) -> bool {
+        self.input.number_batches <= 1
+            && self
+                .input
+                .measured_exp_vals
+                .values()
+                .all(|exp_val| exp_val.is_linear())
+    }
+}
+
+impl MeasureComplexExpectationValues for ClassicalShadows {
+    /// Executes the classical shadows measurement and returns complex expectation values.
+    ///
+    /// # Arguments
+    ///
+    /// * `bit_registers` - The classical bit registers as a HashMap with the register name as key.
+    /// * `float_registers` - The classical float registers as a HashMap with the register name as key.
+    /// * `complex_registers` - The classical complex registers as a HashMap with the register name as key.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Some(ComplexExpectationValues))` - The measurement has been evaluated successfully.
+    /// * `Ok(None)` - The measurement did not fail but is incomplete. A new round of measurements is needed.
+    /// * `Err(RoqoqoError)` - An error occured in the classical shadows measurement.
+    #[allow(unused_variables)]
+    fn evaluate_complex(
+        &self,
+        bit_registers: HashMap<String, BitOutputRegister>,
+        float_registers: HashMap<String, FloatOutputRegister>,
+        complex_registers: HashMap<String, ComplexOutputRegister>,
+    ) -> Result<Option<ComplexExpectationValues>, RoqoqoError> {
+        let pauli_products = self.pauli_product_estimates(&bit_registers)?;
+        Ok(Some(ComplexExpectationValues::from_groups(
+            complex_exp_vals_from_pauli_products(&self.input.measured_exp_vals, &pauli_products)?,
+            &self.input.exp_val_groups,
+        )?))
+    }
+}
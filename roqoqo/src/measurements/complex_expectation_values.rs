@@ -0,0 +1,107 @@
+// Copyright © 2021 HQS Quantum Simulations GmbH. All Rights Reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except
+// in compliance with the License. You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the
+// License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either
+// express or implied. See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::{ExpValGroup, PauliProductsToExpVal};
+use crate::RoqoqoError;
+use ndarray::{Array1, Array2};
+use num_complex::Complex64;
+use std::collections::HashMap;
+
+/// Complex expectation values returned by [crate::measurements::MeasureComplexExpectationValues].
+///
+/// Contains all expectation values of the measurement and the named vectors and matrices
+/// of expectation values defined in the measurement input.
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "json_schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct ComplexExpectationValues {
+    /// The measured expectation values with the name of the expectation value as key.
+    pub expectation_values: HashMap<String, Complex64>,
+    /// The vectors of expectation values with the name of the vector as key.
+    pub vectors: HashMap<String, Array1<Complex64>>,
+    /// The matrices of expectation values with the name of the matrix as key.
+    pub matrices: HashMap<String, Array2<Complex64>>,
+}
+
+impl ComplexExpectationValues {
+    /// Creates ComplexExpectationValues by grouping expectation values into vectors and matrices.
+    ///
+    /// # Arguments
+    ///
+    /// * `expectation_values` - The measured expectation values with the name of the expectation value as key.
+    /// * `exp_val_groups` - The named vectors and matrices of expectation values.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Self)` - The expectation values with the vectors and matrices.
+    /// * `Err([RoqoqoError::ExpValMissing])` - A component of a vector or matrix has not been measured.
+    pub fn from_groups(
+        expectation_values: HashMap<String, Complex64>,
+        exp_val_groups: &HashMap<String, ExpValGroup>,
+    ) -> Result<Self, RoqoqoError> {
+        let component = |name: &String| -> Result<Complex64, RoqoqoError> {
+            expectation_values
+                .get(name)
+                .copied()
+                .ok_or_else(|| RoqoqoError::ExpValMissing { name: name.clone() })
+        };
+        let mut vectors: HashMap<String, Array1<Complex64>> = HashMap::new();
+        let mut matrices: HashMap<String, Array2<Complex64>> = HashMap::new();
+        for (name, group) in exp_val_groups.iter() {
+            match group {
+                ExpValGroup::Vector(components) => {
+                    let vector = components
+                        .iter()
+                        .map(component)
+                        .collect::<Result<Array1<Complex64>, RoqoqoError>>()?;
+                    vectors.insert(name.clone(), vector);
+                }
+                ExpValGroup::Matrix(rows) => {
+                    let number_columns = rows.first().map(|row| row.len()).unwrap_or(0);
+                    let mut matrix: Array2<Complex64> = Array2::zeros((rows.len(), number_columns));
+                    for (row_index, row) in rows.iter().enumerate() {
+                        for (column_index, name) in row.iter().enumerate() {
+                            matrix[[row_index, column_index]] = component(name)?;
+                        }
+                    }
+                    matrices.insert(name.clone(), matrix);
+                }
+            }
+        }
+        Ok(Self {
+            expectation_values,
+            vectors,
+            matrices,
+        })
+    }
+
+    /// Returns the real parts of the expectation values.
+    ///
+    /// The real parts are the expectation values returned by [crate::measurements::MeasureExpectationValues].
+    pub fn real_parts(&self) -> HashMap<String, f64> {
+        self.expectation_values
+            .iter()
+            .map(|(name, value)| (name.clone(), value.re))
+            .collect()
+    }
+}
+
+/// Returns the complex expectation values constructed from the expectation values of Pauli products.
+pub(crate) fn complex_exp_vals_from_pauli_products(
+    measured_exp_vals: &HashMap<String, PauliProductsToExpVal>,
+    pauli_products: &Array1<f64>,
+) -> Result<HashMap<String, Complex64>, RoqoqoError> {
+    measured_exp_vals
+        .iter()
+        .map(|(name, evaluation)| Ok((name.clone(), evaluation.complex_value(pauli_products)?)))
+        .collect()
+}
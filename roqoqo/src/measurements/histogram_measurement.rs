@@ -0,0 +1,221 @@
+// Copyright © 2021 HQS Quantum Simulations GmbH. All Rights Reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except
+// in compliance with the License. You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the
+// License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either
+// express or implied. See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::*;
+#[cfg(feature = "serialize")]
+use serde::{Deserialize, Serialize};
+
+/// Histogram measurement.
+///
+/// Runs a sequence of circuits and evaluates the measured bit registers into histograms
+/// of bitstring counts and marginal probability distributions on selected qubits.
+///
+/// In the bitstrings the `k`-th character is the value ('0' or '1') of the `k`-th readout bit.
+///
+/// # Example
+///
+/// ```
+/// use roqoqo::measurements::{Histogram, HistogramInput};
+/// use std::collections::HashMap;
+///
+/// let mut input = HistogramInput::new();
+/// input.add_marginal("first".to_string(), "ro".to_string(), vec![0]).unwrap();
+/// let measurement = Histogram {
+///     constant_circuit: None,
+///     circuits: vec![],
+///     input,
+/// };
+///
+/// let mut bit_registers: HashMap<String, Vec<Vec<bool>>> = HashMap::new();
+/// bit_registers.insert("ro".to_string(), vec![vec![true, false], vec![true, true]]);
+/// let result = measurement
+///     .evaluate(bit_registers, HashMap::new(), HashMap::new())
+///     .unwrap();
+/// assert_eq!(result.counts["ro"]["10"], 1);
+/// assert_eq!(result.marginal_probabilities["first"]["1"], 1.0);
+/// ```
+#[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "json_schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+pub struct Histogram {
+    /// Constant Circuit that is executed before each Circuit in circuits.
+    pub constant_circuit: Option<Circuit>,
+    /// Collection of quantum circuits that are measured.
+    pub circuits: Vec<Circuit>,
+    /// Additional input information required for measurement.
+    pub input: HistogramInput,
+}
+
+/// Result of a [Histogram] measurement.
+#[derive(Debug, PartialEq, Clone, Default)]
+#[cfg_attr(feature = "json_schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+pub struct HistogramResult {
+    /// Number of times each bitstring was measured with the name of the readout register as key.
+    pub counts: HashMap<String, HashMap<String, usize>>,
+    /// Probabilities of the outcomes on the selected qubits with the name of the marginal as key.
+    ///
+    /// Only outcomes with a non-zero probability are included.
+    pub marginal_probabilities: HashMap<String, HashMap<String, f64>>,
+}
+
+impl Histogram {
+    /// Evaluates the measured bit registers into bitstring histograms and marginal probabilities.
+    ///
+    /// Marginal probabilities are the observed frequencies of the outcomes,
+    /// or the readout-error mitigated probabilities when readout mitigation is set in the input.
+    ///
+    /// # Arguments
+    ///
+    /// * `bit_registers` - The classical bit registers with the register name as key.
+    /// * `_float_registers` - The classical float registers with the register name as key (not used).
+    /// * `_complex_registers` - The classical complex registers with the register name as key (not used).
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(HistogramResult)` - The bitstring counts and marginal probabilities.
+    /// * `Err([RoqoqoError::MissingRegister])` - The register of a marginal is missing.
+    /// * `Err([RoqoqoError::HistogramMeasurementError])` - The register of a marginal is empty or too short.
+    pub fn evaluate(
+        &self,
+        bit_registers: HashMap<String, BitOutputRegister>,
+        _float_registers: HashMap<String, FloatOutputRegister>,
+        _complex_registers: HashMap<String, ComplexOutputRegister>,
+    ) -> Result<HistogramResult, RoqoqoError> {
+        let mut counts: HashMap<String, HashMap<String, usize>> = HashMap::new();
+        for (name, register) in bit_registers.iter() {
+            let mut histogram: HashMap<String, usize> = HashMap::new();
+            for shot in register.iter() {
+                *histogram
+                    .entry(bitstring(shot.iter().copied()))
+                    .or_insert(0) += 1;
+            }
+            counts.insert(name.clone(), histogram);
+        }
+
+        let mut marginal_probabilities: HashMap<String, HashMap<String, f64>> = HashMap::new();
+        for (name, (readout, qubits)) in self.input.marginals.iter() {
+            let register =
+                bit_registers
+                    .get(readout)
+                    .ok_or_else(|| RoqoqoError::MissingRegister {
+                        name: readout.clone(),
+                    })?;
+            if register.is_empty() {
+                return Err(RoqoqoError::HistogramMeasurementError {
+                    msg: format!("Register {} of marginal {} is empty", readout, name),
+                });
+            }
+            let max_qubit = qubits.iter().copied().max().unwrap_or(0);
+            if register.iter().any(|shot| shot.len() <= max_qubit) {
+                return Err(RoqoqoError::HistogramMeasurementError {
+                    msg: format!(
+                        "Register {} is too short for qubit {} of marginal {}",
+                        readout, max_qubit, name
+                    ),
+                });
+            }
+            let probabilities = match &self.input.readout_mitigation {
+                Some(mitigation) => {
+                    let mitigated = mitigation.mitigated_probabilities(register, qubits)?;
+                    mitigated
+                        .iter()
+                        .enumerate()
+                        .filter(|(_, probability)| **probability != 0.0)
+                        .map(|(index, probability)| {
+                            let outcome = (0..qubits.len()).map(|k| (index >> k) & 1 == 1);
+                            (bitstring(outcome), *probability)
+                        })
+                        .collect()
+                }
+                None => {
+                    let mut frequencies: HashMap<String, f64> = HashMap::new();
+                    let weight = 1.0 / register.len() as f64;
+                    for shot in register.iter() {
+                        let outcome = bitstring(qubits.iter().map(|qubit| shot[*qubit]));
+                        *frequencies.entry(outcome).or_insert(0.0) += weight;
+                    }
+                    frequencies
+                }
+            };
+            marginal_probabilities.insert(name.clone(), probabilities);
+        }
+
+        Ok(HistogramResult {
+            counts,
+            marginal_probabilities,
+        })
+    }
+}
+
+/// Converts single shot readout values into a bitstring of '0' and '1' characters.
+fn bitstring(bits: impl Iterator<Item = bool>) -> String {
+    bits.map(|bit| if bit { '1' } else { '0' }).collect()
+}
+
+impl Measure for Histogram {
+    /// Returns the constant Circuit that is executed before each Circuit in circuits.
+    ///
+    /// # Returns
+    ///
+    /// * `&Option<Circuit` - The constant Circuit (None if not defined).
+    fn constant_circuit(&self) -> &Option<Circuit> {
+        &self.constant_circuit
+    }
+
+    /// Returns iterator over circuits for measurement.
+    ///
+    /// # Returns
+    ///
+    /// * `Box<dyn Iterator<Item = &'a Circuit> + 'a>` - The quantum circuits.
+    fn circuits<'a>(&'a self) -> Box<dyn Iterator<Item = &'a Circuit> + 'a> {
+        Box::new(self.circuits.iter())
+    }
+
+    /// Returns clone of Measurement with symbolic parameters replaced.
+    ///
+    /// # Arguments
+    ///
+    /// * `substituted_parameters` - The HashMap containing the substitutions to use in the Circuit.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Self)` -  The Circuits with the parameters substituted.
+    /// * `Err(RoqoqoError)` - The substitution failed.
+    ///
+    fn substitute_parameters(
+        &self,
+        substituted_parameters: HashMap<String, f64>,
+    ) -> Result<Self, RoqoqoError> {
+        let mut calculator = qoqo_calculator::Calculator::new();
+        for (name, val) in substituted_parameters.iter() {
+            calculator.set_variable(name, *val)
+        }
+        let new_constant_circuit = match &self.constant_circuit {
+            None => None,
+            Some(c) => Some(c.substitute_parameters(&mut calculator)?),
+        };
+        let mut new_circuits = Vec::new();
+        for circ in self.circuits.iter() {
+            let mut calculator = qoqo_calculator::Calculator::new();
+            for (name, val) in substituted_parameters.iter() {
+                calculator.set_variable(name, *val)
+            }
+            new_circuits.push(circ.substitute_parameters(&mut calculator)?)
+        }
+        Ok(Self {
+            constant_circuit: new_constant_circuit,
+            circuits: new_circuits,
+            input: self.input.clone(),
+        })
+    }
+}
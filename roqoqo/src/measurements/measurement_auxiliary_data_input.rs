@@ -10,8 +10,10 @@
 // express or implied. See the License for the specific language governing permissions and
 // limitations under the License.
 
+use super::{PauliString, ReadoutMitigation};
 use crate::CalculatorFloat;
 use crate::RoqoqoError;
+use ndarray::Array1;
 use num_complex::Complex64;
 use std::collections::HashMap;
 
@@ -29,8 +31,8 @@ pub enum PauliProductsToExpVal {
     /// Expectation value of observable is a linear combination of Pauli Product expectation values.
     ///
     /// Only scalar real expectation values are supported.  
-    /// For complex observables or vector/matrix observables
-    /// components have to be postprocessed separately.
+    /// Complex observables are defined with [PauliProductsToExpVal::LinearComplex],
+    /// vector/matrix observables are grouped with [ExpValGroup].
     Linear(HashMap<usize, f64>),
     /// Expectation value of observable is derived from symbolic expression.
     ///
@@ -38,6 +40,108 @@ pub enum PauliProductsToExpVal {
     /// The i-th PauliProduct us hardcoded as the variable `pauli_product_i`
     /// in the string expression of CalculatorFloat.
     Symbolic(CalculatorFloat),
+    /// Expectation value of observable is a linear combination of Pauli Product expectation values
+    /// with complex coefficients.
+    ///
+    /// When evaluating real expectation values only the real part is returned,
+    /// the full value is returned by [crate::measurements::MeasureComplexExpectationValues].
+    LinearComplex(HashMap<usize, Complex64>),
+}
+
+impl PauliProductsToExpVal {
+    /// Returns true if the expectation value is a linear combination of Pauli product expectation values.
+    ///
+    /// Symbolic expressions are not analysed and are never considered linear.
+    pub fn is_linear(&self) -> bool {
+        !matches!(self, PauliProductsToExpVal::Symbolic(_))
+    }
+
+    /// Returns the expectation value constructed from the expectation values of Pauli products.
+    ///
+    /// # Arguments
+    ///
+    /// * `pauli_products` - The expectation values of the Pauli products in the order of their indices.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Complex64)` - The expectation value, real unless defined by [PauliProductsToExpVal::LinearComplex].
+    /// * `Err([RoqoqoError::CalculatorError])` - The symbolic expression could not be evaluated.
+    pub fn complex_value(&self, pauli_products: &Array1<f64>) -> Result<Complex64, RoqoqoError> {
+        Ok(match self {
+            PauliProductsToExpVal::Linear(hm) => {
+                let mut value: f64 = 0.0;
+                for (index, coefficient) in hm {
+                    value += pauli_products[*index] * coefficient;
+                }
+                Complex64::new(value, 0.0)
+            }
+            PauliProductsToExpVal::Symbolic(x) => {
+                let mut calculator = qoqo_calculator::Calculator::new();
+                for (ind, p) in pauli_products.iter().enumerate() {
+                    calculator.set_variable(format!("pauli_product_{}", ind).as_str(), *p);
+                }
+                Complex64::new(calculator.parse_get(x.clone())?, 0.0)
+            }
+            PauliProductsToExpVal::LinearComplex(hm) => {
+                let mut value = Complex64::new(0.0, 0.0);
+                for (index, coefficient) in hm {
+                    value += coefficient * pauli_products[*index];
+                }
+                value
+            }
+        })
+    }
+}
+
+/// Defines a named vector or matrix of expectation values.
+///
+/// The components are the names of expectation values defined in the same measurement input.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "json_schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub enum ExpValGroup {
+    /// Vector of the expectation values with the given names.
+    Vector(Vec<String>),
+    /// Matrix of the expectation values with the given names as a list of rows.
+    Matrix(Vec<Vec<String>>),
+}
+
+/// Adds a named vector or matrix of expectation values to the groups of a measurement input.
+fn add_exp_val_group(
+    exp_val_groups: &mut HashMap<String, ExpValGroup>,
+    name: String,
+    group: ExpValGroup,
+    is_defined: impl Fn(&String) -> bool,
+) -> Result<(), RoqoqoError> {
+    let components: Vec<&String> = match &group {
+        ExpValGroup::Vector(components) => components.iter().collect(),
+        ExpValGroup::Matrix(rows) => {
+            if rows.iter().any(|row| row.len() != rows[0].len()) {
+                return Err(RoqoqoError::GenericError {
+                    msg: format!("Rows of expectation value matrix {} differ in length", name),
+                });
+            }
+            rows.iter().flatten().collect()
+        }
+    };
+    if components.is_empty() {
+        return Err(RoqoqoError::GenericError {
+            msg: format!("Expectation value group {} is empty", name),
+        });
+    }
+    if let Some(missing) = components
+        .into_iter()
+        .find(|component| !is_defined(component))
+    {
+        return Err(RoqoqoError::ExpValMissing {
+            name: missing.clone(),
+        });
+    }
+    if exp_val_groups.contains_key(&name) {
+        return Err(RoqoqoError::ExpValUsedTwice { name });
+    }
+    exp_val_groups.insert(name, group);
+    Ok(())
 }
 
 /// Provides Necessary Information to run a [crate::measurements::BasisRotation] measurement.
@@ -63,6 +167,12 @@ pub struct BasisRotationInput {
     ///
     /// Measurement errors are symmetrized by repeating measurement with final flip of all qubits.
     pub use_flipped_measurement: bool,
+    /// Optional readout error mitigation applied to the measured Pauli products.
+    #[cfg_attr(feature = "serialize", serde(default))]
+    pub readout_mitigation: Option<ReadoutMitigation>,
+    /// Collection of names and components of vectors and matrices of expectation values.
+    #[cfg_attr(feature = "serialize", serde(default))]
+    pub exp_val_groups: HashMap<String, ExpValGroup>,
 }
 
 impl BasisRotationInput {
@@ -85,9 +195,20 @@ impl BasisRotationInput {
             number_pauli_products: 0,
             measured_exp_vals: HashMap::new(),
             use_flipped_measurement,
+            readout_mitigation: None,
+            exp_val_groups: HashMap::new(),
         }
     }
 
+    /// Sets the readout error mitigation applied when evaluating the measurement.
+    ///
+    /// # Arguments
+    ///
+    /// * `readout_mitigation` - The confusion matrices and correction method (None disables mitigation).
+    pub fn set_readout_mitigation(&mut self, readout_mitigation: Option<ReadoutMitigation>) {
+        self.readout_mitigation = readout_mitigation;
+    }
+
     /// Adds measured Pauli product to BasisRotationInput and returns index of Pauli product.
     ///
     /// When the pauli product is already in the measurement input the function only returns
@@ -196,6 +317,330 @@ impl BasisRotationInput {
         }
         Ok(())
     }
+
+    /// Adds linear definition of complex expectation value to measurement input.
+    ///
+    /// Adds an expectation value that is defined by a linear combination
+    /// of expectation values of Pauli products with complex coefficients.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the expectation value.
+    /// * `linear` - The linear combination of expectation values as a map between Pauli product index and complex coefficient.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - The value was successfully added.
+    /// * `Err([RoqoqoError::ExpValUsedTwice])` - The name of expectation value is already taken.
+    pub fn add_linear_complex_exp_val(
+        &mut self,
+        name: String,
+        linear: HashMap<usize, Complex64>,
+    ) -> Result<(), RoqoqoError> {
+        if self
+            .measured_exp_vals
+            .insert(name.clone(), PauliProductsToExpVal::LinearComplex(linear))
+            .is_some()
+        {
+            return Err(RoqoqoError::ExpValUsedTwice { name });
+        }
+        Ok(())
+    }
+
+    /// Adds a named vector of expectation values to measurement input.
+    ///
+    /// The vector is returned by [crate::measurements::MeasureComplexExpectationValues::evaluate_complex].
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the vector.
+    /// * `components` - The names of the expectation values forming the vector.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - The vector was successfully added.
+    /// * `Err([RoqoqoError::ExpValMissing])` - A component is not a defined expectation value.
+    /// * `Err([RoqoqoError::ExpValUsedTwice])` - The name of the vector is already taken.
+    /// * `Err([RoqoqoError::GenericError])` - The vector is empty.
+    pub fn add_exp_val_vector(
+        &mut self,
+        name: String,
+        components: Vec<String>,
+    ) -> Result<(), RoqoqoError> {
+        let defined = &self.measured_exp_vals;
+        add_exp_val_group(
+            &mut self.exp_val_groups,
+            name,
+            ExpValGroup::Vector(components),
+            |component| defined.contains_key(component),
+        )
+    }
+
+    /// Adds a named matrix of expectation values to measurement input.
+    ///
+    /// The matrix is returned by [crate::measurements::MeasureComplexExpectationValues::evaluate_complex].
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the matrix.
+    /// * `rows` - The names of the expectation values forming the matrix as a list of rows.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - The matrix was successfully added.
+    /// * `Err([RoqoqoError::ExpValMissing])` - A component is not a defined expectation value.
+    /// * `Err([RoqoqoError::ExpValUsedTwice])` - The name of the matrix is already taken.
+    /// * `Err([RoqoqoError::GenericError])` - The matrix is empty or the rows differ in length.
+    pub fn add_exp_val_matrix(
+        &mut self,
+        name: String,
+        rows: Vec<Vec<String>>,
+    ) -> Result<(), RoqoqoError> {
+        let defined = &self.measured_exp_vals;
+        add_exp_val_group(
+            &mut self.exp_val_groups,
+            name,
+            ExpValGroup::Matrix(rows),
+            |component| defined.contains_key(component),
+        )
+    }
+}
+
+/// Provides necessary information to run a [crate::measurements::ClassicalShadows] measurement.
+///
+/// The expectation values are defined as combinations of expectation values of Pauli products
+/// that are estimated from measurements in randomized single qubit bases.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "json_schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct ClassicalShadowsInput {
+    /// Number of qubits that are measured.
+    pub number_qubits: usize,
+    /// Number of batches of measurement bases used in the median-of-means estimation.
+    pub number_batches: usize,
+    /// The basis ('X', 'Y' or 'Z') each qubit is measured in with the readout register as key.
+    pub measurement_bases: HashMap<String, Vec<char>>,
+    /// The estimated Pauli products in the order of their indices.
+    pub pauli_products: Vec<PauliString>,
+    /// Collection of names and construction methods of expectation values.
+    ///
+    /// The construction methods are given by [PauliProductsToExpVal] enums.
+    pub measured_exp_vals: HashMap<String, PauliProductsToExpVal>,
+    /// Collection of names and components of vectors and matrices of expectation values.
+    #[cfg_attr(feature = "serialize", serde(default))]
+    pub exp_val_groups: HashMap<String, ExpValGroup>,
+}
+
+impl ClassicalShadowsInput {
+    /// Creates new ClassicalShadowsInput.
+    ///
+    /// The measurement bases are set when creating a [crate::measurements::ClassicalShadows] measurement.
+    /// The pauli_products and measured_exp_vals start empty
+    /// and can be extended with [ClassicalShadowsInput::add_pauli_product],
+    /// [ClassicalShadowsInput::add_linear_exp_val] and [ClassicalShadowsInput::add_symbolic_exp_val].
+    ///
+    /// # Arguments
+    ///
+    /// * `number_qubits` - The number of qubits in the ClassicalShadows measurement.
+    /// * `number_batches` - The number of batches used in the median-of-means estimation.
+    pub fn new(number_qubits: usize, number_batches: usize) -> Self {
+        Self {
+            number_qubits,
+            number_batches,
+            measurement_bases: HashMap::new(),
+            pauli_products: Vec::new(),
+            measured_exp_vals: HashMap::new(),
+            exp_val_groups: HashMap::new(),
+        }
+    }
+
+    /// Adds estimated Pauli product to ClassicalShadowsInput and returns index of Pauli product.
+    ///
+    /// When the pauli product is already in the measurement input the function only returns
+    /// its index.
+    ///
+    /// # Arguments
+    ///
+    /// * `pauli_product` - The Pauli product, e.g. parsed with [crate::measurements::parse_pauli_product].
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(usize)` - The index of the added Pauli product in the list of all Pauli products.
+    /// * `Err([RoqoqoError::PauliProductExceedsQubits])` - The pauli product involves a qubit exceeding the maximum number of qubits.
+    /// * `Err([RoqoqoError::ClassicalShadowsMeasurementError])` - The pauli product contains an operator other than 'X', 'Y' or 'Z'.
+    pub fn add_pauli_product(&mut self, pauli_product: PauliString) -> Result<usize, RoqoqoError> {
+        for (qubit, pauli) in pauli_product.iter() {
+            if qubit >= &self.number_qubits {
+                return Err(RoqoqoError::PauliProductExceedsQubits {
+                    pp_qubit: *qubit,
+                    number_qubits: self.number_qubits,
+                });
+            }
+            if !matches!(pauli, 'X' | 'Y' | 'Z') {
+                return Err(RoqoqoError::ClassicalShadowsMeasurementError {
+                    msg: format!("Unexpected Pauli operator {} on qubit {}", pauli, qubit),
+                });
+            }
+        }
+        if let Some(index) = self
+            .pauli_products
+            .iter()
+            .position(|existing| existing == &pauli_product)
+        {
+            return Ok(index);
+        }
+        self.pauli_products.push(pauli_product);
+        Ok(self.pauli_products.len() - 1)
+    }
+
+    /// Adds linear definition of expectation value to measurement input.
+    ///
+    /// Adds an expectation value that is defined by a linear combination
+    /// of expectation values of Pauli products.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the expectation value.
+    /// * `linear` - The linear combination of expectation values as a map between Pauli product index and coefficient.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - The value was successfully added.
+    /// * `Err([RoqoqoError::ExpValUsedTwice])` - The name of expectation value is already taken.
+    pub fn add_linear_exp_val(
+        &mut self,
+        name: String,
+        linear: HashMap<usize, f64>,
+    ) -> Result<(), RoqoqoError> {
+        if self
+            .measured_exp_vals
+            .insert(name.clone(), PauliProductsToExpVal::Linear(linear))
+            .is_some()
+        {
+            return Err(RoqoqoError::ExpValUsedTwice { name });
+        }
+        Ok(())
+    }
+
+    /// Adds symbolic definition of expectation value to measurement input.
+    ///
+    /// Adds an expectation value that is defined by a symbolic combination
+    /// of expectation values of Pauli products.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the expectation value.
+    /// * `symbolic` - The symbolic expression for the expectation values
+    ///                given by [qoqo_calculator::CalculatorFloat].
+    ///
+    /// The i-th PauliProduct is hardcoded as variable `pauli_product_i`
+    /// in the string expression of CalculatorFloat.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - The value was successfully added.
+    /// * `Err([RoqoqoError::ExpValUsedTwice])` - The name of expectation value is already taken.
+    pub fn add_symbolic_exp_val(
+        &mut self,
+        name: String,
+        symbolic: CalculatorFloat,
+    ) -> Result<(), RoqoqoError> {
+        if self
+            .measured_exp_vals
+            .insert(name.clone(), PauliProductsToExpVal::Symbolic(symbolic))
+            .is_some()
+        {
+            return Err(RoqoqoError::ExpValUsedTwice { name });
+        }
+        Ok(())
+    }
+
+    /// Adds linear definition of complex expectation value to measurement input.
+    ///
+    /// Adds an expectation value that is defined by a linear combination
+    /// of expectation values of Pauli products with complex coefficients.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the expectation value.
+    /// * `linear` - The linear combination of expectation values as a map between Pauli product index and complex coefficient.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - The value was successfully added.
+    /// * `Err([RoqoqoError::ExpValUsedTwice])` - The name of expectation value is already taken.
+    pub fn add_linear_complex_exp_val(
+        &mut self,
+        name: String,
+        linear: HashMap<usize, Complex64>,
+    ) -> Result<(), RoqoqoError> {
+        if self
+            .measured_exp_vals
+            .insert(name.clone(), PauliProductsToExpVal::LinearComplex(linear))
+            .is_some()
+        {
+            return Err(RoqoqoError::ExpValUsedTwice { name });
+        }
+        Ok(())
+    }
+
+    /// Adds a named vector of expectation values to measurement input.
+    ///
+    /// The vector is returned by [crate::measurements::MeasureComplexExpectationValues::evaluate_complex].
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the vector.
+    /// * `components` - The names of the expectation values forming the vector.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - The vector was successfully added.
+    /// * `Err([RoqoqoError::ExpValMissing])` - A component is not a defined expectation value.
+    /// * `Err([RoqoqoError::ExpValUsedTwice])` - The name of the vector is already taken.
+    /// * `Err([RoqoqoError::GenericError])` - The vector is empty.
+    pub fn add_exp_val_vector(
+        &mut self,
+        name: String,
+        components: Vec<String>,
+    ) -> Result<(), RoqoqoError> {
+        let defined = &self.measured_exp_vals;
+        add_exp_val_group(
+            &mut self.exp_val_groups,
+            name,
+            ExpValGroup::Vector(components),
+            |component| defined.contains_key(component),
+        )
+    }
+
+    /// Adds a named matrix of expectation values to measurement input.
+    ///
+    /// The matrix is returned by [crate::measurements::MeasureComplexExpectationValues::evaluate_complex].
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the matrix.
+    /// * `rows` - The names of the expectation values forming the matrix as a list of rows.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - The matrix was successfully added.
+    /// * `Err([RoqoqoError::ExpValMissing])` - A component is not a defined expectation value.
+    /// * `Err([RoqoqoError::ExpValUsedTwice])` - The name of the matrix is already taken.
+    /// * `Err([RoqoqoError::GenericError])` - The matrix is empty or the rows differ in length.
+    pub fn add_exp_val_matrix(
+        &mut self,
+        name: String,
+        rows: Vec<Vec<String>>,
+    ) -> Result<(), RoqoqoError> {
+        let defined = &self.measured_exp_vals;
+        add_exp_val_group(
+            &mut self.exp_val_groups,
+            name,
+            ExpValGroup::Matrix(rows),
+            |component| defined.contains_key(component),
+        )
+    }
 }
 
 /// Provides necessary information to run a [crate::measurements::CheatedBasisRotation] measurement.
@@ -211,6 +656,9 @@ pub struct CheatedBasisRotationInput {
     pub measured_exp_vals: HashMap<String, PauliProductsToExpVal>,
     /// Mapping the pauli product indices to the readout keys.
     pub pauli_product_keys: HashMap<String, usize>,
+    /// Collection of names and components of vectors and matrices of expectation values.
+    #[cfg_attr(feature = "serialize", serde(default))]
+    pub exp_val_groups: HashMap<String, ExpValGroup>,
 }
 
 impl Default for CheatedBasisRotationInput {
@@ -231,6 +679,7 @@ impl CheatedBasisRotationInput {
         Self {
             measured_exp_vals: HashMap::new(),
             pauli_product_keys: HashMap::new(),
+            exp_val_groups: HashMap::new(),
         }
     }
 
@@ -316,6 +765,93 @@ impl CheatedBasisRotationInput {
         }
         Ok(())
     }
+
+    /// Adds linear definition of complex expectation value to measurement input.
+    ///
+    /// Adds an expectation value that is defined by a linear combination
+    /// of expectation values of Pauli products with complex coefficients.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the expectation value.
+    /// * `linear` - The linear combination of expectation values as a map between Pauli product index and complex coefficient.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - The value was successfully added.
+    /// * `Err([RoqoqoError::ExpValUsedTwice])` - The name of expectation value is already taken.
+    pub fn add_linear_complex_exp_val(
+        &mut self,
+        name: String,
+        linear: HashMap<usize, Complex64>,
+    ) -> Result<(), RoqoqoError> {
+        if self
+            .measured_exp_vals
+            .insert(name.clone(), PauliProductsToExpVal::LinearComplex(linear))
+            .is_some()
+        {
+            return Err(RoqoqoError::ExpValUsedTwice { name });
+        }
+        Ok(())
+    }
+
+    /// Adds a named vector of expectation values to measurement input.
+    ///
+    /// The vector is returned by [crate::measurements::MeasureComplexExpectationValues::evaluate_complex].
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the vector.
+    /// * `components` - The names of the expectation values forming the vector.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - The vector was successfully added.
+    /// * `Err([RoqoqoError::ExpValMissing])` - A component is not a defined expectation value.
+    /// * `Err([RoqoqoError::ExpValUsedTwice])` - The name of the vector is already taken.
+    /// * `Err([RoqoqoError::GenericError])` - The vector is empty.
+    pub fn add_exp_val_vector(
+        &mut self,
+        name: String,
+        components: Vec<String>,
+    ) -> Result<(), RoqoqoError> {
+        let defined = &self.measured_exp_vals;
+        add_exp_val_group(
+            &mut self.exp_val_groups,
+            name,
+            ExpValGroup::Vector(components),
+            |component| defined.contains_key(component),
+        )
+    }
+
+    /// Adds a named matrix of expectation values to measurement input.
+    ///
+    /// The matrix is returned by [crate::measurements::MeasureComplexExpectationValues::evaluate_complex].
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the matrix.
+    /// * `rows` - The names of the expectation values forming the matrix as a list of rows.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - The matrix was successfully added.
+    /// * `Err([RoqoqoError::ExpValMissing])` - A component is not a defined expectation value.
+    /// * `Err([RoqoqoError::ExpValUsedTwice])` - The name of the matrix is already taken.
+    /// * `Err([RoqoqoError::GenericError])` - The matrix is empty or the rows differ in length.
+    pub fn add_exp_val_matrix(
+        &mut self,
+        name: String,
+        rows: Vec<Vec<String>>,
+    ) -> Result<(), RoqoqoError> {
+        let defined = &self.measured_exp_vals;
+        add_exp_val_group(
+            &mut self.exp_val_groups,
+            name,
+            ExpValGroup::Matrix(rows),
+            |component| defined.contains_key(component),
+        )
+    }
 }
 
 /// Provides necessary information to run a [crate::measurements::Cheated] measurement.
@@ -329,6 +865,9 @@ pub struct CheatedInput {
     pub measured_operators: HashMap<String, (OperatorSparseVec, String)>,
     /// Number of qubits that defines the dimension of the Hilbertspace.
     pub number_qubits: usize,
+    /// Collection of names and components of vectors and matrices of expectation values.
+    #[cfg_attr(feature = "serialize", serde(default))]
+    pub exp_val_groups: HashMap<String, ExpValGroup>,
 }
 
 /// Represents Operator acting on Hilbert space as a sparse list of two indices and a value.
@@ -354,6 +893,7 @@ impl CheatedInput {
         Self {
             measured_operators: HashMap::new(),
             number_qubits,
+            exp_val_groups: HashMap::new(),
         }
     }
 
@@ -397,4 +937,141 @@ impl CheatedInput {
         }
         Ok(())
     }
+
+    /// Adds a named vector of expectation values to measurement input.
+    ///
+    /// The vector is returned by [crate::measurements::MeasureComplexExpectationValues::evaluate_complex].
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the vector.
+    /// * `components` - The names of the expectation values forming the vector.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - The vector was successfully added.
+    /// * `Err([RoqoqoError::ExpValMissing])` - A component is not a defined expectation value.
+    /// * `Err([RoqoqoError::ExpValUsedTwice])` - The name of the vector is already taken.
+    /// * `Err([RoqoqoError::GenericError])` - The vector is empty.
+    pub fn add_exp_val_vector(
+        &mut self,
+        name: String,
+        components: Vec<String>,
+    ) -> Result<(), RoqoqoError> {
+        let defined = &self.measured_operators;
+        add_exp_val_group(
+            &mut self.exp_val_groups,
+            name,
+            ExpValGroup::Vector(components),
+            |component| defined.contains_key(component),
+        )
+    }
+
+    /// Adds a named matrix of expectation values to measurement input.
+    ///
+    /// The matrix is returned by [crate::measurements::MeasureComplexExpectationValues::evaluate_complex].
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the matrix.
+    /// * `rows` - The names of the expectation values forming the matrix as a list of rows.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - The matrix was successfully added.
+    /// * `Err([RoqoqoError::ExpValMissing])` - A component is not a defined expectation value.
+    /// * `Err([RoqoqoError::ExpValUsedTwice])` - The name of the matrix is already taken.
+    /// * `Err([RoqoqoError::GenericError])` - The matrix is empty or the rows differ in length.
+    pub fn add_exp_val_matrix(
+        &mut self,
+        name: String,
+        rows: Vec<Vec<String>>,
+    ) -> Result<(), RoqoqoError> {
+        let defined = &self.measured_operators;
+        add_exp_val_group(
+            &mut self.exp_val_groups,
+            name,
+            ExpValGroup::Matrix(rows),
+            |component| defined.contains_key(component),
+        )
+    }
+}
+
+/// Provides necessary information to run a [crate::measurements::Histogram] measurement.
+///
+/// Every readout register is evaluated into a histogram of measured bitstrings.
+/// Additionally, marginal probability distributions can be defined on selected qubits
+/// of a readout register.
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "json_schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct HistogramInput {
+    /// Collection of names and definitions of marginal probability distributions.
+    ///
+    /// The definition is given by the name of the readout register and the measured qubits
+    /// (readout bits) of the marginal distribution.
+    pub marginals: HashMap<String, (String, Vec<usize>)>,
+    /// Optional readout error mitigation applied to the marginal probability distributions.
+    pub readout_mitigation: Option<ReadoutMitigation>,
+}
+
+impl HistogramInput {
+    /// Creates new HistogramInput.
+    ///
+    /// The marginals start empty and can be extended with [HistogramInput::add_marginal].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the readout error mitigation applied to the marginal probability distributions.
+    ///
+    /// # Arguments
+    ///
+    /// * `readout_mitigation` - The confusion matrices and correction method (None disables mitigation).
+    pub fn set_readout_mitigation(&mut self, readout_mitigation: Option<ReadoutMitigation>) {
+        self.readout_mitigation = readout_mitigation;
+    }
+
+    /// Adds a marginal probability distribution to the measurement input.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the marginal probability distribution.
+    /// * `readout` - The name of the readout register the distribution is evaluated from.
+    /// * `qubits` - The measured qubits (readout bits) the distribution is defined on.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - The marginal was successfully added.
+    /// * `Err([RoqoqoError::ExpValUsedTwice])` - The name of the marginal is already taken.
+    /// * `Err([RoqoqoError::HistogramMeasurementError])` - The qubits are empty or contain duplicates.
+    pub fn add_marginal(
+        &mut self,
+        name: String,
+        readout: String,
+        qubits: Vec<usize>,
+    ) -> Result<(), RoqoqoError> {
+        if qubits.is_empty() {
+            return Err(RoqoqoError::HistogramMeasurementError {
+                msg: format!("Marginal {} is not defined on any qubit", name),
+            });
+        }
+        if let Some((index, qubit)) = qubits
+            .iter()
+            .enumerate()
+            .find(|(index, qubit)| qubits[..*index].contains(qubit))
+        {
+            return Err(RoqoqoError::HistogramMeasurementError {
+                msg: format!(
+                    "Qubit {} appears more than once in marginal {} at position {}",
+                    qubit, name, index
+                ),
+            });
+        }
+        if self.marginals.contains_key(&name) {
+            return Err(RoqoqoError::ExpValUsedTwice { name });
+        }
+        self.marginals.insert(name, (readout, qubits));
+        Ok(())
+    }
 }
@@ -33,11 +33,38 @@ pub use cheated_basis_rotation_measurement::*;
 mod basis_rotation_measurement;
 pub use basis_rotation_measurement::*;
 #[doc(hidden)]
+mod adaptive_shots;
+pub use adaptive_shots::*;
+#[doc(hidden)]
+mod basis_rotation_builder;
+pub use basis_rotation_builder::*;
+#[doc(hidden)]
 mod cheated_measurement;
 pub use cheated_measurement::*;
 #[doc(hidden)]
 mod classical_register_measurement;
 pub use classical_register_measurement::*;
+#[doc(hidden)]
+mod complex_expectation_values;
+pub use complex_expectation_values::*;
+#[doc(hidden)]
+mod classical_shadows_measurement;
+pub use classical_shadows_measurement::*;
+#[doc(hidden)]
+mod histogram_measurement;
+pub use histogram_measurement::*;
+#[doc(hidden)]
+mod readout_mitigation;
+pub use readout_mitigation::*;
+#[doc(hidden)]
+mod sparse_operator;
+pub use sparse_operator::*;
+#[doc(hidden)]
+mod statistical_errors;
+pub use statistical_errors::*;
+#[doc(hidden)]
+mod zero_noise_extrapolation;
+pub use zero_noise_extrapolation::*;
 
 use crate::registers::BitOutputRegister;
 use crate::{
@@ -203,4 +230,66 @@ pub trait MeasureExpectationValues: PartialEq + Clone + Measure {
         float_registers: HashMap<String, FloatOutputRegister>,
         complex_registers: HashMap<String, ComplexOutputRegister>,
     ) -> Result<Option<HashMap<String, f64>>, RoqoqoError>;
+
+    /// Returns true if the expectation values are linear functions of the states prepared by the circuits.
+    ///
+    /// Linear expectation values can be differentiated operation by operation with the parameter-shift rule,
+    /// see [crate::optimization::parameter_shift_gradient].
+    /// The default implementation returns false.
+    fn is_linear(&self) -> bool {
+        false
+    }
+}
+
+/// Allows generic interfacing with roqoqo measurements that estimate the statistical errors of expectation values.
+///
+/// The statistical errors are the shot noise of sampled readouts or the spread of
+/// simulated trajectories.
+pub trait MeasureExpectationValuesWithErrors: MeasureExpectationValues {
+    /// Evaluates measurement results based on classical registers and estimates their statistical errors.
+    ///
+    /// Arguments:
+    ///
+    /// * `bit_registers` - The classical bit registers as a HashMap with the register name as key.
+    /// * `float_registers` - The classical float registers as a HashMap with the register name as key.
+    /// * `complex_registers` - The classical complex registers as a HashMap with the register name as key.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Some(ExpectationValuesWithErrors))` - The measurement has been evaluated successfully. Contains the means and standard errors of the expectation values and the covariance of the Pauli products.
+    /// * `Ok(None)` - The measurement did not fail but is incomplete. A new round of measurements is needed.
+    /// * `Err(RoqoqoError)` - The measurement evaluation failed.
+    fn evaluate_with_errors(
+        &self,
+        bit_registers: HashMap<String, BitOutputRegister>,
+        float_registers: HashMap<String, FloatOutputRegister>,
+        complex_registers: HashMap<String, ComplexOutputRegister>,
+    ) -> Result<Option<ExpectationValuesWithErrors>, RoqoqoError>;
+}
+
+/// Allows generic interfacing with roqoqo measurements that evaluate complex expectation values.
+///
+/// The real parts of the complex expectation values are the expectation values returned by
+/// [MeasureExpectationValues::evaluate]. The expectation values are additionally grouped into the
+/// named vectors and matrices defined in the measurement input.
+pub trait MeasureComplexExpectationValues: MeasureExpectationValues {
+    /// Evaluates measurement results based on classical registers into complex expectation values.
+    ///
+    /// Arguments:
+    ///
+    /// * `bit_registers` - The classical bit registers as a HashMap with the register name as key.
+    /// * `float_registers` - The classical float registers as a HashMap with the register name as key.
+    /// * `complex_registers` - The classical complex registers as a HashMap with the register name as key.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Some(ComplexExpectationValues))` - The measurement has been evaluated successfully. Contains the complex expectation values and their vectors and matrices.
+    /// * `Ok(None)` - The measurement did not fail but is incomplete. A new round of measurements is needed.
+    /// * `Err(RoqoqoError)` - The measurement evaluation failed.
+    fn evaluate_complex(
+        &self,
+        bit_registers: HashMap<String, BitOutputRegister>,
+        float_registers: HashMap<String, FloatOutputRegister>,
+        complex_registers: HashMap<String, ComplexOutputRegister>,
+    ) -> Result<Option<ComplexExpectationValues>, RoqoqoError>;
 }
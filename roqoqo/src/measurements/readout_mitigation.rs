@@ -0,0 +1,506 @@
+// Copyright © 2021 HQS Quantum Simulations GmbH. All Rights Reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except
+// in compliance with the License. You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the
+// License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either
+// express or implied. See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::devices::Device;
+use crate::RoqoqoError;
+use nalgebra::DMatrix;
+use ndarray::{Array1, Array2};
+use std::collections::HashMap;
+
+/// Method used to correct the measured probabilities for readout errors.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "json_schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub enum ReadoutMitigationMethod {
+    /// Applies the inverse of the tensor product of the confusion matrices to the measured probabilities.
+    ///
+    /// For a small number of shots the corrected probabilities can become negative.
+    TensoredInversion,
+    /// Corrects the measured probabilities with iterative Bayesian unfolding.
+    ///
+    /// The corrected probabilities are always non-negative.
+    IterativeBayesianUnfolding {
+        /// Maximal number of iterations.
+        max_iterations: usize,
+        /// Iteration stops when no probability changes by more than the tolerance.
+        tolerance: f64,
+    },
+}
+
+/// Provides the readout error information used to mitigate readout errors in a [crate::measurements::BasisRotation] measurement.
+///
+/// Readout errors are described by confusion matrices where the entry `[i, j]` is the probability
+/// to read out `i` when the qubit is in state `j`, so every column sums up to one.
+/// Confusion matrices are assigned to the indices in the readout registers, which are the qubit indices
+/// when the qubits are measured with the default mapping.
+///
+/// Groups of readout bits with correlated readout errors are described by a single confusion matrix
+/// of dimension `2^n` where bit `k` of the row and column index corresponds to the `k`-th readout bit of the group.
+/// The correlated confusion matrix replaces the single qubit confusion matrices of the bits in the group.
+/// Readout bits without a confusion matrix are assumed to be read out perfectly.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "json_schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct ReadoutMitigation {
+    /// Confusion matrices of single readout bits.
+    pub single_qubit_confusion_matrices: HashMap<usize, Array2<f64>>,
+    /// Confusion matrices of groups of readout bits with correlated readout errors.
+    pub correlated_confusion_matrices: Vec<(Vec<usize>, Array2<f64>)>,
+    /// The method used to correct the measured probabilities.
+    pub method: ReadoutMitigationMethod,
+}
+
+/// Confusion matrix acting on a subset of the readout bits involved in a Pauli product.
+struct ConfusionBlock {
+    /// Positions of the readout bits of the block in the mitigated bits.
+    positions: Vec<usize>,
+    matrix: Array2<f64>,
+}
+
+impl ReadoutMitigation {
+    /// Creates new ReadoutMitigation without any confusion matrices.
+    ///
+    /// # Arguments
+    ///
+    /// * `method` - The method used to correct the measured probabilities.
+    pub fn new(method: ReadoutMitigationMethod) -> Self {
+        Self {
+            single_qubit_confusion_matrices: HashMap::new(),
+            correlated_confusion_matrices: Vec::new(),
+            method,
+        }
+    }
+
+    /// Creates new ReadoutMitigation with the single qubit confusion matrices of a device.
+    ///
+    /// # Arguments
+    ///
+    /// * `device` - The device providing the confusion matrices with [Device::readout_confusion_matrix].
+    /// * `method` - The method used to correct the measured probabilities.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Self)` - The ReadoutMitigation for the device.
+    /// * `Err([RoqoqoError::BasisRotationMeasurementError])` - The device returned an invalid confusion matrix.
+    pub fn from_device<T>(device: &T, method: ReadoutMitigationMethod) -> Result<Self, RoqoqoError>
+    where
+        T: Device,
+    {
+        let mut mitigation = Self::new(method);
+        for qubit in 0..device.number_qubits() {
+            if let Some(matrix) = device.readout_confusion_matrix(&qubit) {
+                mitigation.add_single_qubit_confusion_matrix(qubit, matrix)?;
+            }
+        }
+        Ok(mitigation)
+    }
+
+    /// Adds the confusion matrix of a single readout bit.
+    ///
+    /// # Arguments
+    ///
+    /// * `qubit` - The index of the readout bit.
+    /// * `matrix` - The 2x2 confusion matrix.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - The confusion matrix was added.
+    /// * `Err([RoqoqoError::BasisRotationMeasurementError])` - The matrix is not a valid 2x2 confusion matrix.
+    pub fn add_single_qubit_confusion_matrix(
+        &mut self,
+        qubit: usize,
+        matrix: Array2<f64>,
+    ) -> Result<(), RoqoqoError> {
+        validate_confusion_matrix(&matrix, 1)?;
+        self.single_qubit_confusion_matrices.insert(qubit, matrix);
+        Ok(())
+    }
+
+    /// Adds the confusion matrix of a group of readout bits with correlated readout errors.
+    ///
+    /// # Arguments
+    ///
+    /// * `qubits` - The indices of the readout bits in the group.
+    /// * `matrix` - The confusion matrix of dimension `2^qubits.len()`.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - The confusion matrix was added.
+    /// * `Err([RoqoqoError::BasisRotationMeasurementError])` - The matrix is not a valid confusion matrix or the group overlaps with another group.
+    pub fn add_correlated_confusion_matrix(
+        &mut self,
+        qubits: Vec<usize>,
+        matrix: Array2<f64>,
+    ) -> Result<(), RoqoqoError> {
+        if qubits.is_empty() {
+            return Err(RoqoqoError::BasisRotationMeasurementError {
+                msg: "Correlated confusion matrix needs at least one readout bit".to_string(),
+            });
+        }
+        for (index, qubit) in qubits.iter().enumerate() {
+            if qubits[..index].contains(qubit)
+                || self
+                    .correlated_confusion_matrices
+                    .iter()
+                    .any(|(group, _)| group.contains(qubit))
+            {
+                return Err(RoqoqoError::BasisRotationMeasurementError {
+                    msg: format!(
+                        "Readout bit {} is part of more than one correlated confusion matrix",
+                        qubit
+                    ),
+                });
+            }
+        }
+        validate_confusion_matrix(&matrix, qubits.len())?;
+        self.correlated_confusion_matrices.push((qubits, matrix));
+        Ok(())
+    }
+
+    /// Returns the readout-error mitigated expectation value of a Pauli product.
+    ///
+    /// The measured probabilities of the readout bits in the Pauli product (and of the bits correlated with them)
+    /// are corrected with the chosen method before the expectation value is calculated.
+    ///
+    /// # Arguments
+    ///
+    /// * `register` - The single shot readouts of the readout register.
+    /// * `mask` - The indices of the readout bits involved in the Pauli product.
+    /// * `flipped` - Whether all qubits were flipped before the readout.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(f64)` - The mitigated expectation value of the Pauli product.
+    /// * `Err([RoqoqoError::BasisRotationMeasurementError])` - The correction failed.
+    pub fn mitigated_pauli_product(
+        &self,
+        register: &[Vec<bool>],
+        mask: &[usize],
+        flipped: bool,
+    ) -> Result<f64, RoqoqoError> {
+        if mask.is_empty() {
+            return Ok(1.0);
+        }
+        let (bits, blocks) = self.confusion_blocks(mask);
+        let corrected = self.corrected_probabilities(register, &bits, &blocks)?;
+        Ok(corrected.dot(&parity_signs(&bits, mask, flipped)))
+    }
+
+    /// Returns the readout-error mitigated probabilities of the outcomes of a set of readout bits.
+    ///
+    /// The readout bits correlated with the requested bits are mitigated together with them
+    /// and summed over afterwards.
+    /// Corrections with [ReadoutMitigationMethod::TensoredInversion] can lead to negative probabilities.
+    ///
+    /// # Arguments
+    ///
+    /// * `register` - The single shot readouts of the readout register.
+    /// * `bits` - The distinct indices of the readout bits, bit `k` of the outcome index corresponds to `bits[k]`.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Array1<f64>)` - The mitigated probabilities of the `2^bits.len()` outcomes.
+    /// * `Err([RoqoqoError::BasisRotationMeasurementError])` - The correction failed.
+    pub fn mitigated_probabilities(
+        &self,
+        register: &[Vec<bool>],
+        bits: &[usize],
+    ) -> Result<Array1<f64>, RoqoqoError> {
+        let (all_bits, blocks) = self.confusion_blocks(bits);
+        let corrected = self.corrected_probabilities(register, &all_bits, &blocks)?;
+        let outcome_mask = (1 << bits.len()) - 1;
+        let mut probabilities: Array1<f64> = Array1::zeros(1 << bits.len());
+        for (index, probability) in corrected.iter().enumerate() {
+            probabilities[index & outcome_mask] += probability;
+        }
+        Ok(probabilities)
+    }
+
+    /// Returns effective single shot values of a Pauli product corrected for readout errors.
+    ///
+    /// The effective value of a shot is the entry of the readout outcome in the Pauli product
+    /// transformed with the inverse of the confusion matrices, so the mean of the effective values is the
+    /// expectation value corrected with tensored inversion.
+    /// The values are used to estimate statistical errors and linearize
+    /// iterative Bayesian unfolding around the tensored inversion.
+    ///
+    /// # Arguments
+    ///
+    /// * `register` - The single shot readouts of the readout register.
+    /// * `mask` - The indices of the readout bits involved in the Pauli product.
+    /// * `flipped` - Whether all qubits were flipped before the readout.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Array1<f64>)` - The effective value of each shot.
+    /// * `Err([RoqoqoError::BasisRotationMeasurementError])` - The correction failed.
+    pub fn single_shot_pauli_product(
+        &self,
+        register: &[Vec<bool>],
+        mask: &[usize],
+        flipped: bool,
+    ) -> Result<Array1<f64>, RoqoqoError> {
+        if mask.is_empty() {
+            return Ok(Array1::ones(register.len()));
+        }
+        let (bits, blocks) = self.confusion_blocks(mask);
+        let mut weights = parity_signs(&bits, mask, flipped);
+        for block in blocks.iter() {
+            weights = apply_block(
+                &weights,
+                &block.positions,
+                &invert(&block.matrix)?.t().to_owned(),
+            );
+        }
+        Ok(shot_indices(register, &bits)?
+            .into_iter()
+            .map(|index| weights[index])
+            .collect())
+    }
+
+    /// Returns the measured probabilities of the outcomes of the bits corrected with the confusion blocks.
+    fn corrected_probabilities(
+        &self,
+        register: &[Vec<bool>],
+        bits: &[usize],
+        blocks: &[ConfusionBlock],
+    ) -> Result<Array1<f64>, RoqoqoError> {
+        if register.is_empty() {
+            return Err(RoqoqoError::BasisRotationMeasurementError {
+                msg: "Readout register contains no measurements".to_string(),
+            });
+        }
+        let mut measured: Array1<f64> = Array1::zeros(1 << bits.len());
+        for index in shot_indices(register, bits)? {
+            measured[index] += 1.0;
+        }
+        measured /= register.len() as f64;
+
+        Ok(match self.method {
+            ReadoutMitigationMethod::TensoredInversion => {
+                let mut corrected = measured;
+                for block in blocks.iter() {
+                    corrected = apply_block(&corrected, &block.positions, &invert(&block.matrix)?);
+                }
+                corrected
+            }
+            ReadoutMitigationMethod::IterativeBayesianUnfolding {
+                max_iterations,
+                tolerance,
+            } => iterative_bayesian_unfolding(&measured, blocks, max_iterations, tolerance),
+        })
+    }
+
+    /// Returns the readout bits that need to be mitigated together with the bits in mask
+    /// and the confusion matrices acting on them.
+    fn confusion_blocks(&self, mask: &[usize]) -> (Vec<usize>, Vec<ConfusionBlock>) {
+        let mut bits: Vec<usize> = Vec::new();
+        for bit in mask {
+            if !bits.contains(bit) {
+                bits.push(*bit);
+            }
+        }
+        let groups: Vec<&(Vec<usize>, Array2<f64>)> = self
+            .correlated_confusion_matrices
+            .iter()
+            .filter(|(group, _)| group.iter().any(|bit| bits.contains(bit)))
+            .collect();
+        for (group, _) in groups.iter() {
+            for bit in group {
+                if !bits.contains(bit) {
+                    bits.push(*bit);
+                }
+            }
+        }
+        let mut blocks: Vec<ConfusionBlock> = groups
+            .into_iter()
+            .map(|(group, matrix)| ConfusionBlock {
+                positions: group
+                    .iter()
+                    .filter_map(|bit| bits.iter().position(|b| b == bit))
+                    .collect(),
+                matrix: matrix.clone(),
+            })
+            .collect();
+        for (position, bit) in bits.iter().enumerate() {
+            if blocks
+                .iter()
+                .any(|block| block.positions.contains(&position))
+            {
+                continue;
+            }
+            if let Some(matrix) = self.single_qubit_confusion_matrices.get(bit) {
+                blocks.push(ConfusionBlock {
+                    positions: vec![position],
+                    matrix: matrix.clone(),
+                });
+            }
+        }
+        (bits, blocks)
+    }
+}
+
+/// Returns the index of the readout outcome of the bits for every shot.
+fn shot_indices(register: &[Vec<bool>], bits: &[usize]) -> Result<Vec<usize>, RoqoqoError> {
+    let mut indices: Vec<usize> = Vec::with_capacity(register.len());
+    for values in register.iter() {
+        let mut index = 0;
+        for (position, bit) in bits.iter().enumerate() {
+            match values.get(*bit) {
+                Some(true) => index |= 1 << position,
+                Some(false) => (),
+                None => {
+                    return Err(RoqoqoError::BasisRotationMeasurementError {
+                        msg: format!("Readout bit {} not found in readout register", bit),
+                    })
+                }
+            }
+        }
+        indices.push(index);
+    }
+    Ok(indices)
+}
+
+/// Returns the value (1 or -1) of the Pauli product for every readout outcome of the bits.
+fn parity_signs(bits: &[usize], mask: &[usize], flipped: bool) -> Array1<f64> {
+    let mask_positions: Vec<usize> = mask
+        .iter()
+        .filter_map(|bit| bits.iter().position(|b| b == bit))
+        .collect();
+    Array1::from_shape_fn(1 << bits.len(), |index| {
+        // For flipped readout a false (0) qubit measurement will flip the parity
+        let parity = mask_positions
+            .iter()
+            .filter(|position| ((index >> **position) & 1 == 1) ^ flipped)
+            .count()
+            % 2;
+        match parity {
+            0 => 1.0,
+            _ => -1.0,
+        }
+    })
+}
+
+/// Checks that a matrix is a confusion matrix for the given number of readout bits.
+fn validate_confusion_matrix(matrix: &Array2<f64>, number_bits: usize) -> Result<(), RoqoqoError> {
+    let dimension = 1 << number_bits;
+    if matrix.shape() != [dimension, dimension] {
+        return Err(RoqoqoError::BasisRotationMeasurementError {
+            msg: format!(
+                "Confusion matrix of {} readout bits must have shape [{}, {}] but has shape {:?}",
+                number_bits,
+                dimension,
+                dimension,
+                matrix.shape()
+            ),
+        });
+    }
+    if matrix.iter().any(|probability| *probability < 0.0) {
+        return Err(RoqoqoError::BasisRotationMeasurementError {
+            msg: "Confusion matrix contains negative probabilities".to_string(),
+        });
+    }
+    for column in matrix.columns() {
+        if (column.sum() - 1.0).abs() > 1e-6 {
+            return Err(RoqoqoError::BasisRotationMeasurementError {
+                msg: "Columns of confusion matrix do not sum up to one".to_string(),
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Inverts a confusion matrix.
+fn invert(matrix: &Array2<f64>) -> Result<Array2<f64>, RoqoqoError> {
+    let dimension = matrix.nrows();
+    let inverse = DMatrix::from_fn(dimension, dimension, |i, j| matrix[[i, j]])
+        .try_inverse()
+        .ok_or_else(|| RoqoqoError::BasisRotationMeasurementError {
+            msg: "Confusion matrix is not invertible".to_string(),
+        })?;
+    Ok(Array2::from_shape_fn((dimension, dimension), |(i, j)| {
+        inverse[(i, j)]
+    }))
+}
+
+/// Applies a matrix acting on the bits at positions to a probability vector over all mitigated bits.
+fn apply_block(
+    probabilities: &Array1<f64>,
+    positions: &[usize],
+    matrix: &Array2<f64>,
+) -> Array1<f64> {
+    let block_mask: usize = positions.iter().map(|position| 1 << *position).sum();
+    let deposit = |local: usize| -> usize {
+        positions
+            .iter()
+            .enumerate()
+            .filter(|(k, _)| (local >> *k) & 1 == 1)
+            .map(|(_, position)| 1 << *position)
+            .sum()
+    };
+    let extract = |index: usize| -> usize {
+        positions
+            .iter()
+            .enumerate()
+            .filter(|(_, position)| (index >> **position) & 1 == 1)
+            .map(|(k, _)| 1 << k)
+            .sum()
+    };
+    let mut result: Array1<f64> = Array1::zeros(probabilities.len());
+    for (index, value) in result.iter_mut().enumerate() {
+        let local_out = extract(index);
+        let base = index & !block_mask;
+        for local_in in 0..matrix.ncols() {
+            *value += matrix[[local_out, local_in]] * probabilities[base | deposit(local_in)];
+        }
+    }
+    result
+}
+
+/// Corrects measured probabilities with iterative Bayesian unfolding.
+fn iterative_bayesian_unfolding(
+    measured: &Array1<f64>,
+    blocks: &[ConfusionBlock],
+    max_iterations: usize,
+    tolerance: f64,
+) -> Array1<f64> {
+    let transposed: Vec<Array2<f64>> = blocks
+        .iter()
+        .map(|block| block.matrix.t().to_owned())
+        .collect();
+    let mut estimate: Array1<f64> = Array1::from_elem(measured.len(), 1.0 / measured.len() as f64);
+    for _ in 0..max_iterations {
+        let mut expected = estimate.clone();
+        for block in blocks.iter() {
+            expected = apply_block(&expected, &block.positions, &block.matrix);
+        }
+        let mut ratio: Array1<f64> = measured
+            .iter()
+            .zip(expected.iter())
+            .map(|(m, e)| if *e > 0.0 { m / e } else { 0.0 })
+            .collect();
+        for (block, matrix) in blocks.iter().zip(transposed.iter()) {
+            ratio = apply_block(&ratio, &block.positions, matrix);
+        }
+        let new_estimate = &estimate * &ratio;
+        let change = new_estimate
+            .iter()
+            .zip(estimate.iter())
+            .map(|(new, old)| (new - old).abs())
+            .fold(0.0, f64::max);
+        estimate = new_estimate;
+        if change < tolerance {
+            break;
+        }
+    }
+    estimate
+}
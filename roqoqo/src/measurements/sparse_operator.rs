@@ -0,0 +1,151 @@
+// Copyright © 2021 HQS Quantum Simulations GmbH. All Rights Reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except
+// in compliance with the License. You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the
+// License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either
+// express or implied. See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::RoqoqoError;
+use num_complex::Complex64;
+
+/// Operator on the Hilbert space of qubits in compressed sparse row (CSR) format.
+///
+/// Used by [crate::measurements::Cheated] to evaluate expectation values of operators
+/// given as [crate::measurements::OperatorSparseVec]. The non-zero entries of row `i` are
+/// stored in `values[row_offsets[i]..row_offsets[i + 1]]` with the corresponding column
+/// indices in `column_indices`. Entries with the same row and column index are summed.
+///
+/// # Example
+///
+/// ```
+/// use roqoqo::measurements::SparseOperatorCsr;
+/// use num_complex::Complex64;
+///
+/// let operator = SparseOperatorCsr::from_triplets(
+///     &[(0, 0, Complex64::new(1.0, 0.0)), (1, 1, Complex64::new(-1.0, 0.0))],
+///     1,
+/// )
+/// .unwrap();
+/// let value = operator.vector_expectation_value(&[Complex64::new(1.0, 0.0), Complex64::new(0.0, 0.0)]);
+/// assert_eq!(value, Complex64::new(1.0, 0.0));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct SparseOperatorCsr {
+    dimension: usize,
+    row_offsets: Vec<usize>,
+    column_indices: Vec<usize>,
+    values: Vec<Complex64>,
+}
+
+impl SparseOperatorCsr {
+    /// Creates a new SparseOperatorCsr from the triplets of the non-zero entries of an operator.
+    ///
+    /// # Arguments
+    ///
+    /// * `triplets` - The entries of the operator of the form (row, col, value).
+    /// * `number_qubits` - The number of qubits defining the dimension of the Hilbert space.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Self)` - The operator in CSR format.
+    /// * `Err([RoqoqoError::MismatchedOperatorDimension])` - The index of an entry exceeds the Hilbert space dimension of the qubits.
+    pub fn from_triplets(
+        triplets: &[(usize, usize, Complex64)],
+        number_qubits: usize,
+    ) -> Result<Self, RoqoqoError> {
+        let dimension = 2_usize.pow(number_qubits as u32);
+        if let Some((row, column, _)) = triplets
+            .iter()
+            .find(|(row, column, _)| row >= &dimension || column >= &dimension)
+        {
+            return Err(RoqoqoError::MismatchedOperatorDimension {
+                index: (*row, *column),
+                number_qubits,
+            });
+        }
+        let mut sorted: Vec<(usize, usize, Complex64)> = triplets.to_vec();
+        sorted.sort_unstable_by_key(|(row, column, _)| (*row, *column));
+
+        let mut row_offsets: Vec<usize> = vec![0; dimension + 1];
+        let mut column_indices: Vec<usize> = Vec::with_capacity(sorted.len());
+        let mut values: Vec<Complex64> = Vec::with_capacity(sorted.len());
+        let mut last_entry: Option<(usize, usize)> = None;
+        for (row, column, value) in sorted {
+            if last_entry == Some((row, column)) {
+                if let Some(last_value) = values.last_mut() {
+                    *last_value += value;
+                }
+                continue;
+            }
+            row_offsets[row + 1] += 1;
+            column_indices.push(column);
+            values.push(value);
+            last_entry = Some((row, column));
+        }
+        for row in 0..dimension {
+            row_offsets[row + 1] += row_offsets[row];
+        }
+        Ok(Self {
+            dimension,
+            row_offsets,
+            column_indices,
+            values,
+        })
+    }
+
+    /// Returns the dimension of the Hilbert space the operator acts on.
+    pub fn dimension(&self) -> usize {
+        self.dimension
+    }
+
+    /// Returns the number of stored non-zero entries of the operator.
+    pub fn number_nonzero(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Returns the expectation value <ψ|O|ψ> of the operator for a state vector.
+    ///
+    /// The length of the state vector has to be equal to the dimension of the operator.
+    ///
+    /// # Arguments
+    ///
+    /// * `vector` - The state vector |ψ>.
+    pub fn vector_expectation_value(&self, vector: &[Complex64]) -> Complex64 {
+        let mut value = Complex64::new(0.0, 0.0);
+        for (row, bounds) in self.row_offsets.windows(2).enumerate() {
+            if bounds[0] == bounds[1] {
+                continue;
+            }
+            let mut row_value = Complex64::new(0.0, 0.0);
+            for index in bounds[0]..bounds[1] {
+                row_value += self.values[index] * vector[self.column_indices[index]];
+            }
+            value += vector[row].conj() * row_value;
+        }
+        value
+    }
+
+    /// Returns the expectation value Tr(Oρ) of the operator for a density matrix.
+    ///
+    /// The density matrix is flattened in row-major order and has to have the
+    /// squared dimension of the operator as length.
+    ///
+    /// # Arguments
+    ///
+    /// * `density_matrix` - The flattened density matrix ρ.
+    pub fn density_matrix_expectation_value(&self, density_matrix: &[Complex64]) -> Complex64 {
+        let mut value = Complex64::new(0.0, 0.0);
+        for (row, bounds) in self.row_offsets.windows(2).enumerate() {
+            for index in bounds[0]..bounds[1] {
+                value += self.values[index]
+                    * density_matrix[self.column_indices[index] * self.dimension + row];
+            }
+        }
+        value
+    }
+}
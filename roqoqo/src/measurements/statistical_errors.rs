@@ -0,0 +1,191 @@
+// Copyright © 2021 HQS Quantum Simulations GmbH. All Rights Reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except
+// in compliance with the License. You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the
+// License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either
+// express or implied. See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::PauliProductsToExpVal;
+use crate::RoqoqoError;
+use ndarray::{Array1, Array2, ArrayView2};
+use std::collections::HashMap;
+
+/// Step size of the central finite differences used to propagate errors through symbolic expressions.
+const FINITE_DIFFERENCE_STEP: f64 = 1e-6;
+
+/// Mean and statistical standard error of a measured expectation value.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "json_schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct ExpectationValueWithError {
+    /// The mean of the expectation value.
+    pub mean: f64,
+    /// The standard error of the mean.
+    pub standard_error: f64,
+}
+
+/// Expectation values with statistical errors returned by [crate::measurements::MeasureExpectationValuesWithErrors].
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "json_schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct ExpectationValuesWithErrors {
+    /// The measured expectation values with their standard errors.
+    pub expectation_values: HashMap<String, ExpectationValueWithError>,
+    /// The covariance matrix of the means of the measured Pauli products.
+    ///
+    /// The rows and columns correspond to the Pauli product indices of the measurement input.
+    /// Empty for measurements that are not based on Pauli products.
+    pub pauli_product_covariance: Array2<f64>,
+}
+
+/// Returns the covariance matrix of the means of the columns of single shot results.
+///
+/// # Arguments
+///
+/// * `single_shot_values` - The single shot results with one row per shot.
+///
+/// # Returns
+///
+/// * `Ok(Array2<f64>)` - The sample covariance matrix divided by the number of shots.
+/// * `Err([RoqoqoError::GenericError])` - Less than two shots are available.
+pub(crate) fn covariance_of_means(
+    single_shot_values: ArrayView2<f64>,
+) -> Result<Array2<f64>, RoqoqoError> {
+    let number_shots = single_shot_values.nrows();
+    if number_shots < 2 {
+        return Err(RoqoqoError::GenericError {
+            msg: format!(
+                "At least two shots are needed to estimate statistical errors, found {}",
+                number_shots
+            ),
+        });
+    }
+    let means = single_shot_values
+        .mean_axis(ndarray::Axis(0))
+        .expect("Unexpectedly could not calculate mean of single shot values");
+    let deviations = &single_shot_values - &means;
+    Ok(deviations.t().dot(&deviations) / ((number_shots - 1) as f64 * number_shots as f64))
+}
+
+/// Returns the mean and standard error of the mean of independent samples.
+///
+/// The standard error of a single sample is zero.
+pub(crate) fn mean_with_error(samples: &Array1<f64>) -> Option<ExpectationValueWithError> {
+    let mean = samples.mean()?;
+    let number_samples = samples.len();
+    let standard_error = if number_samples < 2 {
+        0.0
+    } else {
+        (samples.var(1.0) / number_samples as f64).sqrt()
+    };
+    Some(ExpectationValueWithError {
+        mean,
+        standard_error,
+    })
+}
+
+/// Propagates the covariance of the Pauli products to the measured expectation values.
+///
+/// Errors are propagated exactly through [PauliProductsToExpVal::Linear] and to linear order
+/// through [PauliProductsToExpVal::Symbolic], using central finite differences for the gradient.
+/// For [PauliProductsToExpVal::LinearComplex] the errors of the real part are returned.
+///
+/// # Arguments
+///
+/// * `measured_exp_vals` - The construction of the expectation values from the Pauli products.
+/// * `pauli_products` - The means of the Pauli products.
+/// * `covariance` - The covariance matrix of the means of the Pauli products.
+///
+/// # Returns
+///
+/// * `Ok(HashMap<String, ExpectationValueWithError>)` - The expectation values with standard errors.
+/// * `Err(RoqoqoError::CalculatorError)` - A symbolic expression could not be evaluated.
+pub(crate) fn propagate_errors(
+    measured_exp_vals: &HashMap<String, PauliProductsToExpVal>,
+    pauli_products: &Array1<f64>,
+    covariance: &Array2<f64>,
+) -> Result<HashMap<String, ExpectationValueWithError>, RoqoqoError> {
+    let mut results: HashMap<String, ExpectationValueWithError> = HashMap::new();
+    for (name, evaluation) in measured_exp_vals.iter() {
+        let (mean, gradient) = mean_and_gradient(evaluation, pauli_products, covariance)?;
+        let variance = gradient.dot(&covariance.dot(&gradient));
+        results.insert(
+            name.clone(),
+            ExpectationValueWithError {
+                mean,
+                standard_error: variance.max(0.0).sqrt(),
+            },
+        );
+    }
+    Ok(results)
+}
+
+/// Returns an expectation value and its gradient with respect to the Pauli products.
+///
+/// The gradient of [PauliProductsToExpVal::Symbolic] is calculated with central finite differences
+/// for the Pauli products with non-zero variance.
+///
+/// # Arguments
+///
+/// * `evaluation` - The construction of the expectation value from the Pauli products.
+/// * `pauli_products` - The means of the Pauli products.
+/// * `covariance` - The covariance matrix of the means of the Pauli products.
+///
+/// # Returns
+///
+/// * `Ok((f64, Array1<f64>))` - The expectation value and its gradient.
+/// * `Err(RoqoqoError::CalculatorError)` - A symbolic expression could not be evaluated.
+pub(crate) fn mean_and_gradient(
+    evaluation: &PauliProductsToExpVal,
+    pauli_products: &Array1<f64>,
+    covariance: &Array2<f64>,
+) -> Result<(f64, Array1<f64>), RoqoqoError> {
+    Ok(match evaluation {
+        PauliProductsToExpVal::Linear(hm) => {
+            let mut mean: f64 = 0.0;
+            let mut gradient: Array1<f64> = Array1::zeros(pauli_products.len());
+            for (index, coefficient) in hm {
+                mean += pauli_products[*index] * coefficient;
+                gradient[*index] += coefficient;
+            }
+            (mean, gradient)
+        }
+        PauliProductsToExpVal::Symbolic(x) => {
+            let evaluate_at = |values: &Array1<f64>| -> Result<f64, RoqoqoError> {
+                let mut calculator = qoqo_calculator::Calculator::new();
+                for (ind, p) in values.iter().enumerate() {
+                    calculator.set_variable(format!("pauli_product_{}", ind).as_str(), *p);
+                }
+                Ok(calculator.parse_get(x.clone())?)
+            };
+            let mean = evaluate_at(pauli_products)?;
+            let mut gradient: Array1<f64> = Array1::zeros(pauli_products.len());
+            for index in 0..pauli_products.len() {
+                if covariance[[index, index]] == 0.0 {
+                    continue;
+                }
+                let mut shifted = pauli_products.clone();
+                shifted[index] += FINITE_DIFFERENCE_STEP;
+                let upper = evaluate_at(&shifted)?;
+                shifted[index] -= 2.0 * FINITE_DIFFERENCE_STEP;
+                let lower = evaluate_at(&shifted)?;
+                gradient[index] = (upper - lower) / (2.0 * FINITE_DIFFERENCE_STEP);
+            }
+            (mean, gradient)
+        }
+        PauliProductsToExpVal::LinearComplex(hm) => {
+            let mut mean: f64 = 0.0;
+            let mut gradient: Array1<f64> = Array1::zeros(pauli_products.len());
+            for (index, coefficient) in hm {
+                mean += pauli_products[*index] * coefficient.re;
+                gradient[*index] += coefficient.re;
+            }
+            (mean, gradient)
+        }
+    })
+}
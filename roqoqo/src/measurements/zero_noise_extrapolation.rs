@@ -0,0 +1,518 @@
+// Copyright © 2021 HQS Quantum Simulations GmbH. All Rights Reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except
+// in compliance with the License. You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the
+// License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either
+// express or implied. See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::{Measure, MeasureExpectationValues};
+use crate::backends::EvaluatingBackend;
+use crate::operations::*;
+use crate::registers::{BitOutputRegister, ComplexOutputRegister, FloatOutputRegister};
+use crate::{Circuit, RoqoqoBackendError, RoqoqoError};
+use qoqo_calculator::CalculatorFloat;
+use std::collections::HashMap;
+
+/// Method used to scale the noise of the measurement circuits.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "json_schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub enum NoiseScaling {
+    /// Adds a [crate::operations::PragmaBoostNoise] with the scale factor to the start of the constant circuit.
+    ///
+    /// Only supported by simulator backends that apply noise according to the gate times.
+    BoostNoise,
+    /// Replaces every gate G by G (G<sup>†</sup> G)<sup>n</sup>.
+    ///
+    /// For a circuit with d gates and scale factor λ the number of folded gates is round((λ-1)/2 · d),
+    /// distributed over the gates starting from the beginning of the circuit.
+    /// Scale factors that cannot be reached exactly are rounded to the closest achievable value.
+    UnitaryFolding,
+}
+
+/// Method used to extrapolate the expectation values to zero noise.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "json_schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub enum Extrapolation {
+    /// Least squares fit of a straight line through all scale factors.
+    Linear,
+    /// Polynomial through all scale factors, the polynomial degree is the number of scale factors minus one.
+    Richardson,
+    /// Least squares fit of an exponential decay `a · exp(-b λ)` towards zero.
+    ///
+    /// The expectation values at all scale factors need to have the same sign.
+    Exponential,
+}
+
+/// Zero-noise extrapolation of the expectation values of a measurement.
+///
+/// The measurement is run at several noise scale factors with an [crate::backends::EvaluatingBackend]
+/// and the expectation values are extrapolated to a scale factor of zero.
+///
+/// # Example
+/// ```
+/// use roqoqo::measurements::{Extrapolation, NoiseScaling, ZeroNoiseExtrapolation};
+/// use roqoqo::operations::RotateX;
+/// use roqoqo::Circuit;
+///
+/// let zne = ZeroNoiseExtrapolation::new(
+///     NoiseScaling::UnitaryFolding,
+///     Extrapolation::Richardson,
+///     vec![1.0, 3.0],
+/// )
+/// .unwrap();
+/// let mut circuit = Circuit::new();
+/// circuit += RotateX::new(0, 0.5.into());
+/// let folded = zne.scale_circuit(&circuit, 3.0).unwrap();
+///
+/// let mut expected = Circuit::new();
+/// expected += RotateX::new(0, 0.5.into());
+/// expected += RotateX::new(0, (-0.5).into());
+/// expected += RotateX::new(0, 0.5.into());
+/// assert_eq!(folded, expected);
+///
+/// let extrapolated = zne.extrapolate_values(&[0.8, 0.4]).unwrap();
+/// assert!((extrapolated - 1.0).abs() < 1e-10);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "json_schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct ZeroNoiseExtrapolation {
+    /// The method used to scale the noise.
+    pub noise_scaling: NoiseScaling,
+    /// The method used to extrapolate to zero noise.
+    pub extrapolation: Extrapolation,
+    /// The noise scale factors the measurement is run at.
+    pub scale_factors: Vec<f64>,
+}
+
+/// A measurement with noise scaled circuits.
+///
+/// Evaluates the measured registers with the original measurement.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScaledMeasurement<T> {
+    /// The original measurement.
+    pub measurement: T,
+    /// The noise scaled constant circuit.
+    pub constant_circuit: Option<Circuit>,
+    /// The noise scaled measurement circuits.
+    pub circuits: Vec<Circuit>,
+}
+
+impl ZeroNoiseExtrapolation {
+    /// Creates a new ZeroNoiseExtrapolation.
+    ///
+    /// # Arguments
+    ///
+    /// * `noise_scaling` - The method used to scale the noise.
+    /// * `extrapolation` - The method used to extrapolate to zero noise.
+    /// * `scale_factors` - The noise scale factors the measurement is run at.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Self)` - The new ZeroNoiseExtrapolation.
+    /// * `Err(RoqoqoError::ZeroNoiseExtrapolationError)` - Less than two scale factors, a scale factor smaller than one or repeated scale factors.
+    pub fn new(
+        noise_scaling: NoiseScaling,
+        extrapolation: Extrapolation,
+        scale_factors: Vec<f64>,
+    ) -> Result<Self, RoqoqoError> {
+        if scale_factors.len() < 2 {
+            return Err(RoqoqoError::ZeroNoiseExtrapolationError {
+                msg: "At least two scale factors are needed for extrapolation".to_string(),
+            });
+        }
+        for (index, factor) in scale_factors.iter().enumerate() {
+            if !factor.is_finite() || *factor < 1.0 {
+                return Err(RoqoqoError::ZeroNoiseExtrapolationError {
+                    msg: format!("Scale factor {} is smaller than one", factor),
+                });
+            }
+            if scale_factors[..index]
+                .iter()
+                .any(|other| (other - factor).abs() < f64::EPSILON)
+            {
+                return Err(RoqoqoError::ZeroNoiseExtrapolationError {
+                    msg: format!("Scale factor {} is used twice", factor),
+                });
+            }
+        }
+        Ok(Self {
+            noise_scaling,
+            extrapolation,
+            scale_factors,
+        })
+    }
+
+    /// Returns a circuit with the noise scaled by the scale factor.
+    ///
+    /// For [NoiseScaling::BoostNoise] a [crate::operations::PragmaBoostNoise] is added to the start of the circuit.
+    ///
+    /// # Arguments
+    ///
+    /// * `circuit` - The circuit that is scaled.
+    /// * `scale_factor` - The factor by which the noise is scaled.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Circuit)` - The noise scaled circuit.
+    /// * `Err(RoqoqoError)` - The circuit contains a gate that cannot be inverted for unitary folding.
+    pub fn scale_circuit(
+        &self,
+        circuit: &Circuit,
+        scale_factor: f64,
+    ) -> Result<Circuit, RoqoqoError> {
+        match self.noise_scaling {
+            NoiseScaling::BoostNoise => {
+                let mut scaled = Circuit::new();
+                scaled += PragmaBoostNoise::new(CalculatorFloat::from(scale_factor));
+                scaled += circuit;
+                Ok(scaled)
+            }
+            NoiseScaling::UnitaryFolding => fold_gates(circuit, scale_factor),
+        }
+    }
+
+    /// Returns a measurement with the noise of all circuits scaled by the scale factor.
+    ///
+    /// For [NoiseScaling::BoostNoise] the [crate::operations::PragmaBoostNoise] is added to the constant circuit only.
+    ///
+    /// # Arguments
+    ///
+    /// * `measurement` - The measurement that is scaled.
+    /// * `scale_factor` - The factor by which the noise is scaled.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(ScaledMeasurement<T>)` - The noise scaled measurement.
+    /// * `Err(RoqoqoError)` - A circuit contains a gate that cannot be inverted for unitary folding.
+    pub fn scale_measurement<T>(
+        &self,
+        measurement: &T,
+        scale_factor: f64,
+    ) -> Result<ScaledMeasurement<T>, RoqoqoError>
+    where
+        T: MeasureExpectationValues,
+    {
+        let (constant_circuit, circuits) = match self.noise_scaling {
+            NoiseScaling::BoostNoise => {
+                let empty = Circuit::new();
+                let constant_circuit = measurement.constant_circuit().as_ref().unwrap_or(&empty);
+                (
+                    Some(self.scale_circuit(constant_circuit, scale_factor)?),
+                    measurement.circuits().cloned().collect(),
+                )
+            }
+            NoiseScaling::UnitaryFolding => (
+                match measurement.constant_circuit() {
+                    Some(circuit) => Some(self.scale_circuit(circuit, scale_factor)?),
+                    None => None,
+                },
+                measurement
+                    .circuits()
+                    .map(|circuit| self.scale_circuit(circuit, scale_factor))
+                    .collect::<Result<Vec<Circuit>, RoqoqoError>>()?,
+            ),
+        };
+        Ok(ScaledMeasurement {
+            measurement: measurement.clone(),
+            constant_circuit,
+            circuits,
+        })
+    }
+
+    /// Runs the measurement at all scale factors and extrapolates the expectation values to zero noise.
+    ///
+    /// # Arguments
+    ///
+    /// * `backend` - The backend the scaled measurements are run with.
+    /// * `measurement` - The measurement that is run.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Some(HashMap<String, f64>))` - The extrapolated expectation values.
+    /// * `Ok(None)` - The evaluation of the measurement at one of the scale factors is incomplete.
+    /// * `Err(RoqoqoBackendError)` - Scaling, running or extrapolating the measurement failed.
+    pub fn run<T, B>(
+        &self,
+        backend: &B,
+        measurement: &T,
+    ) -> Result<Option<HashMap<String, f64>>, RoqoqoBackendError>
+    where
+        T: MeasureExpectationValues,
+        B: EvaluatingBackend,
+    {
+        let mut results: Vec<HashMap<String, f64>> = Vec::with_capacity(self.scale_factors.len());
+        for scale_factor in self.scale_factors.iter() {
+            let scaled = self.scale_measurement(measurement, *scale_factor)?;
+            match backend.run_measurement(&scaled)? {
+                Some(result) => results.push(result),
+                None => return Ok(None),
+            }
+        }
+        Ok(Some(self.extrapolate(&results)?))
+    }
+
+    /// Extrapolates the expectation values measured at the scale factors to zero noise.
+    ///
+    /// # Arguments
+    ///
+    /// * `results` - The expectation values for each scale factor, in the order of the scale factors.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(HashMap<String, f64>)` - The extrapolated expectation values.
+    /// * `Err(RoqoqoError)` - The number of results does not match the scale factors, an expectation value is missing or the extrapolation failed.
+    pub fn extrapolate(
+        &self,
+        results: &[HashMap<String, f64>],
+    ) -> Result<HashMap<String, f64>, RoqoqoError> {
+        if results.len() != self.scale_factors.len() {
+            return Err(RoqoqoError::ZeroNoiseExtrapolationError {
+                msg: format!(
+                    "Got {} results for {} scale factors",
+                    results.len(),
+                    self.scale_factors.len()
+                ),
+            });
+        }
+        let mut extrapolated: HashMap<String, f64> = HashMap::new();
+        for name in results[0].keys() {
+            let values = results
+                .iter()
+                .map(|result| {
+                    result.get(name).copied().ok_or_else(|| {
+                        RoqoqoError::ZeroNoiseExtrapolationError {
+                            msg: format!(
+                                "Expectation value {} is missing for a scale factor",
+                                name
+                            ),
+                        }
+                    })
+                })
+                .collect::<Result<Vec<f64>, RoqoqoError>>()?;
+            extrapolated.insert(name.clone(), self.extrapolate_values(&values)?);
+        }
+        Ok(extrapolated)
+    }
+
+    /// Extrapolates a single expectation value measured at the scale factors to zero noise.
+    ///
+    /// # Arguments
+    ///
+    /// * `values` - The expectation value for each scale factor, in the order of the scale factors.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(f64)` - The extrapolated expectation value.
+    /// * `Err(RoqoqoError)` - The number of values does not match the scale factors or the extrapolation failed.
+    pub fn extrapolate_values(&self, values: &[f64]) -> Result<f64, RoqoqoError> {
+        if values.len() != self.scale_factors.len() {
+            return Err(RoqoqoError::ZeroNoiseExtrapolationError {
+                msg: format!(
+                    "Got {} values for {} scale factors",
+                    values.len(),
+                    self.scale_factors.len()
+                ),
+            });
+        }
+        match self.extrapolation {
+            Extrapolation::Linear => Ok(linear_intercept(&self.scale_factors, values)),
+            Extrapolation::Richardson => {
+                // Lagrange interpolation polynomial evaluated at zero
+                let mut extrapolated = 0.0;
+                for (i, (factor_i, value)) in self.scale_factors.iter().zip(values).enumerate() {
+                    let mut weight = 1.0;
+                    for (j, factor_j) in self.scale_factors.iter().enumerate() {
+                        if i != j {
+                            weight *= factor_j / (factor_j - factor_i);
+                        }
+                    }
+                    extrapolated += weight * value;
+                }
+                Ok(extrapolated)
+            }
+            Extrapolation::Exponential => {
+                if values.iter().all(|value| *value == 0.0) {
+                    return Ok(0.0);
+                }
+                let sign = values[0].signum();
+                if values
+                    .iter()
+                    .any(|value| *value == 0.0 || value.signum() != sign)
+                {
+                    return Err(RoqoqoError::ZeroNoiseExtrapolationError {
+                        msg: "Exponential extrapolation needs values with the same sign"
+                            .to_string(),
+                    });
+                }
+                let logarithms: Vec<f64> = values.iter().map(|value| value.abs().ln()).collect();
+                Ok(sign * linear_intercept(&self.scale_factors, &logarithms).exp())
+            }
+        }
+    }
+}
+
+impl<T> Measure for ScaledMeasurement<T>
+where
+    T: MeasureExpectationValues,
+{
+    /// Returns the noise scaled circuits.
+    fn circuits<'a>(&'a self) -> Box<dyn Iterator<Item = &'a Circuit> + 'a> {
+        Box::new(self.circuits.iter())
+    }
+
+    /// Returns the noise scaled constant circuit.
+    fn constant_circuit(&self) -> &Option<Circuit> {
+        &self.constant_circuit
+    }
+
+    /// Returns clone of the ScaledMeasurement with symbolic parameters replaced.
+    fn substitute_parameters(
+        &self,
+        substituted_parameters: HashMap<String, f64>,
+    ) -> Result<Self, RoqoqoError> {
+        let mut calculator = qoqo_calculator::Calculator::new();
+        for (name, val) in substituted_parameters.iter() {
+            calculator.set_variable(name, *val)
+        }
+        let new_constant_circuit = match &self.constant_circuit {
+            None => None,
+            Some(c) => Some(c.substitute_parameters(&mut calculator)?),
+        };
+        let mut new_circuits = Vec::new();
+        for circ in self.circuits.iter() {
+            new_circuits.push(circ.substitute_parameters(&mut calculator)?)
+        }
+        Ok(Self {
+            measurement: self
+                .measurement
+                .substitute_parameters(substituted_parameters)?,
+            constant_circuit: new_constant_circuit,
+            circuits: new_circuits,
+        })
+    }
+}
+
+impl<T> MeasureExpectationValues for ScaledMeasurement<T>
+where
+    T: MeasureExpectationValues,
+{
+    /// Evaluates the measured registers with the original measurement.
+    fn evaluate(
+        &self,
+        bit_registers: HashMap<String, BitOutputRegister>,
+        float_registers: HashMap<String, FloatOutputRegister>,
+        complex_registers: HashMap<String, ComplexOutputRegister>,
+    ) -> Result<Option<HashMap<String, f64>>, RoqoqoError> {
+        self.measurement
+            .evaluate(bit_registers, float_registers, complex_registers)
+    }
+}
+
+/// Returns the intercept of the least squares straight line through the points.
+fn linear_intercept(x: &[f64], y: &[f64]) -> f64 {
+    let number_points = x.len() as f64;
+    let mean_x = x.iter().sum::<f64>() / number_points;
+    let mean_y = y.iter().sum::<f64>() / number_points;
+    let covariance: f64 = x
+        .iter()
+        .zip(y)
+        .map(|(xi, yi)| (xi - mean_x) * (yi - mean_y))
+        .sum();
+    let variance: f64 = x.iter().map(|xi| (xi - mean_x).powi(2)).sum();
+    mean_y - covariance / variance * mean_x
+}
+
+/// Replaces the gates G of the circuit by G (G<sup>†</sup> G)<sup>n</sup>.
+fn fold_gates(circuit: &Circuit, scale_factor: f64) -> Result<Circuit, RoqoqoError> {
+    let number_gates = circuit
+        .iter()
+        .filter(|operation| operation.tags().contains(&"GateOperation"))
+        .count();
+    let number_folds = ((scale_factor - 1.0) / 2.0 * number_gates as f64).round() as usize;
+    let mut folded = Circuit::new();
+    let mut gate_index = 0;
+    for operation in circuit.iter() {
+        folded += operation.clone();
+        if !operation.tags().contains(&"GateOperation") {
+            continue;
+        }
+        let mut repetitions = number_folds / number_gates;
+        if gate_index < number_folds % number_gates {
+            repetitions += 1;
+        }
+        if repetitions > 0 {
+            let inverse = inverse_gate(operation)?;
+            for _ in 0..repetitions {
+                folded += inverse.clone();
+                folded += operation.clone();
+            }
+        }
+        gate_index += 1;
+    }
+    Ok(folded)
+}
+
+/// Returns the inverse of a gate operation.
+///
+/// Gates without free parameters are inverted with [OperateConstantGate::inverse],
+/// rotations are inverted by negating the rotation angle.
+/// Single qubit gates without a simpler inverse are inverted to a [crate::operations::SingleQubitGate].
+fn inverse_gate(operation: &Operation) -> Result<Operation, RoqoqoError> {
+    Ok(match operation {
+        Operation::PauliX(op) => op.inverse().into(),
+        Operation::PauliY(op) => op.inverse().into(),
+        Operation::PauliZ(op) => op.inverse().into(),
+        Operation::Hadamard(op) => op.inverse().into(),
+        Operation::SqrtPauliX(op) => op.inverse().into(),
+        Operation::InvSqrtPauliX(op) => op.inverse().into(),
+        Operation::CNOT(op) => op.inverse().into(),
+        Operation::SWAP(op) => op.inverse().into(),
+        Operation::FSwap(op) => op.inverse().into(),
+        Operation::ISwap(op) => op.inverse().into(),
+        Operation::SqrtISwap(op) => op.inverse().into(),
+        Operation::InvSqrtISwap(op) => op.inverse().into(),
+        Operation::ControlledPauliY(op) => op.inverse().into(),
+        Operation::ControlledPauliZ(op) => op.inverse().into(),
+        Operation::MolmerSorensenXX(op) => op.inverse().into(),
+        Operation::RotateX(_)
+        | Operation::RotateY(_)
+        | Operation::RotateZ(_)
+        | Operation::PhaseShiftState0(_)
+        | Operation::PhaseShiftState1(_)
+        | Operation::RotateAroundSphericalAxis(_)
+        | Operation::XY(_)
+        | Operation::ControlledPhaseShift(_)
+        | Operation::VariableMSXX(_)
+        | Operation::MultiQubitMS(_)
+        | Operation::MultiQubitZZ(_) => Rotation::try_from(operation.clone())?
+            .powercf(CalculatorFloat::from(-1.0))
+            .into(),
+        _ => match SingleQubitGateOperation::try_from(operation.clone()) {
+            Ok(gate) => SingleQubitGate::new(
+                *gate.qubit(),
+                gate.alpha_r(),
+                -gate.alpha_i(),
+                -gate.beta_r(),
+                -gate.beta_i(),
+                -gate.global_phase(),
+            )
+            .into(),
+            Err(_) => {
+                return Err(RoqoqoError::ZeroNoiseExtrapolationError {
+                    msg: format!(
+                        "Gate {} cannot be inverted for unitary folding",
+                        operation.hqslang()
+                    ),
+                })
+            }
+        },
+    })
+}
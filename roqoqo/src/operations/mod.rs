@@ -447,7 +447,7 @@ pub trait Define: Operate + InvolveQubits + Substitute + Clone + PartialEq {
 pub trait OperateConstantGate:
     OperateGate + Operate + InvolveQubits + Substitute + Clone + PartialEq
 {
-    /// Returns true when unitary operation U is self inverse U*U = I.
+    /// Returns the inverse U<sup>†</sup> of the unitary operation U.
     fn inverse(&self) -> GateOperation;
 }
 
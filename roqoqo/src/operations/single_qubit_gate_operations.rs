@@ -17,8 +17,8 @@ use std::convert::TryFrom;
 use std::f64::consts::PI;
 
 use crate::operations::{
-    InvolveQubits, InvolvedQubits, Operate, OperateGate, OperateSingleQubit,
-    OperateSingleQubitGate, Rotate, Substitute,
+    GateOperation, InvolveQubits, InvolvedQubits, Operate, OperateConstantGate, OperateGate,
+    OperateSingleQubit, OperateSingleQubitGate, Rotate, Substitute,
 };
 use crate::RoqoqoError;
 #[cfg(feature = "overrotate")]
@@ -519,6 +519,18 @@ impl OperateGate for PauliX {
     }
 }
 
+/// Trait for unitary operations without any free parameters.
+impl OperateConstantGate for PauliX {
+    /// Returns the inverse of the gate.
+    ///
+    /// # Returns
+    ///
+    /// * `GateOperation` - The gate undoing the PauliX gate.
+    fn inverse(&self) -> GateOperation {
+        self.clone().into()
+    }
+}
+
 /// Trait for unitary operations acting on exactly one qubit.
 impl OperateSingleQubitGate for PauliX {
     /// Returns the alpha_r parameter of the operation.
@@ -612,6 +624,18 @@ impl OperateGate for PauliY {
     }
 }
 
+/// Trait for unitary operations without any free parameters.
+impl OperateConstantGate for PauliY {
+    /// Returns the inverse of the gate.
+    ///
+    /// # Returns
+    ///
+    /// * `GateOperation` - The gate undoing the PauliY gate.
+    fn inverse(&self) -> GateOperation {
+        self.clone().into()
+    }
+}
+
 /// Trait for unitary operations acting on exactly one qubit.
 impl OperateSingleQubitGate for PauliY {
     /// Returns the alpha_r parameter of the operation.
@@ -705,6 +729,18 @@ impl OperateGate for PauliZ {
     }
 }
 
+/// Trait for unitary operations without any free parameters.
+impl OperateConstantGate for PauliZ {
+    /// Returns the inverse of the gate.
+    ///
+    /// # Returns
+    ///
+    /// * `GateOperation` - The gate undoing the PauliZ gate.
+    fn inverse(&self) -> GateOperation {
+        self.clone().into()
+    }
+}
+
 /// Trait for unitary operations acting on exactly one qubit.
 impl OperateSingleQubitGate for PauliZ {
     /// Returns the alpha_r parameter of the operation.
@@ -801,6 +837,18 @@ impl OperateGate for SqrtPauliX {
     }
 }
 
+/// Trait for unitary operations without any free parameters.
+impl OperateConstantGate for SqrtPauliX {
+    /// Returns the inverse of the gate.
+    ///
+    /// # Returns
+    ///
+    /// * `GateOperation` - The gate undoing the SqrtPauliX gate.
+    fn inverse(&self) -> GateOperation {
+        InvSqrtPauliX::new(self.qubit).into()
+    }
+}
+
 /// Trait for unitary operations acting on exactly one qubit.
 impl OperateSingleQubitGate for SqrtPauliX {
     /// Returns the alpha_r parameter of the operation.
@@ -897,6 +945,18 @@ impl OperateGate for InvSqrtPauliX {
     }
 }
 
+/// Trait for unitary operations without any free parameters.
+impl OperateConstantGate for InvSqrtPauliX {
+    /// Returns the inverse of the gate.
+    ///
+    /// # Returns
+    ///
+    /// * `GateOperation` - The gate undoing the InvSqrtPauliX gate.
+    fn inverse(&self) -> GateOperation {
+        SqrtPauliX::new(self.qubit).into()
+    }
+}
+
 /// Trait for unitary operations acting on exactly one qubit.
 impl OperateSingleQubitGate for InvSqrtPauliX {
     /// Returns the alpha_r parameter of the operation.
@@ -991,6 +1051,18 @@ impl OperateGate for Hadamard {
     }
 }
 
+/// Trait for unitary operations without any free parameters.
+impl OperateConstantGate for Hadamard {
+    /// Returns the inverse of the gate.
+    ///
+    /// # Returns
+    ///
+    /// * `GateOperation` - The gate undoing the Hadamard gate.
+    fn inverse(&self) -> GateOperation {
+        self.clone().into()
+    }
+}
+
 /// Trait for unitary operations acting on exactly one qubit.
 impl OperateSingleQubitGate for Hadamard {
     /// Returns the alpha_r parameter of the operation.
@@ -11,6 +11,7 @@
 // limitations under the License.
 
 use crate::operations::single_qubit_gate_operations::*;
+use crate::operations::GateOperation;
 use crate::prelude::*;
 use crate::Circuit;
 use crate::RoqoqoError;
@@ -120,6 +121,18 @@ impl OperateGate for CNOT {
     }
 }
 
+/// Trait for unitary operations without any free parameters.
+impl OperateConstantGate for CNOT {
+    /// Returns the inverse of the gate.
+    ///
+    /// # Returns
+    ///
+    /// * `GateOperation` - The gate undoing the CNOT gate.
+    fn inverse(&self) -> GateOperation {
+        self.clone().into()
+    }
+}
+
 /// Trait for all gate operations acting on exactly two qubits.
 impl OperateTwoQubitGate for CNOT {
     /// Returns [KakDecomposition] of the  gate.
@@ -225,6 +238,18 @@ impl OperateGate for SWAP {
     }
 }
 
+/// Trait for unitary operations without any free parameters.
+impl OperateConstantGate for SWAP {
+    /// Returns the inverse of the gate.
+    ///
+    /// # Returns
+    ///
+    /// * `GateOperation` - The gate undoing the SWAP gate.
+    fn inverse(&self) -> GateOperation {
+        self.clone().into()
+    }
+}
+
 /// Trait for all gate operations acting on exactly two qubits.
 impl OperateTwoQubitGate for SWAP {
     /// Returns [KakDecomposition] of the gate.
@@ -322,6 +347,18 @@ impl OperateGate for ISwap {
     }
 }
 
+/// Trait for unitary operations without any free parameters.
+impl OperateConstantGate for ISwap {
+    /// Returns the inverse of the gate.
+    ///
+    /// # Returns
+    ///
+    /// * `GateOperation` - The gate undoing the ISwap gate.
+    fn inverse(&self) -> GateOperation {
+        XY::new(self.control, self.target, CalculatorFloat::from(-PI)).into()
+    }
+}
+
 /// Trait for all gate operations acting on exactly two qubits.
 impl OperateTwoQubitGate for ISwap {
     /// Returns [KakDecomposition] of the gate.
@@ -419,6 +456,18 @@ impl OperateGate for FSwap {
     }
 }
 
+/// Trait for unitary operations without any free parameters.
+impl OperateConstantGate for FSwap {
+    /// Returns the inverse of the gate.
+    ///
+    /// # Returns
+    ///
+    /// * `GateOperation` - The gate undoing the FSwap gate.
+    fn inverse(&self) -> GateOperation {
+        self.clone().into()
+    }
+}
+
 /// Trait for all gate operations acting on exactly two qubits.
 impl OperateTwoQubitGate for FSwap {
     /// Returns [KakDecomposition] of the gate.
@@ -521,6 +570,18 @@ impl OperateGate for SqrtISwap {
     }
 }
 
+/// Trait for unitary operations without any free parameters.
+impl OperateConstantGate for SqrtISwap {
+    /// Returns the inverse of the gate.
+    ///
+    /// # Returns
+    ///
+    /// * `GateOperation` - The gate undoing the SqrtISwap gate.
+    fn inverse(&self) -> GateOperation {
+        InvSqrtISwap::new(self.control, self.target).into()
+    }
+}
+
 /// Trait for all gate operations acting on exactly two qubits.
 impl OperateTwoQubitGate for SqrtISwap {
     /// Returns [KakDecomposition] of the gate.
@@ -619,6 +680,18 @@ impl OperateGate for InvSqrtISwap {
     }
 }
 
+/// Trait for unitary operations without any free parameters.
+impl OperateConstantGate for InvSqrtISwap {
+    /// Returns the inverse of the gate.
+    ///
+    /// # Returns
+    ///
+    /// * `GateOperation` - The gate undoing the InvSqrtISwap gate.
+    fn inverse(&self) -> GateOperation {
+        SqrtISwap::new(self.control, self.target).into()
+    }
+}
+
 /// Trait for all gate operations acting on exactly two qubits.
 impl OperateTwoQubitGate for InvSqrtISwap {
     /// Returns [KakDecomposition] of the gate.
@@ -927,6 +1000,18 @@ impl OperateGate for ControlledPauliY {
     }
 }
 
+/// Trait for unitary operations without any free parameters.
+impl OperateConstantGate for ControlledPauliY {
+    /// Returns the inverse of the gate.
+    ///
+    /// # Returns
+    ///
+    /// * `GateOperation` - The gate undoing the ControlledPauliY gate.
+    fn inverse(&self) -> GateOperation {
+        self.clone().into()
+    }
+}
+
 /// Trait for all gate operations acting on exactly two qubits.
 impl OperateTwoQubitGate for ControlledPauliY {
     /// Returns [KakDecomposition] of the gate.
@@ -1032,6 +1117,18 @@ impl OperateGate for ControlledPauliZ {
     }
 }
 
+/// Trait for unitary operations without any free parameters.
+impl OperateConstantGate for ControlledPauliZ {
+    /// Returns the inverse of the gate.
+    ///
+    /// # Returns
+    ///
+    /// * `GateOperation` - The gate undoing the ControlledPauliZ gate.
+    fn inverse(&self) -> GateOperation {
+        self.clone().into()
+    }
+}
+
 /// Trait for all gate operations acting on exactly two qubits.
 impl OperateTwoQubitGate for ControlledPauliZ {
     /// Returns [KakDecomposition] of the gate.
@@ -1136,6 +1233,18 @@ impl OperateGate for MolmerSorensenXX {
     }
 }
 
+/// Trait for unitary operations without any free parameters.
+impl OperateConstantGate for MolmerSorensenXX {
+    /// Returns the inverse of the gate.
+    ///
+    /// # Returns
+    ///
+    /// * `GateOperation` - The gate undoing the MolmerSorensenXX gate.
+    fn inverse(&self) -> GateOperation {
+        VariableMSXX::new(self.control, self.target, CalculatorFloat::from(-PI / 2.0)).into()
+    }
+}
+
 /// Trait for all gate operations acting on exactly two qubits.
 impl OperateTwoQubitGate for MolmerSorensenXX {
     /// Returns [KakDecomposition] of the gate.
@@ -0,0 +1,482 @@
+// Copyright © 2021 HQS Quantum Simulations GmbH. All Rights Reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except
+// in compliance with the License. You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the
+// License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either
+// express or implied. See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::backends::{merge_registers, EvaluatingBackend};
+use crate::measurements::MeasureExpectationValues;
+use crate::operations::*;
+use crate::registers::{BitOutputRegister, ComplexOutputRegister, FloatOutputRegister};
+use crate::{Circuit, RoqoqoBackendError};
+use qoqo_calculator::{Calculator, CalculatorFloat};
+use std::collections::HashMap;
+use std::f64::consts::FRAC_PI_2;
+
+/// Relative tolerance used to decide if a rotation angle depends affinely on a parameter.
+const AFFINE_TOLERANCE: f64 = 1e-6;
+
+/// Output registers of a single circuit run on a backend.
+type Registers = (
+    HashMap<String, BitOutputRegister>,
+    HashMap<String, FloatOutputRegister>,
+    HashMap<String, ComplexOutputRegister>,
+);
+
+/// Returns the gradients of the expectation values of a measurement with the parameter-shift rule.
+///
+/// Every parametrized operation in the constant circuit and in the circuits of the measurement is
+/// differentiated separately and the contributions are summed up (product rule).
+///
+/// Rotations generated by a single Pauli product or phase ([RotateX], [RotateY], [RotateZ],
+/// [RotateAroundSphericalAxis] with a fixed axis, [PhaseShiftState0], [PhaseShiftState1], [ControlledPhaseShift],
+/// [VariableMSXX], [MultiQubitMS] and [MultiQubitZZ]) are shiftable.
+/// When the angle `theta` of a shiftable rotation depends affinely on a parameter, the derivative is
+/// (E(θ + π/2) - E(θ - π/2)) / 2 multiplied with the coefficient of the parameter in `theta` (chain rule).
+/// The shifted expectation values are obtained by inserting an additional rotation of ±π/2 after the gate.
+///
+/// All other parametrized operations, e.g. rotations with a non-affine angle or pragmas with symbolic parameters,
+/// fall back to central finite differences of the single operation.
+///
+/// Only the circuit containing the differentiated operation is run again on the backend,
+/// the other circuits reuse their output registers for the unshifted parameters.
+///
+/// Differentiating single operations is only valid when the expectation values are linear functions of the
+/// measured states ([MeasureExpectationValues::is_linear]). For other measurements, e.g. with symbolic
+/// expectation values or readout error mitigation, the gradient falls back to central finite differences
+/// of the whole measurement.
+///
+/// # Arguments
+///
+/// * `backend` - The backend the circuits are run on.
+/// * `measurement` - The measurement with symbolic parameters.
+/// * `parameter_names` - The names of the parameters the gradient is calculated for.
+/// * `parameters` - The values of the parameters in the order of `parameter_names`.
+/// * `finite_difference_step` - The step of the finite differences for operations that are not shiftable.
+///
+/// # Returns
+///
+/// * `Ok(HashMap<String, Vec<f64>>)` - The derivatives of each expectation value with respect to each parameter.
+/// * `Err(RoqoqoBackendError::GenericError)` - The number of parameters is wrong or the measurement is incomplete.
+/// * `Err(RoqoqoBackendError)` - The substitution, the run on the backend or the evaluation failed.
+pub fn parameter_shift_gradient<B, M>(
+    backend: &B,
+    measurement: &M,
+    parameter_names: &[String],
+    parameters: &[f64],
+    finite_difference_step: f64,
+) -> Result<HashMap<String, Vec<f64>>, RoqoqoBackendError>
+where
+    B: EvaluatingBackend,
+    M: MeasureExpectationValues,
+{
+    if parameters.len() != parameter_names.len() {
+        return Err(RoqoqoBackendError::GenericError {
+            msg: format!(
+                "Wrong number of parameters {} parameters expected {} parameters given",
+                parameter_names.len(),
+                parameters.len()
+            ),
+        });
+    }
+    if !measurement.is_linear() {
+        return whole_measurement_gradient(
+            backend,
+            measurement,
+            parameter_names,
+            parameters,
+            finite_difference_step,
+        );
+    }
+    let substituted_parameters: HashMap<String, f64> = parameter_names
+        .iter()
+        .zip(parameters.iter())
+        .map(|(key, value)| (key.clone(), *value))
+        .collect();
+    let substituted_measurement = measurement.substitute_parameters(substituted_parameters)?;
+    let base_results = substituted_measurement
+        .circuits()
+        .map(|circuit| {
+            run_circuit(
+                backend,
+                substituted_measurement.constant_circuit().as_ref(),
+                circuit,
+            )
+        })
+        .collect::<Result<Vec<Registers>, RoqoqoBackendError>>()?;
+    let runner = ShiftedRunner {
+        backend,
+        measurement: &substituted_measurement,
+        base_results,
+    };
+
+    let mut gradients: HashMap<String, Vec<f64>> = HashMap::new();
+    let mut add_derivatives =
+        |forward: HashMap<String, f64>, backward: HashMap<String, f64>, factors: &[f64]| {
+            for (name, value) in forward.iter() {
+                let difference = value - backward.get(name).copied().unwrap_or(f64::NAN);
+                let gradient = gradients
+                    .entry(name.clone())
+                    .or_insert_with(|| vec![0.0; parameters.len()]);
+                for (total, factor) in gradient.iter_mut().zip(factors.iter()) {
+                    if *factor != 0.0 {
+                        *total += difference * factor;
+                    }
+                }
+            }
+        };
+
+    let circuits = measurement
+        .constant_circuit()
+        .iter()
+        .map(|circuit| (None, circuit))
+        .chain(
+            measurement
+                .circuits()
+                .enumerate()
+                .map(|(index, circuit)| (Some(index), circuit)),
+        );
+    for (circuit_index, circuit) in circuits {
+        for (index, operation) in circuit.iter().enumerate() {
+            if !operation.is_parametrized() {
+                continue;
+            }
+            // Derivatives of the shiftable angle, None for parameters with non-affine dependence
+            let coefficients: Vec<Option<f64>> = match shiftable_angle(operation) {
+                Some(theta) => (0..parameters.len())
+                    .map(|parameter_index| {
+                        affine_coefficient(
+                            theta,
+                            circuit,
+                            parameter_names,
+                            parameters,
+                            parameter_index,
+                            finite_difference_step,
+                        )
+                    })
+                    .collect::<Result<Vec<Option<f64>>, RoqoqoBackendError>>()?,
+                None => vec![None; parameters.len()],
+            };
+
+            let shift_factors: Vec<f64> = coefficients
+                .iter()
+                .map(|coefficient| coefficient.unwrap_or(0.0) / 2.0)
+                .collect();
+            if shift_factors.iter().any(|factor| *factor != 0.0) {
+                let substituted_operation = runner.operation(circuit_index, index)?;
+                let shifted = |shift: f64| {
+                    let gate = shift_gate(&substituted_operation, shift).ok_or_else(|| {
+                        RoqoqoBackendError::GenericError {
+                            msg: format!(
+                                "Operation {} can not be shifted",
+                                substituted_operation.hqslang()
+                            ),
+                        }
+                    })?;
+                    runner.replaced(circuit_index, index, &[substituted_operation.clone(), gate])
+                };
+                let forward = shifted(FRAC_PI_2)?;
+                let backward = shifted(-FRAC_PI_2)?;
+                add_derivatives(forward, backward, &shift_factors);
+            }
+
+            for (parameter_index, coefficient) in coefficients.iter().enumerate() {
+                if coefficient.is_some() {
+                    continue;
+                }
+                let shifted_operation = |step: f64| {
+                    let mut shifted_parameters = parameters.to_vec();
+                    shifted_parameters[parameter_index] += step;
+                    operation.substitute_parameters(&mut calculator(
+                        circuit,
+                        parameter_names,
+                        &shifted_parameters,
+                    ))
+                };
+                let forward_operation = shifted_operation(finite_difference_step)?;
+                let backward_operation = shifted_operation(-finite_difference_step)?;
+                if forward_operation == backward_operation {
+                    continue;
+                }
+                let forward = runner.replaced(circuit_index, index, &[forward_operation])?;
+                let backward = runner.replaced(circuit_index, index, &[backward_operation])?;
+                let mut factors = vec![0.0; parameters.len()];
+                factors[parameter_index] = 1.0 / (2.0 * finite_difference_step);
+                add_derivatives(forward, backward, &factors);
+            }
+        }
+    }
+
+    // Expectation values that do not depend on any parameter have a vanishing gradient
+    for name in runner.evaluate(runner.base_results.clone())?.keys() {
+        gradients
+            .entry(name.clone())
+            .or_insert_with(|| vec![0.0; parameters.len()]);
+    }
+    Ok(gradients)
+}
+
+/// Returns the gradients of the expectation values with central finite differences of the whole measurement.
+fn whole_measurement_gradient<B, M>(
+    backend: &B,
+    measurement: &M,
+    parameter_names: &[String],
+    parameters: &[f64],
+    step: f64,
+) -> Result<HashMap<String, Vec<f64>>, RoqoqoBackendError>
+where
+    B: EvaluatingBackend,
+    M: MeasureExpectationValues,
+{
+    let expectation_values = |parameter_index: usize, shift: f64| {
+        let mut shifted_parameters = parameters.to_vec();
+        shifted_parameters[parameter_index] += shift;
+        let substituted_parameters: HashMap<String, f64> = parameter_names
+            .iter()
+            .cloned()
+            .zip(shifted_parameters.into_iter())
+            .collect();
+        let substituted_measurement = measurement.substitute_parameters(substituted_parameters)?;
+        backend
+            .run_measurement(&substituted_measurement)?
+            .ok_or_else(|| RoqoqoBackendError::GenericError {
+                msg: "Measurement returned no expectation values for the shifted parameters"
+                    .to_string(),
+            })
+    };
+    let mut gradients: HashMap<String, Vec<f64>> = HashMap::new();
+    for parameter_index in 0..parameters.len() {
+        let forward = expectation_values(parameter_index, step)?;
+        let backward = expectation_values(parameter_index, -step)?;
+        for (name, value) in forward {
+            let difference = value - backward.get(&name).copied().unwrap_or(f64::NAN);
+            gradients
+                .entry(name)
+                .or_insert_with(|| vec![0.0; parameters.len()])[parameter_index] =
+                difference / (2.0 * step);
+        }
+    }
+    Ok(gradients)
+}
+
+/// Runs circuits of a measurement with one operation replaced and evaluates the expectation values.
+struct ShiftedRunner<'a, B, M> {
+    /// The backend the circuits are run on.
+    backend: &'a B,
+    /// The measurement with all parameters substituted.
+    measurement: &'a M,
+    /// The output registers of each circuit for the unshifted parameters.
+    base_results: Vec<Registers>,
+}
+
+impl<'a, B, M> ShiftedRunner<'a, B, M>
+where
+    B: EvaluatingBackend,
+    M: MeasureExpectationValues,
+{
+    /// Returns the substituted circuit (None for the constant circuit).
+    fn circuit(&self, circuit_index: Option<usize>) -> Result<&'a Circuit, RoqoqoBackendError> {
+        let circuit = match circuit_index {
+            None => self.measurement.constant_circuit().as_ref(),
+            Some(index) => self.measurement.circuits().nth(index),
+        };
+        circuit.ok_or_else(|| RoqoqoBackendError::GenericError {
+            msg: "Circuit of measurement is missing after substitution".to_string(),
+        })
+    }
+
+    /// Returns the substituted operation at an index of a circuit.
+    fn operation(
+        &self,
+        circuit_index: Option<usize>,
+        index: usize,
+    ) -> Result<Operation, RoqoqoBackendError> {
+        self.circuit(circuit_index)?
+            .get(index)
+            .cloned()
+            .ok_or_else(|| RoqoqoBackendError::GenericError {
+                msg: format!(
+                    "Operation {} of circuit is missing after substitution",
+                    index
+                ),
+            })
+    }
+
+    /// Returns the expectation values with the operation at an index of a circuit replaced by other operations.
+    fn replaced(
+        &self,
+        circuit_index: Option<usize>,
+        index: usize,
+        replacement: &[Operation],
+    ) -> Result<HashMap<String, f64>, RoqoqoBackendError> {
+        let circuit = replace_operation(self.circuit(circuit_index)?, index, replacement);
+        let results = match circuit_index {
+            None => self
+                .measurement
+                .circuits()
+                .map(|measured| run_circuit(self.backend, Some(&circuit), measured))
+                .collect::<Result<Vec<Registers>, RoqoqoBackendError>>()?,
+            Some(position) => {
+                let mut results = self.base_results.clone();
+                results[position] = run_circuit(
+                    self.backend,
+                    self.measurement.constant_circuit().as_ref(),
+                    &circuit,
+                )?;
+                results
+            }
+        };
+        self.evaluate(results)
+    }
+
+    /// Evaluates the expectation values of the output registers of all circuits.
+    fn evaluate(
+        &self,
+        results: Vec<Registers>,
+    ) -> Result<HashMap<String, f64>, RoqoqoBackendError> {
+        let (bit_registers, float_registers, complex_registers) =
+            merge_registers(results.into_iter().map(Ok))?;
+        self.measurement
+            .evaluate(bit_registers, float_registers, complex_registers)?
+            .ok_or_else(|| RoqoqoBackendError::GenericError {
+                msg: "Measurement returned no expectation values for the shifted circuits"
+                    .to_string(),
+            })
+    }
+}
+
+/// Runs a circuit after the constant circuit on the backend.
+fn run_circuit<B>(
+    backend: &B,
+    constant_circuit: Option<&Circuit>,
+    circuit: &Circuit,
+) -> Result<Registers, RoqoqoBackendError>
+where
+    B: EvaluatingBackend,
+{
+    match constant_circuit {
+        Some(x) => backend.run_circuit_iterator(x.iter().chain(circuit.iter())),
+        None => backend.run_circuit_iterator(circuit.iter()),
+    }
+}
+
+/// Returns a copy of the circuit with the operation at an index replaced by other operations.
+fn replace_operation(circuit: &Circuit, index: usize, replacement: &[Operation]) -> Circuit {
+    let mut replaced = Circuit::new();
+    for (position, operation) in circuit.iter().enumerate() {
+        if position == index {
+            for new_operation in replacement.iter() {
+                replaced.add_operation(new_operation.clone());
+            }
+        } else {
+            replaced.add_operation(operation.clone());
+        }
+    }
+    replaced
+}
+
+/// Returns a calculator substituting the operations of a circuit.
+///
+/// The parameters are set as variables followed by the [InputSymbolic] definitions of the circuit,
+/// like in [Circuit::substitute_parameters].
+fn calculator(circuit: &Circuit, parameter_names: &[String], parameters: &[f64]) -> Calculator {
+    let mut calculator = Calculator::new();
+    for (name, value) in parameter_names.iter().zip(parameters.iter()) {
+        calculator.set_variable(name, *value);
+    }
+    for definition in circuit.definitions() {
+        if let Operation::InputSymbolic(input) = definition {
+            calculator.set_variable(input.name(), *input.input());
+        }
+    }
+    calculator
+}
+
+/// Returns the angle of an operation that can be differentiated with the parameter-shift rule.
+fn shiftable_angle(operation: &Operation) -> Option<&CalculatorFloat> {
+    match operation {
+        Operation::RotateX(op) => Some(op.theta()),
+        Operation::RotateY(op) => Some(op.theta()),
+        Operation::RotateZ(op) => Some(op.theta()),
+        Operation::RotateAroundSphericalAxis(op)
+            if op.spherical_theta().is_float() && op.spherical_phi().is_float() =>
+        {
+            Some(op.theta())
+        }
+        Operation::PhaseShiftState0(op) => Some(op.theta()),
+        Operation::PhaseShiftState1(op) => Some(op.theta()),
+        Operation::ControlledPhaseShift(op) => Some(op.theta()),
+        Operation::VariableMSXX(op) => Some(op.theta()),
+        Operation::MultiQubitMS(op) => Some(op.theta()),
+        Operation::MultiQubitZZ(op) => Some(op.theta()),
+        _ => None,
+    }
+}
+
+/// Returns a rotation of the same type and on the same qubits as a shiftable operation with the angle `shift`.
+fn shift_gate(operation: &Operation, shift: f64) -> Option<Operation> {
+    let shift = CalculatorFloat::from(shift);
+    match operation {
+        Operation::RotateX(op) => Some(RotateX::new(*op.qubit(), shift).into()),
+        Operation::RotateY(op) => Some(RotateY::new(*op.qubit(), shift).into()),
+        Operation::RotateZ(op) => Some(RotateZ::new(*op.qubit(), shift).into()),
+        Operation::RotateAroundSphericalAxis(op) => Some(
+            RotateAroundSphericalAxis::new(
+                *op.qubit(),
+                shift,
+                op.spherical_theta().clone(),
+                op.spherical_phi().clone(),
+            )
+            .into(),
+        ),
+        Operation::PhaseShiftState0(op) => Some(PhaseShiftState0::new(*op.qubit(), shift).into()),
+        Operation::PhaseShiftState1(op) => Some(PhaseShiftState1::new(*op.qubit(), shift).into()),
+        Operation::ControlledPhaseShift(op) => {
+            Some(ControlledPhaseShift::new(*op.control(), *op.target(), shift).into())
+        }
+        Operation::VariableMSXX(op) => {
+            Some(VariableMSXX::new(*op.control(), *op.target(), shift).into())
+        }
+        Operation::MultiQubitMS(op) => Some(MultiQubitMS::new(op.qubits().clone(), shift).into()),
+        Operation::MultiQubitZZ(op) => Some(MultiQubitZZ::new(op.qubits().clone(), shift).into()),
+        _ => None,
+    }
+}
+
+/// Returns the derivative of an angle with respect to a parameter if the angle depends affinely on the parameter.
+///
+/// The dependence is affine when the differences of the angle for unit steps in both directions
+/// agree with each other and with the local derivative (central finite differences with `step`).
+fn affine_coefficient(
+    theta: &CalculatorFloat,
+    circuit: &Circuit,
+    parameter_names: &[String],
+    parameters: &[f64],
+    parameter_index: usize,
+    step: f64,
+) -> Result<Option<f64>, RoqoqoBackendError> {
+    if theta.is_float() {
+        return Ok(Some(0.0));
+    }
+    let evaluate = |shift: f64| -> Result<f64, RoqoqoBackendError> {
+        let mut shifted_parameters = parameters.to_vec();
+        shifted_parameters[parameter_index] += shift;
+        Ok(calculator(circuit, parameter_names, &shifted_parameters).parse_get(theta.clone())?)
+    };
+    let center = evaluate(0.0)?;
+    let forward = evaluate(1.0)? - center;
+    let backward = center - evaluate(-1.0)?;
+    let local = (evaluate(step)? - evaluate(-step)?) / (2.0 * step);
+    let tolerance = AFFINE_TOLERANCE * (1.0 + forward.abs());
+    if (forward - backward).abs() <= tolerance && (forward - local).abs() <= tolerance {
+        Ok(Some((forward + backward) / 2.0))
+    } else {
+        Ok(None)
+    }
+}
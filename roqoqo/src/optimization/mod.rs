@@ -0,0 +1,104 @@
+// Copyright © 2021 HQS Quantum Simulations GmbH. All Rights Reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except
+// in compliance with the License. You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the
+// License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either
+// express or implied. See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Variational optimization of the free parameters of a [crate::QuantumProgram].
+//!
+//! An [Optimizer] minimizes an [Objective], a real function of the parameters.
+//! The [QuantumProgramObjective] runs a QuantumProgram on a backend and evaluates a [CostFunction]
+//! of the returned expectation values, e.g. the energy in a variational quantum eigensolver.
+//!
+//! The Optimizer supports the Nelder-Mead simplex method, simultaneous perturbation stochastic approximation (SPSA),
+//! gradient descent and Adam (see [OptimizerMethod]). It records the full optimization history and,
+//! with the `serialize` feature, can be serialized and resumed later.
+
+use crate::RoqoqoBackendError;
+
+#[doc(hidden)]
+mod gradient;
+pub use gradient::*;
+#[doc(hidden)]
+mod objective;
+pub use objective::*;
+#[doc(hidden)]
+mod optimizer;
+pub use optimizer::*;
+
+/// Default step of the central finite differences used by [Objective::gradient].
+pub const DEFAULT_FINITE_DIFFERENCE_STEP: f64 = 1e-6;
+
+/// Real function of real parameters that is minimized by an [Optimizer].
+pub trait Objective {
+    /// Returns the value of the objective.
+    ///
+    /// # Arguments
+    ///
+    /// * `parameters` - The parameters the objective is evaluated for.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(f64)` - The value of the objective.
+    /// * `Err(RoqoqoBackendError)` - The evaluation failed.
+    fn value(&self, parameters: &[f64]) -> Result<f64, RoqoqoBackendError>;
+
+    /// Returns the gradient of the objective.
+    ///
+    /// Defaults to central finite differences with [DEFAULT_FINITE_DIFFERENCE_STEP].
+    ///
+    /// # Arguments
+    ///
+    /// * `parameters` - The parameters the gradient is evaluated for.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Vec<f64>)` - The derivatives with respect to each parameter.
+    /// * `Err(RoqoqoBackendError)` - The evaluation failed.
+    fn gradient(&self, parameters: &[f64]) -> Result<Vec<f64>, RoqoqoBackendError> {
+        finite_difference_gradient(
+            |parameters| self.value(parameters),
+            parameters,
+            DEFAULT_FINITE_DIFFERENCE_STEP,
+        )
+    }
+}
+
+/// Returns the gradient of a function approximated by central finite differences.
+///
+/// # Arguments
+///
+/// * `function` - The function that is differentiated.
+/// * `parameters` - The parameters the gradient is evaluated for.
+/// * `step` - The step of the finite differences.
+///
+/// # Returns
+///
+/// * `Ok(Vec<f64>)` - The derivatives with respect to each parameter.
+/// * `Err(RoqoqoBackendError)` - An evaluation of the function failed.
+pub fn finite_difference_gradient<F>(
+    function: F,
+    parameters: &[f64],
+    step: f64,
+) -> Result<Vec<f64>, RoqoqoBackendError>
+where
+    F: Fn(&[f64]) -> Result<f64, RoqoqoBackendError>,
+{
+    let mut shifted = parameters.to_vec();
+    let mut gradient: Vec<f64> = Vec::with_capacity(parameters.len());
+    for (index, parameter) in parameters.iter().enumerate() {
+        shifted[index] = parameter + step;
+        let forward = function(&shifted)?;
+        shifted[index] = parameter - step;
+        let backward = function(&shifted)?;
+        shifted[index] = *parameter;
+        gradient.push((forward - backward) / (2.0 * step));
+    }
+    Ok(gradient)
+}
@@ -0,0 +1,198 @@
+// Copyright © 2021 HQS Quantum Simulations GmbH. All Rights Reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except
+// in compliance with the License. You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the
+// License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either
+// express or implied. See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::{finite_difference_gradient, Objective, DEFAULT_FINITE_DIFFERENCE_STEP};
+use crate::backends::EvaluatingBackend;
+use crate::{QuantumProgram, RoqoqoBackendError};
+use std::collections::HashMap;
+
+/// Cost function of the expectation values returned by a [QuantumProgram].
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "json_schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub enum CostFunction {
+    /// The expectation value with the given name.
+    ExpectationValue(String),
+    /// A symbolic expression (e.g. "energy + 0.5 * penalty^2") evaluated with [qoqo_calculator::Calculator]
+    /// with the expectation values set as variables.
+    Expression(String),
+}
+
+impl CostFunction {
+    /// Returns the cost of a set of expectation values.
+    ///
+    /// # Arguments
+    ///
+    /// * `expectation_values` - The expectation values with their name as key.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(f64)` - The cost.
+    /// * `Err(RoqoqoBackendError::GenericError)` - The expectation value is missing.
+    /// * `Err(RoqoqoBackendError::CalculatorError)` - The expression could not be evaluated.
+    pub fn evaluate(
+        &self,
+        expectation_values: &HashMap<String, f64>,
+    ) -> Result<f64, RoqoqoBackendError> {
+        match self {
+            CostFunction::ExpectationValue(name) => expectation_values
+                .get(name)
+                .copied()
+                .ok_or_else(|| RoqoqoBackendError::GenericError {
+                    msg: format!("Expectation value {} of cost function is missing", name),
+                }),
+            CostFunction::Expression(expression) => {
+                let mut calculator = qoqo_calculator::Calculator::new();
+                for (name, value) in expectation_values.iter() {
+                    calculator.set_variable(name, *value);
+                }
+                Ok(calculator.parse_str(expression)?)
+            }
+        }
+    }
+}
+
+/// Method used by [QuantumProgramObjective] to calculate gradients.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "json_schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub enum GradientMethod {
+    /// Parameter-shift rule applied to the rotation gates of the circuits, see [QuantumProgram::gradient].
+    ///
+    /// Operations that cannot be shifted are differentiated with central finite differences.
+    ParameterShift,
+    /// Central finite differences with the given step.
+    FiniteDifference {
+        /// The step of the finite differences.
+        step: f64,
+    },
+}
+
+/// [Objective] running a [QuantumProgram] on a backend and evaluating a [CostFunction] of its expectation values.
+///
+/// The parameters of the objective are the `input_parameter_names` of the QuantumProgram in the same order.
+#[derive(Debug)]
+pub struct QuantumProgramObjective<'a, B>
+where
+    B: EvaluatingBackend,
+{
+    program: &'a QuantumProgram,
+    backend: &'a B,
+    cost_function: CostFunction,
+    gradient_method: GradientMethod,
+}
+
+impl<'a, B> QuantumProgramObjective<'a, B>
+where
+    B: EvaluatingBackend,
+{
+    /// Creates a new QuantumProgramObjective.
+    ///
+    /// # Arguments
+    ///
+    /// * `program` - The QuantumProgram returning expectation values.
+    /// * `backend` - The backend the QuantumProgram is run on.
+    /// * `cost_function` - The cost function of the expectation values that is minimized.
+    /// * `gradient_method` - The method used to calculate gradients.
+    ///
+    /// # Returns
+    ///
+    /// * `Self` - The new QuantumProgramObjective.
+    pub fn new(
+        program: &'a QuantumProgram,
+        backend: &'a B,
+        cost_function: CostFunction,
+        gradient_method: GradientMethod,
+    ) -> Self {
+        Self {
+            program,
+            backend,
+            cost_function,
+            gradient_method,
+        }
+    }
+
+    /// Returns the cost function of the objective.
+    pub fn cost_function(&self) -> &CostFunction {
+        &self.cost_function
+    }
+
+    /// Returns the method used to calculate gradients.
+    pub fn gradient_method(&self) -> GradientMethod {
+        self.gradient_method
+    }
+
+    /// Runs the QuantumProgram and returns its expectation values.
+    fn expectation_values(
+        &self,
+        parameters: &[f64],
+    ) -> Result<HashMap<String, f64>, RoqoqoBackendError> {
+        self.program.run(self.backend, parameters)?.ok_or_else(|| {
+            RoqoqoBackendError::GenericError {
+                msg: "QuantumProgram returned no expectation values".to_string(),
+            }
+        })
+    }
+}
+
+impl<'a, B> Objective for QuantumProgramObjective<'a, B>
+where
+    B: EvaluatingBackend,
+{
+    fn value(&self, parameters: &[f64]) -> Result<f64, RoqoqoBackendError> {
+        self.cost_function
+            .evaluate(&self.expectation_values(parameters)?)
+    }
+
+    /// Returns the gradient of the cost function with the gradient method of the objective.
+    ///
+    /// For the parameter-shift rule the gradients of the expectation values are combined with the
+    /// derivatives of the cost function with respect to the expectation values (chain rule).
+    fn gradient(&self, parameters: &[f64]) -> Result<Vec<f64>, RoqoqoBackendError> {
+        match self.gradient_method {
+            GradientMethod::FiniteDifference { step } => {
+                finite_difference_gradient(|parameters| self.value(parameters), parameters, step)
+            }
+            GradientMethod::ParameterShift => {
+                let gradients = self.program.gradient(self.backend, parameters)?;
+                if let CostFunction::ExpectationValue(name) = &self.cost_function {
+                    return gradients.get(name).cloned().ok_or_else(|| {
+                        RoqoqoBackendError::GenericError {
+                            msg: format!("Expectation value {} of cost function is missing", name),
+                        }
+                    });
+                }
+                let expectation_values = self.expectation_values(parameters)?;
+                let mut gradient = vec![0.0; parameters.len()];
+                for (name, expectation_gradient) in gradients.iter() {
+                    let expectation_value = match expectation_values.get(name) {
+                        Some(value) => *value,
+                        None => continue,
+                    };
+                    let cost_derivative = finite_difference_gradient(
+                        |value| {
+                            let mut values = expectation_values.clone();
+                            values.insert(name.clone(), value[0]);
+                            self.cost_function.evaluate(&values)
+                        },
+                        &[expectation_value],
+                        DEFAULT_FINITE_DIFFERENCE_STEP,
+                    )?[0];
+                    for (total, partial) in gradient.iter_mut().zip(expectation_gradient.iter()) {
+                        *total += cost_derivative * partial;
+                    }
+                }
+                Ok(gradient)
+            }
+        }
+    }
+}
@@ -0,0 +1,510 @@
+// Copyright © 2021 HQS Quantum Simulations GmbH. All Rights Reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except
+// in compliance with the License. You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the
+// License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either
+// express or implied. See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::Objective;
+use crate::RoqoqoBackendError;
+use std::cmp::Ordering;
+
+/// Method used by an [Optimizer] to minimize an [Objective].
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "json_schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub enum OptimizerMethod {
+    /// Nelder-Mead simplex method without gradients.
+    NelderMead {
+        /// The distance of the initial simplex vertices from the initial parameters along each parameter.
+        initial_step: f64,
+    },
+    /// Simultaneous perturbation stochastic approximation (SPSA).
+    ///
+    /// In iteration k the parameters are perturbed by c_k = perturbation / (k + 1)^gamma along a
+    /// random direction of ±1 entries and updated with the step a_k = learning_rate / (k + 1 + stability)^alpha.
+    /// Robust against noisy objectives, e.g. from sampled measurements.
+    Spsa {
+        /// The scale a of the update step.
+        learning_rate: f64,
+        /// The scale c of the perturbation.
+        perturbation: f64,
+        /// The stability constant A delaying the decay of the update step.
+        stability: f64,
+        /// The decay exponent of the update step.
+        alpha: f64,
+        /// The decay exponent of the perturbation.
+        gamma: f64,
+        /// The seed of the random perturbation directions.
+        seed: u64,
+    },
+    /// Gradient descent with a fixed learning rate using [Objective::gradient].
+    GradientDescent {
+        /// The learning rate.
+        learning_rate: f64,
+    },
+    /// Adam gradient descent with adaptive moments using [Objective::gradient].
+    Adam {
+        /// The learning rate.
+        learning_rate: f64,
+        /// The decay rate of the first moment.
+        beta1: f64,
+        /// The decay rate of the second moment.
+        beta2: f64,
+        /// The regularization of the division by the second moment.
+        epsilon: f64,
+    },
+}
+
+impl OptimizerMethod {
+    /// Returns the Nelder-Mead method with an initial step of 0.1.
+    pub fn nelder_mead() -> Self {
+        OptimizerMethod::NelderMead { initial_step: 0.1 }
+    }
+
+    /// Returns SPSA with the standard decay exponents alpha = 0.602 and gamma = 0.101.
+    ///
+    /// # Arguments
+    ///
+    /// * `learning_rate` - The scale of the update step.
+    /// * `perturbation` - The scale of the perturbation.
+    /// * `seed` - The seed of the random perturbation directions.
+    pub fn spsa(learning_rate: f64, perturbation: f64, seed: u64) -> Self {
+        OptimizerMethod::Spsa {
+            learning_rate,
+            perturbation,
+            stability: 0.0,
+            alpha: 0.602,
+            gamma: 0.101,
+            seed,
+        }
+    }
+
+    /// Returns gradient descent with a fixed learning rate.
+    ///
+    /// # Arguments
+    ///
+    /// * `learning_rate` - The learning rate.
+    pub fn gradient_descent(learning_rate: f64) -> Self {
+        OptimizerMethod::GradientDescent { learning_rate }
+    }
+
+    /// Returns Adam with the standard decay rates beta1 = 0.9, beta2 = 0.999 and epsilon = 1e-8.
+    ///
+    /// # Arguments
+    ///
+    /// * `learning_rate` - The learning rate.
+    pub fn adam(learning_rate: f64) -> Self {
+        OptimizerMethod::Adam {
+            learning_rate,
+            beta1: 0.9,
+            beta2: 0.999,
+            epsilon: 1e-8,
+        }
+    }
+}
+
+/// Conditions stopping [Optimizer::minimize].
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "json_schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct StoppingCriteria {
+    /// The maximal total number of iterations, including iterations before the optimization has been resumed.
+    pub max_iterations: usize,
+    /// The optimization has converged when the value changes by less than the tolerance in an iteration
+    /// (for Nelder-Mead when the values of the simplex differ by less than the tolerance).
+    pub tolerance: f64,
+}
+
+impl Default for StoppingCriteria {
+    fn default() -> Self {
+        Self {
+            max_iterations: 100,
+            tolerance: 1e-8,
+        }
+    }
+}
+
+/// Parameters and value of the objective after an iteration of an [Optimizer].
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "json_schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct OptimizationRecord {
+    /// The iteration (0 for the initial parameters).
+    pub iteration: usize,
+    /// The parameters after the iteration.
+    pub parameters: Vec<f64>,
+    /// The value of the objective for the parameters.
+    pub value: f64,
+}
+
+/// Internal state of the optimizer method between iterations.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "json_schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+enum MethodState {
+    /// No internal state.
+    Empty,
+    /// Vertices of the Nelder-Mead simplex with their values.
+    Simplex(Vec<(Vec<f64>, f64)>),
+    /// First and second moments of Adam.
+    Moments(Vec<f64>, Vec<f64>),
+}
+
+/// Optimizer minimizing an [Objective] iteratively.
+///
+/// The optimizer holds the full state of the optimization: the current parameters, the history of
+/// all iterations and the internal state of the method. With the `serialize` feature it can be
+/// serialized at any point and the optimization can be resumed by calling [Optimizer::minimize] or
+/// [Optimizer::step] on the deserialized optimizer.
+///
+/// # Example
+///
+/// ```
+/// use roqoqo::optimization::{Objective, OptimizerMethod, Optimizer, StoppingCriteria};
+/// use roqoqo::RoqoqoBackendError;
+///
+/// struct Parabola;
+///
+/// impl Objective for Parabola {
+///     fn value(&self, parameters: &[f64]) -> Result<f64, RoqoqoBackendError> {
+///         Ok((parameters[0] - 1.0).powi(2))
+///     }
+/// }
+///
+/// let mut optimizer = Optimizer::new(OptimizerMethod::nelder_mead(), vec![0.0]);
+/// let best = optimizer.minimize(&Parabola, &StoppingCriteria::default()).unwrap();
+/// assert!((best.parameters[0] - 1.0).abs() < 1e-3);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "json_schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct Optimizer {
+    method: OptimizerMethod,
+    parameters: Vec<f64>,
+    history: Vec<OptimizationRecord>,
+    state: MethodState,
+}
+
+impl Optimizer {
+    /// Creates a new Optimizer.
+    ///
+    /// # Arguments
+    ///
+    /// * `method` - The optimization method.
+    /// * `initial_parameters` - The parameters the optimization starts from.
+    ///
+    /// # Returns
+    ///
+    /// * `Self` - The new Optimizer with an empty history.
+    pub fn new(method: OptimizerMethod, initial_parameters: Vec<f64>) -> Self {
+        Self {
+            method,
+            parameters: initial_parameters,
+            history: Vec::new(),
+            state: MethodState::Empty,
+        }
+    }
+
+    /// Returns the optimization method.
+    pub fn method(&self) -> &OptimizerMethod {
+        &self.method
+    }
+
+    /// Returns the current parameters.
+    pub fn parameters(&self) -> &[f64] {
+        &self.parameters
+    }
+
+    /// Returns the number of performed iterations.
+    pub fn iteration(&self) -> usize {
+        self.history
+            .last()
+            .map(|record| record.iteration)
+            .unwrap_or(0)
+    }
+
+    /// Returns the records of the initial parameters and of all iterations.
+    pub fn history(&self) -> &[OptimizationRecord] {
+        &self.history
+    }
+
+    /// Returns the record with the lowest value of the objective (None before the first evaluation).
+    pub fn best(&self) -> Option<&OptimizationRecord> {
+        self.history
+            .iter()
+            .min_by(|a, b| a.value.partial_cmp(&b.value).unwrap_or(Ordering::Equal))
+    }
+
+    /// Returns true when the optimization has converged.
+    ///
+    /// # Arguments
+    ///
+    /// * `tolerance` - The tolerance of the change of the value, see [StoppingCriteria::tolerance].
+    pub fn is_converged(&self, tolerance: f64) -> bool {
+        if let MethodState::Simplex(simplex) = &self.state {
+            let values = simplex.iter().map(|(_, value)| *value);
+            let spread = values.clone().fold(f64::NEG_INFINITY, f64::max)
+                - values.fold(f64::INFINITY, f64::min);
+            return spread < tolerance;
+        }
+        match self.history.as_slice() {
+            [.., previous, last] => (last.value - previous.value).abs() < tolerance,
+            _ => false,
+        }
+    }
+
+    /// Minimizes the objective until the optimization has converged or the maximal number of iterations is reached.
+    ///
+    /// # Arguments
+    ///
+    /// * `objective` - The objective that is minimized.
+    /// * `criteria` - The conditions stopping the optimization.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(OptimizationRecord)` - The record with the lowest value of the objective.
+    /// * `Err(RoqoqoBackendError)` - An evaluation of the objective failed.
+    pub fn minimize<O>(
+        &mut self,
+        objective: &O,
+        criteria: &StoppingCriteria,
+    ) -> Result<OptimizationRecord, RoqoqoBackendError>
+    where
+        O: Objective + ?Sized,
+    {
+        self.initialize(objective)?;
+        while self.iteration() < criteria.max_iterations && !self.is_converged(criteria.tolerance) {
+            self.step(objective)?;
+        }
+        Ok(self
+            .best()
+            .cloned()
+            .expect("Unexpectedly found empty history after initialization"))
+    }
+
+    /// Performs a single iteration of the optimization method.
+    ///
+    /// The initial parameters are evaluated and recorded before the first iteration.
+    ///
+    /// # Arguments
+    ///
+    /// * `objective` - The objective that is minimized.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(&OptimizationRecord)` - The record of the iteration.
+    /// * `Err(RoqoqoBackendError)` - An evaluation of the objective failed.
+    pub fn step<O>(&mut self, objective: &O) -> Result<&OptimizationRecord, RoqoqoBackendError>
+    where
+        O: Objective + ?Sized,
+    {
+        self.initialize(objective)?;
+        let iteration = self.iteration();
+        let (parameters, value) = match self.method {
+            OptimizerMethod::NelderMead { .. } => self.nelder_mead_step(objective)?,
+            OptimizerMethod::Spsa {
+                learning_rate,
+                perturbation,
+                stability,
+                alpha,
+                gamma,
+                seed,
+            } => {
+                let k = iteration as f64;
+                let step = learning_rate / (k + 1.0 + stability).powf(alpha);
+                let shift = perturbation / (k + 1.0).powf(gamma);
+                let direction: Vec<f64> = (0..self.parameters.len())
+                    .map(|index| perturbation_sign(seed, iteration, index))
+                    .collect();
+                let shifted = |sign: f64| -> Vec<f64> {
+                    self.parameters
+                        .iter()
+                        .zip(direction.iter())
+                        .map(|(parameter, delta)| parameter + sign * shift * delta)
+                        .collect()
+                };
+                let difference =
+                    objective.value(&shifted(1.0))? - objective.value(&shifted(-1.0))?;
+                let parameters: Vec<f64> = self
+                    .parameters
+                    .iter()
+                    .zip(direction.iter())
+                    .map(|(parameter, delta)| parameter - step * difference / (2.0 * shift * delta))
+                    .collect();
+                let value = objective.value(&parameters)?;
+                (parameters, value)
+            }
+            OptimizerMethod::GradientDescent { learning_rate } => {
+                let gradient = objective.gradient(&self.parameters)?;
+                let parameters: Vec<f64> = self
+                    .parameters
+                    .iter()
+                    .zip(gradient.iter())
+                    .map(|(parameter, derivative)| parameter - learning_rate * derivative)
+                    .collect();
+                let value = objective.value(&parameters)?;
+                (parameters, value)
+            }
+            OptimizerMethod::Adam {
+                learning_rate,
+                beta1,
+                beta2,
+                epsilon,
+            } => {
+                let gradient = objective.gradient(&self.parameters)?;
+                let (mut first, mut second) =
+                    match std::mem::replace(&mut self.state, MethodState::Empty) {
+                        MethodState::Moments(first, second) => (first, second),
+                        _ => (vec![0.0; gradient.len()], vec![0.0; gradient.len()]),
+                    };
+                let t = (iteration + 1) as i32;
+                let mut parameters = self.parameters.clone();
+                for (index, derivative) in gradient.iter().enumerate() {
+                    first[index] = beta1 * first[index] + (1.0 - beta1) * derivative;
+                    second[index] = beta2 * second[index] + (1.0 - beta2) * derivative.powi(2);
+                    let first_corrected = first[index] / (1.0 - beta1.powi(t));
+                    let second_corrected = second[index] / (1.0 - beta2.powi(t));
+                    parameters[index] -=
+                        learning_rate * first_corrected / (second_corrected.sqrt() + epsilon);
+                }
+                self.state = MethodState::Moments(first, second);
+                let value = objective.value(&parameters)?;
+                (parameters, value)
+            }
+        };
+        self.parameters = parameters.clone();
+        self.history.push(OptimizationRecord {
+            iteration: iteration + 1,
+            parameters,
+            value,
+        });
+        Ok(self
+            .history
+            .last()
+            .expect("Unexpectedly found empty history after iteration"))
+    }
+
+    /// Evaluates and records the initial parameters (and the initial Nelder-Mead simplex) before the first iteration.
+    fn initialize<O>(&mut self, objective: &O) -> Result<(), RoqoqoBackendError>
+    where
+        O: Objective + ?Sized,
+    {
+        if !self.history.is_empty() {
+            return Ok(());
+        }
+        let value = objective.value(&self.parameters)?;
+        if let OptimizerMethod::NelderMead { initial_step } = self.method {
+            let mut simplex = vec![(self.parameters.clone(), value)];
+            for index in 0..self.parameters.len() {
+                let mut vertex = self.parameters.clone();
+                vertex[index] += initial_step;
+                let vertex_value = objective.value(&vertex)?;
+                simplex.push((vertex, vertex_value));
+            }
+            self.state = MethodState::Simplex(simplex);
+        }
+        self.history.push(OptimizationRecord {
+            iteration: 0,
+            parameters: self.parameters.clone(),
+            value,
+        });
+        Ok(())
+    }
+
+    /// Performs one Nelder-Mead iteration and returns the best vertex of the new simplex.
+    fn nelder_mead_step<O>(&mut self, objective: &O) -> Result<(Vec<f64>, f64), RoqoqoBackendError>
+    where
+        O: Objective + ?Sized,
+    {
+        let mut simplex = match std::mem::replace(&mut self.state, MethodState::Empty) {
+            MethodState::Simplex(simplex) => simplex,
+            _ => {
+                return Err(RoqoqoBackendError::GenericError {
+                    msg: "Nelder-Mead simplex is missing from optimizer state".to_string(),
+                })
+            }
+        };
+        simplex.sort_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(Ordering::Equal));
+        let number_vertices = simplex.len();
+        if number_vertices > 1 {
+            let (worst, worst_value) = simplex[number_vertices - 1].clone();
+            let dimension = worst.len();
+            let mut centroid = vec![0.0; dimension];
+            for (vertex, _) in simplex[..number_vertices - 1].iter() {
+                for (center, coordinate) in centroid.iter_mut().zip(vertex.iter()) {
+                    *center += coordinate / (number_vertices - 1) as f64;
+                }
+            }
+            // Point on the line from the worst vertex through the centroid
+            let along = |factor: f64| -> Vec<f64> {
+                centroid
+                    .iter()
+                    .zip(worst.iter())
+                    .map(|(center, coordinate)| center + factor * (center - coordinate))
+                    .collect()
+            };
+            let reflected = along(1.0);
+            let reflected_value = objective.value(&reflected)?;
+            let best_value = simplex[0].1;
+            let second_worst_value = simplex[number_vertices - 2].1;
+            if reflected_value < best_value {
+                let expanded = along(2.0);
+                let expanded_value = objective.value(&expanded)?;
+                simplex[number_vertices - 1] = if expanded_value < reflected_value {
+                    (expanded, expanded_value)
+                } else {
+                    (reflected, reflected_value)
+                };
+            } else if reflected_value < second_worst_value {
+                simplex[number_vertices - 1] = (reflected, reflected_value);
+            } else {
+                let contracted = if reflected_value < worst_value {
+                    along(0.5)
+                } else {
+                    along(-0.5)
+                };
+                let contracted_value = objective.value(&contracted)?;
+                if contracted_value < reflected_value.min(worst_value) {
+                    simplex[number_vertices - 1] = (contracted, contracted_value);
+                } else {
+                    let best = simplex[0].0.clone();
+                    for (vertex, value) in simplex.iter_mut().skip(1) {
+                        for (coordinate, best_coordinate) in vertex.iter_mut().zip(best.iter()) {
+                            *coordinate = best_coordinate + 0.5 * (*coordinate - best_coordinate);
+                        }
+                        *value = objective.value(vertex)?;
+                    }
+                }
+            }
+            simplex.sort_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(Ordering::Equal));
+        }
+        let best = simplex[0].clone();
+        self.state = MethodState::Simplex(simplex);
+        Ok(best)
+    }
+}
+
+/// Returns the sign (±1) of a parameter in the random SPSA perturbation direction of an iteration.
+///
+/// Uses the SplitMix64 hash of the seed, the iteration and the parameter index, so that
+/// resumed optimizations use the same directions as uninterrupted ones.
+fn perturbation_sign(seed: u64, iteration: usize, index: usize) -> f64 {
+    let mut hash = seed
+        ^ (iteration as u64).wrapping_mul(0x9E37_79B9_7F4A_7C15)
+        ^ (index as u64).wrapping_mul(0xC2B2_AE3D_27D4_EB4F);
+    hash = hash.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    hash = (hash ^ (hash >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    hash = (hash ^ (hash >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    hash ^= hash >> 31;
+    if hash & 1 == 1 {
+        1.0
+    } else {
+        -1.0
+    }
+}
@@ -19,9 +19,12 @@
 //!```
 //!
 
-pub use crate::backends::EvaluatingBackend;
+pub use crate::backends::{EvaluatingBackend, JobBackend};
 pub use crate::circuit::AsVec;
-pub use crate::measurements::{Measure, MeasureExpectationValues};
+pub use crate::measurements::{
+    Measure, MeasureComplexExpectationValues, MeasureExpectationValues,
+    MeasureExpectationValuesWithErrors,
+};
 pub use crate::operations::{
     Define, InvolveQubits, InvolvedQubits, Operate, OperateConstantGate, OperateGate,
     OperateMultiQubit, OperateMultiQubitGate, OperatePragma, OperatePragmaNoise,
@@ -0,0 +1,370 @@
+// Copyright © 2021 HQS Quantum Simulations GmbH. All Rights Reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except
+// in compliance with the License. You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the
+// License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either
+// express or implied. See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Export of roqoqo circuits to the QIR base profile.
+//!
+//! [circuit_to_qir] translates a [crate::Circuit] into textual LLVM IR following the
+//! [QIR base profile](https://github.com/qir-alliance/qir-spec/blob/main/specification/under_development/profiles/Base_Profile.md).
+//!
+//! * Gates are translated to `__quantum__qis__*__body` calls, qubits are addressed statically.
+//! * [crate::operations::MeasureQubit] is translated to `__quantum__qis__mz__body`.
+//! * Every [crate::operations::DefinitionBit] register marked as output is recorded with
+//!   `__quantum__rt__array_record_output` followed by one `__quantum__rt__result_record_output` per entry.
+//!   Results of all registers are numbered consecutively in the order of the definitions.
+//!
+//! The number of repetitions of a circuit is not part of a QIR program, so
+//! [crate::operations::PragmaRepeatedMeasurement] is exported as a single measurement of the qubits
+//! and [crate::operations::PragmaSetNumberOfMeasurements] is ignored.
+//! All parameters must be substituted before export, see [crate::Circuit::substitute_parameters].
+//!
+//! # Example
+//!
+//! ```
+//! use roqoqo::Circuit;
+//! use roqoqo::operations::{CNOT, Hadamard, MeasureQubit, DefinitionBit};
+//! use roqoqo::qir::circuit_to_qir;
+//!
+//! let mut circuit = Circuit::new();
+//! circuit += DefinitionBit::new("ro".to_string(), 2, true);
+//! circuit += Hadamard::new(0);
+//! circuit += CNOT::new(0, 1);
+//! circuit += MeasureQubit::new(0, "ro".to_string(), 0);
+//! circuit += MeasureQubit::new(1, "ro".to_string(), 1);
+//!
+//! let qir = circuit_to_qir(&circuit).unwrap();
+//! assert!(qir.contains("call void @__quantum__qis__h__body(%Qubit* null)"));
+//! ```
+
+use crate::operations::{
+    Define, InvolveQubits, InvolvedQubits, Operate, OperateSingleQubit, OperateTwoQubit, Operation,
+    Rotate,
+};
+use crate::{Circuit, RoqoqoBackendError, RoqoqoError};
+use qoqo_calculator::CalculatorFloat;
+use std::collections::{BTreeSet, HashMap};
+use std::f64::consts::FRAC_PI_2;
+
+/// Name of the exporter used in error messages.
+const BACKEND_NAME: &str = "QIR";
+
+/// Exports a circuit to QIR base profile LLVM IR.
+///
+/// # Arguments
+///
+/// * `circuit` - The Circuit that is exported.
+///
+/// # Returns
+///
+/// * `Ok(String)` - The textual LLVM IR module.
+/// * `Err(RoqoqoBackendError::OperationNotInBackend)` - The circuit contains an operation that cannot be exported.
+/// * `Err(RoqoqoBackendError::GenericError)` - The circuit contains symbolic parameters that have not been substituted.
+/// * `Err(RoqoqoBackendError::RoqoqoError)` - A measurement writes to a register that is not defined by a DefinitionBit.
+pub fn circuit_to_qir(circuit: &Circuit) -> Result<String, RoqoqoBackendError> {
+    // Offsets of the bit registers in the consecutively numbered results
+    let mut registers: Vec<(usize, bool)> = Vec::new();
+    let mut offsets: HashMap<String, (usize, usize)> = HashMap::new();
+    let mut number_results: usize = 0;
+    for definition in circuit.definitions() {
+        if let Operation::DefinitionBit(def) = definition {
+            offsets.insert(def.name().clone(), (number_results, *def.length()));
+            registers.push((*def.length(), *def.is_output()));
+            number_results += *def.length();
+        }
+    }
+    let mut number_qubits: usize = 0;
+    for operation in circuit.iter() {
+        let qubits: Vec<usize> = match (operation, operation.involved_qubits()) {
+            // The mapped qubits of a repeated measurement are measured even if no other operation acts on them
+            (Operation::PragmaRepeatedMeasurement(op), _) => op
+                .qubit_mapping()
+                .as_ref()
+                .map(|mapping| mapping.keys().cloned().collect())
+                .unwrap_or_default(),
+            (_, InvolvedQubits::Set(qubits)) => qubits.into_iter().collect(),
+            _ => Vec::new(),
+        };
+        if let Some(max_qubit) = qubits.iter().max() {
+            number_qubits = number_qubits.max(max_qubit + 1);
+        }
+    }
+
+    let mut body = QirBody::new();
+    body.call("__quantum__rt__initialize", "i8* null".to_string());
+    for operation in circuit.operations() {
+        match operation {
+            Operation::Hadamard(op) => body.gate("h", &[], &[*op.qubit()]),
+            Operation::PauliX(op) => body.gate("x", &[], &[*op.qubit()]),
+            Operation::PauliY(op) => body.gate("y", &[], &[*op.qubit()]),
+            Operation::PauliZ(op) => body.gate("z", &[], &[*op.qubit()]),
+            Operation::SGate(op) => body.gate("s", &[], &[*op.qubit()]),
+            Operation::TGate(op) => body.gate("t", &[], &[*op.qubit()]),
+            Operation::SqrtPauliX(op) => body.gate("rx", &[FRAC_PI_2], &[*op.qubit()]),
+            Operation::InvSqrtPauliX(op) => body.gate("rx", &[-FRAC_PI_2], &[*op.qubit()]),
+            Operation::RotateX(op) => {
+                let theta = float_parameter(operation, op.theta())?;
+                body.gate("rx", &[theta], &[*op.qubit()])
+            }
+            Operation::RotateY(op) => {
+                let theta = float_parameter(operation, op.theta())?;
+                body.gate("ry", &[theta], &[*op.qubit()])
+            }
+            Operation::RotateZ(op) => {
+                let theta = float_parameter(operation, op.theta())?;
+                body.gate("rz", &[theta], &[*op.qubit()])
+            }
+            Operation::CNOT(op) => body.gate("cnot", &[], &[*op.control(), *op.target()]),
+            Operation::ControlledPauliZ(op) => body.gate("cz", &[], &[*op.control(), *op.target()]),
+            Operation::SWAP(op) => body.gate("swap", &[], &[*op.control(), *op.target()]),
+            Operation::MeasureQubit(op) => {
+                let result = result_index(&offsets, op.readout(), *op.readout_index())?;
+                body.measure(*op.qubit(), result);
+            }
+            Operation::PragmaRepeatedMeasurement(op) => {
+                let (offset, length) =
+                    *offsets
+                        .get(op.readout())
+                        .ok_or_else(|| RoqoqoError::MissingRegister {
+                            name: op.readout().clone(),
+                        })?;
+                let mut measured: Vec<(usize, usize)> = match op.qubit_mapping() {
+                    Some(mapping) => mapping
+                        .iter()
+                        .map(|(qubit, index)| (*qubit, *index))
+                        .collect(),
+                    None => (0..length.min(number_qubits))
+                        .map(|qubit| (qubit, qubit))
+                        .collect(),
+                };
+                measured.sort_unstable();
+                for (qubit, index) in measured {
+                    if index >= length {
+                        return Err(RoqoqoBackendError::GenericError {
+                            msg: format!(
+                                "Readout index {} exceeds length {} of register {}",
+                                index,
+                                length,
+                                op.readout()
+                            ),
+                        });
+                    }
+                    body.measure(qubit, offset + index);
+                }
+            }
+            Operation::DefinitionBit(_)
+            | Operation::DefinitionFloat(_)
+            | Operation::DefinitionComplex(_)
+            | Operation::DefinitionUsize(_)
+            | Operation::InputSymbolic(_)
+            | Operation::PragmaSetNumberOfMeasurements(_)
+            | Operation::PragmaGlobalPhase(_)
+            | Operation::PragmaStopParallelBlock(_)
+            | Operation::PragmaStartDecompositionBlock(_)
+            | Operation::PragmaStopDecompositionBlock(_) => (),
+            _ => {
+                return Err(RoqoqoBackendError::OperationNotInBackend {
+                    backend: BACKEND_NAME,
+                    hqslang: operation.hqslang(),
+                })
+            }
+        }
+    }
+    let mut offset: usize = 0;
+    for (length, is_output) in registers.iter() {
+        if *is_output {
+            body.call(
+                "__quantum__rt__array_record_output",
+                format!("i64 {}, i8* null", length),
+            );
+            for index in offset..offset + length {
+                body.call(
+                    "__quantum__rt__result_record_output",
+                    format!("{}, i8* null", result_pointer(index)),
+                );
+            }
+        }
+        offset += length;
+    }
+    Ok(body.into_module(number_qubits, number_results))
+}
+
+/// Instructions and declarations of the entry point function.
+#[derive(Debug, Clone, Default)]
+struct QirBody {
+    /// Instructions in the entry block.
+    instructions: Vec<String>,
+    /// Names of the called functions.
+    functions: BTreeSet<&'static str>,
+}
+
+impl QirBody {
+    fn new() -> Self {
+        Self::default()
+    }
+
+    fn call(&mut self, function: &'static str, arguments: String) {
+        self.functions.insert(function);
+        self.instructions
+            .push(format!("  call void @{}({})", function, arguments));
+    }
+
+    fn gate(&mut self, name: &'static str, parameters: &[f64], qubits: &[usize]) {
+        let mut arguments: Vec<String> = parameters
+            .iter()
+            .map(|parameter| format!("double {}", float_literal(*parameter)))
+            .collect();
+        arguments.extend(qubits.iter().map(|qubit| qubit_pointer(*qubit)));
+        let function = qis_function(name);
+        self.call(function, arguments.join(", "));
+    }
+
+    fn measure(&mut self, qubit: usize, result: usize) {
+        self.call(
+            "__quantum__qis__mz__body",
+            format!("{}, {}", qubit_pointer(qubit), result_pointer(result)),
+        );
+    }
+
+    fn into_module(self, number_qubits: usize, number_results: usize) -> String {
+        let mut module: Vec<String> = vec![
+            "; ModuleID = 'roqoqo'".to_string(),
+            "source_filename = \"roqoqo\"".to_string(),
+            String::new(),
+            "%Qubit = type opaque".to_string(),
+            "%Result = type opaque".to_string(),
+            String::new(),
+            "define void @main() #0 {".to_string(),
+            "entry:".to_string(),
+        ];
+        module.extend(self.instructions);
+        module.push("  ret void".to_string());
+        module.push("}".to_string());
+        module.push(String::new());
+        for function in self.functions.iter() {
+            module.push(declaration(function));
+        }
+        module.push(String::new());
+        module.push(format!(
+            "attributes #0 = {{ \"entry_point\" \"output_labeling_schema\" \"qir_profiles\"=\"base_profile\" \"required_num_qubits\"=\"{}\" \"required_num_results\"=\"{}\" }}",
+            number_qubits, number_results
+        ));
+        module.push("attributes #1 = { \"irreversible\" }".to_string());
+        module.push(String::new());
+        module.push("!llvm.module.flags = !{!0, !1, !2, !3}".to_string());
+        module.push(String::new());
+        module.push("!0 = !{i32 1, !\"qir_major_version\", i32 1}".to_string());
+        module.push("!1 = !{i32 7, !\"qir_minor_version\", i32 0}".to_string());
+        module.push("!2 = !{i32 1, !\"dynamic_qubit_management\", i1 false}".to_string());
+        module.push("!3 = !{i32 1, !\"dynamic_result_management\", i1 false}".to_string());
+        let mut module = module.join("\n");
+        module.push('\n');
+        module
+    }
+}
+
+/// Returns the name of the quantum instruction set function of a gate.
+fn qis_function(name: &str) -> &'static str {
+    match name {
+        "h" => "__quantum__qis__h__body",
+        "x" => "__quantum__qis__x__body",
+        "y" => "__quantum__qis__y__body",
+        "z" => "__quantum__qis__z__body",
+        "s" => "__quantum__qis__s__body",
+        "t" => "__quantum__qis__t__body",
+        "rx" => "__quantum__qis__rx__body",
+        "ry" => "__quantum__qis__ry__body",
+        "rz" => "__quantum__qis__rz__body",
+        "cnot" => "__quantum__qis__cnot__body",
+        "cz" => "__quantum__qis__cz__body",
+        "swap" => "__quantum__qis__swap__body",
+        _ => unreachable!("Internal error: unknown QIR gate {}", name),
+    }
+}
+
+/// Returns the declaration of a called function.
+fn declaration(function: &str) -> String {
+    let signature = match function {
+        "__quantum__rt__initialize" => "(i8*)",
+        "__quantum__rt__array_record_output" => "(i64, i8*)",
+        "__quantum__rt__result_record_output" => "(%Result*, i8*)",
+        "__quantum__qis__mz__body" => {
+            return format!("declare void @{}(%Qubit*, %Result* writeonly) #1", function)
+        }
+        "__quantum__qis__rx__body" | "__quantum__qis__ry__body" | "__quantum__qis__rz__body" => {
+            "(double, %Qubit*)"
+        }
+        "__quantum__qis__cnot__body"
+        | "__quantum__qis__cz__body"
+        | "__quantum__qis__swap__body" => "(%Qubit*, %Qubit*)",
+        _ => "(%Qubit*)",
+    };
+    format!("declare void @{}{}", function, signature)
+}
+
+/// Returns the float value of a parameter or an error for unsubstituted symbolic parameters.
+fn float_parameter(
+    operation: &Operation,
+    parameter: &CalculatorFloat,
+) -> Result<f64, RoqoqoBackendError> {
+    match parameter {
+        CalculatorFloat::Float(value) => Ok(*value),
+        CalculatorFloat::Str(expression) => Err(RoqoqoBackendError::GenericError {
+            msg: format!(
+                "Symbolic parameter {} of operation {} has not been substituted, QIR export requires float parameters",
+                expression,
+                operation.hqslang()
+            ),
+        }),
+    }
+}
+
+/// Returns the index of a result in the consecutively numbered results of all bit registers.
+fn result_index(
+    offsets: &HashMap<String, (usize, usize)>,
+    readout: &str,
+    readout_index: usize,
+) -> Result<usize, RoqoqoBackendError> {
+    let (offset, length) = offsets
+        .get(readout)
+        .ok_or_else(|| RoqoqoError::MissingRegister {
+            name: readout.to_string(),
+        })?;
+    if readout_index >= *length {
+        return Err(RoqoqoBackendError::GenericError {
+            msg: format!(
+                "Readout index {} exceeds length {} of register {}",
+                readout_index, length, readout
+            ),
+        });
+    }
+    Ok(offset + readout_index)
+}
+
+/// Returns a statically allocated qubit pointer.
+fn qubit_pointer(qubit: usize) -> String {
+    match qubit {
+        0 => "%Qubit* null".to_string(),
+        _ => format!("%Qubit* inttoptr (i64 {} to %Qubit*)", qubit),
+    }
+}
+
+/// Returns a statically allocated result pointer.
+fn result_pointer(result: usize) -> String {
+    match result {
+        0 => "%Result* null".to_string(),
+        _ => format!("%Result* inttoptr (i64 {} to %Result*)", result),
+    }
+}
+
+/// Returns the exact hexadecimal LLVM representation of a double.
+fn float_literal(value: f64) -> String {
+    format!("0x{:016X}", value.to_bits())
+}
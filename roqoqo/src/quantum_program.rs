@@ -10,13 +10,24 @@
 // express or implied. See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap};
 
-use crate::backends::{EvaluatingBackend, RegisterResult};
+use crate::backends::{
+    BackendCapabilities, EvaluatingBackend, Incompatibility, JobBackend, JobId, JobStatus,
+    RegisterResult,
+};
 use crate::measurements;
-use crate::measurements::Measure;
-use crate::RoqoqoBackendError;
+use crate::measurements::{Measure, MeasureExpectationValues};
+use crate::operations::{InputSymbolic, Operation};
+use crate::optimization;
+use crate::registers::{BitOutputRegister, ComplexOutputRegister, FloatOutputRegister};
+use crate::{Circuit, RoqoqoBackendError, RoqoqoError};
+use ndarray::{Array2, ArrayView1};
+use qoqo_calculator::{Calculator, CalculatorError};
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
 use std::fmt::{Display, Formatter};
+use std::time::Instant;
 /// Represents a quantum program evaluating measurements based on a one or more free float parameters.
 ///
 /// The main use of QuantumProgram is to contain a Measurements implementing [crate::measurements::Measure]
@@ -62,6 +73,20 @@ pub enum QuantumProgram {
         /// List of free input parameters that can be set when the QuantumProgram is executed
         input_parameter_names: Vec<String>,
     },
+    /// Variant for classical shadows measurement based quantum programs
+    ClassicalShadows {
+        /// The measurement that is performed
+        measurement: measurements::ClassicalShadows,
+        /// List of free input parameters that can be set when the QuantumProgram is executed
+        input_parameter_names: Vec<String>,
+    },
+    /// Variant for quantum programs returning bitstring histograms and marginal probabilities
+    Histogram {
+        /// The measurement that is performed
+        measurement: measurements::Histogram,
+        /// List of free input parameters that can be set when the QuantumProgram is executed
+        input_parameter_names: Vec<String>,
+    },
 }
 
 impl QuantumProgram {
@@ -84,29 +109,26 @@ impl QuantumProgram {
     {
         match self{
             QuantumProgram::BasisRotation{measurement, input_parameter_names } => {
-                if parameters.len() != input_parameter_names.len() { return Err(RoqoqoBackendError::GenericError{msg: format!("Wrong number of parameters {} parameters expected {} parameters given", input_parameter_names.len(), parameters.len())})};
-                let substituted_parameters: HashMap<String, f64> = input_parameter_names.iter().zip(parameters.iter()).map(|(key, value)| (key.clone(), *value)).collect();
-                let substituted_measurement = measurement.substitute_parameters(
-                    substituted_parameters
-                )?;
+                let substituted_measurement =
+                    substitute_measurement(measurement, input_parameter_names, parameters)?;
                 backend.run_measurement(&substituted_measurement)
             }
             QuantumProgram::CheatedBasisRotation{measurement, input_parameter_names } => {
-                if parameters.len() != input_parameter_names.len() { return Err(RoqoqoBackendError::GenericError{msg: format!("Wrong number of parameters {} parameters expected {} parameters given", input_parameter_names.len(), parameters.len())})};
-                let substituted_parameters: HashMap<String, f64> = input_parameter_names.iter().zip(parameters.iter()).map(|(key, value)| (key.clone(), *value)).collect();
-                let substituted_measurement = measurement.substitute_parameters(
-                    substituted_parameters
-                )?;
+                let substituted_measurement =
+                    substitute_measurement(measurement, input_parameter_names, parameters)?;
                 backend.run_measurement(&substituted_measurement)
             }
             QuantumProgram::Cheated{measurement, input_parameter_names } => {
-                if parameters.len() != input_parameter_names.len() { return Err(RoqoqoBackendError::GenericError{msg: format!("Wrong number of parameters {} parameters expected {} parameters given", input_parameter_names.len(), parameters.len())})};
-                let substituted_parameters: HashMap<String, f64> = input_parameter_names.iter().zip(parameters.iter()).map(|(key, value)| (key.clone(), *value)).collect();
-                let substituted_measurement = measurement.substitute_parameters(
-                    substituted_parameters
-                )?;
+                let substituted_measurement =
+                    substitute_measurement(measurement, input_parameter_names, parameters)?;
+                backend.run_measurement(&substituted_measurement)
+            }
+            QuantumProgram::ClassicalShadows{measurement, input_parameter_names } => {
+                let substituted_measurement =
+                    substitute_measurement(measurement, input_parameter_names, parameters)?;
                 backend.run_measurement(&substituted_measurement)
             }
+            QuantumProgram::Histogram{..} => Err(RoqoqoBackendError::GenericError{msg: "A quantum programm returning histograms cannot be executed by `run` use `run_histogram` instead".to_string()}),
             _ => Err(RoqoqoBackendError::GenericError{msg: "A quantum programm returning classical registeres cannot be executed by `run` use `run_registers` instead".to_string()})
         }
     }
@@ -129,16 +151,1229 @@ impl QuantumProgram {
     {
         match self{
             QuantumProgram::ClassicalRegister{measurement, input_parameter_names } => {
-                if parameters.len() != input_parameter_names.len() { return Err(RoqoqoBackendError::GenericError{msg: format!("Wrong number of parameters {} parameters expected {} parameters given", input_parameter_names.len(), parameters.len())})};
-                let substituted_parameters: HashMap<String, f64> = input_parameter_names.iter().zip(parameters.iter()).map(|(key, value)| (key.clone(), *value)).collect();
-                let substituted_measurement = measurement.substitute_parameters(
-                    substituted_parameters
-                )?;
+                let substituted_measurement =
+                    substitute_measurement(measurement, input_parameter_names, parameters)?;
+                backend.run_measurement_registers(&substituted_measurement)
+            }
+            QuantumProgram::Histogram{measurement, input_parameter_names } => {
+                let substituted_measurement =
+                    substitute_measurement(measurement, input_parameter_names, parameters)?;
                 backend.run_measurement_registers(&substituted_measurement)
             }
             _ => Err(RoqoqoBackendError::GenericError{msg: "A quantum programm returning expectation values cannot be executed by `run_registers` use `run` instead".to_string()})
         }
     }
+
+    /// Runs the QuantumProgram and returns bitstring histograms and marginal probabilities.
+    ///
+    /// Runs the quantum programm for a given set of parameters passed in the same order as the parameters
+    /// listed in `input_parameter_names` and evaluates the measured bit registers
+    /// with the [crate::measurements::Histogram] measurement.
+    ///
+    /// Arguments:
+    ///
+    /// * `backend` - The backend the program is executed on.
+    /// * `parameters` - List of float ([f64]) parameters of the function call in order of `input_parameter_names`
+    pub fn run_histogram<T>(
+        &self,
+        backend: T,
+        parameters: &[f64],
+    ) -> Result<measurements::HistogramResult, RoqoqoBackendError>
+    where
+        T: EvaluatingBackend,
+    {
+        match self{
+            QuantumProgram::Histogram{measurement, input_parameter_names } => {
+                let substituted_measurement =
+                    substitute_measurement(measurement, input_parameter_names, parameters)?;
+                let (bit_registers, float_registers, complex_registers) = backend.run_measurement_registers(&substituted_measurement)?;
+                Ok(substituted_measurement.evaluate(bit_registers, float_registers, complex_registers)?)
+            }
+            _ => Err(RoqoqoBackendError::GenericError{msg: "Only a quantum programm with a histogram measurement can be executed by `run_histogram`".to_string()})
+        }
+    }
+}
+
+impl QuantumProgram {
+    /// Runs the QuantumProgram for a batch of parameter sets and returns the expectation values in a table.
+    ///
+    /// Each parameter set is passed in the same order as the parameters listed in `input_parameter_names`.
+    /// The parameter sets are run one after another with the borrowed backend.
+    ///
+    /// Arguments:
+    ///
+    /// * `backend` - The backend the program is executed on.
+    /// * `parameter_sets` - List of parameter sets, each a list of float ([f64]) parameters in order of `input_parameter_names`
+    ///
+    /// Returns:
+    ///
+    /// * `Ok(ParameterSweepResult)` - The parameter sets and the expectation values of each run.
+    /// * `Err(RoqoqoBackendError)` - A run failed.
+    pub fn run_batch<T>(
+        &self,
+        backend: &T,
+        parameter_sets: &[Vec<f64>],
+    ) -> Result<ParameterSweepResult, RoqoqoBackendError>
+    where
+        T: EvaluatingBackend,
+    {
+        let results = parameter_sets
+            .iter()
+            .map(|parameters| self.run(backend, parameters))
+            .collect::<Result<Vec<Option<HashMap<String, f64>>>, RoqoqoBackendError>>()?;
+        Ok(ParameterSweepResult::from_results(
+            self.input_parameter_names().to_vec(),
+            parameter_sets,
+            results,
+        )?)
+    }
+
+    /// Runs the QuantumProgram for a batch of parameter sets in parallel and returns the expectation values in a table.
+    ///
+    /// Behaves like [QuantumProgram::run_batch] but runs the parameter sets in parallel
+    /// using the rayon thread pool.
+    ///
+    /// Arguments:
+    ///
+    /// * `backend` - The backend the program is executed on.
+    /// * `parameter_sets` - List of parameter sets, each a list of float ([f64]) parameters in order of `input_parameter_names`
+    #[cfg(feature = "parallel")]
+    pub fn run_batch_parallel<T>(
+        &self,
+        backend: &T,
+        parameter_sets: &[Vec<f64>],
+    ) -> Result<ParameterSweepResult, RoqoqoBackendError>
+    where
+        T: EvaluatingBackend + Sync,
+    {
+        let results = parameter_sets
+            .par_iter()
+            .map(|parameters| self.run(backend, parameters))
+            .collect::<Result<Vec<Option<HashMap<String, f64>>>, RoqoqoBackendError>>()?;
+        Ok(ParameterSweepResult::from_results(
+            self.input_parameter_names().to_vec(),
+            parameter_sets,
+            results,
+        )?)
+    }
+
+    /// Runs the QuantumProgram on a grid of parameters and returns the expectation values in a table.
+    ///
+    /// The grid contains all combinations of the values of each parameter, see [parameter_grid].
+    ///
+    /// Arguments:
+    ///
+    /// * `backend` - The backend the program is executed on.
+    /// * `axes` - The values of each parameter in order of `input_parameter_names`
+    pub fn run_grid<T>(
+        &self,
+        backend: &T,
+        axes: &[Vec<f64>],
+    ) -> Result<ParameterSweepResult, RoqoqoBackendError>
+    where
+        T: EvaluatingBackend,
+    {
+        self.run_batch(backend, &parameter_grid(axes))
+    }
+
+    /// Runs the QuantumProgram and returns the expectation values together with information about the run.
+    ///
+    /// Behaves like [QuantumProgram::run] but returns a [RunResult] with the substituted parameters,
+    /// the number of run circuits and shots, the time of the run and the metadata of the backend
+    /// ([crate::backends::EvaluatingBackend::metadata]).
+    ///
+    /// Arguments:
+    ///
+    /// * `backend` - The backend the program is executed on.
+    /// * `parameters` - List of float ([f64]) parameters of the function call in order of `input_parameter_names`
+    /// * `keep_registers` - Whether the output registers of the run are stored in the RunResult.
+    ///
+    /// Returns:
+    ///
+    /// * `Ok(RunResult)` - The expectation values and the information about the run.
+    /// * `Err(RoqoqoBackendError)` - The QuantumProgram does not return expectation values or the run failed.
+    pub fn run_with_metadata<T>(
+        &self,
+        backend: &T,
+        parameters: &[f64],
+        keep_registers: bool,
+    ) -> Result<RunResult, RoqoqoBackendError>
+    where
+        T: EvaluatingBackend,
+    {
+        match self {
+            QuantumProgram::BasisRotation {
+                measurement,
+                input_parameter_names,
+            } => run_measurement_with_metadata(
+                backend,
+                measurement,
+                input_parameter_names,
+                parameters,
+                keep_registers,
+            ),
+            QuantumProgram::CheatedBasisRotation {
+                measurement,
+                input_parameter_names,
+            } => run_measurement_with_metadata(
+                backend,
+                measurement,
+                input_parameter_names,
+                parameters,
+                keep_registers,
+            ),
+            QuantumProgram::Cheated {
+                measurement,
+                input_parameter_names,
+            } => run_measurement_with_metadata(
+                backend,
+                measurement,
+                input_parameter_names,
+                parameters,
+                keep_registers,
+            ),
+            QuantumProgram::ClassicalShadows {
+                measurement,
+                input_parameter_names,
+            } => run_measurement_with_metadata(
+                backend,
+                measurement,
+                input_parameter_names,
+                parameters,
+                keep_registers,
+            ),
+            QuantumProgram::Histogram { .. } => Err(RoqoqoBackendError::GenericError {
+                msg: "A quantum programm returning histograms cannot be executed by `run_with_metadata` use `run_histogram` instead".to_string(),
+            }),
+            _ => Err(RoqoqoBackendError::GenericError {
+                msg: "A quantum programm returning classical registeres cannot be executed by `run_with_metadata` use `run_registers` instead".to_string(),
+            }),
+        }
+    }
+
+    /// Returns the gradients of the expectation values with respect to the input parameters.
+    ///
+    /// Rotation gates whose angle depends affinely on the input parameters are differentiated with the
+    /// parameter-shift rule, all other parametrized operations with central finite differences,
+    /// see [crate::optimization::parameter_shift_gradient].
+    /// Measurements with expectation values that are not linear in the state are differentiated
+    /// with central finite differences of the whole measurement.
+    ///
+    /// Arguments:
+    ///
+    /// * `backend` - The backend the program is executed on.
+    /// * `parameters` - List of float ([f64]) parameters of the function call in order of `input_parameter_names`
+    ///
+    /// Returns:
+    ///
+    /// * `Ok(HashMap<String, Vec<f64>>)` - The derivatives of each expectation value in order of `input_parameter_names`.
+    /// * `Err(RoqoqoBackendError)` - The QuantumProgram does not return expectation values or a run failed.
+    pub fn gradient<T>(
+        &self,
+        backend: &T,
+        parameters: &[f64],
+    ) -> Result<HashMap<String, Vec<f64>>, RoqoqoBackendError>
+    where
+        T: EvaluatingBackend,
+    {
+        let step = optimization::DEFAULT_FINITE_DIFFERENCE_STEP;
+        match self {
+            QuantumProgram::BasisRotation {
+                measurement,
+                input_parameter_names,
+            } => optimization::parameter_shift_gradient(
+                backend,
+                measurement,
+                input_parameter_names,
+                parameters,
+                step,
+            ),
+            QuantumProgram::CheatedBasisRotation {
+                measurement,
+                input_parameter_names,
+            } => optimization::parameter_shift_gradient(
+                backend,
+                measurement,
+                input_parameter_names,
+                parameters,
+                step,
+            ),
+            QuantumProgram::Cheated {
+                measurement,
+                input_parameter_names,
+            } => optimization::parameter_shift_gradient(
+                backend,
+                measurement,
+                input_parameter_names,
+                parameters,
+                step,
+            ),
+            QuantumProgram::ClassicalShadows {
+                measurement,
+                input_parameter_names,
+            } => optimization::parameter_shift_gradient(
+                backend,
+                measurement,
+                input_parameter_names,
+                parameters,
+                step,
+            ),
+            _ => Err(RoqoqoBackendError::GenericError {
+                msg: "Only a quantum programm returning expectation values can be differentiated by `gradient`".to_string(),
+            }),
+        }
+    }
+
+    /// Returns the names of the free input parameters of the QuantumProgram.
+    fn input_parameter_names(&self) -> &[String] {
+        match self {
+            QuantumProgram::BasisRotation {
+                input_parameter_names,
+                ..
+            } => input_parameter_names,
+            QuantumProgram::CheatedBasisRotation {
+                input_parameter_names,
+                ..
+            } => input_parameter_names,
+            QuantumProgram::Cheated {
+                input_parameter_names,
+                ..
+            } => input_parameter_names,
+            QuantumProgram::ClassicalRegister {
+                input_parameter_names,
+                ..
+            } => input_parameter_names,
+            QuantumProgram::ClassicalShadows {
+                input_parameter_names,
+                ..
+            } => input_parameter_names,
+            QuantumProgram::Histogram {
+                input_parameter_names,
+                ..
+            } => input_parameter_names,
+        }
+    }
+}
+
+/// Returns all combinations of the values of each parameter.
+///
+/// The parameter sets are ordered like nested loops over the axes with the last axis in the innermost loop.
+///
+/// # Arguments
+///
+/// * `axes` - The values of each parameter.
+///
+/// # Returns
+///
+/// * `Vec<Vec<f64>>` - The parameter sets of the grid (one empty set for no axes).
+///
+/// # Example
+///
+/// ```
+/// use roqoqo::parameter_grid;
+///
+/// let grid = parameter_grid(&[vec![0.0, 1.0], vec![2.0, 3.0]]);
+/// assert_eq!(grid, vec![vec![0.0, 2.0], vec![0.0, 3.0], vec![1.0, 2.0], vec![1.0, 3.0]]);
+/// ```
+pub fn parameter_grid(axes: &[Vec<f64>]) -> Vec<Vec<f64>> {
+    axes.iter().fold(vec![Vec::new()], |sets, axis| {
+        sets.iter()
+            .flat_map(|set| {
+                axis.iter().map(move |value| {
+                    let mut new_set = set.clone();
+                    new_set.push(*value);
+                    new_set
+                })
+            })
+            .collect()
+    })
+}
+
+/// Expectation values of a [QuantumProgram] run for a batch of parameter sets.
+///
+/// Tabular result of [QuantumProgram::run_batch] with one row for each parameter set.
+/// The columns of `parameters` correspond to `parameter_names` and the columns of
+/// `expectation_values` to `expectation_value_names`.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "json_schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct ParameterSweepResult {
+    /// The names of the input parameters.
+    pub parameter_names: Vec<String>,
+    /// The parameter sets with one row for each run.
+    pub parameters: Array2<f64>,
+    /// The names of the expectation values in alphabetical order.
+    pub expectation_value_names: Vec<String>,
+    /// The expectation values with one row for each run (NaN when a run returned no value).
+    pub expectation_values: Array2<f64>,
+}
+
+impl ParameterSweepResult {
+    /// Creates a new ParameterSweepResult from the expectation values of the runs.
+    ///
+    /// # Arguments
+    ///
+    /// * `parameter_names` - The names of the input parameters.
+    /// * `parameter_sets` - The parameter sets of the runs.
+    /// * `results` - The expectation values returned by each run.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Self)` - The tabular result.
+    /// * `Err([RoqoqoError::GenericError])` - The number of results or the length of a parameter set does not match.
+    pub fn from_results(
+        parameter_names: Vec<String>,
+        parameter_sets: &[Vec<f64>],
+        results: Vec<Option<HashMap<String, f64>>>,
+    ) -> Result<Self, RoqoqoError> {
+        if parameter_sets.len() != results.len() {
+            return Err(RoqoqoError::GenericError {
+                msg: format!(
+                    "Number of results {} does not match number of parameter sets {}",
+                    results.len(),
+                    parameter_sets.len()
+                ),
+            });
+        }
+        let mut parameters: Array2<f64> =
+            Array2::zeros((parameter_sets.len(), parameter_names.len()));
+        for (mut row, set) in parameters.outer_iter_mut().zip(parameter_sets.iter()) {
+            if set.len() != parameter_names.len() {
+                return Err(RoqoqoError::GenericError {
+                    msg: format!(
+                        "Parameter set with {} parameters does not match {} parameter names",
+                        set.len(),
+                        parameter_names.len()
+                    ),
+                });
+            }
+            row.assign(&ArrayView1::from(set.as_slice()));
+        }
+        let names: BTreeSet<&String> = results
+            .iter()
+            .flatten()
+            .flat_map(|values| values.keys())
+            .collect();
+        let expectation_value_names: Vec<String> = names.into_iter().cloned().collect();
+        let mut expectation_values: Array2<f64> =
+            Array2::from_elem((results.len(), expectation_value_names.len()), f64::NAN);
+        for (mut row, values) in expectation_values.outer_iter_mut().zip(results.iter()) {
+            if let Some(values) = values {
+                for (value, name) in row.iter_mut().zip(expectation_value_names.iter()) {
+                    if let Some(result) = values.get(name) {
+                        *value = *result;
+                    }
+                }
+            }
+        }
+        Ok(Self {
+            parameter_names,
+            parameters,
+            expectation_value_names,
+            expectation_values,
+        })
+    }
+
+    /// Returns the values of an expectation value for all runs.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the expectation value.
+    ///
+    /// # Returns
+    ///
+    /// * `Some(ArrayView1<f64>)` - The expectation value of each run.
+    /// * `None` - No run returned the expectation value.
+    pub fn expectation_value(&self, name: &str) -> Option<ArrayView1<f64>> {
+        let column = self
+            .expectation_value_names
+            .iter()
+            .position(|value_name| value_name == name)?;
+        Some(self.expectation_values.column(column))
+    }
+}
+
+/// Output registers returned by a backend with the register name as key.
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "json_schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct OutputRegisters {
+    /// The bit registers with the register name as key.
+    pub bit_registers: HashMap<String, BitOutputRegister>,
+    /// The float registers with the register name as key.
+    pub float_registers: HashMap<String, FloatOutputRegister>,
+    /// The complex registers with the register name as key.
+    pub complex_registers: HashMap<String, ComplexOutputRegister>,
+}
+
+/// Expectation values of a single [QuantumProgram] run together with information about the run.
+///
+/// Returned by [QuantumProgram::run_with_metadata].
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "json_schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct RunResult {
+    /// The expectation values with their name as key (None when the measurement did not return a result).
+    pub expectation_values: Option<HashMap<String, f64>>,
+    /// The output registers of the run (None when they have not been kept).
+    pub registers: Option<OutputRegisters>,
+    /// The names of the input parameters.
+    pub parameter_names: Vec<String>,
+    /// The values of the input parameters in order of `parameter_names`.
+    pub parameters: Vec<f64>,
+    /// The number of circuits run on the backend.
+    pub number_circuits: usize,
+    /// The total number of shots of all circuits.
+    ///
+    /// The shots of a circuit are set by [crate::operations::PragmaSetNumberOfMeasurements] or
+    /// [crate::operations::PragmaRepeatedMeasurement]. Circuits with a [crate::operations::MeasureQubit]
+    /// but without these pragmas count as a single shot, circuits without measurements (e.g. cheated measurements) as none.
+    pub total_shots: usize,
+    /// The wall-clock time of running the circuits on the backend and evaluating the registers in seconds.
+    pub elapsed_seconds: f64,
+    /// The metadata supplied by the backend, see [crate::backends::EvaluatingBackend::metadata].
+    pub backend_metadata: HashMap<String, String>,
+}
+
+impl RunResult {
+    /// Creates a new RunResult of a run of a measurement.
+    ///
+    /// The number of circuits and the total number of shots are determined from the measurement.
+    ///
+    /// # Arguments
+    ///
+    /// * `measurement` - The measurement with substituted parameters that has been run.
+    /// * `parameter_names` - The names of the input parameters.
+    /// * `parameters` - The values of the input parameters in order of `parameter_names`.
+    /// * `expectation_values` - The evaluated expectation values.
+    /// * `registers` - The output registers of the run (None when they are not kept).
+    /// * `elapsed_seconds` - The wall-clock time of the run in seconds.
+    /// * `backend_metadata` - The metadata supplied by the backend.
+    ///
+    /// # Returns
+    ///
+    /// * `Self` - The new RunResult.
+    pub fn from_run<M>(
+        measurement: &M,
+        parameter_names: Vec<String>,
+        parameters: Vec<f64>,
+        expectation_values: Option<HashMap<String, f64>>,
+        registers: Option<OutputRegisters>,
+        elapsed_seconds: f64,
+        backend_metadata: HashMap<String, String>,
+    ) -> Self
+    where
+        M: Measure,
+    {
+        let constant_circuit = measurement.constant_circuit().as_ref();
+        let total_shots = measurement
+            .circuits()
+            .map(|circuit| match constant_circuit {
+                Some(x) => number_shots(x.iter().chain(circuit.iter())),
+                None => number_shots(circuit.iter()),
+            })
+            .sum();
+        Self {
+            expectation_values,
+            registers,
+            parameter_names,
+            parameters,
+            number_circuits: measurement.circuits().count(),
+            total_shots,
+            elapsed_seconds,
+            backend_metadata,
+        }
+    }
+}
+
+/// Returns the measurement with the free input parameters replaced by the given parameters.
+///
+/// # Arguments
+///
+/// * `measurement` - The measurement with symbolic parameters.
+/// * `input_parameter_names` - The names of the free input parameters.
+/// * `parameters` - The values of the parameters in order of `input_parameter_names`.
+///
+/// # Returns
+///
+/// * `Ok(M)` - The measurement with the parameters substituted.
+/// * `Err(RoqoqoBackendError::GenericError)` - The number of parameters is wrong.
+/// * `Err(RoqoqoBackendError::RoqoqoError)` - The substitution failed.
+fn substitute_measurement<M>(
+    measurement: &M,
+    input_parameter_names: &[String],
+    parameters: &[f64],
+) -> Result<M, RoqoqoBackendError>
+where
+    M: Measure,
+{
+    if parameters.len() != input_parameter_names.len() {
+        return Err(RoqoqoBackendError::GenericError {
+            msg: format!(
+                "Wrong number of parameters {} parameters expected {} parameters given",
+                input_parameter_names.len(),
+                parameters.len()
+            ),
+        });
+    };
+    let substituted_parameters: HashMap<String, f64> = input_parameter_names
+        .iter()
+        .zip(parameters.iter())
+        .map(|(key, value)| (key.clone(), *value))
+        .collect();
+    Ok(measurement.substitute_parameters(substituted_parameters)?)
+}
+
+/// Runs a measurement with substituted parameters and returns the [RunResult].
+fn run_measurement_with_metadata<T, M>(
+    backend: &T,
+    measurement: &M,
+    input_parameter_names: &[String],
+    parameters: &[f64],
+    keep_registers: bool,
+) -> Result<RunResult, RoqoqoBackendError>
+where
+    T: EvaluatingBackend,
+    M: MeasureExpectationValues,
+{
+    let substituted_measurement =
+        substitute_measurement(measurement, input_parameter_names, parameters)?;
+    let start = Instant::now();
+    let (bit_registers, float_registers, complex_registers) =
+        backend.run_measurement_registers(&substituted_measurement)?;
+    let registers = if keep_registers {
+        Some(OutputRegisters {
+            bit_registers: bit_registers.clone(),
+            float_registers: float_registers.clone(),
+            complex_registers: complex_registers.clone(),
+        })
+    } else {
+        None
+    };
+    let expectation_values =
+        substituted_measurement.evaluate(bit_registers, float_registers, complex_registers)?;
+    Ok(RunResult::from_run(
+        &substituted_measurement,
+        input_parameter_names.to_vec(),
+        parameters.to_vec(),
+        expectation_values,
+        registers,
+        start.elapsed().as_secs_f64(),
+        backend.metadata(),
+    ))
+}
+
+/// Returns the number of shots of a circuit, see [RunResult::total_shots].
+fn number_shots<'a>(circuit: impl Iterator<Item = &'a Operation>) -> usize {
+    let mut measured = false;
+    let mut shots = 0;
+    for operation in circuit {
+        match operation {
+            Operation::PragmaSetNumberOfMeasurements(op) => {
+                shots = shots.max(*op.number_measurements())
+            }
+            Operation::PragmaRepeatedMeasurement(op) => {
+                shots = shots.max(*op.number_measurements())
+            }
+            Operation::MeasureQubit(_) => measured = true,
+            _ => (),
+        }
+    }
+    if shots == 0 && measured {
+        1
+    } else {
+        shots
+    }
+}
+
+/// Evaluates an expression for the measurement and input parameter names of every QuantumProgram variant.
+///
+/// All measurements have public `constant_circuit` and `circuits` fields, which can be accessed in the expression.
+macro_rules! match_measurement {
+    ($program:expr, $measurement:ident, $input_parameter_names:ident => $body:expr) => {
+        match $program {
+            QuantumProgram::BasisRotation {
+                measurement: $measurement,
+                input_parameter_names: $input_parameter_names,
+            } => $body,
+            QuantumProgram::CheatedBasisRotation {
+                measurement: $measurement,
+                input_parameter_names: $input_parameter_names,
+            } => $body,
+            QuantumProgram::Cheated {
+                measurement: $measurement,
+                input_parameter_names: $input_parameter_names,
+            } => $body,
+            QuantumProgram::ClassicalRegister {
+                measurement: $measurement,
+                input_parameter_names: $input_parameter_names,
+            } => $body,
+            QuantumProgram::ClassicalShadows {
+                measurement: $measurement,
+                input_parameter_names: $input_parameter_names,
+            } => $body,
+            QuantumProgram::Histogram {
+                measurement: $measurement,
+                input_parameter_names: $input_parameter_names,
+            } => $body,
+        }
+    };
+}
+
+impl QuantumProgram {
+    /// Returns all operations of the QuantumProgram a backend with the given capabilities cannot run.
+    ///
+    /// Every circuit of the measurement is checked as it is run on the backend, i.e. after the constant circuit.
+    /// Incompatible operations of the constant circuit are reported once with `circuit` set to None.
+    ///
+    /// Arguments:
+    ///
+    /// * `capabilities` - The capabilities of the backend, e.g. from [crate::backends::EvaluatingBackend::capabilities].
+    pub fn check_capabilities(&self, capabilities: &BackendCapabilities) -> Vec<Incompatibility> {
+        let (constant_circuit, circuits) = self.measurement_circuits();
+        let mut incompatibilities: Vec<Incompatibility> = Vec::new();
+        let mut add_run = |run: Vec<(Option<usize>, &Circuit)>| {
+            for incompatibility in capabilities.check_circuits(&run) {
+                if !incompatibilities.contains(&incompatibility) {
+                    incompatibilities.push(incompatibility);
+                }
+            }
+        };
+        if circuits.is_empty() {
+            add_run(constant_circuit.map(|c| (None, c)).into_iter().collect());
+        }
+        for (index, circuit) in circuits.into_iter().enumerate() {
+            add_run(
+                constant_circuit
+                    .map(|c| (None, c))
+                    .into_iter()
+                    .chain(std::iter::once((Some(index), circuit)))
+                    .collect(),
+            );
+        }
+        incompatibilities
+            .sort_by_key(|incompatibility| (incompatibility.circuit, incompatibility.index));
+        incompatibilities
+    }
+
+    /// Checks before running the QuantumProgram that a backend can run all its operations.
+    ///
+    /// Backends that do not describe their capabilities ([crate::backends::EvaluatingBackend::capabilities]
+    /// returns None) pass the check.
+    ///
+    /// Arguments:
+    ///
+    /// * `backend` - The backend the program is going to be executed on.
+    ///
+    /// Returns:
+    ///
+    /// * `Ok(())` - The backend can run the QuantumProgram.
+    /// * `Err(RoqoqoBackendError::GenericError)` - The backend cannot run the QuantumProgram, the message lists every incompatible operation.
+    pub fn preflight<T>(&self, backend: &T) -> Result<(), RoqoqoBackendError>
+    where
+        T: EvaluatingBackend,
+    {
+        let capabilities = match backend.capabilities() {
+            Some(capabilities) => capabilities,
+            None => return Ok(()),
+        };
+        let incompatibilities = self.check_capabilities(&capabilities);
+        if incompatibilities.is_empty() {
+            return Ok(());
+        }
+        Err(RoqoqoBackendError::GenericError {
+            msg: format!(
+                "QuantumProgram cannot be run on the backend:\n{}",
+                incompatibilities
+                    .iter()
+                    .map(|incompatibility| incompatibility.to_string())
+                    .collect::<Vec<String>>()
+                    .join("\n")
+            ),
+        })
+    }
+
+    /// Returns the constant circuit and the circuits of the measurement of the QuantumProgram.
+    fn measurement_circuits(&self) -> (Option<&Circuit>, Vec<&Circuit>) {
+        match_measurement!(self, measurement, _input_parameter_names => (
+            measurement.constant_circuit.as_ref(),
+            measurement.circuits.iter().collect(),
+        ))
+    }
+}
+
+/// Mismatch between the input parameters of a [QuantumProgram] and the symbols used in its circuits.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "json_schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub enum SymbolWarning {
+    /// Input parameter that is not used by any circuit of the measurement.
+    UnusedParameter(String),
+    /// Symbol used in a circuit of the measurement that is not an input parameter.
+    UndeclaredSymbol(String),
+}
+
+impl Display for SymbolWarning {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SymbolWarning::UnusedParameter(name) => write!(
+                f,
+                "Input parameter {} is not used in the circuits of the measurement",
+                name
+            ),
+            SymbolWarning::UndeclaredSymbol(name) => write!(
+                f,
+                "Symbol {} is used in the circuits of the measurement but is not an input parameter",
+                name
+            ),
+        }
+    }
+}
+
+/// Value assigned to unset symbols while searching the free symbols of a circuit.
+///
+/// Only used to continue the substitution after a missing symbol has been found, the value itself is discarded.
+const SYMBOL_PROBE_VALUE: f64 = 0.318_309_886;
+
+/// Returns the free symbols of a circuit.
+///
+/// Symbols set by an [crate::operations::InputSymbolic] definition of the circuit are not free.
+fn circuit_free_symbols(circuit: &Circuit) -> Result<BTreeSet<String>, RoqoqoError> {
+    let mut calculator = Calculator::new();
+    let mut symbols: BTreeSet<String> = BTreeSet::new();
+    loop {
+        match circuit.substitute_parameters(&mut calculator) {
+            Ok(_) => return Ok(symbols),
+            Err(RoqoqoError::CalculatorError(CalculatorError::VariableNotSet { name })) => {
+                // A symbol that is still missing after being set cannot be resolved by further iterations
+                if !symbols.insert(name.clone()) {
+                    return Err(RoqoqoError::CalculatorError(
+                        CalculatorError::VariableNotSet { name },
+                    ));
+                }
+                calculator.set_variable(&name, SYMBOL_PROBE_VALUE);
+            }
+            Err(err) => return Err(err),
+        }
+    }
+}
+
+impl QuantumProgram {
+    /// Runs the QuantumProgram with parameters given by name and returns expectation values.
+    ///
+    /// Arguments:
+    ///
+    /// * `backend` - The backend the program is executed on.
+    /// * `parameters` - Values of the input parameters with the name of the parameter as key.
+    ///
+    /// Returns:
+    ///
+    /// * `Ok(Option<HashMap<String, f64>>)` - The expectation values of the run, see [QuantumProgram::run].
+    /// * `Err(RoqoqoBackendError::RoqoqoError)` - An input parameter is missing or an unknown name is given.
+    pub fn run_named<T>(
+        &self,
+        backend: T,
+        parameters: &HashMap<String, f64>,
+    ) -> Result<Option<HashMap<String, f64>>, RoqoqoBackendError>
+    where
+        T: EvaluatingBackend,
+    {
+        let values = self.parameter_values(parameters)?;
+        self.run(backend, &values)
+    }
+
+    /// Orders parameters given by name like the input parameters of the QuantumProgram.
+    ///
+    /// Arguments:
+    ///
+    /// * `parameters` - Values of the input parameters with the name of the parameter as key.
+    ///
+    /// Returns:
+    ///
+    /// * `Ok(Vec<f64>)` - The values in order of `input_parameter_names`.
+    /// * `Err(RoqoqoError::GenericError)` - An input parameter is missing or an unknown name is given.
+    pub fn parameter_values(
+        &self,
+        parameters: &HashMap<String, f64>,
+    ) -> Result<Vec<f64>, RoqoqoError> {
+        let input_parameter_names = self.input_parameter_names();
+        self.check_parameter_names(parameters)?;
+        let missing: Vec<&str> = input_parameter_names
+            .iter()
+            .filter(|name| !parameters.contains_key(*name))
+            .map(|name| name.as_str())
+            .collect();
+        if !missing.is_empty() {
+            return Err(RoqoqoError::GenericError {
+                msg: format!("Missing values for input parameters {}", missing.join(", ")),
+            });
+        }
+        Ok(input_parameter_names
+            .iter()
+            .map(|name| parameters[name])
+            .collect())
+    }
+
+    /// Replaces a subset of the input parameters of the QuantumProgram with fixed values.
+    ///
+    /// The bound parameters are removed from `input_parameter_names` and set by
+    /// [crate::operations::InputSymbolic] definitions in the constant circuit and all circuits of the measurement.
+    /// The remaining input parameters keep their order.
+    ///
+    /// Arguments:
+    ///
+    /// * `parameters` - Values of the bound input parameters with the name of the parameter as key.
+    ///
+    /// Returns:
+    ///
+    /// * `Ok(QuantumProgram)` - The QuantumProgram with the remaining input parameters.
+    /// * `Err(RoqoqoError::GenericError)` - A name is not an input parameter of the QuantumProgram.
+    pub fn bind_parameters(
+        &self,
+        parameters: &HashMap<String, f64>,
+    ) -> Result<QuantumProgram, RoqoqoError> {
+        self.check_parameter_names(parameters)?;
+        let mut bound: Vec<(&String, &f64)> = parameters.iter().collect();
+        bound.sort_by(|a, b| a.0.cmp(b.0));
+        let mut program = self.clone();
+        let (input_parameter_names, circuits) = program.parameters_and_circuits_mut();
+        input_parameter_names.retain(|name| !parameters.contains_key(name));
+        for circuit in circuits {
+            for (name, value) in bound.iter() {
+                circuit.add_operation(InputSymbolic::new((*name).clone(), **value));
+            }
+        }
+        Ok(program)
+    }
+
+    /// Returns the symbols that are used in the circuits of the measurement.
+    ///
+    /// Every circuit is searched as it is substituted when running the QuantumProgram,
+    /// symbols set by [crate::operations::InputSymbolic] definitions are not included.
+    ///
+    /// Returns:
+    ///
+    /// * `Ok(BTreeSet<String>)` - The names of the free symbols.
+    /// * `Err(RoqoqoError)` - A circuit cannot be substituted independent of the values of its symbols.
+    pub fn free_symbols(&self) -> Result<BTreeSet<String>, RoqoqoError> {
+        let (constant_circuit, circuits) = self.measurement_circuits();
+        let mut symbols: BTreeSet<String> = BTreeSet::new();
+        for circuit in constant_circuit.into_iter().chain(circuits.into_iter()) {
+            symbols.extend(circuit_free_symbols(circuit)?);
+        }
+        Ok(symbols)
+    }
+
+    /// Compares the input parameters of the QuantumProgram with the symbols used in its circuits.
+    ///
+    /// Unused input parameters are harmless, undeclared symbols make every run of the QuantumProgram fail.
+    ///
+    /// Returns:
+    ///
+    /// * `Ok(Vec<SymbolWarning>)` - The unused input parameters followed by the undeclared symbols (empty if they match).
+    /// * `Err(RoqoqoError)` - A circuit cannot be substituted independent of the values of its symbols.
+    pub fn check_symbols(&self) -> Result<Vec<SymbolWarning>, RoqoqoError> {
+        let symbols = self.free_symbols()?;
+        let input_parameter_names = self.input_parameter_names();
+        let mut warnings: Vec<SymbolWarning> = input_parameter_names
+            .iter()
+            .filter(|name| !symbols.contains(*name))
+            .map(|name| SymbolWarning::UnusedParameter(name.clone()))
+            .collect();
+        warnings.extend(
+            symbols
+                .into_iter()
+                .filter(|symbol| !input_parameter_names.contains(symbol))
+                .map(SymbolWarning::UndeclaredSymbol),
+        );
+        Ok(warnings)
+    }
+
+    /// Returns an error listing all names that are not input parameters of the QuantumProgram.
+    fn check_parameter_names(&self, parameters: &HashMap<String, f64>) -> Result<(), RoqoqoError> {
+        let input_parameter_names = self.input_parameter_names();
+        let mut unknown: Vec<&str> = parameters
+            .keys()
+            .filter(|name| !input_parameter_names.contains(*name))
+            .map(|name| name.as_str())
+            .collect();
+        if unknown.is_empty() {
+            return Ok(());
+        }
+        unknown.sort_unstable();
+        Err(RoqoqoError::GenericError {
+            msg: format!(
+                "Names {} are not input parameters of the QuantumProgram",
+                unknown.join(", ")
+            ),
+        })
+    }
+
+    /// Returns the input parameter names and all circuits of the measurement for modification.
+    fn parameters_and_circuits_mut(&mut self) -> (&mut Vec<String>, Vec<&mut Circuit>) {
+        match_measurement!(self, measurement, input_parameter_names => (
+            input_parameter_names,
+            measurement
+                .constant_circuit
+                .iter_mut()
+                .chain(measurement.circuits.iter_mut())
+                .collect(),
+        ))
+    }
+}
+
+impl QuantumProgram {
+    /// Submits the QuantumProgram to a job based backend.
+    ///
+    /// Replaces the free parameters of the quantum program with the given parameters passed in the same
+    /// order as the parameters listed in `input_parameter_names` and submits all circuits of the measurement
+    /// as a single job. The returned [QuantumProgramJob] is used to poll the job and to evaluate its result.
+    ///
+    /// Arguments:
+    ///
+    /// * `backend` - The backend the program is submitted to.
+    /// * `parameters` - List of float ([f64]) parameters of the function call in order of `input_parameter_names`
+    pub fn submit<T>(
+        &self,
+        backend: &mut T,
+        parameters: &[f64],
+    ) -> Result<QuantumProgramJob, RoqoqoBackendError>
+    where
+        T: JobBackend,
+    {
+        let program = self.substitute_input_parameters(parameters)?;
+        let job_id = match &program {
+            QuantumProgram::BasisRotation { measurement, .. } => {
+                backend.submit_measurement(measurement)?
+            }
+            QuantumProgram::CheatedBasisRotation { measurement, .. } => {
+                backend.submit_measurement(measurement)?
+            }
+            QuantumProgram::Cheated { measurement, .. } => {
+                backend.submit_measurement(measurement)?
+            }
+            QuantumProgram::ClassicalRegister { measurement, .. } => {
+                backend.submit_measurement(measurement)?
+            }
+            QuantumProgram::ClassicalShadows { measurement, .. } => {
+                backend.submit_measurement(measurement)?
+            }
+            QuantumProgram::Histogram { measurement, .. } => {
+                backend.submit_measurement(measurement)?
+            }
+        };
+        Ok(QuantumProgramJob { job_id, program })
+    }
+
+    /// Returns the QuantumProgram with the free input parameters replaced by the given parameters.
+    fn substitute_input_parameters(
+        &self,
+        parameters: &[f64],
+    ) -> Result<QuantumProgram, RoqoqoBackendError> {
+        Ok(match self {
+            QuantumProgram::BasisRotation {
+                measurement,
+                input_parameter_names,
+            } => QuantumProgram::BasisRotation {
+                measurement: substitute_measurement(
+                    measurement,
+                    input_parameter_names,
+                    parameters,
+                )?,
+                input_parameter_names: Vec::new(),
+            },
+            QuantumProgram::CheatedBasisRotation {
+                measurement,
+                input_parameter_names,
+            } => QuantumProgram::CheatedBasisRotation {
+                measurement: substitute_measurement(
+                    measurement,
+                    input_parameter_names,
+                    parameters,
+                )?,
+                input_parameter_names: Vec::new(),
+            },
+            QuantumProgram::Cheated {
+                measurement,
+                input_parameter_names,
+            } => QuantumProgram::Cheated {
+                measurement: substitute_measurement(
+                    measurement,
+                    input_parameter_names,
+                    parameters,
+                )?,
+                input_parameter_names: Vec::new(),
+            },
+            QuantumProgram::ClassicalRegister {
+                measurement,
+                input_parameter_names,
+            } => QuantumProgram::ClassicalRegister {
+                measurement: substitute_measurement(
+                    measurement,
+                    input_parameter_names,
+                    parameters,
+                )?,
+                input_parameter_names: Vec::new(),
+            },
+            QuantumProgram::ClassicalShadows {
+                measurement,
+                input_parameter_names,
+            } => QuantumProgram::ClassicalShadows {
+                measurement: substitute_measurement(
+                    measurement,
+                    input_parameter_names,
+                    parameters,
+                )?,
+                input_parameter_names: Vec::new(),
+            },
+            QuantumProgram::Histogram {
+                measurement,
+                input_parameter_names,
+            } => QuantumProgram::Histogram {
+                measurement: substitute_measurement(
+                    measurement,
+                    input_parameter_names,
+                    parameters,
+                )?,
+                input_parameter_names: Vec::new(),
+            },
+        })
+    }
+}
+
+/// Handle of a [QuantumProgram] submitted to a [crate::backends::JobBackend].
+///
+/// Contains the identifier of the job and the submitted quantum program with all free parameters
+/// replaced, which is used to evaluate the output registers of the finished job.
+#[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct QuantumProgramJob {
+    /// The identifier of the job on the backend.
+    pub job_id: JobId,
+    /// The submitted quantum program with all free parameters replaced.
+    pub program: QuantumProgram,
+}
+
+impl QuantumProgramJob {
+    /// Returns the current status of the job.
+    ///
+    /// Arguments:
+    ///
+    /// * `backend` - The backend the program has been submitted to.
+    pub fn status<T>(&self, backend: &mut T) -> Result<JobStatus, RoqoqoBackendError>
+    where
+        T: JobBackend,
+    {
+        backend.poll(self.job_id)
+    }
+
+    /// Cancels the job.
+    ///
+    /// Arguments:
+    ///
+    /// * `backend` - The backend the program has been submitted to.
+    pub fn cancel<T>(&self, backend: &mut T) -> Result<(), RoqoqoBackendError>
+    where
+        T: JobBackend,
+    {
+        backend.cancel(self.job_id)
+    }
+
+    /// Fetches the result of the finished job and returns expectation values.
+    ///
+    /// Equivalent of [QuantumProgram::run] for job based backends.
+    ///
+    /// Arguments:
+    ///
+    /// * `backend` - The backend the program has been submitted to.
+    pub fn result<T>(
+        &self,
+        backend: &mut T,
+    ) -> Result<Option<HashMap<String, f64>>, RoqoqoBackendError>
+    where
+        T: JobBackend,
+    {
+        match &self.program {
+            QuantumProgram::BasisRotation { measurement, .. } => {
+                self.evaluate_result(backend, measurement)
+            }
+            QuantumProgram::CheatedBasisRotation { measurement, .. } => {
+                self.evaluate_result(backend, measurement)
+            }
+            QuantumProgram::Cheated { measurement, .. } => self.evaluate_result(backend, measurement),
+            QuantumProgram::ClassicalShadows { measurement, .. } => {
+                self.evaluate_result(backend, measurement)
+            }
+            QuantumProgram::Histogram { .. } => Err(RoqoqoBackendError::GenericError {
+                msg: "The result of a quantum programm returning histograms cannot be fetched by `result` use `result_histogram` instead".to_string(),
+            }),
+            _ => Err(RoqoqoBackendError::GenericError {
+                msg: "The result of a quantum programm returning classical registeres cannot be fetched by `result` use `result_registers` instead".to_string(),
+            }),
+        }
+    }
+
+    /// Fetches the result of the finished job and returns the classical registers.
+    ///
+    /// Equivalent of [QuantumProgram::run_registers] for job based backends.
+    ///
+    /// Arguments:
+    ///
+    /// * `backend` - The backend the program has been submitted to.
+    pub fn result_registers<T>(&self, backend: &mut T) -> RegisterResult
+    where
+        T: JobBackend,
+    {
+        match &self.program {
+            QuantumProgram::ClassicalRegister { .. } | QuantumProgram::Histogram { .. } => {
+                backend.fetch_result(self.job_id)
+            }
+            _ => Err(RoqoqoBackendError::GenericError {
+                msg: "The result of a quantum programm returning expectation values cannot be fetched by `result_registers` use `result` instead".to_string(),
+            }),
+        }
+    }
+
+    /// Fetches the result of the finished job and returns bitstring histograms and marginal probabilities.
+    ///
+    /// Equivalent of [QuantumProgram::run_histogram] for job based backends.
+    ///
+    /// Arguments:
+    ///
+    /// * `backend` - The backend the program has been submitted to.
+    pub fn result_histogram<T>(
+        &self,
+        backend: &mut T,
+    ) -> Result<measurements::HistogramResult, RoqoqoBackendError>
+    where
+        T: JobBackend,
+    {
+        match &self.program {
+            QuantumProgram::Histogram { measurement, .. } => {
+                let (bit_registers, float_registers, complex_registers) =
+                    backend.fetch_result(self.job_id)?;
+                Ok(measurement.evaluate(bit_registers, float_registers, complex_registers)?)
+            }
+            _ => Err(RoqoqoBackendError::GenericError {
+                msg: "Only the result of a quantum programm with a histogram measurement can be fetched by `result_histogram`".to_string(),
+            }),
+        }
+    }
+
+    /// Fetches the output registers of the job and evaluates them with the measurement.
+    fn evaluate_result<T, M>(
+        &self,
+        backend: &mut T,
+        measurement: &M,
+    ) -> Result<Option<HashMap<String, f64>>, RoqoqoBackendError>
+    where
+        T: JobBackend,
+        M: MeasureExpectationValues,
+    {
+        let (bit_registers, float_registers, complex_registers) =
+            backend.fetch_result(self.job_id)?;
+        Ok(measurement.evaluate(bit_registers, float_registers, complex_registers)?)
+    }
 }
 
 /// Implements the Display trait for QuantumProgram.
@@ -159,6 +1394,12 @@ impl Display for QuantumProgram {
             QuantumProgram::ClassicalRegister { .. } => {
                 s.push_str("QuantumProgram::ClassicalRegister");
             }
+            QuantumProgram::ClassicalShadows { .. } => {
+                s.push_str("QuantumProgram::ClassicalShadows");
+            }
+            QuantumProgram::Histogram { .. } => {
+                s.push_str("QuantumProgram::Histogram");
+            }
         }
 
         write!(f, "{}", s)
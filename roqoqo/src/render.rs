@@ -0,0 +1,833 @@
+// Copyright © 2021 HQS Quantum Simulations GmbH. All Rights Reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except
+// in compliance with the License. You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the
+// License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either
+// express or implied. See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Rendering of roqoqo circuits to publication-quality figures.
+//!
+//! Provides renderers that translate a [crate::Circuit] into
+//! * quantikz LaTeX source ([circuit_to_quantikz]) that can be included in a document using the `quantikz` package,
+//! * a standalone SVG image ([circuit_to_svg]).
+//!
+//! Both renderers use the same layout. Gates are placed in the leftmost column where all the qubits
+//! they span are free. Symbolic parameters are shown as their [qoqo_calculator::CalculatorFloat] expressions.
+//! Optionally, regions enclosed by [crate::operations::PragmaStartDecompositionBlock] and
+//! [crate::operations::PragmaStopDecompositionBlock] are drawn as labelled boxes.
+//!
+//! # Example
+//!
+//! ```
+//! use roqoqo::Circuit;
+//! use roqoqo::operations::{CNOT, Hadamard, MeasureQubit, DefinitionBit};
+//! use roqoqo::render::{circuit_to_quantikz, circuit_to_svg, RenderOptions};
+//!
+//! let mut circuit = Circuit::new();
+//! circuit += DefinitionBit::new("ro".to_string(), 2, true);
+//! circuit += Hadamard::new(0);
+//! circuit += CNOT::new(0, 1);
+//! circuit += MeasureQubit::new(0, "ro".to_string(), 0);
+//! circuit += MeasureQubit::new(1, "ro".to_string(), 1);
+//!
+//! let options = RenderOptions::new();
+//! let latex = circuit_to_quantikz(&circuit, &options).unwrap();
+//! assert!(latex.contains("\\targ{}"));
+//! let svg = circuit_to_svg(&circuit, &options).unwrap();
+//! assert!(svg.starts_with("<svg"));
+//! ```
+
+use crate::operations::{
+    InvolveQubits, InvolvedQubits, Operate, OperateMultiQubit, OperateSingleQubit, OperateTwoQubit,
+    Operation, Rotate, Rotation,
+};
+use crate::{Circuit, RoqoqoError};
+use qoqo_calculator::CalculatorFloat;
+use std::collections::HashMap;
+use std::convert::TryFrom;
+
+/// Width of the column containing the qubit labels in SVG output (in pixels).
+const SVG_LABEL_WIDTH: f64 = 60.0;
+/// Minimal width of a gate column in SVG output (in pixels).
+const SVG_MIN_COLUMN_WIDTH: f64 = 50.0;
+/// Approximate width of a single character in SVG output (in pixels).
+const SVG_CHARACTER_WIDTH: f64 = 7.5;
+/// Height of a qubit row in SVG output (in pixels).
+const SVG_ROW_HEIGHT: f64 = 50.0;
+/// Half of the height of a single qubit gate box in SVG output (in pixels).
+const SVG_GATE_HALF_HEIGHT: f64 = 16.0;
+
+/// Options controlling how a [crate::Circuit] is rendered.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RenderOptions {
+    /// Custom labels of the qubits. Qubits without a custom label are labelled by their index.
+    ///
+    /// The labels are inserted verbatim, in LaTeX output they are placed inside `\lstick{}`.
+    pub qubit_labels: HashMap<usize, String>,
+    /// Whether qubit labels are drawn at all.
+    pub show_qubit_labels: bool,
+    /// Minimal number of qubits that are drawn. The number of qubits is otherwise inferred from the circuit.
+    pub number_qubits: Option<usize>,
+    /// Whether regions between PragmaStartDecompositionBlock and PragmaStopDecompositionBlock are drawn as labelled boxes.
+    pub group_decomposition_blocks: bool,
+    /// Label of the boxes drawn around decomposition blocks.
+    pub decomposition_block_label: String,
+    /// Whether PRAGMA operations (apart from measurements and decomposition blocks) are drawn as boxes.
+    pub show_pragmas: bool,
+    /// Number of decimal places used when printing float parameters.
+    pub float_precision: usize,
+}
+
+impl Default for RenderOptions {
+    /// Creates a default (here, new) instance of RenderOptions.
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RenderOptions {
+    /// Creates new RenderOptions.
+    ///
+    /// # Returns
+    ///
+    /// * `Self` - The new RenderOptions labelling qubits by index, grouping decomposition blocks
+    ///            and hiding PRAGMA operations.
+    pub fn new() -> Self {
+        Self {
+            qubit_labels: HashMap::new(),
+            show_qubit_labels: true,
+            number_qubits: None,
+            group_decomposition_blocks: true,
+            decomposition_block_label: "Decomposition".to_string(),
+            show_pragmas: false,
+            float_precision: 3,
+        }
+    }
+}
+
+/// Renders a circuit as quantikz LaTeX source.
+///
+/// The returned string contains a `quantikz` environment that can be included in a document
+/// loading the `quantikz` package.
+///
+/// # Arguments
+///
+/// * `circuit` - The Circuit that is rendered.
+/// * `options` - The options controlling the rendering.
+///
+/// # Returns
+///
+/// * `Ok(String)` - The LaTeX source of the circuit.
+/// * `Err(RoqoqoError::GenericError)` - The decomposition blocks in the circuit are not balanced.
+pub fn circuit_to_quantikz(
+    circuit: &Circuit,
+    options: &RenderOptions,
+) -> Result<String, RoqoqoError> {
+    let layout = CircuitLayout::new(circuit, options)?;
+    let mut cells: Vec<Vec<String>> =
+        vec![vec!["\\qw".to_string(); layout.number_columns]; layout.number_qubits];
+    for (column, element) in layout.elements.iter() {
+        let column = *column;
+        match element {
+            LayoutElement::Gate { qubits, label } => {
+                let (min_qubit, max_qubit) = qubit_range(qubits);
+                let label = label.to_latex(options.float_precision);
+                cells[min_qubit][column] = if min_qubit == max_qubit {
+                    format!("\\gate{{{}}}", label)
+                } else {
+                    format!("\\gate[wires={}]{{{}}}", max_qubit - min_qubit + 1, label)
+                };
+            }
+            LayoutElement::Controlled {
+                control,
+                target,
+                target_symbol,
+            } => {
+                cells[*control][column] =
+                    format!("\\ctrl{{{}}}", *target as isize - *control as isize);
+                cells[*target][column] = match target_symbol {
+                    TargetSymbol::Not => "\\targ{}".to_string(),
+                    TargetSymbol::Dot => "\\control{}".to_string(),
+                    TargetSymbol::Gate(label) => {
+                        format!("\\gate{{{}}}", label.to_latex(options.float_precision))
+                    }
+                };
+            }
+            LayoutElement::Swap { first, second } => {
+                cells[*first][column] = format!("\\swap{{{}}}", *second as isize - *first as isize);
+                cells[*second][column] = "\\targX{}".to_string();
+            }
+            LayoutElement::Measure { qubits } => {
+                for qubit in qubits {
+                    cells[*qubit][column] = "\\meter{}".to_string();
+                }
+            }
+        }
+    }
+    for group in layout.groups.iter() {
+        let cell = &mut cells[group.min_qubit][group.first_column];
+        cell.push_str(&format!(
+            " \\gategroup[wires={},steps={},style={{dashed,rounded corners}},label style={{label position=above}}]{{{}}}",
+            group.max_qubit - group.min_qubit + 1,
+            group.last_column - group.first_column + 1,
+            escape_latex(&group.label)
+        ));
+    }
+
+    let mut rows: Vec<String> = Vec::with_capacity(layout.number_qubits);
+    for (qubit, row) in cells.into_iter().enumerate() {
+        let mut line = String::new();
+        if options.show_qubit_labels {
+            let label = match options.qubit_labels.get(&qubit) {
+                Some(label) => label.clone(),
+                None => format!("$q_{{{}}}$", qubit),
+            };
+            line.push_str(&format!("\\lstick{{{}}} & ", label));
+        }
+        for cell in row {
+            line.push_str(&cell);
+            line.push_str(" & ");
+        }
+        line.push_str("\\qw");
+        rows.push(line);
+    }
+    Ok(format!(
+        "\\begin{{quantikz}}\n{}\n\\end{{quantikz}}\n",
+        rows.join(" \\\\\n")
+    ))
+}
+
+/// Renders a circuit as a standalone SVG image.
+///
+/// # Arguments
+///
+/// * `circuit` - The Circuit that is rendered.
+/// * `options` - The options controlling the rendering.
+///
+/// # Returns
+///
+/// * `Ok(String)` - The SVG document of the circuit.
+/// * `Err(RoqoqoError::GenericError)` - The decomposition blocks in the circuit are not balanced.
+pub fn circuit_to_svg(circuit: &Circuit, options: &RenderOptions) -> Result<String, RoqoqoError> {
+    let layout = CircuitLayout::new(circuit, options)?;
+    let precision = options.float_precision;
+
+    // Column widths are determined by the longest label in the column
+    let mut column_widths: Vec<f64> = vec![SVG_MIN_COLUMN_WIDTH; layout.number_columns];
+    for (column, element) in layout.elements.iter() {
+        let label_length = match element {
+            LayoutElement::Gate { label, .. } => label.to_text(precision).chars().count(),
+            LayoutElement::Controlled {
+                target_symbol: TargetSymbol::Gate(label),
+                ..
+            } => label.to_text(precision).chars().count(),
+            _ => 0,
+        };
+        let width = label_length as f64 * SVG_CHARACTER_WIDTH + 26.0;
+        if width > column_widths[*column] {
+            column_widths[*column] = width;
+        }
+    }
+    let left = if options.show_qubit_labels {
+        SVG_LABEL_WIDTH
+    } else {
+        10.0
+    };
+    let mut column_left: Vec<f64> = Vec::with_capacity(layout.number_columns);
+    let mut position = left;
+    for width in column_widths.iter() {
+        column_left.push(position);
+        position += width;
+    }
+    let width = position + 20.0;
+    let height = layout.number_qubits as f64 * SVG_ROW_HEIGHT + 20.0;
+    let x_center = |column: usize| column_left[column] + column_widths[column] / 2.0;
+    let y_center = |qubit: usize| 20.0 + qubit as f64 * SVG_ROW_HEIGHT + SVG_ROW_HEIGHT / 2.0;
+
+    let mut svg = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w}\" height=\"{h}\" viewBox=\"0 0 {w} {h}\">\n",
+        w = width,
+        h = height
+    );
+    svg.push_str("<rect width=\"100%\" height=\"100%\" fill=\"white\"/>\n");
+    svg.push_str(
+        "<g stroke=\"black\" stroke-width=\"1\" font-family=\"serif\" font-size=\"14\">\n",
+    );
+    // Qubit wires and labels
+    for qubit in 0..layout.number_qubits {
+        let y = y_center(qubit);
+        svg.push_str(&format!(
+            "<line x1=\"{}\" y1=\"{}\" x2=\"{}\" y2=\"{}\"/>\n",
+            left - 5.0,
+            y,
+            width - 10.0,
+            y
+        ));
+        if options.show_qubit_labels {
+            let label = match options.qubit_labels.get(&qubit) {
+                Some(label) => label.clone(),
+                None => format!("q_{}", qubit),
+            };
+            svg.push_str(&svg_text(left - 10.0, y, "end", &label));
+        }
+    }
+    // Decomposition blocks are drawn below the gates
+    for group in layout.groups.iter() {
+        let x = column_left[group.first_column] + 2.0;
+        let x_end = column_left[group.last_column] + column_widths[group.last_column] - 2.0;
+        let y = y_center(group.min_qubit) - SVG_GATE_HALF_HEIGHT - 6.0;
+        let y_end = y_center(group.max_qubit) + SVG_GATE_HALF_HEIGHT + 6.0;
+        svg.push_str(&format!(
+            "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" rx=\"4\" fill=\"none\" stroke-dasharray=\"4 3\"/>\n",
+            x,
+            y,
+            x_end - x,
+            y_end - y
+        ));
+        svg.push_str(&svg_text(x + 2.0, y - 6.0, "start", &group.label));
+    }
+    for (column, element) in layout.elements.iter() {
+        let x = x_center(*column);
+        let gate_width = column_widths[*column] - 10.0;
+        match element {
+            LayoutElement::Gate { qubits, label } => {
+                let (min_qubit, max_qubit) = qubit_range(qubits);
+                let y = y_center(min_qubit) - SVG_GATE_HALF_HEIGHT;
+                let y_end = y_center(max_qubit) + SVG_GATE_HALF_HEIGHT;
+                svg.push_str(&svg_box(x, y, gate_width, y_end - y));
+                svg.push_str(&svg_text(
+                    x,
+                    (y + y_end) / 2.0,
+                    "middle",
+                    &label.to_text(precision),
+                ));
+            }
+            LayoutElement::Controlled {
+                control,
+                target,
+                target_symbol,
+            } => {
+                let y_control = y_center(*control);
+                let y_target = y_center(*target);
+                svg.push_str(&format!(
+                    "<line x1=\"{x}\" y1=\"{}\" x2=\"{x}\" y2=\"{}\"/>\n",
+                    y_control,
+                    y_target,
+                    x = x
+                ));
+                svg.push_str(&svg_dot(x, y_control));
+                match target_symbol {
+                    TargetSymbol::Not => {
+                        svg.push_str(&format!(
+                            "<circle cx=\"{}\" cy=\"{}\" r=\"10\" fill=\"white\"/>\n",
+                            x, y_target
+                        ));
+                        svg.push_str(&format!(
+                            "<line x1=\"{}\" y1=\"{y}\" x2=\"{}\" y2=\"{y}\"/>\n",
+                            x - 10.0,
+                            x + 10.0,
+                            y = y_target
+                        ));
+                        svg.push_str(&format!(
+                            "<line x1=\"{x}\" y1=\"{}\" x2=\"{x}\" y2=\"{}\"/>\n",
+                            y_target - 10.0,
+                            y_target + 10.0,
+                            x = x
+                        ));
+                    }
+                    TargetSymbol::Dot => svg.push_str(&svg_dot(x, y_target)),
+                    TargetSymbol::Gate(label) => {
+                        svg.push_str(&svg_box(
+                            x,
+                            y_target - SVG_GATE_HALF_HEIGHT,
+                            gate_width,
+                            2.0 * SVG_GATE_HALF_HEIGHT,
+                        ));
+                        svg.push_str(&svg_text(x, y_target, "middle", &label.to_text(precision)));
+                    }
+                }
+            }
+            LayoutElement::Swap { first, second } => {
+                let y_first = y_center(*first);
+                let y_second = y_center(*second);
+                svg.push_str(&format!(
+                    "<line x1=\"{x}\" y1=\"{}\" x2=\"{x}\" y2=\"{}\"/>\n",
+                    y_first,
+                    y_second,
+                    x = x
+                ));
+                svg.push_str(&svg_cross(x, y_first));
+                svg.push_str(&svg_cross(x, y_second));
+            }
+            LayoutElement::Measure { qubits } => {
+                for qubit in qubits {
+                    let y = y_center(*qubit);
+                    svg.push_str(&svg_box(
+                        x,
+                        y - SVG_GATE_HALF_HEIGHT,
+                        2.0 * SVG_GATE_HALF_HEIGHT + 4.0,
+                        2.0 * SVG_GATE_HALF_HEIGHT,
+                    ));
+                    svg.push_str(&format!(
+                        "<path d=\"M {} {} A 11 11 0 0 1 {} {}\" fill=\"none\"/>\n",
+                        x - 11.0,
+                        y + 7.0,
+                        x + 11.0,
+                        y + 7.0
+                    ));
+                    svg.push_str(&format!(
+                        "<line x1=\"{}\" y1=\"{}\" x2=\"{}\" y2=\"{}\"/>\n",
+                        x,
+                        y + 7.0,
+                        x + 8.0,
+                        y - 9.0
+                    ));
+                }
+            }
+        }
+    }
+    svg.push_str("</g>\n</svg>\n");
+    Ok(svg)
+}
+
+/// Label of a gate combining the name and the (possibly symbolic) parameters.
+#[derive(Debug, Clone, PartialEq)]
+struct GateLabel {
+    /// Name of the gate in LaTeX math mode.
+    latex: String,
+    /// Name of the gate in plain text.
+    text: String,
+    /// Parameters of the gate.
+    parameters: Vec<CalculatorFloat>,
+}
+
+impl GateLabel {
+    fn new(latex: &str, text: &str, parameters: Vec<CalculatorFloat>) -> Self {
+        Self {
+            latex: latex.to_string(),
+            text: text.to_string(),
+            parameters,
+        }
+    }
+
+    fn to_latex(&self, precision: usize) -> String {
+        if self.parameters.is_empty() {
+            return self.latex.clone();
+        }
+        let parameters: Vec<String> = self
+            .parameters
+            .iter()
+            .map(|parameter| match parameter {
+                CalculatorFloat::Float(x) => format!("{:.*}", precision, x),
+                CalculatorFloat::Str(x) => format!("\\text{{{}}}", escape_latex(x)),
+            })
+            .collect();
+        format!("{}({})", self.latex, parameters.join(", "))
+    }
+
+    fn to_text(&self, precision: usize) -> String {
+        if self.parameters.is_empty() {
+            return self.text.clone();
+        }
+        let parameters: Vec<String> = self
+            .parameters
+            .iter()
+            .map(|parameter| match parameter {
+                CalculatorFloat::Float(x) => format!("{:.*}", precision, x),
+                CalculatorFloat::Str(x) => x.clone(),
+            })
+            .collect();
+        format!("{}({})", self.text, parameters.join(", "))
+    }
+}
+
+/// Symbol drawn on the target qubit of a controlled two-qubit gate.
+#[derive(Debug, Clone, PartialEq)]
+enum TargetSymbol {
+    /// Circled plus of a controlled NOT.
+    Not,
+    /// Filled dot of a symmetric controlled phase.
+    Dot,
+    /// Gate box with a label.
+    Gate(GateLabel),
+}
+
+/// Element of the circuit layout placed in a single column.
+#[derive(Debug, Clone, PartialEq)]
+enum LayoutElement {
+    /// Box spanning all qubits between the smallest and largest qubit.
+    Gate {
+        qubits: Vec<usize>,
+        label: GateLabel,
+    },
+    /// Controlled two-qubit gate.
+    Controlled {
+        control: usize,
+        target: usize,
+        target_symbol: TargetSymbol,
+    },
+    /// SWAP gate.
+    Swap { first: usize, second: usize },
+    /// Measurement of one or more qubits.
+    Measure { qubits: Vec<usize> },
+}
+
+impl LayoutElement {
+    fn qubits(&self) -> Vec<usize> {
+        match self {
+            LayoutElement::Gate { qubits, .. } => qubits.clone(),
+            LayoutElement::Controlled {
+                control, target, ..
+            } => vec![*control, *target],
+            LayoutElement::Swap { first, second } => vec![*first, *second],
+            LayoutElement::Measure { qubits } => qubits.clone(),
+        }
+    }
+}
+
+/// Decomposition block drawn as a labelled box.
+#[derive(Debug, Clone, PartialEq)]
+struct LayoutGroup {
+    min_qubit: usize,
+    max_qubit: usize,
+    first_column: usize,
+    last_column: usize,
+    label: String,
+}
+
+/// Placement of the elements of a circuit in a grid of qubits and columns.
+#[derive(Debug, Clone, PartialEq)]
+struct CircuitLayout {
+    number_qubits: usize,
+    number_columns: usize,
+    elements: Vec<(usize, LayoutElement)>,
+    groups: Vec<LayoutGroup>,
+}
+
+impl CircuitLayout {
+    fn new(circuit: &Circuit, options: &RenderOptions) -> Result<Self, RoqoqoError> {
+        let mut number_qubits = options.number_qubits.unwrap_or(0);
+        for operation in circuit.iter() {
+            let qubits: Vec<usize> = match (operation, operation.involved_qubits()) {
+                // The mapped qubits of a repeated measurement are drawn even if no other operation acts on them
+                (Operation::PragmaRepeatedMeasurement(op), _) => op
+                    .qubit_mapping()
+                    .as_ref()
+                    .map(|mapping| mapping.keys().cloned().collect())
+                    .unwrap_or_default(),
+                (_, InvolvedQubits::Set(qubits)) => qubits.into_iter().collect(),
+                _ => Vec::new(),
+            };
+            if let Some(max_qubit) = qubits.iter().max() {
+                number_qubits = number_qubits.max(max_qubit + 1);
+            }
+        }
+        let mut layout = CircuitLayout {
+            number_qubits,
+            number_columns: 0,
+            elements: Vec::new(),
+            groups: Vec::new(),
+        };
+        // Next free column for each qubit
+        let mut free_columns: Vec<usize> = vec![0; number_qubits];
+        // Open decomposition blocks as (qubits, first column)
+        let mut open_blocks: Vec<(Vec<usize>, usize)> = Vec::new();
+
+        for operation in circuit.iter() {
+            match operation {
+                Operation::PragmaStartDecompositionBlock(op) => {
+                    if !options.group_decomposition_blocks || op.qubits().is_empty() {
+                        continue;
+                    }
+                    let (min_qubit, max_qubit) = qubit_range(op.qubits());
+                    let first_column = free_columns[min_qubit..=max_qubit]
+                        .iter()
+                        .cloned()
+                        .max()
+                        .unwrap_or(0);
+                    // Aligning the wires so that all gates in the block are drawn inside the box
+                    for column in free_columns[min_qubit..=max_qubit].iter_mut() {
+                        *column = first_column;
+                    }
+                    open_blocks.push((op.qubits().clone(), first_column));
+                }
+                Operation::PragmaStopDecompositionBlock(op) => {
+                    if !options.group_decomposition_blocks || op.qubits().is_empty() {
+                        continue;
+                    }
+                    let (qubits, first_column) =
+                        open_blocks
+                            .pop()
+                            .ok_or_else(|| RoqoqoError::GenericError {
+                                msg: "PragmaStopDecompositionBlock without preceding PragmaStartDecompositionBlock".to_string(),
+                            })?;
+                    let (min_qubit, max_qubit) = qubit_range(&qubits);
+                    let end_column = free_columns[min_qubit..=max_qubit]
+                        .iter()
+                        .cloned()
+                        .max()
+                        .unwrap_or(first_column)
+                        .max(first_column + 1);
+                    for column in free_columns[min_qubit..=max_qubit].iter_mut() {
+                        *column = end_column;
+                    }
+                    layout.number_columns = layout.number_columns.max(end_column);
+                    layout.groups.push(LayoutGroup {
+                        min_qubit,
+                        max_qubit,
+                        first_column,
+                        last_column: end_column - 1,
+                        label: options.decomposition_block_label.clone(),
+                    });
+                }
+                _ => {
+                    if let Some(element) = layout_element(operation, number_qubits, options) {
+                        let (min_qubit, max_qubit) = qubit_range(&element.qubits());
+                        let column = free_columns[min_qubit..=max_qubit]
+                            .iter()
+                            .cloned()
+                            .max()
+                            .unwrap_or(0);
+                        for free_column in free_columns[min_qubit..=max_qubit].iter_mut() {
+                            *free_column = column + 1;
+                        }
+                        layout.number_columns = layout.number_columns.max(column + 1);
+                        layout.elements.push((column, element));
+                    }
+                }
+            }
+        }
+        if !open_blocks.is_empty() {
+            return Err(RoqoqoError::GenericError {
+                msg: "PragmaStartDecompositionBlock without matching PragmaStopDecompositionBlock"
+                    .to_string(),
+            });
+        }
+        Ok(layout)
+    }
+}
+
+/// Returns the layout element of an operation or None when the operation is not drawn.
+fn layout_element(
+    operation: &Operation,
+    number_qubits: usize,
+    options: &RenderOptions,
+) -> Option<LayoutElement> {
+    match operation {
+        Operation::CNOT(op) => Some(LayoutElement::Controlled {
+            control: *op.control(),
+            target: *op.target(),
+            target_symbol: TargetSymbol::Not,
+        }),
+        Operation::ControlledPauliZ(op) => Some(LayoutElement::Controlled {
+            control: *op.control(),
+            target: *op.target(),
+            target_symbol: TargetSymbol::Dot,
+        }),
+        Operation::ControlledPauliY(op) => Some(LayoutElement::Controlled {
+            control: *op.control(),
+            target: *op.target(),
+            target_symbol: TargetSymbol::Gate(GateLabel::new("Y", "Y", vec![])),
+        }),
+        Operation::ControlledPhaseShift(op) => Some(LayoutElement::Controlled {
+            control: *op.control(),
+            target: *op.target(),
+            target_symbol: TargetSymbol::Gate(GateLabel::new("P", "P", vec![op.theta().clone()])),
+        }),
+        Operation::SWAP(op) => Some(LayoutElement::Swap {
+            first: *op.control(),
+            second: *op.target(),
+        }),
+        Operation::MeasureQubit(op) => Some(LayoutElement::Measure {
+            qubits: vec![*op.qubit()],
+        }),
+        Operation::PragmaRepeatedMeasurement(op) => {
+            let qubits: Vec<usize> = match op.qubit_mapping() {
+                Some(mapping) => {
+                    let mut qubits: Vec<usize> = mapping.keys().cloned().collect();
+                    qubits.sort_unstable();
+                    qubits
+                }
+                None => (0..number_qubits).collect(),
+            };
+            if qubits.is_empty() {
+                None
+            } else {
+                Some(LayoutElement::Measure { qubits })
+            }
+        }
+        _ => {
+            let tags = operation.tags();
+            let qubits: Vec<usize> = match operation.involved_qubits() {
+                InvolvedQubits::None => return None,
+                InvolvedQubits::All => (0..number_qubits).collect(),
+                InvolvedQubits::Set(set) => {
+                    let mut qubits: Vec<usize> = set.into_iter().collect();
+                    qubits.sort_unstable();
+                    qubits
+                }
+            };
+            if qubits.is_empty() {
+                return None;
+            }
+            if tags.contains(&"GateOperation") {
+                Some(LayoutElement::Gate {
+                    qubits,
+                    label: gate_label(operation),
+                })
+            } else if tags.contains(&"PragmaOperation") && options.show_pragmas {
+                Some(LayoutElement::Gate {
+                    qubits,
+                    label: GateLabel::new(
+                        &format!("\\text{{{}}}", operation.hqslang()),
+                        operation.hqslang(),
+                        vec![],
+                    ),
+                })
+            } else {
+                None
+            }
+        }
+    }
+}
+
+/// Returns the label of a gate operation.
+fn gate_label(operation: &Operation) -> GateLabel {
+    let (latex, text) = match operation.hqslang() {
+        "RotateX" => ("R_{x}".to_string(), "Rx".to_string()),
+        "RotateY" => ("R_{y}".to_string(), "Ry".to_string()),
+        "RotateZ" => ("R_{z}".to_string(), "Rz".to_string()),
+        "PauliX" => ("X".to_string(), "X".to_string()),
+        "PauliY" => ("Y".to_string(), "Y".to_string()),
+        "PauliZ" => ("Z".to_string(), "Z".to_string()),
+        "Hadamard" => ("H".to_string(), "H".to_string()),
+        "SGate" => ("S".to_string(), "S".to_string()),
+        "TGate" => ("T".to_string(), "T".to_string()),
+        "SqrtPauliX" => ("\\sqrt{X}".to_string(), "√X".to_string()),
+        "InvSqrtPauliX" => ("\\sqrt{X}^{\\dagger}".to_string(), "√X†".to_string()),
+        "PhaseShiftState0" => ("P_{0}".to_string(), "P0".to_string()),
+        "PhaseShiftState1" => ("P_{1}".to_string(), "P1".to_string()),
+        "SingleQubitGate" => ("U".to_string(), "U".to_string()),
+        "ISwap" => ("i\\mathrm{SWAP}".to_string(), "iSWAP".to_string()),
+        "SqrtISwap" => ("\\sqrt{i\\mathrm{SWAP}}".to_string(), "√iSWAP".to_string()),
+        "InvSqrtISwap" => (
+            "\\sqrt{i\\mathrm{SWAP}}^{\\dagger}".to_string(),
+            "√iSWAP†".to_string(),
+        ),
+        "FSwap" => ("f\\mathrm{SWAP}".to_string(), "fSWAP".to_string()),
+        "MolmerSorensenXX" | "VariableMSXX" => {
+            ("\\mathrm{MS}_{XX}".to_string(), "MSXX".to_string())
+        }
+        "MultiQubitMS" => ("\\mathrm{MS}".to_string(), "MS".to_string()),
+        "MultiQubitZZ" => ("ZZ".to_string(), "ZZ".to_string()),
+        name => (format!("\\text{{{}}}", name), name.to_string()),
+    };
+    GateLabel {
+        latex,
+        text,
+        parameters: gate_parameters(operation),
+    }
+}
+
+/// Returns the (possibly symbolic) parameters of a gate operation.
+fn gate_parameters(operation: &Operation) -> Vec<CalculatorFloat> {
+    match operation {
+        Operation::SingleQubitGate(_) => vec![],
+        Operation::RotateAroundSphericalAxis(op) => vec![
+            op.theta().clone(),
+            op.spherical_theta().clone(),
+            op.spherical_phi().clone(),
+        ],
+        Operation::GivensRotation(op) => vec![op.theta().clone(), op.phi().clone()],
+        Operation::GivensRotationLittleEndian(op) => vec![op.theta().clone(), op.phi().clone()],
+        Operation::Qsim(op) => vec![op.x().clone(), op.y().clone(), op.z().clone()],
+        Operation::SpinInteraction(op) => vec![op.x().clone(), op.y().clone(), op.z().clone()],
+        Operation::Fsim(op) => vec![op.t().clone(), op.u().clone(), op.delta().clone()],
+        Operation::Bogoliubov(op) => vec![op.delta_real().clone(), op.delta_imag().clone()],
+        Operation::PMInteraction(op) => vec![op.t().clone()],
+        Operation::ComplexPMInteraction(op) => vec![op.t_real().clone(), op.t_imag().clone()],
+        Operation::PhaseShiftedControlledZ(op) => vec![op.phi().clone()],
+        _ => match Rotation::try_from(operation) {
+            Ok(rotation) => vec![rotation.theta().clone()],
+            Err(_) => vec![],
+        },
+    }
+}
+
+/// Returns smallest and largest qubit of a non-empty list of qubits.
+fn qubit_range(qubits: &[usize]) -> (usize, usize) {
+    let min_qubit = qubits.iter().cloned().min().unwrap_or(0);
+    let max_qubit = qubits.iter().cloned().max().unwrap_or(0);
+    (min_qubit, max_qubit)
+}
+
+/// Escapes characters with special meaning in LaTeX text mode.
+fn escape_latex(input: &str) -> String {
+    let mut output = String::with_capacity(input.len());
+    for character in input.chars() {
+        match character {
+            '_' | '&' | '%' | '$' | '#' | '{' | '}' => {
+                output.push('\\');
+                output.push(character);
+            }
+            '\\' => output.push_str("\\textbackslash{}"),
+            _ => output.push(character),
+        }
+    }
+    output
+}
+
+/// Escapes characters with special meaning in XML.
+fn escape_xml(input: &str) -> String {
+    input
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn svg_text(x: f64, y: f64, anchor: &str, text: &str) -> String {
+    format!(
+        "<text x=\"{}\" y=\"{}\" text-anchor=\"{}\" dominant-baseline=\"central\" stroke=\"none\">{}</text>\n",
+        x,
+        y,
+        anchor,
+        escape_xml(text)
+    )
+}
+
+fn svg_box(x_center: f64, y: f64, width: f64, height: f64) -> String {
+    format!(
+        "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"white\"/>\n",
+        x_center - width / 2.0,
+        y,
+        width,
+        height
+    )
+}
+
+fn svg_dot(x: f64, y: f64) -> String {
+    format!(
+        "<circle cx=\"{}\" cy=\"{}\" r=\"4\" fill=\"black\"/>\n",
+        x, y
+    )
+}
+
+fn svg_cross(x: f64, y: f64) -> String {
+    format!(
+        "<line x1=\"{}\" y1=\"{}\" x2=\"{}\" y2=\"{}\"/>\n<line x1=\"{}\" y1=\"{}\" x2=\"{}\" y2=\"{}\"/>\n",
+        x - 6.0,
+        y - 6.0,
+        x + 6.0,
+        y + 6.0,
+        x - 6.0,
+        y + 6.0,
+        x + 6.0,
+        y - 6.0
+    )
+}
@@ -0,0 +1,390 @@
+// Copyright © 2021 HQS Quantum Simulations GmbH. All Rights Reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except
+// in compliance with the License. You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the
+// License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either
+// express or implied. See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! JSON schemas of roqoqo data structures.
+//!
+//! Provides the JSON schema of every roqoqo type implementing [schemars::JsonSchema]
+//! (e.g. [crate::Circuit], the measurements and [crate::QuantumProgram]) as a string
+//! and validates JSON documents against these schemas before deserializing them.
+//! Validation errors contain the JSON pointer of the offending value, e.g. `/operations/0/RotateX/qubit`.
+//!
+//! The validator supports the subset of JSON schema generated by schemars for roqoqo types
+//! (`$ref`, `allOf`, `anyOf`, `oneOf`, `enum`, `const`, `type`, `properties`, `required`,
+//! `additionalProperties`, `items`, `minItems`, `maxItems`, `minimum` and `maximum`).
+//!
+//! # Example
+//!
+//! ```
+//! use roqoqo::Circuit;
+//! use roqoqo::schema::{json_schema, validate_json};
+//!
+//! let schema = json_schema::<Circuit>();
+//! assert!(schema.contains("_roqoqo_version"));
+//! let json = serde_json::to_string(&Circuit::new()).unwrap();
+//! assert!(validate_json::<Circuit>(&json).is_ok());
+//! assert!(validate_json::<Circuit>("{\"operations\": 1}").is_err());
+//! ```
+
+use crate::RoqoqoError;
+use schemars::JsonSchema;
+use serde::de::DeserializeOwned;
+use serde_json::{Map, Value};
+
+/// Returns the JSON schema of a roqoqo type.
+///
+/// # Returns
+///
+/// * `String` - The pretty-printed JSON schema (draft 07) with all referenced definitions.
+pub fn json_schema<T: JsonSchema>() -> String {
+    let schema = schemars::schema_for!(T);
+    serde_json::to_string_pretty(&schema).expect("Internal error: JSON schema is not serializable")
+}
+
+/// Validates a JSON document against the JSON schema of a roqoqo type.
+///
+/// # Arguments
+///
+/// * `json` - The JSON document that is validated.
+///
+/// # Returns
+///
+/// * `Ok(())` - The document matches the schema.
+/// * `Err(RoqoqoError::SerializationError)` - The document is not valid JSON.
+/// * `Err(RoqoqoError::JsonValidationError)` - The document does not match the schema.
+pub fn validate_json<T: JsonSchema>(json: &str) -> Result<(), RoqoqoError> {
+    let document: Value =
+        serde_json::from_str(json).map_err(|err| RoqoqoError::SerializationError {
+            msg: format!("{}", err),
+        })?;
+    let schema = serde_json::to_value(schemars::schema_for!(T))
+        .expect("Internal error: JSON schema is not serializable");
+    validate_value(&schema, &document, &schema, "").map_err(|err| {
+        RoqoqoError::JsonValidationError {
+            path: if err.path.is_empty() {
+                "/".to_string()
+            } else {
+                err.path
+            },
+            msg: err.msg,
+        }
+    })
+}
+
+/// Validates a JSON document against the JSON schema of a roqoqo type and deserializes it.
+///
+/// # Arguments
+///
+/// * `json` - The JSON document that is deserialized.
+///
+/// # Returns
+///
+/// * `Ok(T)` - The deserialized object.
+/// * `Err(RoqoqoError::JsonValidationError)` - The document does not match the schema.
+/// * `Err(RoqoqoError::SerializationError)` - The document could not be deserialized.
+pub fn from_json_validated<T: JsonSchema + DeserializeOwned>(json: &str) -> Result<T, RoqoqoError> {
+    validate_json::<T>(json)?;
+    serde_json::from_str(json).map_err(|err| RoqoqoError::SerializationError {
+        msg: format!("{}", err),
+    })
+}
+
+/// Location and reason of a validation failure.
+#[derive(Debug, Clone, PartialEq)]
+struct ValidationFailure {
+    path: String,
+    msg: String,
+}
+
+impl ValidationFailure {
+    fn new(path: &str, msg: String) -> Self {
+        Self {
+            path: path.to_string(),
+            msg,
+        }
+    }
+}
+
+/// Validates a value against a (sub-)schema.
+fn validate_value(
+    schema: &Value,
+    value: &Value,
+    root: &Value,
+    path: &str,
+) -> Result<(), ValidationFailure> {
+    let schema = match schema {
+        Value::Bool(true) => return Ok(()),
+        Value::Bool(false) => {
+            return Err(ValidationFailure::new(path, "no value allowed".to_string()))
+        }
+        Value::Object(schema) => schema,
+        _ => return Ok(()),
+    };
+    if let Some(Value::String(reference)) = schema.get("$ref") {
+        let resolved = resolve_reference(root, reference).ok_or_else(|| {
+            ValidationFailure::new(path, format!("unresolvable schema reference {}", reference))
+        })?;
+        return validate_value(resolved, value, root, path);
+    }
+    if let Some(Value::Array(schemas)) = schema.get("allOf") {
+        for sub_schema in schemas {
+            validate_value(sub_schema, value, root, path)?;
+        }
+    }
+    if let Some(Value::Array(schemas)) = schema.get("anyOf") {
+        validate_alternatives(schemas, value, root, path, false)?;
+    }
+    if let Some(Value::Array(schemas)) = schema.get("oneOf") {
+        validate_alternatives(schemas, value, root, path, true)?;
+    }
+    if let Some(Value::Array(allowed)) = schema.get("enum") {
+        if !allowed.contains(value) {
+            return Err(ValidationFailure::new(
+                path,
+                format!("{} is not one of {}", value, Value::Array(allowed.clone())),
+            ));
+        }
+    }
+    if let Some(constant) = schema.get("const") {
+        if constant != value {
+            return Err(ValidationFailure::new(
+                path,
+                format!("expected {} but found {}", constant, value),
+            ));
+        }
+    }
+    if let Some(types) = schema.get("type") {
+        let allowed: Vec<&str> = match types {
+            Value::String(name) => vec![name.as_str()],
+            Value::Array(names) => names.iter().filter_map(|name| name.as_str()).collect(),
+            _ => vec![],
+        };
+        if !allowed.is_empty() && !allowed.iter().any(|name| has_type(value, name)) {
+            return Err(ValidationFailure::new(
+                path,
+                format!(
+                    "expected {} but found {}",
+                    allowed.join(" or "),
+                    type_name(value)
+                ),
+            ));
+        }
+    }
+    match value {
+        Value::Number(number) => validate_number(schema, number.as_f64().unwrap_or(0.0), path)?,
+        Value::Object(object) => validate_object(schema, object, root, path)?,
+        Value::Array(array) => validate_array(schema, array, root, path)?,
+        _ => (),
+    }
+    Ok(())
+}
+
+/// Validates a value against alternative schemas (anyOf or oneOf).
+///
+/// When no alternative matches, the failure that reached deepest into the document is reported,
+/// which is usually the variant the document was meant to be.
+fn validate_alternatives(
+    schemas: &[Value],
+    value: &Value,
+    root: &Value,
+    path: &str,
+    exactly_one: bool,
+) -> Result<(), ValidationFailure> {
+    let mut number_matches: usize = 0;
+    let mut deepest_failure: Option<ValidationFailure> = None;
+    for sub_schema in schemas {
+        match validate_value(sub_schema, value, root, path) {
+            Ok(()) => number_matches += 1,
+            Err(failure) => {
+                let deeper = match &deepest_failure {
+                    Some(deepest) => failure.path.len() > deepest.path.len(),
+                    None => true,
+                };
+                if deeper {
+                    deepest_failure = Some(failure);
+                }
+            }
+        }
+    }
+    match number_matches {
+        0 => Err(match deepest_failure {
+            Some(failure) if failure.path.len() > path.len() => failure,
+            _ => ValidationFailure::new(
+                path,
+                format!(
+                    "{} does not match any of the allowed variants",
+                    type_name(value)
+                ),
+            ),
+        }),
+        1 => Ok(()),
+        _ if exactly_one => Err(ValidationFailure::new(
+            path,
+            "value matches more than one variant".to_string(),
+        )),
+        _ => Ok(()),
+    }
+}
+
+fn validate_number(
+    schema: &Map<String, Value>,
+    number: f64,
+    path: &str,
+) -> Result<(), ValidationFailure> {
+    if let Some(minimum) = schema.get("minimum").and_then(|minimum| minimum.as_f64()) {
+        if number < minimum {
+            return Err(ValidationFailure::new(
+                path,
+                format!("{} is smaller than the minimum {}", number, minimum),
+            ));
+        }
+    }
+    if let Some(maximum) = schema.get("maximum").and_then(|maximum| maximum.as_f64()) {
+        if number > maximum {
+            return Err(ValidationFailure::new(
+                path,
+                format!("{} is larger than the maximum {}", number, maximum),
+            ));
+        }
+    }
+    Ok(())
+}
+
+fn validate_object(
+    schema: &Map<String, Value>,
+    object: &Map<String, Value>,
+    root: &Value,
+    path: &str,
+) -> Result<(), ValidationFailure> {
+    if let Some(Value::Array(required)) = schema.get("required") {
+        for key in required.iter().filter_map(|key| key.as_str()) {
+            if !object.contains_key(key) {
+                return Err(ValidationFailure::new(
+                    path,
+                    format!("missing required field {}", key),
+                ));
+            }
+        }
+    }
+    let properties = schema
+        .get("properties")
+        .and_then(|properties| properties.as_object());
+    for (key, value) in object {
+        let child_path = format!("{}/{}", path, escape_pointer(key));
+        match properties.and_then(|properties| properties.get(key)) {
+            Some(property_schema) => validate_value(property_schema, value, root, &child_path)?,
+            None => match schema.get("additionalProperties") {
+                Some(Value::Bool(false)) => {
+                    return Err(ValidationFailure::new(
+                        path,
+                        format!("unknown field {}", key),
+                    ))
+                }
+                Some(additional_schema) => {
+                    validate_value(additional_schema, value, root, &child_path)?
+                }
+                None => (),
+            },
+        }
+    }
+    Ok(())
+}
+
+fn validate_array(
+    schema: &Map<String, Value>,
+    array: &[Value],
+    root: &Value,
+    path: &str,
+) -> Result<(), ValidationFailure> {
+    if let Some(min_items) = schema
+        .get("minItems")
+        .and_then(|min_items| min_items.as_u64())
+    {
+        if (array.len() as u64) < min_items {
+            return Err(ValidationFailure::new(
+                path,
+                format!(
+                    "expected at least {} items but found {}",
+                    min_items,
+                    array.len()
+                ),
+            ));
+        }
+    }
+    if let Some(max_items) = schema
+        .get("maxItems")
+        .and_then(|max_items| max_items.as_u64())
+    {
+        if (array.len() as u64) > max_items {
+            return Err(ValidationFailure::new(
+                path,
+                format!(
+                    "expected at most {} items but found {}",
+                    max_items,
+                    array.len()
+                ),
+            ));
+        }
+    }
+    match schema.get("items") {
+        Some(Value::Array(item_schemas)) => {
+            for (index, (item_schema, item)) in item_schemas.iter().zip(array.iter()).enumerate() {
+                validate_value(item_schema, item, root, &format!("{}/{}", path, index))?;
+            }
+        }
+        Some(item_schema) => {
+            for (index, item) in array.iter().enumerate() {
+                validate_value(item_schema, item, root, &format!("{}/{}", path, index))?;
+            }
+        }
+        None => (),
+    }
+    Ok(())
+}
+
+/// Resolves a local reference like `#/definitions/Circuit`.
+fn resolve_reference<'a>(root: &'a Value, reference: &str) -> Option<&'a Value> {
+    let pointer = reference.strip_prefix('#')?;
+    root.pointer(pointer)
+}
+
+fn has_type(value: &Value, name: &str) -> bool {
+    match name {
+        "null" => value.is_null(),
+        "boolean" => value.is_boolean(),
+        "object" => value.is_object(),
+        "array" => value.is_array(),
+        "string" => value.is_string(),
+        "number" => value.is_number(),
+        "integer" => {
+            value.is_i64()
+                || value.is_u64()
+                || value.as_f64().map(|x| x.fract() == 0.0).unwrap_or(false)
+        }
+        _ => true,
+    }
+}
+
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+/// Escapes a key for use in a JSON pointer.
+fn escape_pointer(key: &str) -> String {
+    key.replace('~', "~0").replace('/', "~1")
+}
@@ -0,0 +1,696 @@
+// Copyright © 2021 HQS Quantum Simulations GmbH. All Rights Reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except
+// in compliance with the License. You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the
+// License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either
+// express or implied. See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Versioned, self-describing binary serialization of roqoqo objects.
+//!
+//! Objects are stored in [CBOR](https://cbor.io) wrapped in an envelope that records
+//! the kind of the stored object and the roqoqo version that wrote the data.
+//! Unlike bincode, CBOR is self-describing, so data written by an older version of roqoqo
+//! can be decoded into a generic [Value] tree and brought up to date by a chain of
+//! [Migration] functions before it is deserialized into the current data structures.
+//!
+//! Data written by a newer version of roqoqo than the library is rejected with
+//! [RoqoqoError::VersionMissmatch].
+//!
+//! Archived data in the bincode or JSON serialization of roqoqo is brought into the
+//! binary format with [import_bincode] and [import_json], which read data written by
+//! roqoqo 0.10 in the layout of that release and migrate it.
+//!
+//! # Example
+//!
+//! ```
+//! use roqoqo::Circuit;
+//! use roqoqo::operations::RotateX;
+//! use roqoqo::serialization::{from_binary, to_binary};
+//!
+//! let mut circuit = Circuit::new();
+//! circuit += RotateX::new(0, "theta".into());
+//! let bytes = to_binary(&circuit).unwrap();
+//! let deserialized: Circuit = from_binary(&bytes).unwrap();
+//! assert_eq!(circuit, deserialized);
+//! ```
+
+use crate::measurements::{
+    BasisRotation, Cheated, CheatedBasisRotation, ClassicalRegister, ClassicalShadows, Histogram,
+    OperatorSparseVec, PauliProductsToExpVal, SingleReadoutPauliProductMasks,
+};
+use crate::operations::Operation;
+use crate::{Circuit, QuantumProgram, RoqoqoError, ROQOQO_VERSION};
+use bincode::Options;
+pub use ciborium::value::Value;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::str::FromStr;
+
+/// Identifier of the binary format stored in every envelope.
+const FORMAT_NAME: &str = "roqoqo";
+/// Version of the envelope layout.
+const FORMAT_VERSION: u32 = 1;
+/// Key of the version stamp roqoqo adds to serialized circuits.
+const VERSION_KEY: &str = "_roqoqo_version";
+
+/// Trait for roqoqo objects that can be stored in the versioned binary format.
+pub trait BinarySerializable: Serialize + DeserializeOwned {
+    /// Name of the object kind stored in the envelope, used to reject data of the wrong kind.
+    const KIND: &'static str;
+}
+
+impl BinarySerializable for Circuit {
+    const KIND: &'static str = "Circuit";
+}
+
+impl BinarySerializable for BasisRotation {
+    const KIND: &'static str = "BasisRotation";
+}
+
+impl BinarySerializable for CheatedBasisRotation {
+    const KIND: &'static str = "CheatedBasisRotation";
+}
+
+impl BinarySerializable for Cheated {
+    const KIND: &'static str = "Cheated";
+}
+
+impl BinarySerializable for ClassicalRegister {
+    const KIND: &'static str = "ClassicalRegister";
+}
+
+impl BinarySerializable for ClassicalShadows {
+    const KIND: &'static str = "ClassicalShadows";
+}
+
+impl BinarySerializable for Histogram {
+    const KIND: &'static str = "Histogram";
+}
+
+impl BinarySerializable for QuantumProgram {
+    const KIND: &'static str = "QuantumProgram";
+}
+
+/// Migration of serialized data from one roqoqo data version to the next.
+#[derive(Debug, Clone, Copy)]
+pub struct Migration {
+    /// (major, minor) version of the data the migration is applied to.
+    pub from_version: (u32, u32),
+    /// (major, minor) version of the data after the migration.
+    pub to_version: (u32, u32),
+    /// Function migrating the payload of an envelope of the given kind in place.
+    pub migrate: fn(kind: &str, payload: &mut Value) -> Result<(), RoqoqoError>,
+}
+
+/// Chain of all known migrations, ordered by version.
+///
+/// When a release changes the serialized layout of a data structure a migration
+/// from the previous minor version has to be added here.
+const MIGRATIONS: &[Migration] = &[Migration {
+    from_version: (0, 10),
+    to_version: (0, 11),
+    migrate: migrate_0_10,
+}];
+
+/// Returns all migrations known to this version of roqoqo.
+///
+/// # Returns
+///
+/// * `&'static [Migration]` - The migrations ordered by version.
+pub fn migrations() -> &'static [Migration] {
+    MIGRATIONS
+}
+
+/// Migration of data written by roqoqo 0.10 to the layout of roqoqo 0.11.
+///
+/// roqoqo 0.11 added the `readout_mitigation` field to the input of [BasisRotation] measurements
+/// and the `exp_val_groups` field to the inputs of [BasisRotation], [CheatedBasisRotation]
+/// and [Cheated] measurements.
+fn migrate_0_10(kind: &str, payload: &mut Value) -> Result<(), RoqoqoError> {
+    match kind {
+        "BasisRotation" => {
+            let input = map_field(payload, "input")?;
+            insert_field(input, "readout_mitigation", Value::Null);
+            insert_field(input, "exp_val_groups", Value::Map(Vec::new()));
+        }
+        "CheatedBasisRotation" | "Cheated" => {
+            let input = map_field(payload, "input")?;
+            insert_field(input, "exp_val_groups", Value::Map(Vec::new()));
+        }
+        "QuantumProgram" => {
+            if let Value::Map(entries) = payload {
+                for (variant, program) in entries.iter_mut() {
+                    if let Value::Text(variant) = variant {
+                        let measurement = map_field(program, "measurement")?;
+                        migrate_0_10(variant, measurement)?;
+                    }
+                }
+            }
+        }
+        _ => (),
+    }
+    Ok(())
+}
+
+/// Serializes a roqoqo object to the versioned binary format.
+///
+/// # Arguments
+///
+/// * `value` - The object that is serialized.
+///
+/// # Returns
+///
+/// * `Ok(Vec<u8>)` - The serialized envelope.
+/// * `Err(RoqoqoError::SerializationError)` - The object could not be serialized.
+pub fn to_binary<T: BinarySerializable>(value: &T) -> Result<Vec<u8>, RoqoqoError> {
+    let payload = Value::serialized(value).map_err(|err| RoqoqoError::SerializationError {
+        msg: format!("{:?}", err),
+    })?;
+    let (major_version, minor_version) = library_version();
+    write_envelope(T::KIND, (major_version, minor_version), payload)
+}
+
+/// Deserializes a roqoqo object from the versioned binary format.
+///
+/// Data written by an older version of roqoqo is migrated to the current version first.
+///
+/// # Arguments
+///
+/// * `bytes` - The serialized envelope.
+///
+/// # Returns
+///
+/// * `Ok(T)` - The deserialized object.
+/// * `Err(RoqoqoError::SerializationError)` - The data is not a valid envelope of kind `T::KIND`.
+/// * `Err(RoqoqoError::VersionMissmatch)` - The data is newer than the library or cannot be migrated.
+pub fn from_binary<T: BinarySerializable>(bytes: &[u8]) -> Result<T, RoqoqoError> {
+    let (kind, version, payload) = read_envelope(bytes)?;
+    if kind != T::KIND {
+        return Err(RoqoqoError::SerializationError {
+            msg: format!("Expected data of kind {} but found {}", T::KIND, kind),
+        });
+    }
+    let payload = migrate(&kind, version, payload)?;
+    payload
+        .deserialized()
+        .map_err(|err| RoqoqoError::SerializationError {
+            msg: format!("{:?}", err),
+        })
+}
+
+/// Returns the kind and the (major, minor) roqoqo version of serialized data without deserializing it.
+///
+/// # Arguments
+///
+/// * `bytes` - The serialized envelope.
+///
+/// # Returns
+///
+/// * `Ok((String, (u32, u32)))` - The kind of the stored object and the data version.
+/// * `Err(RoqoqoError::SerializationError)` - The data is not a valid envelope.
+pub fn binary_header(bytes: &[u8]) -> Result<(String, (u32, u32)), RoqoqoError> {
+    let (kind, version, _) = read_envelope(bytes)?;
+    Ok((kind, version))
+}
+
+/// Migrates serialized data of any kind to the current roqoqo version.
+///
+/// Can be used to upgrade archived data once instead of migrating it on every load.
+///
+/// # Arguments
+///
+/// * `bytes` - The serialized envelope.
+///
+/// # Returns
+///
+/// * `Ok(Vec<u8>)` - The envelope with the payload migrated to the current version.
+/// * `Err(RoqoqoError)` - The data is not a valid envelope or cannot be migrated.
+pub fn upgrade_binary(bytes: &[u8]) -> Result<Vec<u8>, RoqoqoError> {
+    let (kind, version, payload) = read_envelope(bytes)?;
+    let payload = migrate(&kind, version, payload)?;
+    write_envelope(&kind, library_version(), payload)
+}
+
+/// Imports data in the JSON serialization of roqoqo into the versioned binary format.
+///
+/// The data version is read from the version stamps of the contained circuits.
+/// Data written by an older version of roqoqo is read in the layout of that version
+/// and migrated to the current version.
+///
+/// # Arguments
+///
+/// * `json` - The JSON serialization of an object of kind `T::KIND`.
+///
+/// # Returns
+///
+/// * `Ok(Vec<u8>)` - The serialized envelope.
+/// * `Err(RoqoqoError::SerializationError)` - The data is not a JSON serialization of kind `T::KIND`.
+/// * `Err(RoqoqoError::VersionMissmatch)` - The data is newer than the library or cannot be migrated.
+pub fn import_json<T: BinarySerializable>(json: &str) -> Result<Vec<u8>, RoqoqoError> {
+    let document: serde_json::Value =
+        serde_json::from_str(json).map_err(|err| RoqoqoError::SerializationError {
+            msg: format!("{:?}", err),
+        })?;
+    match json_version(&document) {
+        Some(version) if version != library_version() => {
+            let payload = archived_payload(T::KIND, version, Archive::Json(document))?;
+            import_payload::<T>(version, payload)
+        }
+        _ => {
+            let value: T = serde_json::from_value(document).map_err(|err| {
+                RoqoqoError::SerializationError {
+                    msg: format!("{:?}", err),
+                }
+            })?;
+            to_binary(&value)
+        }
+    }
+}
+
+/// Imports data in the bincode serialization of roqoqo into the versioned binary format.
+///
+/// bincode is not self-describing, so the data is read in the current layout first
+/// and in the layout of roqoqo 0.10 when it cannot be read in the current layout.
+/// Data written by roqoqo 0.10 is migrated to the current version.
+///
+/// # Arguments
+///
+/// * `bytes` - The bincode serialization of an object of kind `T::KIND`.
+///
+/// # Returns
+///
+/// * `Ok(Vec<u8>)` - The serialized envelope.
+/// * `Err(RoqoqoError::SerializationError)` - The data can not be read in any supported layout.
+pub fn import_bincode<T: BinarySerializable>(bytes: &[u8]) -> Result<Vec<u8>, RoqoqoError> {
+    let current_error = match bincode_options().deserialize::<T>(bytes) {
+        Ok(value) => return to_binary(&value),
+        Err(err) => err,
+    };
+    match archived_payload(T::KIND, (0, 10), Archive::Bincode(bytes)) {
+        Ok(payload) => import_payload::<T>((0, 10), payload),
+        Err(_) => Err(RoqoqoError::SerializationError {
+            msg: format!("{:?}", current_error),
+        }),
+    }
+}
+
+/// Migrates an imported payload and writes the envelope of the deserialized object.
+fn import_payload<T: BinarySerializable>(
+    version: (u32, u32),
+    payload: Value,
+) -> Result<Vec<u8>, RoqoqoError> {
+    let payload = migrate(T::KIND, version, payload)?;
+    let value: T = payload
+        .deserialized()
+        .map_err(|err| RoqoqoError::SerializationError {
+            msg: format!("{:?}", err),
+        })?;
+    to_binary(&value)
+}
+
+/// Returns the version of the first roqoqo version stamp found in a JSON document.
+fn json_version(document: &serde_json::Value) -> Option<(u32, u32)> {
+    match document {
+        serde_json::Value::Object(entries) => match entries.get(VERSION_KEY) {
+            Some(stamp) => {
+                let major_version = stamp.get("major_version")?.as_u64()?;
+                let minor_version = stamp.get("minor_version")?.as_u64()?;
+                Some((
+                    u32::try_from(major_version).ok()?,
+                    u32::try_from(minor_version).ok()?,
+                ))
+            }
+            None => entries.values().find_map(json_version),
+        },
+        serde_json::Value::Array(values) => values.iter().find_map(json_version),
+        _ => None,
+    }
+}
+
+/// Applies the chain of migrations from `version` to the library version and updates the version stamps.
+fn migrate(kind: &str, version: (u32, u32), mut payload: Value) -> Result<Value, RoqoqoError> {
+    let target = library_version();
+    if version > target {
+        return Err(version_mismatch(version));
+    }
+    let mut current = version;
+    while current != target {
+        let migration = MIGRATIONS
+            .iter()
+            .find(|migration| migration.from_version == current)
+            .ok_or_else(|| version_mismatch(version))?;
+        (migration.migrate)(kind, &mut payload)?;
+        current = migration.to_version;
+    }
+    restamp_version(&mut payload, target);
+    Ok(payload)
+}
+
+/// Returns the error for data of a version that cannot be read by the library.
+fn version_mismatch(version: (u32, u32)) -> RoqoqoError {
+    let (major_version, minor_version) = library_version();
+    RoqoqoError::VersionMissmatch {
+        library_major_version: major_version,
+        library_minor_version: minor_version,
+        data_major_version: version.0,
+        data_minor_version: version.1,
+    }
+}
+
+/// Returns the value of a field of a payload map.
+fn map_field<'a>(payload: &'a mut Value, key: &str) -> Result<&'a mut Value, RoqoqoError> {
+    let missing = || RoqoqoError::SerializationError {
+        msg: format!("Payload is missing the field {}", key),
+    };
+    match payload {
+        Value::Map(entries) => entries
+            .iter_mut()
+            .find(|(entry_key, _)| matches!(entry_key, Value::Text(text) if text == key))
+            .map(|(_, value)| value)
+            .ok_or_else(missing),
+        _ => Err(missing()),
+    }
+}
+
+/// Adds a field to a payload map unless it is already present.
+fn insert_field(payload: &mut Value, key: &str, value: Value) {
+    if let Value::Map(entries) = payload {
+        if !entries
+            .iter()
+            .any(|(entry_key, _)| matches!(entry_key, Value::Text(text) if text == key))
+        {
+            entries.push((Value::Text(key.to_string()), value));
+        }
+    }
+}
+
+/// Sets all roqoqo version stamps in the payload to `version`.
+fn restamp_version(payload: &mut Value, version: (u32, u32)) {
+    match payload {
+        Value::Map(entries) => {
+            for (key, value) in entries.iter_mut() {
+                if matches!(key, Value::Text(text) if text == VERSION_KEY) {
+                    *value = version_value(version);
+                } else {
+                    restamp_version(value, version);
+                }
+            }
+        }
+        Value::Array(values) => {
+            for value in values.iter_mut() {
+                restamp_version(value, version);
+            }
+        }
+        Value::Tag(_, value) => restamp_version(value, version),
+        _ => (),
+    }
+}
+
+/// Returns the CBOR representation of a roqoqo version stamp.
+fn version_value(version: (u32, u32)) -> Value {
+    Value::Map(vec![
+        (
+            Value::Text("major_version".to_string()),
+            Value::Integer(version.0.into()),
+        ),
+        (
+            Value::Text("minor_version".to_string()),
+            Value::Integer(version.1.into()),
+        ),
+    ])
+}
+
+/// Writes the envelope around a payload.
+fn write_envelope(kind: &str, version: (u32, u32), payload: Value) -> Result<Vec<u8>, RoqoqoError> {
+    let envelope = Value::Map(vec![
+        (
+            Value::Text("format".to_string()),
+            Value::Text(FORMAT_NAME.to_string()),
+        ),
+        (
+            Value::Text("format_version".to_string()),
+            Value::Integer(FORMAT_VERSION.into()),
+        ),
+        (
+            Value::Text("kind".to_string()),
+            Value::Text(kind.to_string()),
+        ),
+        (
+            Value::Text("major_version".to_string()),
+            Value::Integer(version.0.into()),
+        ),
+        (
+            Value::Text("minor_version".to_string()),
+            Value::Integer(version.1.into()),
+        ),
+        (Value::Text("payload".to_string()), payload),
+    ]);
+    let mut bytes: Vec<u8> = Vec::new();
+    ciborium::ser::into_writer(&envelope, &mut bytes).map_err(|err| {
+        RoqoqoError::SerializationError {
+            msg: format!("{:?}", err),
+        }
+    })?;
+    Ok(bytes)
+}
+
+/// Reads the envelope and returns kind, data version and payload.
+fn read_envelope(bytes: &[u8]) -> Result<(String, (u32, u32), Value), RoqoqoError> {
+    let envelope: Value =
+        ciborium::de::from_reader(bytes).map_err(|err| RoqoqoError::SerializationError {
+            msg: format!("{:?}", err),
+        })?;
+    let entries = match envelope {
+        Value::Map(entries) => entries,
+        _ => {
+            return Err(RoqoqoError::SerializationError {
+                msg: "Data is not a roqoqo binary envelope".to_string(),
+            })
+        }
+    };
+    let mut format: Option<String> = None;
+    let mut format_version: Option<u32> = None;
+    let mut kind: Option<String> = None;
+    let mut major_version: Option<u32> = None;
+    let mut minor_version: Option<u32> = None;
+    let mut payload: Option<Value> = None;
+    for (key, value) in entries {
+        let key = match key {
+            Value::Text(key) => key,
+            _ => continue,
+        };
+        match key.as_str() {
+            "format" => format = value.into_text().ok(),
+            "format_version" => format_version = integer_field(&value),
+            "kind" => kind = value.into_text().ok(),
+            "major_version" => major_version = integer_field(&value),
+            "minor_version" => minor_version = integer_field(&value),
+            "payload" => payload = Some(value),
+            _ => (),
+        }
+    }
+    if format.as_deref() != Some(FORMAT_NAME) {
+        return Err(RoqoqoError::SerializationError {
+            msg: "Data is not a roqoqo binary envelope".to_string(),
+        });
+    }
+    match format_version {
+        Some(version) if version <= FORMAT_VERSION => (),
+        _ => {
+            return Err(RoqoqoError::SerializationError {
+                msg: format!(
+                    "Unsupported envelope format version {:?}, supported up to {}",
+                    format_version, FORMAT_VERSION
+                ),
+            })
+        }
+    }
+    match (kind, major_version, minor_version, payload) {
+        (Some(kind), Some(major_version), Some(minor_version), Some(payload)) => {
+            Ok((kind, (major_version, minor_version), payload))
+        }
+        _ => Err(RoqoqoError::SerializationError {
+            msg: "Envelope is missing kind, version or payload".to_string(),
+        }),
+    }
+}
+
+/// Reads an unsigned 32 bit integer from a CBOR value.
+fn integer_field(value: &Value) -> Option<u32> {
+    value
+        .as_integer()
+        .and_then(|integer| u32::try_from(integer).ok())
+}
+
+/// Returns the (major, minor) version of the library.
+fn library_version() -> (u32, u32) {
+    let mut rsplit = ROQOQO_VERSION.split('.').take(2);
+    let major_version = u32::from_str(
+        rsplit
+            .next()
+            .expect("Internal error: Version not conforming to semver"),
+    )
+    .expect("Internal error: Major version is not unsigned integer.");
+    let minor_version = u32::from_str(
+        rsplit
+            .next()
+            .expect("Internal error: Version not conforming to semver"),
+    )
+    .expect("Internal error: Minor version is not unsigned integer.");
+    (major_version, minor_version)
+}
+
+/// Returns the bincode options of the serde serialization of roqoqo.
+fn bincode_options() -> impl bincode::Options {
+    bincode::DefaultOptions::new()
+        .with_fixint_encoding()
+        .reject_trailing_bytes()
+}
+
+/// Archived data in one of the serde formats of roqoqo.
+enum Archive<'a> {
+    /// bincode serialization.
+    Bincode(&'a [u8]),
+    /// JSON serialization.
+    Json(serde_json::Value),
+}
+
+impl Archive<'_> {
+    /// Reads the archived data in layout `L` and returns it as a payload.
+    fn payload<L: Serialize + DeserializeOwned>(self) -> Result<Value, RoqoqoError> {
+        let data: L = match self {
+            Archive::Bincode(bytes) => bincode_options().deserialize(bytes).map_err(|err| {
+                RoqoqoError::SerializationError {
+                    msg: format!("{:?}", err),
+                }
+            })?,
+            Archive::Json(document) => {
+                serde_json::from_value(document).map_err(|err| RoqoqoError::SerializationError {
+                    msg: format!("{:?}", err),
+                })?
+            }
+        };
+        Value::serialized(&data).map_err(|err| RoqoqoError::SerializationError {
+            msg: format!("{:?}", err),
+        })
+    }
+}
+
+/// Reads archived data in the layout of the roqoqo version that wrote it.
+fn archived_payload(
+    kind: &str,
+    version: (u32, u32),
+    archive: Archive,
+) -> Result<Value, RoqoqoError> {
+    if version != (0, 10) {
+        return Err(version_mismatch(version));
+    }
+    match kind {
+        "Circuit" => archive.payload::<Circuit0_10>(),
+        "BasisRotation" => archive.payload::<BasisRotation0_10>(),
+        "CheatedBasisRotation" => archive.payload::<CheatedBasisRotation0_10>(),
+        "Cheated" => archive.payload::<Cheated0_10>(),
+        "ClassicalRegister" => archive.payload::<ClassicalRegister0_10>(),
+        "QuantumProgram" => archive.payload::<QuantumProgram0_10>(),
+        _ => Err(RoqoqoError::SerializationError {
+            msg: format!("roqoqo 0.10 did not serialize data of kind {}", kind),
+        }),
+    }
+}
+
+/// Version stamp of serialized circuits, read without checking the version.
+#[derive(Serialize, Deserialize)]
+struct VersionStamp {
+    major_version: u32,
+    minor_version: u32,
+}
+
+/// Layout of [Circuit] in roqoqo 0.10.
+#[derive(Serialize, Deserialize)]
+struct Circuit0_10 {
+    definitions: Vec<Operation>,
+    operations: Vec<Operation>,
+    _roqoqo_version: VersionStamp,
+}
+
+/// Layout of [crate::measurements::BasisRotationInput] in roqoqo 0.10.
+#[derive(Serialize, Deserialize)]
+struct BasisRotationInput0_10 {
+    pauli_product_qubit_masks: HashMap<String, SingleReadoutPauliProductMasks>,
+    number_qubits: usize,
+    number_pauli_products: usize,
+    measured_exp_vals: HashMap<String, PauliProductsToExpVal>,
+    use_flipped_measurement: bool,
+}
+
+/// Layout of [BasisRotation] in roqoqo 0.10.
+#[derive(Serialize, Deserialize)]
+struct BasisRotation0_10 {
+    constant_circuit: Option<Circuit0_10>,
+    circuits: Vec<Circuit0_10>,
+    input: BasisRotationInput0_10,
+}
+
+/// Layout of [crate::measurements::CheatedBasisRotationInput] in roqoqo 0.10.
+#[derive(Serialize, Deserialize)]
+struct CheatedBasisRotationInput0_10 {
+    measured_exp_vals: HashMap<String, PauliProductsToExpVal>,
+    pauli_product_keys: HashMap<String, usize>,
+}
+
+/// Layout of [CheatedBasisRotation] in roqoqo 0.10.
+#[derive(Serialize, Deserialize)]
+struct CheatedBasisRotation0_10 {
+    constant_circuit: Option<Circuit0_10>,
+    circuits: Vec<Circuit0_10>,
+    input: CheatedBasisRotationInput0_10,
+}
+
+/// Layout of [crate::measurements::CheatedInput] in roqoqo 0.10.
+#[derive(Serialize, Deserialize)]
+struct CheatedInput0_10 {
+    measured_operators: HashMap<String, (OperatorSparseVec, String)>,
+    number_qubits: usize,
+}
+
+/// Layout of [Cheated] in roqoqo 0.10.
+#[derive(Serialize, Deserialize)]
+struct Cheated0_10 {
+    constant_circuit: Option<Circuit0_10>,
+    circuits: Vec<Circuit0_10>,
+    input: CheatedInput0_10,
+}
+
+/// Layout of [ClassicalRegister] in roqoqo 0.10.
+#[derive(Serialize, Deserialize)]
+struct ClassicalRegister0_10 {
+    constant_circuit: Option<Circuit0_10>,
+    circuits: Vec<Circuit0_10>,
+}
+
+/// Layout of [QuantumProgram] in roqoqo 0.10.
+#[derive(Serialize, Deserialize)]
+enum QuantumProgram0_10 {
+    BasisRotation {
+        measurement: BasisRotation0_10,
+        input_parameter_names: Vec<String>,
+    },
+    CheatedBasisRotation {
+        measurement: CheatedBasisRotation0_10,
+        input_parameter_names: Vec<String>,
+    },
+    Cheated {
+        measurement: Cheated0_10,
+        input_parameter_names: Vec<String>,
+    },
+    ClassicalRegister {
+        measurement: ClassicalRegister0_10,
+        input_parameter_names: Vec<String>,
+    },
+}
@@ -0,0 +1,248 @@
+// Copyright © 2021 HQS Quantum Simulations GmbH. All Rights Reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except
+// in compliance with the License. You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the
+// License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either
+// express or implied. See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Streaming serialization of very large circuits.
+//!
+//! [CircuitStreamWriter] serializes operations one at a time to any [std::io::Write]
+//! and [CircuitStreamReader] lazily reads them back from any [std::io::Read].
+//! Neither side needs to hold the whole [crate::Circuit] in memory.
+//! The operations are serialized with bincode, the stream starts with the roqoqo version
+//! of the writer and ends with an end marker so that truncated streams are detected.
+//!
+//! The operations of a reader can be run with
+//! [crate::backends::EvaluatingBackend::run_circuit_stream].
+//! Backends overriding it run every operation as soon as it has been read,
+//! the default implementation collects the read operations before running them.
+//!
+//! # Example
+//!
+//! ```
+//! use roqoqo::operations::{Operation, RotateX};
+//! use roqoqo::streaming::{CircuitStreamReader, CircuitStreamWriter};
+//!
+//! let mut writer = CircuitStreamWriter::new(Vec::new()).unwrap();
+//! for qubit in 0..1000 {
+//!     writer
+//!         .write_operation(&Operation::from(RotateX::new(qubit, 0.1.into())))
+//!         .unwrap();
+//! }
+//! let bytes = writer.finish().unwrap();
+//!
+//! let reader = CircuitStreamReader::new(&bytes[..]).unwrap();
+//! assert_eq!(reader.count(), 1000);
+//! ```
+
+use crate::operations::Operation;
+use crate::{Circuit, RoqoqoError, RoqoqoVersion, RoqoqoVersionSerializable};
+use std::io::{Read, Write};
+
+/// Magic bytes identifying a roqoqo operation stream.
+const STREAM_MAGIC: &[u8; 4] = b"RQOS";
+/// Record tag preceding every serialized operation.
+const OPERATION_TAG: u8 = 1;
+/// Record tag marking the end of the stream.
+const END_TAG: u8 = 0;
+
+/// Writer serializing operations incrementally to an [std::io::Write].
+///
+/// The stream is only complete after [CircuitStreamWriter::finish] has been called.
+#[derive(Debug)]
+pub struct CircuitStreamWriter<W: Write> {
+    /// The underlying writer.
+    writer: W,
+    /// Number of operations written so far.
+    number_operations: usize,
+}
+
+impl<W: Write> CircuitStreamWriter<W> {
+    /// Creates a new CircuitStreamWriter and writes the stream header.
+    ///
+    /// # Arguments
+    ///
+    /// * `writer` - The writer the operations are serialized to.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Self)` - The new CircuitStreamWriter.
+    /// * `Err(RoqoqoError::SerializationError)` - The header could not be written.
+    pub fn new(mut writer: W) -> Result<Self, RoqoqoError> {
+        writer.write_all(STREAM_MAGIC).map_err(io_error)?;
+        bincode::serialize_into(&mut writer, &RoqoqoVersionSerializable::from(RoqoqoVersion))
+            .map_err(bincode_error)?;
+        Ok(Self {
+            writer,
+            number_operations: 0,
+        })
+    }
+
+    /// Serializes a single operation to the stream.
+    ///
+    /// # Arguments
+    ///
+    /// * `operation` - The operation that is written.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - The operation was written.
+    /// * `Err(RoqoqoError::SerializationError)` - The operation could not be written.
+    pub fn write_operation(&mut self, operation: &Operation) -> Result<(), RoqoqoError> {
+        self.writer.write_all(&[OPERATION_TAG]).map_err(io_error)?;
+        bincode::serialize_into(&mut self.writer, operation).map_err(bincode_error)?;
+        self.number_operations += 1;
+        Ok(())
+    }
+
+    /// Serializes all operations of a circuit to the stream.
+    ///
+    /// # Arguments
+    ///
+    /// * `circuit` - The circuit whose operations are written.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - The operations were written.
+    /// * `Err(RoqoqoError::SerializationError)` - An operation could not be written.
+    pub fn write_circuit(&mut self, circuit: &Circuit) -> Result<(), RoqoqoError> {
+        for operation in circuit.iter() {
+            self.write_operation(operation)?;
+        }
+        Ok(())
+    }
+
+    /// Returns the number of operations written so far.
+    pub fn number_operations(&self) -> usize {
+        self.number_operations
+    }
+
+    /// Writes the end marker, flushes and returns the underlying writer.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(W)` - The underlying writer.
+    /// * `Err(RoqoqoError::SerializationError)` - The end marker could not be written.
+    pub fn finish(mut self) -> Result<W, RoqoqoError> {
+        self.writer.write_all(&[END_TAG]).map_err(io_error)?;
+        self.writer.flush().map_err(io_error)?;
+        Ok(self.writer)
+    }
+}
+
+/// Iterator lazily reading operations from an [std::io::Read].
+///
+/// Every item is read from the underlying reader when it is requested.
+/// For unbuffered sources like files the reader should be wrapped in a [std::io::BufReader].
+/// After the first error the iterator is exhausted.
+#[derive(Debug)]
+pub struct CircuitStreamReader<R: Read> {
+    /// The underlying reader.
+    reader: R,
+    /// Whether the end marker or an error has been reached.
+    finished: bool,
+}
+
+impl<R: Read> CircuitStreamReader<R> {
+    /// Creates a new CircuitStreamReader and checks the stream header.
+    ///
+    /// # Arguments
+    ///
+    /// * `reader` - The reader the operations are read from.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Self)` - The new CircuitStreamReader.
+    /// * `Err(RoqoqoError::SerializationError)` - The data is not a roqoqo operation stream.
+    /// * `Err(RoqoqoError::VersionMissmatch)` - The stream was written by an incompatible version of roqoqo.
+    pub fn new(mut reader: R) -> Result<Self, RoqoqoError> {
+        let mut magic = [0u8; 4];
+        reader.read_exact(&mut magic).map_err(io_error)?;
+        if &magic != STREAM_MAGIC {
+            return Err(RoqoqoError::SerializationError {
+                msg: "Data is not a roqoqo operation stream".to_string(),
+            });
+        }
+        let version: RoqoqoVersionSerializable =
+            bincode::deserialize_from(&mut reader).map_err(bincode_error)?;
+        RoqoqoVersion::try_from(version)?;
+        Ok(Self {
+            reader,
+            finished: false,
+        })
+    }
+
+    /// Reads all remaining operations into a Circuit.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Circuit)` - The Circuit containing all remaining operations of the stream.
+    /// * `Err(RoqoqoError)` - An operation could not be read.
+    pub fn into_circuit(self) -> Result<Circuit, RoqoqoError> {
+        let mut circuit = Circuit::new();
+        for operation in self {
+            circuit.add_operation(operation?);
+        }
+        Ok(circuit)
+    }
+
+    fn read_operation(&mut self) -> Result<Option<Operation>, RoqoqoError> {
+        let mut tag = [0u8; 1];
+        self.reader
+            .read_exact(&mut tag)
+            .map_err(|err| match err.kind() {
+                std::io::ErrorKind::UnexpectedEof => RoqoqoError::SerializationError {
+                    msg: "Operation stream ended without end marker".to_string(),
+                },
+                _ => io_error(err),
+            })?;
+        match tag[0] {
+            END_TAG => Ok(None),
+            OPERATION_TAG => Ok(Some(
+                bincode::deserialize_from(&mut self.reader).map_err(bincode_error)?,
+            )),
+            tag => Err(RoqoqoError::SerializationError {
+                msg: format!("Unknown record tag {} in operation stream", tag),
+            }),
+        }
+    }
+}
+
+impl<R: Read> Iterator for CircuitStreamReader<R> {
+    type Item = Result<Operation, RoqoqoError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.finished {
+            return None;
+        }
+        match self.read_operation() {
+            Ok(Some(operation)) => Some(Ok(operation)),
+            Ok(None) => {
+                self.finished = true;
+                None
+            }
+            Err(err) => {
+                self.finished = true;
+                Some(Err(err))
+            }
+        }
+    }
+}
+
+fn io_error(err: std::io::Error) -> RoqoqoError {
+    RoqoqoError::SerializationError {
+        msg: format!("{}", err),
+    }
+}
+
+fn bincode_error(err: bincode::Error) -> RoqoqoError {
+    RoqoqoError::SerializationError {
+        msg: format!("{}", err),
+    }
+}
@@ -0,0 +1,289 @@
+// Copyright © 2021 HQS Quantum Simulations GmbH. All Rights Reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except
+// in compliance with the License. You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the
+// License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either
+// express or implied. See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Integration test for backend capabilities and pre-flight checks
+
+use roqoqo::backends::{
+    BackendCapabilities, Incompatibility, IncompatibilityReason, LocalJobQueue, RegisterResult,
+};
+use roqoqo::measurements::ClassicalRegister;
+use roqoqo::operations::*;
+use roqoqo::prelude::*;
+use roqoqo::{Circuit, QuantumProgram, RoqoqoBackendError};
+use std::collections::HashMap;
+use test_case::test_case;
+
+/// Backend that runs nothing and reports fixed capabilities
+#[derive(Debug, Clone)]
+struct CapableBackend {
+    capabilities: Option<BackendCapabilities>,
+}
+
+impl EvaluatingBackend for CapableBackend {
+    fn run_circuit_iterator<'a>(
+        &self,
+        _circuit: impl Iterator<Item = &'a Operation>,
+    ) -> RegisterResult {
+        Ok((HashMap::new(), HashMap::new(), HashMap::new()))
+    }
+
+    fn capabilities(&self) -> Option<BackendCapabilities> {
+        self.capabilities.clone()
+    }
+}
+
+fn gate_capabilities() -> BackendCapabilities {
+    let mut capabilities = BackendCapabilities::new();
+    capabilities
+        .supported_tags
+        .insert("SingleQubitGateOperation".to_string());
+    capabilities.supported_tags.insert("Definition".to_string());
+    capabilities
+        .supported_operations
+        .insert("MeasureQubit".to_string());
+    capabilities.max_qubits = Some(2);
+    capabilities
+}
+
+fn incompatibility(
+    circuit: Option<usize>,
+    index: usize,
+    hqslang: &str,
+    reason: IncompatibilityReason,
+) -> Incompatibility {
+    Incompatibility {
+        circuit,
+        index,
+        hqslang: hqslang.to_string(),
+        reason,
+    }
+}
+
+#[test]
+fn test_supports_operation() {
+    let capabilities = gate_capabilities();
+    assert!(capabilities.supports_operation(&PauliX::new(0).into()));
+    assert!(capabilities.supports_operation(&MeasureQubit::new(0, "ro".to_string(), 0).into()));
+    assert!(!capabilities.supports_operation(&CNOT::new(0, 1).into()));
+    assert!(!BackendCapabilities::new().supports_operation(&PauliX::new(0).into()));
+}
+
+#[test]
+fn test_check_circuit_compatible() {
+    let mut circuit = Circuit::new();
+    circuit += DefinitionBit::new("ro".to_string(), 2, true);
+    circuit += RotateX::new(0, "theta".into());
+    circuit += MeasureQubit::new(0, "ro".to_string(), 0);
+    circuit += MeasureQubit::new(1, "ro".to_string(), 1);
+    assert!(circuit.check_capabilities(&gate_capabilities()).is_empty());
+}
+
+#[test]
+fn test_check_circuit_lists_all_incompatibilities() {
+    let mut circuit = Circuit::new();
+    circuit += PauliX::new(0);
+    circuit += CNOT::new(0, 1);
+    circuit += RotateZ::new(4, 0.1.into());
+    circuit += PragmaDamping::new(0, 0.01.into(), 0.1.into());
+    circuit += MeasureQubit::new(3, "ro".to_string(), 0);
+    let incompatibilities = circuit.check_capabilities(&gate_capabilities());
+    assert_eq!(
+        incompatibilities,
+        vec![
+            incompatibility(None, 1, "CNOT", IncompatibilityReason::UnsupportedOperation),
+            incompatibility(
+                None,
+                2,
+                "RotateZ",
+                IncompatibilityReason::QubitOutOfRange {
+                    qubit: 4,
+                    max_qubits: 2
+                }
+            ),
+            incompatibility(None, 3, "PragmaDamping", IncompatibilityReason::NoisePragma),
+            incompatibility(
+                None,
+                4,
+                "MeasureQubit",
+                IncompatibilityReason::QubitOutOfRange {
+                    qubit: 3,
+                    max_qubits: 2
+                }
+            ),
+        ]
+    );
+    let mut capabilities = gate_capabilities();
+    capabilities.noise_pragmas = true;
+    capabilities
+        .supported_operations
+        .insert("PragmaDamping".to_string());
+    assert_eq!(circuit.check_capabilities(&capabilities).len(), 3);
+}
+
+#[test_case(false, 1; "not supported")]
+#[test_case(true, 0; "supported")]
+fn test_check_mid_circuit_measurement(supported: bool, number_incompatibilities: usize) {
+    let mut capabilities = gate_capabilities();
+    capabilities.mid_circuit_measurement = supported;
+    let mut circuit = Circuit::new();
+    circuit += MeasureQubit::new(0, "ro".to_string(), 0);
+    circuit += PauliX::new(1);
+    circuit += MeasureQubit::new(1, "ro".to_string(), 1);
+    circuit += PauliX::new(0);
+    let incompatibilities = circuit.check_capabilities(&capabilities);
+    assert_eq!(incompatibilities.len(), number_incompatibilities);
+    if !supported {
+        assert_eq!(
+            incompatibilities[0],
+            incompatibility(
+                None,
+                0,
+                "MeasureQubit",
+                IncompatibilityReason::MidCircuitMeasurement
+            )
+        );
+    }
+}
+
+#[test]
+fn test_check_cheated_and_conditional() {
+    let mut conditional_circuit = Circuit::new();
+    conditional_circuit += CNOT::new(0, 1);
+    let mut circuit = Circuit::new();
+    circuit += PragmaConditional::new("ro".to_string(), 0, conditional_circuit);
+    circuit += PragmaGetStateVector::new("state".to_string(), None);
+    let incompatibilities = circuit.check_capabilities(&gate_capabilities());
+    assert_eq!(
+        incompatibilities,
+        vec![
+            incompatibility(
+                None,
+                0,
+                "PragmaConditional",
+                IncompatibilityReason::ConditionalOperation
+            ),
+            incompatibility(None, 0, "CNOT", IncompatibilityReason::UnsupportedOperation),
+            incompatibility(
+                None,
+                1,
+                "PragmaGetStateVector",
+                IncompatibilityReason::CheatedReadout
+            ),
+        ]
+    );
+    assert_eq!(
+        incompatibilities[2].to_string(),
+        "Operation PragmaGetStateVector at index 1: backend does not support cheated readout"
+    );
+
+    let mut capabilities = gate_capabilities();
+    capabilities.cheated_readout = true;
+    capabilities.conditional_operations = true;
+    capabilities
+        .supported_tags
+        .insert("TwoQubitGateOperation".to_string());
+    assert!(circuit.check_capabilities(&capabilities).is_empty());
+}
+
+fn classical_register_program() -> QuantumProgram {
+    let mut constant_circuit = Circuit::new();
+    constant_circuit += DefinitionBit::new("ro".to_string(), 2, true);
+    constant_circuit += CNOT::new(0, 1);
+    let mut first = Circuit::new();
+    first += PauliX::new(0);
+    first += MeasureQubit::new(0, "ro".to_string(), 0);
+    let mut second = Circuit::new();
+    second += RotateX::new(5, "theta".into());
+    QuantumProgram::ClassicalRegister {
+        measurement: ClassicalRegister {
+            constant_circuit: Some(constant_circuit),
+            circuits: vec![first, second],
+        },
+        input_parameter_names: vec!["theta".to_string()],
+    }
+}
+
+#[test]
+fn test_quantum_program_check_capabilities() {
+    let program = classical_register_program();
+    let incompatibilities = program.check_capabilities(&gate_capabilities());
+    assert_eq!(
+        incompatibilities,
+        vec![
+            incompatibility(None, 1, "CNOT", IncompatibilityReason::UnsupportedOperation),
+            incompatibility(
+                Some(1),
+                0,
+                "RotateX",
+                IncompatibilityReason::QubitOutOfRange {
+                    qubit: 5,
+                    max_qubits: 2
+                }
+            ),
+        ]
+    );
+    assert_eq!(
+        incompatibilities[1].to_string(),
+        "Operation RotateX at index 0 of circuit 1: qubit 5 exceeds the maximal number of qubits 2 of the backend"
+    );
+}
+
+#[test]
+fn test_quantum_program_preflight() {
+    let program = classical_register_program();
+    let backend = CapableBackend {
+        capabilities: Some(gate_capabilities()),
+    };
+    let error = program.preflight(&backend).unwrap_err();
+    match error {
+        RoqoqoBackendError::GenericError { msg } => {
+            assert!(msg.contains("Operation CNOT at index 1"));
+            assert!(msg.contains("Operation RotateX at index 0 of circuit 1"));
+        }
+        _ => panic!("Unexpected error {:?}", error),
+    }
+
+    let mut capabilities = gate_capabilities();
+    capabilities.max_qubits = None;
+    capabilities.supported_operations.insert("CNOT".to_string());
+    let backend = CapableBackend {
+        capabilities: Some(capabilities.clone()),
+    };
+    assert!(program.preflight(&backend).is_ok());
+
+    let unknown_backend = CapableBackend { capabilities: None };
+    assert!(program.preflight(&unknown_backend).is_ok());
+
+    let queue = LocalJobQueue::new(backend);
+    assert_eq!(queue.capabilities(), Some(capabilities));
+}
+
+#[test]
+fn test_bound_program_preflight() {
+    let mut capabilities = BackendCapabilities::new();
+    for hqslang in ["DefinitionBit", "CNOT", "PauliX", "MeasureQubit", "RotateX"] {
+        capabilities
+            .supported_operations
+            .insert(hqslang.to_string());
+    }
+    let backend = CapableBackend {
+        capabilities: Some(capabilities.clone()),
+    };
+    let program = classical_register_program();
+    assert!(program.preflight(&backend).is_ok());
+
+    let bound_program = program
+        .bind_parameters(&HashMap::from([("theta".to_string(), 0.5)]))
+        .unwrap();
+    assert!(bound_program.check_capabilities(&capabilities).is_empty());
+    assert!(bound_program.preflight(&backend).is_ok());
+}
@@ -0,0 +1,374 @@
+// Copyright © 2021 HQS Quantum Simulations GmbH. All Rights Reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except
+// in compliance with the License. You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the
+// License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either
+// express or implied. See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Integration test for public API of job based backends
+
+use qoqo_calculator::CalculatorFloat;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use roqoqo::backends::{JobStatus, LocalJobQueue};
+use roqoqo::measurements::{
+    CheatedBasisRotation, CheatedBasisRotationInput, ClassicalRegister, Histogram, HistogramInput,
+};
+use roqoqo::operations::*;
+use roqoqo::prelude::*;
+use roqoqo::registers::{BitOutputRegister, ComplexOutputRegister, FloatOutputRegister};
+use roqoqo::{Circuit, QuantumProgram, RoqoqoBackendError};
+use std::collections::HashMap;
+use std::time::Duration;
+
+/// Backend writing the angles of all RotateX operations to a float register, failing on PauliX
+#[derive(Debug, Clone, Copy)]
+struct AngleBackend;
+
+impl EvaluatingBackend for AngleBackend {
+    fn run_circuit_iterator<'a>(
+        &self,
+        circuit: impl Iterator<Item = &'a Operation>,
+    ) -> roqoqo::backends::RegisterResult {
+        let mut angles: Vec<f64> = Vec::new();
+        for operation in circuit {
+            match operation {
+                Operation::RotateX(rotation) => angles.push(*rotation.theta().float()?),
+                Operation::PauliX(_) => {
+                    return Err(RoqoqoBackendError::OperationNotInBackend {
+                        backend: "AngleBackend",
+                        hqslang: "PauliX",
+                    })
+                }
+                _ => (),
+            }
+        }
+        let mut result_bit: HashMap<String, BitOutputRegister> = HashMap::new();
+        result_bit.insert("ro".to_string(), vec![vec![true, false]]);
+        let mut result_float: HashMap<String, FloatOutputRegister> = HashMap::new();
+        result_float.insert("angles".to_string(), vec![angles]);
+        let result_complex: HashMap<String, ComplexOutputRegister> = HashMap::new();
+        Ok((result_bit, result_float, result_complex))
+    }
+}
+
+/// Backend writing the angles of all RotateX operations and a seeded random number to float registers
+#[derive(Debug, Clone, Copy)]
+struct SeededBackend;
+
+impl EvaluatingBackend for SeededBackend {
+    fn run_circuit_iterator<'a>(
+        &self,
+        circuit: impl Iterator<Item = &'a Operation>,
+    ) -> roqoqo::backends::RegisterResult {
+        self.run_circuit_iterator_with_seed(circuit, rand::thread_rng().gen())
+    }
+
+    fn run_circuit_iterator_with_seed<'a>(
+        &self,
+        circuit: impl Iterator<Item = &'a Operation>,
+        seed: u64,
+    ) -> roqoqo::backends::RegisterResult {
+        let (bit_registers, mut float_registers, complex_registers) =
+            AngleBackend.run_circuit_iterator(circuit)?;
+        let mut rng = StdRng::seed_from_u64(seed);
+        float_registers.insert("random".to_string(), vec![vec![rng.gen()]]);
+        Ok((bit_registers, float_registers, complex_registers))
+    }
+
+    fn supports_seed(&self) -> bool {
+        true
+    }
+}
+
+fn rotation_circuit(theta: CalculatorFloat) -> Circuit {
+    let mut circuit = Circuit::new();
+    circuit += RotateX::new(0, theta);
+    circuit
+}
+
+#[test]
+fn test_submit_circuit() {
+    let mut queue = LocalJobQueue::new(AngleBackend);
+    let job_id = queue.submit_circuit(&rotation_circuit(0.5.into())).unwrap();
+    assert_eq!(queue.number_queued(), 1);
+    assert_eq!(queue.poll(job_id).unwrap(), JobStatus::Completed);
+    assert_eq!(queue.number_queued(), 0);
+    let (bit_registers, float_registers, _) = queue.fetch_result(job_id).unwrap();
+    assert_eq!(bit_registers["ro"], vec![vec![true, false]]);
+    assert_eq!(float_registers["angles"], vec![vec![0.5]]);
+    // The result has been removed from the queue
+    assert!(queue.poll(job_id).is_err());
+    assert!(queue.fetch_result(job_id).is_err());
+}
+
+#[test]
+fn test_submit_measurement() {
+    let mut queue = LocalJobQueue::new(AngleBackend);
+    let measurement = ClassicalRegister {
+        constant_circuit: Some(rotation_circuit(0.1.into())),
+        circuits: vec![rotation_circuit(0.2.into()), rotation_circuit(0.3.into())],
+    };
+    let job_id = queue.submit_measurement(&measurement).unwrap();
+    let (_, float_registers, _) = queue
+        .wait_for_result(job_id, Duration::from_millis(1))
+        .unwrap();
+    assert_eq!(
+        float_registers["angles"],
+        AngleBackend
+            .run_measurement_registers(&measurement)
+            .unwrap()
+            .1["angles"]
+    );
+    assert_eq!(float_registers["angles"].len(), 2);
+}
+
+#[test]
+fn test_queue_order_and_cancel() {
+    let mut queue = LocalJobQueue::new(AngleBackend);
+    let first = queue.submit_circuit(&rotation_circuit(0.1.into())).unwrap();
+    let second = queue.submit_circuit(&rotation_circuit(0.2.into())).unwrap();
+    let third = queue.submit_circuit(&rotation_circuit(0.3.into())).unwrap();
+    assert_ne!(first, second);
+    assert_eq!(queue.number_queued(), 3);
+
+    queue.cancel(second).unwrap();
+    assert_eq!(queue.poll(second).unwrap(), JobStatus::Cancelled);
+    assert_eq!(queue.number_queued(), 2);
+    assert!(queue.fetch_result(second).is_err());
+
+    assert_eq!(queue.process_next(), Some(first));
+    assert_eq!(queue.poll(first).unwrap(), JobStatus::Completed);
+    assert!(queue.cancel(first).is_err());
+    assert_eq!(queue.poll(third).unwrap(), JobStatus::Completed);
+    queue.process_all();
+    assert_eq!(queue.process_next(), None);
+    assert!(queue.cancel(100).is_err());
+}
+
+#[test]
+fn test_failed_job() {
+    let mut queue = LocalJobQueue::new(AngleBackend);
+    let mut circuit = rotation_circuit(0.1.into());
+    circuit += PauliX::new(0);
+    let job_id = queue.submit_circuit(&circuit).unwrap();
+    let status = queue.poll(job_id).unwrap();
+    assert_eq!(status, JobStatus::Failed);
+    assert!(status.is_finished());
+    assert!(!JobStatus::Queued.is_finished());
+    assert_eq!(
+        queue.fetch_result(job_id),
+        Err(RoqoqoBackendError::OperationNotInBackend {
+            backend: "AngleBackend",
+            hqslang: "PauliX",
+        })
+    );
+}
+
+#[test]
+fn test_quantum_program_submit() {
+    let mut input = CheatedBasisRotationInput::new();
+    let index = input.add_pauli_product("angles".to_string());
+    let mut linear: HashMap<usize, f64> = HashMap::new();
+    linear.insert(index, 2.0);
+    input
+        .add_linear_exp_val("double".to_string(), linear)
+        .unwrap();
+    let measurement = CheatedBasisRotation {
+        constant_circuit: None,
+        circuits: vec![rotation_circuit("theta".into())],
+        input,
+    };
+    let program = QuantumProgram::CheatedBasisRotation {
+        measurement,
+        input_parameter_names: vec!["theta".to_string()],
+    };
+
+    let mut queue = LocalJobQueue::new(AngleBackend);
+    assert!(program.submit(&mut queue, &[0.1, 0.2]).is_err());
+    let job = program.submit(&mut queue, &[0.25]).unwrap();
+    match &job.program {
+        QuantumProgram::CheatedBasisRotation {
+            input_parameter_names,
+            ..
+        } => assert!(input_parameter_names.is_empty()),
+        _ => panic!("Unexpected program variant"),
+    }
+    assert_eq!(job.status(&mut queue).unwrap(), JobStatus::Completed);
+    assert!(job.result_registers(&mut queue).is_err());
+    assert!(job.result_histogram(&mut queue).is_err());
+    let result = job.result(&mut queue).unwrap().unwrap();
+    assert_eq!(result["double"], 0.5);
+    assert_eq!(result, program.run(AngleBackend, &[0.25]).unwrap().unwrap());
+
+    let job = program.submit(&mut queue, &[0.25]).unwrap();
+    job.cancel(&mut queue).unwrap();
+    assert!(job.result(&mut queue).is_err());
+}
+
+#[test]
+fn test_quantum_program_submit_registers() {
+    let program = QuantumProgram::ClassicalRegister {
+        measurement: ClassicalRegister {
+            constant_circuit: None,
+            circuits: vec![rotation_circuit("theta".into())],
+        },
+        input_parameter_names: vec!["theta".to_string()],
+    };
+    let mut queue = LocalJobQueue::new(AngleBackend);
+    let job = program.submit(&mut queue, &[0.75]).unwrap();
+    assert!(job.result(&mut queue).is_err());
+    let (_, float_registers, _) = job.result_registers(&mut queue).unwrap();
+    assert_eq!(float_registers["angles"], vec![vec![0.75]]);
+
+    let mut histogram_input = HistogramInput::new();
+    histogram_input
+        .add_marginal("first".to_string(), "ro".to_string(), vec![0])
+        .unwrap();
+    let program = QuantumProgram::Histogram {
+        measurement: Histogram {
+            constant_circuit: None,
+            circuits: vec![rotation_circuit(0.0.into())],
+            input: histogram_input,
+        },
+        input_parameter_names: vec![],
+    };
+    let job = program.submit(&mut queue, &[]).unwrap();
+    assert!(job.result(&mut queue).is_err());
+    let result = job.result_histogram(&mut queue).unwrap();
+    assert_eq!(result.counts["ro"]["10"], 1);
+    assert_eq!(result.marginal_probabilities["first"]["1"], 1.0);
+}
+
+fn many_circuits_measurement() -> ClassicalRegister {
+    ClassicalRegister {
+        constant_circuit: Some(rotation_circuit(0.0.into())),
+        circuits: (1..40)
+            .map(|index| rotation_circuit((index as f64).into()))
+            .collect(),
+    }
+}
+
+#[test]
+fn test_run_measurement_registers_seeded() {
+    let measurement = many_circuits_measurement();
+    let (_, float_registers, _) = SeededBackend
+        .run_measurement_registers_seeded(&measurement, 42)
+        .unwrap();
+    let (_, float_registers_repeated, _) = SeededBackend
+        .run_measurement_registers_seeded(&measurement, 42)
+        .unwrap();
+    let (_, float_registers_other_seed, _) = SeededBackend
+        .run_measurement_registers_seeded(&measurement, 43)
+        .unwrap();
+    assert_eq!(float_registers, float_registers_repeated);
+    assert_ne!(
+        float_registers["random"],
+        float_registers_other_seed["random"]
+    );
+    // Registers are combined in the order of the circuits
+    let expected_angles: Vec<Vec<f64>> = (1..40).map(|index| vec![0.0, index as f64]).collect();
+    assert_eq!(float_registers["angles"], expected_angles);
+    // Different circuits use different seeds
+    assert_ne!(float_registers["random"][0], float_registers["random"][1]);
+
+    // The default implementation ignores the seed
+    assert_eq!(
+        AngleBackend
+            .run_measurement_registers_seeded(&measurement, 42)
+            .unwrap(),
+        AngleBackend
+            .run_measurement_registers(&measurement)
+            .unwrap()
+    );
+}
+
+/// Returns whether a backend passed by value supports seeds
+fn supports_seed<B: EvaluatingBackend>(backend: B) -> bool {
+    backend.supports_seed()
+}
+
+#[test]
+fn test_supports_seed() {
+    assert!(!supports_seed(AngleBackend));
+    assert!(supports_seed(SeededBackend));
+    // References forward to the referenced backend
+    let angle_backend: &AngleBackend = &AngleBackend;
+    let seeded_backend: &SeededBackend = &SeededBackend;
+    assert!(!supports_seed(angle_backend));
+    assert!(supports_seed(seeded_backend));
+}
+
+#[cfg(feature = "serialize")]
+#[test]
+fn test_supports_seed_wrappers() {
+    use roqoqo::backends::{CachingBackend, RecordingBackend, ReplayBackend};
+    let measurement = many_circuits_measurement();
+    assert!(!CachingBackend::new(AngleBackend, 10).supports_seed());
+    assert!(!RecordingBackend::new(AngleBackend).supports_seed());
+
+    // Without cache entries every run is executed by the seeded backend
+    let caching_backend = CachingBackend::new(SeededBackend, 0);
+    assert!(caching_backend.supports_seed());
+    let registers = caching_backend
+        .run_measurement_registers_seeded(&measurement, 42)
+        .unwrap();
+    assert_eq!(
+        caching_backend
+            .run_measurement_registers_seeded(&measurement, 42)
+            .unwrap(),
+        registers
+    );
+
+    let recording_backend = RecordingBackend::new(SeededBackend);
+    assert!(recording_backend.supports_seed());
+    assert_eq!(
+        recording_backend
+            .run_measurement_registers_seeded(&measurement, 42)
+            .unwrap(),
+        registers
+    );
+    let replay_backend = ReplayBackend::new(recording_backend.recording());
+    assert!(replay_backend.supports_seed());
+    assert_eq!(
+        replay_backend
+            .run_measurement_registers_seeded(&measurement, 42)
+            .unwrap(),
+        registers
+    );
+}
+
+#[cfg(feature = "parallel")]
+#[test]
+fn test_run_measurement_registers_parallel() {
+    let measurement = many_circuits_measurement();
+    for seed in [0, 7, u64::MAX] {
+        assert_eq!(
+            SeededBackend
+                .run_measurement_registers_parallel(&measurement, seed)
+                .unwrap(),
+            SeededBackend
+                .run_measurement_registers_seeded(&measurement, seed)
+                .unwrap()
+        );
+    }
+    assert_eq!(
+        AngleBackend
+            .run_measurement_registers_parallel(&measurement, 0)
+            .unwrap(),
+        AngleBackend
+            .run_measurement_registers(&measurement)
+            .unwrap()
+    );
+
+    let mut failing_measurement = measurement;
+    failing_measurement.circuits[3] += PauliX::new(0);
+    assert!(SeededBackend
+        .run_measurement_registers_parallel(&failing_measurement, 0)
+        .is_err());
+}
@@ -0,0 +1,260 @@
+// Copyright © 2021 HQS Quantum Simulations GmbH. All Rights Reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except
+// in compliance with the License. You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the
+// License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either
+// express or implied. See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Integration test for public API of the caching backend
+
+use roqoqo::backends::{stable_hash_operations, CacheStatistics, CachingBackend};
+use roqoqo::measurements::ClassicalRegister;
+use roqoqo::operations::*;
+use roqoqo::prelude::*;
+use roqoqo::registers::{BitOutputRegister, ComplexOutputRegister, FloatOutputRegister};
+use roqoqo::{Circuit, QuantumProgram, RoqoqoBackendError};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Backend counting how often it runs a circuit, failing on PauliX
+#[derive(Debug, Default)]
+struct CountingBackend {
+    runs: AtomicUsize,
+}
+
+impl EvaluatingBackend for CountingBackend {
+    fn run_circuit_iterator<'a>(
+        &self,
+        circuit: impl Iterator<Item = &'a Operation>,
+    ) -> roqoqo::backends::RegisterResult {
+        self.run_circuit_iterator_with_seed(circuit, 0)
+    }
+
+    fn run_circuit_iterator_with_seed<'a>(
+        &self,
+        circuit: impl Iterator<Item = &'a Operation>,
+        seed: u64,
+    ) -> roqoqo::backends::RegisterResult {
+        let run = self.runs.fetch_add(1, Ordering::SeqCst);
+        let mut angles: Vec<f64> = Vec::new();
+        for operation in circuit {
+            match operation {
+                Operation::RotateX(rotation) => angles.push(*rotation.theta().float()?),
+                Operation::PauliX(_) => {
+                    return Err(RoqoqoBackendError::OperationNotInBackend {
+                        backend: "CountingBackend",
+                        hqslang: "PauliX",
+                    })
+                }
+                _ => (),
+            }
+        }
+        let result_bit: HashMap<String, BitOutputRegister> = HashMap::new();
+        let mut result_float: HashMap<String, FloatOutputRegister> = HashMap::new();
+        result_float.insert("angles".to_string(), vec![angles]);
+        result_float.insert("run".to_string(), vec![vec![run as f64, seed as f64]]);
+        let result_complex: HashMap<String, ComplexOutputRegister> = HashMap::new();
+        Ok((result_bit, result_float, result_complex))
+    }
+}
+
+fn rotation_circuit(theta: f64) -> Circuit {
+    let mut circuit = Circuit::new();
+    circuit += DefinitionBit::new("ro".to_string(), 1, true);
+    circuit += RotateX::new(0, theta.into());
+    circuit
+}
+
+#[test]
+fn test_stable_hash() {
+    let circuit = rotation_circuit(0.5);
+    assert_eq!(
+        circuit.stable_hash().unwrap(),
+        rotation_circuit(0.5).stable_hash().unwrap()
+    );
+    assert_ne!(
+        circuit.stable_hash().unwrap(),
+        rotation_circuit(0.25).stable_hash().unwrap()
+    );
+    assert_eq!(
+        circuit.stable_hash().unwrap(),
+        stable_hash_operations(circuit.iter()).unwrap()
+    );
+    assert_ne!(
+        Circuit::new().stable_hash().unwrap(),
+        circuit.stable_hash().unwrap()
+    );
+}
+
+#[test]
+fn test_caching() {
+    let backend = CachingBackend::new(CountingBackend::default(), 10);
+    assert_eq!(backend.capacity(), 10);
+    let first = backend.run_circuit(&rotation_circuit(0.5)).unwrap();
+    let repeated = backend.run_circuit(&rotation_circuit(0.5)).unwrap();
+    assert_eq!(first, repeated);
+    let other = backend.run_circuit(&rotation_circuit(0.25)).unwrap();
+    assert_ne!(first, other);
+    assert_eq!(backend.backend().runs.load(Ordering::SeqCst), 2);
+    assert_eq!(
+        backend.statistics(),
+        CacheStatistics {
+            hits: 1,
+            misses: 2,
+            evictions: 0,
+            entries: 2
+        }
+    );
+
+    backend.clear();
+    assert_eq!(backend.statistics(), CacheStatistics::default());
+    let _ = backend.run_circuit(&rotation_circuit(0.5)).unwrap();
+    assert_eq!(backend.backend().runs.load(Ordering::SeqCst), 3);
+}
+
+#[test]
+fn test_caching_seeded() {
+    let backend = CachingBackend::new(CountingBackend::default(), 10);
+    let circuit = rotation_circuit(0.5);
+    let unseeded = backend.run_circuit(&circuit).unwrap();
+    let seeded = backend
+        .run_circuit_iterator_with_seed(circuit.iter(), 3)
+        .unwrap();
+    let seeded_repeated = backend
+        .run_circuit_iterator_with_seed(circuit.iter(), 3)
+        .unwrap();
+    let other_seed = backend
+        .run_circuit_iterator_with_seed(circuit.iter(), 4)
+        .unwrap();
+    assert_ne!(unseeded, seeded);
+    assert_eq!(seeded, seeded_repeated);
+    assert_ne!(seeded, other_seed);
+    assert_eq!(backend.backend().runs.load(Ordering::SeqCst), 3);
+}
+
+#[test]
+fn test_lru_eviction() {
+    let backend = CachingBackend::new(CountingBackend::default(), 2);
+    let _ = backend.run_circuit(&rotation_circuit(0.1)).unwrap();
+    let _ = backend.run_circuit(&rotation_circuit(0.2)).unwrap();
+    // Using 0.1 makes 0.2 the least recently used result
+    let _ = backend.run_circuit(&rotation_circuit(0.1)).unwrap();
+    let _ = backend.run_circuit(&rotation_circuit(0.3)).unwrap();
+    assert_eq!(backend.statistics().evictions, 1);
+    assert_eq!(backend.statistics().entries, 2);
+    let _ = backend.run_circuit(&rotation_circuit(0.1)).unwrap();
+    assert_eq!(backend.backend().runs.load(Ordering::SeqCst), 3);
+    let _ = backend.run_circuit(&rotation_circuit(0.2)).unwrap();
+    assert_eq!(backend.backend().runs.load(Ordering::SeqCst), 4);
+
+    let uncached = CachingBackend::new(CountingBackend::default(), 0);
+    let _ = uncached.run_circuit(&rotation_circuit(0.1)).unwrap();
+    let _ = uncached.run_circuit(&rotation_circuit(0.1)).unwrap();
+    assert_eq!(uncached.backend().runs.load(Ordering::SeqCst), 2);
+    assert_eq!(uncached.statistics().entries, 0);
+}
+
+#[test]
+fn test_errors_not_cached() {
+    let backend = CachingBackend::new(CountingBackend::default(), 10);
+    let mut circuit = rotation_circuit(0.1);
+    circuit += PauliX::new(0);
+    assert!(backend.run_circuit(&circuit).is_err());
+    assert!(backend.run_circuit(&circuit).is_err());
+    assert_eq!(backend.backend().runs.load(Ordering::SeqCst), 2);
+    assert_eq!(backend.statistics().entries, 0);
+}
+
+#[test]
+fn test_quantum_program_caching() {
+    let program = QuantumProgram::ClassicalRegister {
+        measurement: ClassicalRegister {
+            constant_circuit: None,
+            circuits: vec![{
+                let mut circuit = Circuit::new();
+                circuit += RotateX::new(0, "theta".into());
+                circuit
+            }],
+        },
+        input_parameter_names: vec!["theta".to_string()],
+    };
+    let backend = CachingBackend::new(CountingBackend::default(), 10);
+    let first = program.run_registers(&backend, &[0.5]).unwrap();
+    let second = program.run_registers(&backend, &[0.5]).unwrap();
+    let _ = program.run_registers(&backend, &[0.75]).unwrap();
+    assert_eq!(first, second);
+    assert_eq!(backend.statistics().hits, 1);
+    assert_eq!(backend.statistics().misses, 2);
+}
+
+#[test]
+fn test_write_read_cache() {
+    let backend = CachingBackend::new(CountingBackend::default(), 10);
+    let first = backend.run_circuit(&rotation_circuit(0.1)).unwrap();
+    let _ = backend.run_circuit(&rotation_circuit(0.2)).unwrap();
+    let mut buffer: Vec<u8> = Vec::new();
+    backend.write_cache(&mut buffer).unwrap();
+
+    let restored = CachingBackend::new(CountingBackend::default(), 10);
+    restored.read_cache(buffer.as_slice()).unwrap();
+    assert_eq!(restored.statistics().entries, 2);
+    assert_eq!(restored.run_circuit(&rotation_circuit(0.1)).unwrap(), first);
+    assert_eq!(restored.backend().runs.load(Ordering::SeqCst), 0);
+
+    let small = CachingBackend::new(CountingBackend::default(), 1);
+    small.read_cache(buffer.as_slice()).unwrap();
+    assert_eq!(small.statistics().entries, 1);
+    // The most recently used result is kept
+    let _ = small.run_circuit(&rotation_circuit(0.2)).unwrap();
+    assert_eq!(small.backend().runs.load(Ordering::SeqCst), 0);
+
+    assert!(restored.read_cache(&buffer[..3]).is_err());
+}
+
+/// Circuit with multi-entry HashMaps, whose iteration order differs between HashMap instances
+fn mapping_circuit(reversed: bool) -> Circuit {
+    let mut keys: Vec<usize> = (0..16).collect();
+    if reversed {
+        keys.reverse();
+    }
+    let mut qubit_mapping: HashMap<usize, usize> = HashMap::new();
+    let mut qubit_paulis: HashMap<usize, usize> = HashMap::new();
+    for key in keys {
+        let _ = qubit_mapping.insert(key, 15 - key);
+        let _ = qubit_paulis.insert(key, key % 4);
+    }
+    let mut circuit = rotation_circuit(0.5);
+    circuit += PragmaGetPauliProduct::new(qubit_paulis, "ro".to_string(), Circuit::new());
+    circuit += PragmaRepeatedMeasurement::new("ro".to_string(), 10, Some(qubit_mapping));
+    circuit
+}
+
+#[test]
+fn test_stable_hash_maps() {
+    let circuit = mapping_circuit(false);
+    let hash = circuit.stable_hash().unwrap();
+    assert_eq!(mapping_circuit(true).stable_hash().unwrap(), hash);
+    let serialized = bincode::serialize(&circuit).unwrap();
+    let deserialized: Circuit = bincode::deserialize(&serialized).unwrap();
+    assert_eq!(deserialized, circuit);
+    assert_eq!(deserialized.stable_hash().unwrap(), hash);
+}
+
+#[test]
+fn test_write_read_cache_maps() {
+    let backend = CachingBackend::new(CountingBackend::default(), 10);
+    let first = backend.run_circuit(&mapping_circuit(false)).unwrap();
+    let mut buffer: Vec<u8> = Vec::new();
+    backend.write_cache(&mut buffer).unwrap();
+
+    let restored = CachingBackend::new(CountingBackend::default(), 10);
+    restored.read_cache(buffer.as_slice()).unwrap();
+    assert_eq!(restored.run_circuit(&mapping_circuit(true)).unwrap(), first);
+    assert_eq!(restored.backend().runs.load(Ordering::SeqCst), 0);
+    assert_eq!(restored.statistics().hits, 1);
+}
@@ -16,6 +16,12 @@ mod operations;
 #[cfg(test)]
 mod measurements;
 
+#[cfg(test)]
+mod backends;
+
+#[cfg(test)]
+mod backend_capabilities;
+
 #[cfg(test)]
 mod circuit;
 
@@ -24,3 +30,27 @@ mod devices;
 
 #[cfg(test)]
 mod quantum_program;
+
+#[cfg(test)]
+mod optimization;
+
+#[cfg(test)]
+mod qir;
+
+#[cfg(test)]
+mod render;
+
+#[cfg(feature = "binary_format")]
+mod serialization;
+
+#[cfg(feature = "serialize")]
+mod streaming;
+
+#[cfg(feature = "serialize")]
+mod caching_backend;
+
+#[cfg(feature = "serialize")]
+mod recording_backend;
+
+#[cfg(feature = "json_schema")]
+mod schema;
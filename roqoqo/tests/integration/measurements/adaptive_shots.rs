@@ -0,0 +1,190 @@
+// Copyright © 2021 HQS Quantum Simulations GmbH. All Rights Reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except
+// in compliance with the License. You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the
+// License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either
+// express or implied. See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Integration test for public API of adaptive shot allocation
+
+use roqoqo::backends::RegisterResult;
+use roqoqo::measurements::{AdaptiveBasisRotation, BasisRotation, BasisRotationInput};
+use roqoqo::operations::*;
+use roqoqo::prelude::*;
+use roqoqo::registers::{BitOutputRegister, ComplexOutputRegister, FloatOutputRegister};
+use roqoqo::Circuit;
+use std::cell::Cell;
+use std::collections::HashMap;
+
+/// Creates the readout of a single qubit where every period-th shot is true
+fn register(number_shots: usize, period: usize) -> BitOutputRegister {
+    (0..number_shots)
+        .map(|shot| vec![shot % period == period - 1])
+        .collect()
+}
+
+/// Backend measuring < Z > = 0 in register ro_a and < Z > = 0.8 in register ro_b
+struct CountingBackend {
+    shots: Cell<usize>,
+}
+
+impl EvaluatingBackend for CountingBackend {
+    fn run_circuit_iterator<'a>(
+        &self,
+        circuit: impl Iterator<Item = &'a Operation>,
+    ) -> RegisterResult {
+        let mut bit_registers: HashMap<String, BitOutputRegister> = HashMap::new();
+        for operation in circuit {
+            if let Operation::PragmaRepeatedMeasurement(op) = operation {
+                let number_shots = *op.number_measurements();
+                self.shots.set(self.shots.get() + number_shots);
+                let period = if op.readout() == "ro_a" { 2 } else { 10 };
+                bit_registers.insert(op.readout().clone(), register(number_shots, period));
+            }
+        }
+        let float_registers: HashMap<String, FloatOutputRegister> = HashMap::new();
+        let complex_registers: HashMap<String, ComplexOutputRegister> = HashMap::new();
+        Ok((bit_registers, float_registers, complex_registers))
+    }
+}
+
+/// Creates a measurement of the sum of the Pauli products in registers ro_a and ro_b
+fn measurement() -> BasisRotation {
+    let mut input = BasisRotationInput::new(1, false);
+    let index_a = input
+        .add_pauli_product("ro_a".to_string(), vec![0])
+        .unwrap();
+    let index_b = input
+        .add_pauli_product("ro_b".to_string(), vec![0])
+        .unwrap();
+    let mut linear: HashMap<usize, f64> = HashMap::new();
+    linear.insert(index_a, 1.0);
+    linear.insert(index_b, 1.0);
+    input.add_linear_exp_val("sum".to_string(), linear).unwrap();
+    let mut circuits: Vec<Circuit> = Vec::new();
+    for readout in ["ro_a", "ro_b"] {
+        let mut circuit = Circuit::new();
+        circuit += DefinitionBit::new(readout.to_string(), 1, true);
+        circuit += PragmaRepeatedMeasurement::new(readout.to_string(), 10, None);
+        circuits.push(circuit);
+    }
+    BasisRotation {
+        constant_circuit: None,
+        circuits,
+        input,
+    }
+}
+
+/// Test that evaluation is incomplete until the target precision is reached
+#[test]
+fn evaluate_incomplete() {
+    let mut bit_registers: HashMap<String, BitOutputRegister> = HashMap::new();
+    bit_registers.insert("ro_a".to_string(), register(100, 2));
+    bit_registers.insert("ro_b".to_string(), register(100, 10));
+
+    let adaptive = AdaptiveBasisRotation::new(measurement(), 0.01, 10000);
+    let result = adaptive
+        .evaluate(bit_registers.clone(), HashMap::new(), HashMap::new())
+        .unwrap();
+    assert_eq!(result, None);
+
+    let adaptive = AdaptiveBasisRotation::new(measurement(), 1.0, 10000);
+    let result = adaptive
+        .evaluate(bit_registers.clone(), HashMap::new(), HashMap::new())
+        .unwrap()
+        .unwrap();
+    assert!((result["sum"] - 0.8).abs() < 1e-10);
+
+    // The budget is exhausted
+    let adaptive = AdaptiveBasisRotation::new(measurement(), 0.01, 200);
+    let result = adaptive
+        .evaluate(bit_registers, HashMap::new(), HashMap::new())
+        .unwrap();
+    assert!(result.is_some());
+}
+
+/// Test that more shots are allocated to the register with the larger variance
+#[test]
+fn shot_allocation() {
+    let mut bit_registers: HashMap<String, BitOutputRegister> = HashMap::new();
+    bit_registers.insert("ro_a".to_string(), register(100, 2));
+    bit_registers.insert("ro_b".to_string(), register(100, 10));
+
+    let adaptive = AdaptiveBasisRotation::new(measurement(), 0.01, 10000);
+    let allocation = adaptive.shot_allocation(&bit_registers).unwrap();
+    assert_eq!(allocation["ro_a"] + allocation["ro_b"], 1000);
+    assert!(allocation["ro_a"] > allocation["ro_b"]);
+    assert!(allocation["ro_b"] > 0);
+
+    // Allocation is limited by the remaining budget
+    let adaptive = AdaptiveBasisRotation::new(measurement(), 0.01, 250);
+    let allocation = adaptive.shot_allocation(&bit_registers).unwrap();
+    assert_eq!(allocation["ro_a"] + allocation["ro_b"], 50);
+
+    // No shots are allocated when the target precision is reached
+    let adaptive = AdaptiveBasisRotation::new(measurement(), 1.0, 10000);
+    let allocation = adaptive.shot_allocation(&bit_registers).unwrap();
+    assert_eq!(allocation["ro_a"] + allocation["ro_b"], 0);
+}
+
+/// Test running until the target precision is reached
+#[test]
+fn run_to_precision() {
+    let backend = CountingBackend {
+        shots: Cell::new(0),
+    };
+    let adaptive = AdaptiveBasisRotation::new(measurement(), 0.02, 100000)
+        .with_initial_shots(100)
+        .with_shots_per_round(500);
+    let result = adaptive.run(&backend).unwrap().unwrap();
+    assert!((result["sum"] - 0.8).abs() < 0.05);
+    assert!(backend.shots.get() > 200);
+    assert!(backend.shots.get() < 100000);
+}
+
+/// Test running until the shot budget is exhausted
+#[test]
+fn run_to_budget() {
+    let backend = CountingBackend {
+        shots: Cell::new(0),
+    };
+    let adaptive = AdaptiveBasisRotation::new(measurement(), 1e-6, 1000).with_initial_shots(100);
+    let result = adaptive.run(&backend).unwrap();
+    assert!(result.is_some());
+    assert_eq!(backend.shots.get(), 1000);
+}
+
+/// Test that circuits need to set the number of measurements
+#[test]
+fn run_without_number_measurements() {
+    let backend = CountingBackend {
+        shots: Cell::new(0),
+    };
+    let mut measurement = measurement();
+    measurement.circuits[0] = Circuit::new();
+    let adaptive = AdaptiveBasisRotation::new(measurement, 0.01, 1000);
+    assert!(adaptive.run(&backend).is_err());
+}
+
+/// Test parameter substitution
+#[test]
+fn substitute_parameters() {
+    let mut measurement = measurement();
+    let mut constant_circuit = Circuit::new();
+    constant_circuit += RotateX::new(0, "theta".into());
+    measurement.constant_circuit = Some(constant_circuit);
+    let adaptive = AdaptiveBasisRotation::new(measurement, 0.01, 1000).with_shots_per_round(10);
+    let mut parameters: HashMap<String, f64> = HashMap::new();
+    parameters.insert("theta".to_string(), 0.5);
+    let substituted = adaptive.substitute_parameters(parameters).unwrap();
+    let mut expected = Circuit::new();
+    expected += RotateX::new(0, 0.5.into());
+    assert_eq!(substituted.constant_circuit(), &Some(expected));
+    assert_eq!(substituted.shots_per_round, 10);
+    assert_eq!(substituted.target_standard_error, 0.01);
+}
@@ -0,0 +1,341 @@
+// Copyright © 2021 HQS Quantum Simulations GmbH. All Rights Reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except
+// in compliance with the License. You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the
+// License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either
+// express or implied. See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Integration test for public API of the BasisRotation builder
+
+use ndarray::Array2;
+use num_complex::Complex64;
+use roqoqo::measurements::{
+    parse_pauli_product, BasisRotation, BasisRotationBuilder, PauliGrouping, PauliProductsToExpVal,
+    PauliString,
+};
+use roqoqo::operations::*;
+use roqoqo::prelude::*;
+use roqoqo::registers::BitOutputRegister;
+use roqoqo::{Circuit, RoqoqoError};
+use std::collections::HashMap;
+use std::f64::consts::FRAC_PI_2;
+use test_case::test_case;
+
+fn operator(terms: &[(&str, f64)]) -> HashMap<String, f64> {
+    terms
+        .iter()
+        .map(|(pauli_product, coefficient)| (pauli_product.to_string(), *coefficient))
+        .collect()
+}
+
+/// Test parsing of Pauli products
+#[test]
+fn parse_pauli_products() {
+    let mut expected = PauliString::new();
+    expected.insert(0, 'X');
+    expected.insert(12, 'Z');
+    assert_eq!(parse_pauli_product("0X12Z").unwrap(), expected);
+    assert_eq!(parse_pauli_product("0X1I12Z").unwrap(), expected);
+    assert_eq!(parse_pauli_product("").unwrap(), PauliString::new());
+}
+
+/// Test that invalid Pauli products are rejected
+#[test_case("X"; "missing qubit")]
+#[test_case("0"; "missing operator")]
+#[test_case("0A"; "unknown operator")]
+#[test_case("0X0Z"; "repeated qubit")]
+fn parse_invalid_pauli_products(pauli_product: &str) {
+    assert!(parse_pauli_product(pauli_product).is_err());
+}
+
+/// Test grouping into qubit-wise commuting sets and the generated circuits
+#[test]
+fn build_basis_rotation() {
+    let mut builder = BasisRotationBuilder::new(3, 100);
+    builder
+        .add_operator(
+            "energy".to_string(),
+            &operator(&[("0X1Z", 0.5), ("2Y", -1.0), ("0Z", 0.3), ("", 2.0)]),
+        )
+        .unwrap();
+    let measurement = builder.build().unwrap();
+
+    assert_eq!(measurement.circuits.len(), 2);
+    assert_eq!(measurement.input.number_pauli_products, 4);
+    assert_eq!(measurement.constant_circuit, None);
+
+    let mut expected = Circuit::new();
+    expected += DefinitionBit::new("ro_0".to_string(), 3, true);
+    expected += Hadamard::new(0);
+    expected += RotateX::new(2, FRAC_PI_2.into());
+    expected += PragmaRepeatedMeasurement::new("ro_0".to_string(), 100, None);
+    assert_eq!(measurement.circuits[0], expected);
+
+    let mut expected = Circuit::new();
+    expected += DefinitionBit::new("ro_1".to_string(), 3, true);
+    expected += PragmaRepeatedMeasurement::new("ro_1".to_string(), 100, None);
+    assert_eq!(measurement.circuits[1], expected);
+
+    let masks = &measurement.input.pauli_product_qubit_masks;
+    assert_eq!(masks["ro_0"].len(), 3);
+    assert!(masks["ro_0"].values().any(|mask| mask == &vec![0, 1]));
+    assert!(masks["ro_0"].values().any(|mask| mask == &vec![2]));
+    assert!(masks["ro_0"].values().any(|mask| mask.is_empty()));
+    assert_eq!(masks["ro_1"].values().next().unwrap(), &vec![0]);
+}
+
+/// Test evaluating the built measurement
+#[test]
+fn evaluate_built_measurement() {
+    let mut builder = BasisRotationBuilder::new(2, 2);
+    builder
+        .add_operator(
+            "energy".to_string(),
+            &operator(&[("0X", 1.0), ("1Z", 0.5), ("0Z", 0.25), ("", 2.0)]),
+        )
+        .unwrap();
+    builder
+        .add_operator("magnetization".to_string(), &operator(&[("1Z", 1.0)]))
+        .unwrap();
+    let measurement = builder.build().unwrap();
+    assert_eq!(measurement.input.number_pauli_products, 4);
+
+    let mut registers: HashMap<String, BitOutputRegister> = HashMap::new();
+    // X0 = -1 and Z1 = 1 in the first basis, Z0 = 0 in the second basis
+    registers.insert(
+        "ro_0".to_string(),
+        vec![vec![true, false], vec![true, false]],
+    );
+    registers.insert(
+        "ro_1".to_string(),
+        vec![vec![true, false], vec![false, false]],
+    );
+    let result = measurement
+        .evaluate(registers, HashMap::new(), HashMap::new())
+        .unwrap()
+        .unwrap();
+    assert!((result["energy"] - 1.5).abs() < 1e-10);
+    assert!((result["magnetization"] - 1.0).abs() < 1e-10);
+}
+
+/// Test options for flipped measurements, MeasureQubit and the constant circuit
+#[test]
+fn build_options() {
+    let mut constant_circuit = Circuit::new();
+    constant_circuit += Hadamard::new(0);
+    let mut builder = BasisRotationBuilder::new(2, 10)
+        .with_flipped_measurement(true)
+        .with_measure_qubit(true)
+        .with_constant_circuit(Some(constant_circuit.clone()));
+    builder
+        .add_operator("x".to_string(), &operator(&[("0X", 1.0)]))
+        .unwrap();
+    let measurement = builder.build().unwrap();
+    assert_eq!(measurement.constant_circuit, Some(constant_circuit));
+    assert!(measurement.input.use_flipped_measurement);
+    assert_eq!(measurement.circuits.len(), 2);
+
+    let mut expected = Circuit::new();
+    expected += DefinitionBit::new("ro_0_flipped".to_string(), 2, true);
+    expected += Hadamard::new(0);
+    expected += PauliX::new(0);
+    expected += PauliX::new(1);
+    expected += MeasureQubit::new(0, "ro_0_flipped".to_string(), 0);
+    expected += MeasureQubit::new(1, "ro_0_flipped".to_string(), 1);
+    expected += PragmaSetNumberOfMeasurements::new(10, "ro_0_flipped".to_string());
+    assert_eq!(measurement.circuits[1], expected);
+}
+
+/// Test that an operator containing only the identity is measured
+#[test]
+fn build_identity() {
+    let mut builder = BasisRotationBuilder::new(1, 10);
+    builder
+        .add_operator("constant".to_string(), &operator(&[("", 3.0)]))
+        .unwrap();
+    let measurement = builder.build().unwrap();
+    assert_eq!(measurement.circuits.len(), 1);
+    let mut registers: HashMap<String, BitOutputRegister> = HashMap::new();
+    registers.insert("ro_0".to_string(), vec![vec![false]]);
+    let result = measurement
+        .evaluate(registers, HashMap::new(), HashMap::new())
+        .unwrap()
+        .unwrap();
+    assert_eq!(result["constant"], 3.0);
+}
+
+/// Test errors when adding operators
+#[test]
+fn add_operator_errors() {
+    let mut builder = BasisRotationBuilder::new(2, 10);
+    assert_eq!(
+        builder.add_operator("x".to_string(), &operator(&[("2X", 1.0)])),
+        Err(RoqoqoError::PauliProductExceedsQubits {
+            pp_qubit: 2,
+            number_qubits: 2
+        })
+    );
+    builder
+        .add_operator("x".to_string(), &operator(&[("0X", 1.0)]))
+        .unwrap();
+    assert_eq!(
+        builder.add_operator("x".to_string(), &operator(&[("1X", 1.0)])),
+        Err(RoqoqoError::ExpValUsedTwice {
+            name: "x".to_string()
+        })
+    );
+}
+
+/// Returns the matrix of a Pauli product with qubit 0 as the least significant bit
+fn pauli_matrix(pauli_string: &PauliString, number_qubits: usize) -> Array2<Complex64> {
+    let dimension = 1 << number_qubits;
+    let mut matrix: Array2<Complex64> = Array2::zeros((dimension, dimension));
+    for column in 0..dimension {
+        let mut row = column;
+        let mut value = Complex64::new(1.0, 0.0);
+        for (&qubit, pauli) in pauli_string.iter() {
+            let bit = (column >> qubit) & 1 == 1;
+            match pauli {
+                'X' => row ^= 1 << qubit,
+                'Y' => {
+                    row ^= 1 << qubit;
+                    value *= if bit {
+                        Complex64::new(0.0, -1.0)
+                    } else {
+                        Complex64::new(0.0, 1.0)
+                    };
+                }
+                _ => {
+                    if bit {
+                        value = -value
+                    }
+                }
+            }
+        }
+        matrix[[row, column]] = value;
+    }
+    matrix
+}
+
+/// Returns the matrix of the gates in a basis rotation circuit with qubit 0 as the least significant bit
+fn rotation_matrix(circuit: &Circuit, number_qubits: usize) -> Array2<Complex64> {
+    let dimension = 1 << number_qubits;
+    let mut unitary: Array2<Complex64> = Array2::eye(dimension);
+    for operation in circuit.iter() {
+        let mut gate: Array2<Complex64> = Array2::zeros((dimension, dimension));
+        let single_qubit =
+            |qubit: usize, matrix: Array2<Complex64>, gate: &mut Array2<Complex64>| {
+                for column in 0..dimension {
+                    let bit = (column >> qubit) & 1;
+                    for new_bit in 0..2 {
+                        let row = (column & !(1 << qubit)) | (new_bit << qubit);
+                        gate[[row, column]] = matrix[[new_bit, bit]];
+                    }
+                }
+            };
+        match operation {
+            Operation::Hadamard(op) => {
+                single_qubit(*op.qubit(), op.unitary_matrix().unwrap(), &mut gate)
+            }
+            Operation::SGate(op) => {
+                single_qubit(*op.qubit(), op.unitary_matrix().unwrap(), &mut gate)
+            }
+            Operation::CNOT(op) => {
+                for column in 0..dimension {
+                    let row = if (column >> *op.control()) & 1 == 1 {
+                        column ^ (1 << *op.target())
+                    } else {
+                        column
+                    };
+                    gate[[row, column]] = Complex64::new(1.0, 0.0);
+                }
+            }
+            Operation::DefinitionBit(_) | Operation::PragmaRepeatedMeasurement(_) => continue,
+            _ => panic!("Unexpected operation {:?}", operation),
+        }
+        unitary = gate.dot(&unitary);
+    }
+    unitary
+}
+
+/// Checks that the circuit maps each Pauli product to the measured parity with the right sign
+fn assert_diagonalized(
+    measurement: &BasisRotation,
+    operators: &[(&str, &str)],
+    number_qubits: usize,
+) {
+    for (name, pauli_product) in operators {
+        let (index, sign) = match &measurement.input.measured_exp_vals[*name] {
+            PauliProductsToExpVal::Linear(linear) => {
+                linear.iter().map(|(k, v)| (*k, *v)).next().unwrap()
+            }
+            _ => panic!("Expected linear expectation value"),
+        };
+        let (readout, mask) = measurement
+            .input
+            .pauli_product_qubit_masks
+            .iter()
+            .find_map(|(readout, masks)| masks.get(&index).map(|mask| (readout.clone(), mask)))
+            .unwrap();
+        let circuit_index: usize = readout.trim_start_matches("ro_").parse().unwrap();
+        let unitary = rotation_matrix(&measurement.circuits[circuit_index], number_qubits);
+        let transformed = unitary
+            .dot(&pauli_matrix(
+                &parse_pauli_product(pauli_product).unwrap(),
+                number_qubits,
+            ))
+            .dot(&unitary.t().mapv(|x| x.conj()));
+        let parity: PauliString = mask.iter().map(|qubit| (*qubit, 'Z')).collect();
+        let expected = pauli_matrix(&parity, number_qubits).mapv(|x| x * sign);
+        for (a, b) in transformed.iter().zip(expected.iter()) {
+            assert!((a - b).norm() < 1e-10, "{} not diagonalized", pauli_product);
+        }
+    }
+}
+
+/// Test grouping by general commutativity and the Clifford diagonalization circuits
+#[test_case(&[("xx", "0X1X"), ("yy", "0Y1Y"), ("zz", "0Z1Z")], 2, 1; "bell basis")]
+#[test_case(&[("a", "0X1X2X"), ("b", "0Y1Y2X"), ("c", "0Z1Z"), ("d", "1Z2Z"), ("e", "0Z")], 3, 2; "ghz stabilizers")]
+#[test_case(&[("a", "0X1X"), ("b", "0Y1Y"), ("c", "0Z1Z"), ("d", "2Y"), ("e", "0X1X2Y")], 3, 1; "dependent products")]
+#[test_case(&[("a", "0Y"), ("b", "1X"), ("c", "0Z2Z")], 3, 2; "single qubits")]
+fn build_general_commuting(
+    operators: &[(&str, &str)],
+    number_qubits: usize,
+    number_circuits: usize,
+) {
+    let mut builder = BasisRotationBuilder::new(number_qubits, 100)
+        .with_grouping(PauliGrouping::GeneralCommuting);
+    for (name, pauli_product) in operators {
+        builder
+            .add_operator(name.to_string(), &operator(&[(*pauli_product, 1.0)]))
+            .unwrap();
+    }
+    let measurement = builder.build().unwrap();
+    assert_eq!(measurement.circuits.len(), number_circuits);
+    assert_diagonalized(&measurement, operators, number_qubits);
+}
+
+/// Test that general commuting grouping needs fewer circuits than qubit-wise grouping
+#[test]
+fn general_commuting_fewer_circuits() {
+    let hamiltonian = operator(&[("0X1X", 0.5), ("0Y1Y", 0.5), ("0Z1Z", 0.5), ("", -1.0)]);
+    let mut builder = BasisRotationBuilder::new(2, 100);
+    builder
+        .add_operator("energy".to_string(), &hamiltonian)
+        .unwrap();
+    assert_eq!(builder.build().unwrap().circuits.len(), 3);
+
+    let mut builder =
+        BasisRotationBuilder::new(2, 100).with_grouping(PauliGrouping::GeneralCommuting);
+    builder
+        .add_operator("energy".to_string(), &hamiltonian)
+        .unwrap();
+    let measurement = builder.build().unwrap();
+    assert_eq!(measurement.circuits.len(), 1);
+    assert_eq!(measurement.input.number_pauli_products, 4);
+}
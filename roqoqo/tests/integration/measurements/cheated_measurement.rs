@@ -135,6 +135,7 @@ fn test_substitute_parameters_fail() {
 #[test_case(vec![vec![Complex64::new(std::f64::consts::FRAC_1_SQRT_2, 0.0), Complex64::new(0.0,std::f64::consts::FRAC_1_SQRT_2)]], 0.0, 1.0; "simple_state_off_diagonal")]
 #[test_case(vec![vec![Complex64::new(0.5, 0.0), Complex64::new(0.0, 0.0), Complex64::new(0.0, 0.0), Complex64::new(0.5, 0.0)]], 0.0, 0.0; "density_matrix_diagonal")]
 #[test_case(vec![vec![Complex64::new(0.5, 0.0), Complex64::new(0.0, -0.5), Complex64::new(0.0, 0.5), Complex64::new(0.5, 0.0)]], 0.0, 1.0; "density_matrix_off_diagonal")]
+#[test_case(vec![vec![Complex64::new(0.5, 0.0), Complex64::new(0.0, -0.25), Complex64::new(0.0, 0.25), Complex64::new(0.5, 0.0)]], 0.0, 0.5; "mixed_density_matrix_off_diagonal")]
 #[test_case(vec![vec![Complex64::new(1.0,0.0), Complex64::new(0.0,0.0)], vec![Complex64::new(0.0,0.0), Complex64::new(1.0,0.0)], vec![Complex64::new(1.0,0.0), Complex64::new(0.0,0.0)]], 1.0/3.0, 0.0; "simple_state_diagonal_averaginv")]
 fn test_evaluate(register: Vec<Vec<Complex64>>, value_diagonal: f64, value_off_diagonal: f64) {
     let mut bri = CheatedInput::new(1);
@@ -233,3 +234,59 @@ fn test_evaluate_error() {
         })
     );
 }
+
+#[test]
+fn test_evaluate_multiple_readouts() {
+    let mut bri = CheatedInput::new(1);
+    let z_matrix = vec![
+        (0, 0, Complex64::new(1.0, 0.0)),
+        (1, 1, Complex64::new(-1.0, 0.0)),
+    ];
+    let x_matrix = vec![
+        (0, 1, Complex64::new(1.0, 0.0)),
+        (1, 0, Complex64::new(1.0, 0.0)),
+    ];
+    bri.add_operator_exp_val("z_0".to_string(), z_matrix.clone(), "ro_0".to_string())
+        .unwrap();
+    bri.add_operator_exp_val("x_0".to_string(), x_matrix.clone(), "ro_0".to_string())
+        .unwrap();
+    bri.add_operator_exp_val("z_1".to_string(), z_matrix, "ro_1".to_string())
+        .unwrap();
+    bri.add_operator_exp_val("x_1".to_string(), x_matrix, "ro_1".to_string())
+        .unwrap();
+    let br = Cheated {
+        constant_circuit: None,
+        circuits: vec![Circuit::new()],
+        input: bri,
+    };
+
+    let amplitude = std::f64::consts::FRAC_1_SQRT_2;
+    let mut measured_registers: HashMap<String, ComplexOutputRegister> = HashMap::new();
+    let _ = measured_registers.insert(
+        "ro_0".to_string(),
+        vec![
+            vec![Complex64::new(1.0, 0.0), Complex64::new(0.0, 0.0)],
+            vec![
+                Complex64::new(amplitude, 0.0),
+                Complex64::new(amplitude, 0.0),
+            ],
+        ],
+    );
+    let _ = measured_registers.insert(
+        "ro_1".to_string(),
+        vec![vec![
+            Complex64::new(0.0, 0.0),
+            Complex64::new(0.0, 0.0),
+            Complex64::new(0.0, 0.0),
+            Complex64::new(1.0, 0.0),
+        ]],
+    );
+    let result = br
+        .evaluate(HashMap::new(), HashMap::new(), measured_registers)
+        .unwrap()
+        .unwrap();
+    assert!((result["z_0"] - 0.5).abs() < 1e-10);
+    assert!((result["x_0"] - 0.5).abs() < 1e-10);
+    assert!((result["z_1"] + 1.0).abs() < 1e-10);
+    assert!(result["x_1"].abs() < 1e-10);
+}
@@ -0,0 +1,242 @@
+// Copyright © 2021 HQS Quantum Simulations GmbH. All Rights Reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except
+// in compliance with the License. You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the
+// License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either
+// express or implied. See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Integration test for public API of the classical shadows measurement
+
+use qoqo_calculator::CalculatorFloat;
+use roqoqo::measurements::{parse_pauli_product, ClassicalShadows, ClassicalShadowsInput};
+use roqoqo::operations::*;
+use roqoqo::prelude::*;
+use roqoqo::registers::BitOutputRegister;
+use roqoqo::{Circuit, RoqoqoError};
+use std::collections::HashMap;
+use std::f64::consts::FRAC_PI_2;
+
+/// Creates an input with the expectation values of single Pauli products
+fn input(
+    number_qubits: usize,
+    number_batches: usize,
+    pauli_products: &[&str],
+) -> ClassicalShadowsInput {
+    let mut input = ClassicalShadowsInput::new(number_qubits, number_batches);
+    for pauli_product in pauli_products {
+        let index = input
+            .add_pauli_product(parse_pauli_product(pauli_product).unwrap())
+            .unwrap();
+        let mut linear: HashMap<usize, f64> = HashMap::new();
+        linear.insert(index, 1.0);
+        input
+            .add_linear_exp_val(pauli_product.to_string(), linear)
+            .unwrap();
+    }
+    input
+}
+
+/// Creates a measurement measuring in the given bases into registers ro_0, ro_1, ...
+fn measurement(input: ClassicalShadowsInput, bases: &[&str]) -> ClassicalShadows {
+    let mut input = input;
+    for (index, basis) in bases.iter().enumerate() {
+        input
+            .measurement_bases
+            .insert(format!("ro_{}", index), basis.chars().collect());
+    }
+    ClassicalShadows {
+        constant_circuit: None,
+        circuits: vec![Circuit::new(); bases.len()],
+        input,
+    }
+}
+
+/// Test the randomized measurement circuits
+#[test]
+fn randomized_circuits() {
+    let measurement = ClassicalShadows::new(None, input(3, 1, &["0Z"]), 20, 10, 1);
+    assert_eq!(measurement.circuits.len(), 20);
+    assert_eq!(measurement.input.measurement_bases.len(), 20);
+    for (index, circuit) in measurement.circuits.iter().enumerate() {
+        let readout = format!("ro_{}", index);
+        let basis = &measurement.input.measurement_bases[&readout];
+        let mut expected = Circuit::new();
+        expected += DefinitionBit::new(readout.clone(), 3, true);
+        for (qubit, pauli) in basis.iter().enumerate() {
+            match pauli {
+                'X' => expected += Hadamard::new(qubit),
+                'Y' => expected += RotateX::new(qubit, FRAC_PI_2.into()),
+                'Z' => (),
+                _ => panic!("Unexpected basis {}", pauli),
+            }
+        }
+        expected += PragmaRepeatedMeasurement::new(readout, 10, None);
+        assert_eq!(circuit, &expected);
+    }
+    // All bases are drawn
+    let bases: Vec<char> = measurement
+        .input
+        .measurement_bases
+        .values()
+        .flatten()
+        .copied()
+        .collect();
+    assert!(bases.contains(&'X') && bases.contains(&'Y') && bases.contains(&'Z'));
+}
+
+/// Test that the bases are reproducible with the seed
+#[test]
+fn seeded_bases() {
+    let first = ClassicalShadows::new(None, input(4, 1, &[]), 10, 1, 7);
+    let second = ClassicalShadows::new(None, input(4, 1, &[]), 10, 1, 7);
+    let other = ClassicalShadows::new(None, input(4, 1, &[]), 10, 1, 8);
+    assert_eq!(first, second);
+    assert_ne!(first, other);
+}
+
+/// Test estimation of Pauli products for the state |00>
+#[test]
+fn evaluate_all_bases() {
+    let bases = ["XX", "XY", "XZ", "YX", "YY", "YZ", "ZX", "ZY", "ZZ"];
+    let mut input = input(2, 1, &["0Z", "0Z1Z", "0X", "1Y", "0Z1X"]);
+    input
+        .add_symbolic_exp_val(
+            "symbolic".to_string(),
+            CalculatorFloat::from("2 * pauli_product_0 + 1"),
+        )
+        .unwrap();
+    let measurement = measurement(input, &bases);
+
+    let mut registers: HashMap<String, BitOutputRegister> = HashMap::new();
+    for (index, basis) in bases.iter().enumerate() {
+        // Z results are always 0, X and Y results are evenly distributed
+        let register: BitOutputRegister = (0..4)
+            .map(|shot| {
+                basis
+                    .chars()
+                    .enumerate()
+                    .map(|(qubit, pauli)| pauli != 'Z' && (shot >> qubit) & 1 == 1)
+                    .collect()
+            })
+            .collect();
+        registers.insert(format!("ro_{}", index), register);
+    }
+    let result = measurement
+        .evaluate(registers, HashMap::new(), HashMap::new())
+        .unwrap()
+        .unwrap();
+    assert!((result["0Z"] - 1.0).abs() < 1e-10);
+    assert!((result["0Z1Z"] - 1.0).abs() < 1e-10);
+    assert!(result["0X"].abs() < 1e-10);
+    assert!(result["1Y"].abs() < 1e-10);
+    assert!(result["0Z1X"].abs() < 1e-10);
+    assert!((result["symbolic"] - 3.0).abs() < 1e-10);
+}
+
+/// Test that the median of means suppresses outlying batches
+#[test]
+fn median_of_means() {
+    let bases = ["X", "Y", "Z", "X", "Y", "Z", "X", "Y", "Z"];
+    let mut registers: HashMap<String, BitOutputRegister> = HashMap::new();
+    for index in 0..bases.len() {
+        // The Z measurement of the last batch is an outlier
+        registers.insert(format!("ro_{}", index), vec![vec![index == 8]]);
+    }
+    let result = measurement(input(1, 3, &["0Z"]), &bases)
+        .evaluate(registers.clone(), HashMap::new(), HashMap::new())
+        .unwrap()
+        .unwrap();
+    assert!((result["0Z"] - 1.0).abs() < 1e-10);
+
+    let result = measurement(input(1, 1, &["0Z"]), &bases)
+        .evaluate(registers, HashMap::new(), HashMap::new())
+        .unwrap()
+        .unwrap();
+    assert!((result["0Z"] - 1.0 / 3.0).abs() < 1e-10);
+}
+
+/// Test errors of the measurement input
+#[test]
+fn input_errors() {
+    let mut input = ClassicalShadowsInput::new(2, 1);
+    assert_eq!(
+        input.add_pauli_product(parse_pauli_product("2X").unwrap()),
+        Err(RoqoqoError::PauliProductExceedsQubits {
+            pp_qubit: 2,
+            number_qubits: 2
+        })
+    );
+    let mut invalid = parse_pauli_product("0X").unwrap();
+    invalid.insert(1, 'A');
+    assert!(input.add_pauli_product(invalid).is_err());
+    assert_eq!(
+        input.add_pauli_product(parse_pauli_product("0X").unwrap()),
+        Ok(0)
+    );
+    assert_eq!(
+        input.add_pauli_product(parse_pauli_product("0X").unwrap()),
+        Ok(0)
+    );
+    input
+        .add_linear_exp_val("x".to_string(), HashMap::new())
+        .unwrap();
+    assert_eq!(
+        input.add_symbolic_exp_val("x".to_string(), CalculatorFloat::from(1.0)),
+        Err(RoqoqoError::ExpValUsedTwice {
+            name: "x".to_string()
+        })
+    );
+}
+
+/// Test errors when evaluating incomplete registers
+#[test]
+fn evaluate_errors() {
+    let measurement = measurement(input(2, 1, &["0Z"]), &["ZZ"]);
+    let result = measurement.evaluate(HashMap::new(), HashMap::new(), HashMap::new());
+    assert_eq!(
+        result,
+        Err(RoqoqoError::MissingRegister {
+            name: "ro_0".to_string()
+        })
+    );
+
+    let mut registers: HashMap<String, BitOutputRegister> = HashMap::new();
+    registers.insert("ro_0".to_string(), vec![vec![false]]);
+    let result = measurement.evaluate(registers, HashMap::new(), HashMap::new());
+    assert!(result.is_err());
+
+    let mut registers: HashMap<String, BitOutputRegister> = HashMap::new();
+    registers.insert("ro_0".to_string(), Vec::new());
+    let result = measurement.evaluate(registers, HashMap::new(), HashMap::new());
+    assert!(result.is_err());
+}
+
+/// Test parameter substitution
+#[test]
+fn substitute_parameters() {
+    let mut constant_circuit = Circuit::new();
+    constant_circuit += RotateX::new(0, "theta".into());
+    let measurement = ClassicalShadows::new(Some(constant_circuit), input(1, 1, &["0X"]), 3, 5, 3);
+    let mut parameters: HashMap<String, f64> = HashMap::new();
+    parameters.insert("theta".to_string(), 0.5);
+    let substituted = measurement.substitute_parameters(parameters).unwrap();
+    let mut expected = Circuit::new();
+    expected += RotateX::new(0, 0.5.into());
+    assert_eq!(substituted.constant_circuit(), &Some(expected));
+    assert_eq!(substituted.input, measurement.input);
+}
+
+/// Test serialization of the measurement with the drawn bases
+#[cfg(feature = "serialize")]
+#[test]
+fn serialize_classical_shadows() {
+    let measurement = ClassicalShadows::new(None, input(2, 2, &["0X1Z"]), 5, 10, 3);
+    let serialized = bincode::serialize(&measurement).unwrap();
+    let deserialized: ClassicalShadows = bincode::deserialize(&serialized).unwrap();
+    assert_eq!(deserialized, measurement);
+}
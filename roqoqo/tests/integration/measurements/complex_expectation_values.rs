@@ -0,0 +1,293 @@
+// Copyright © 2021 HQS Quantum Simulations GmbH. All Rights Reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except
+// in compliance with the License. You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the
+// License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either
+// express or implied. See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Integration test for complex and grouped expectation values
+
+use std::collections::HashMap;
+
+use ndarray::{array, Array1, Array2};
+use num_complex::Complex64;
+use roqoqo::measurements::{
+    BasisRotation, BasisRotationInput, Cheated, CheatedBasisRotation, CheatedBasisRotationInput,
+    CheatedInput, ComplexExpectationValues, ExpValGroup, PauliProductsToExpVal,
+};
+use roqoqo::prelude::*;
+use roqoqo::registers::{BitOutputRegister, ComplexOutputRegister, FloatOutputRegister};
+use roqoqo::{Circuit, RoqoqoError};
+
+fn cheated_basis_rotation() -> CheatedBasisRotation {
+    let mut input = CheatedBasisRotationInput::new();
+    input.add_pauli_product("ro_x".to_string());
+    input.add_pauli_product("ro_y".to_string());
+    let mut sigma_plus: HashMap<usize, Complex64> = HashMap::new();
+    sigma_plus.insert(0, Complex64::new(0.5, 0.0));
+    sigma_plus.insert(1, Complex64::new(0.0, 0.5));
+    input
+        .add_linear_complex_exp_val("sigma_plus".to_string(), sigma_plus)
+        .unwrap();
+    let mut sigma_minus: HashMap<usize, Complex64> = HashMap::new();
+    sigma_minus.insert(0, Complex64::new(0.5, 0.0));
+    sigma_minus.insert(1, Complex64::new(0.0, -0.5));
+    input
+        .add_linear_complex_exp_val("sigma_minus".to_string(), sigma_minus)
+        .unwrap();
+    let mut x: HashMap<usize, f64> = HashMap::new();
+    x.insert(0, 1.0);
+    input.add_linear_exp_val("x".to_string(), x).unwrap();
+    CheatedBasisRotation {
+        constant_circuit: None,
+        circuits: vec![Circuit::new(), Circuit::new()],
+        input,
+    }
+}
+
+fn float_registers() -> HashMap<String, FloatOutputRegister> {
+    let mut float_registers: HashMap<String, FloatOutputRegister> = HashMap::new();
+    float_registers.insert("ro_x".to_string(), vec![vec![0.4]]);
+    float_registers.insert("ro_y".to_string(), vec![vec![0.2]]);
+    float_registers
+}
+
+#[test]
+fn test_linear_complex_value() {
+    let mut linear: HashMap<usize, Complex64> = HashMap::new();
+    linear.insert(0, Complex64::new(1.0, 2.0));
+    linear.insert(2, Complex64::new(0.0, -1.0));
+    let evaluation = PauliProductsToExpVal::LinearComplex(linear);
+    let value = evaluation.complex_value(&array![0.5, 0.3, 0.25]).unwrap();
+    assert_eq!(value, Complex64::new(0.5, 0.75));
+
+    let mut real: HashMap<usize, f64> = HashMap::new();
+    real.insert(1, 2.0);
+    let value = PauliProductsToExpVal::Linear(real)
+        .complex_value(&array![0.5, 0.3, 0.25])
+        .unwrap();
+    assert_eq!(value, Complex64::new(0.6, 0.0));
+}
+
+#[test]
+fn test_cheated_basis_rotation_complex() {
+    let measurement = cheated_basis_rotation();
+    let result = measurement
+        .evaluate_complex(HashMap::new(), float_registers(), HashMap::new())
+        .unwrap()
+        .unwrap();
+    let plus = result.expectation_values["sigma_plus"];
+    let minus = result.expectation_values["sigma_minus"];
+    assert!((plus - Complex64::new(0.2, 0.1)).norm() < 1e-10);
+    assert!((minus - Complex64::new(0.2, -0.1)).norm() < 1e-10);
+    assert!((result.expectation_values["x"] - Complex64::new(0.4, 0.0)).norm() < 1e-10);
+    assert!(result.vectors.is_empty());
+    assert!(result.matrices.is_empty());
+}
+
+#[test]
+fn test_evaluate_returns_real_part() {
+    let measurement = cheated_basis_rotation();
+    let real = measurement
+        .evaluate(HashMap::new(), float_registers(), HashMap::new())
+        .unwrap()
+        .unwrap();
+    let complex = measurement
+        .evaluate_complex(HashMap::new(), float_registers(), HashMap::new())
+        .unwrap()
+        .unwrap();
+    assert_eq!(real, complex.real_parts());
+    assert!((real["sigma_plus"] - 0.2).abs() < 1e-10);
+    assert!((real["sigma_minus"] - 0.2).abs() < 1e-10);
+}
+
+#[test]
+fn test_basis_rotation_complex() {
+    let mut input = BasisRotationInput::new(1, false);
+    let index = input.add_pauli_product("ro".to_string(), vec![0]).unwrap();
+    let mut linear: HashMap<usize, Complex64> = HashMap::new();
+    linear.insert(index, Complex64::new(0.0, 1.0));
+    input
+        .add_linear_complex_exp_val("iz".to_string(), linear)
+        .unwrap();
+    input
+        .add_exp_val_vector(
+            "vector".to_string(),
+            vec!["iz".to_string(), "iz".to_string()],
+        )
+        .unwrap();
+    let measurement = BasisRotation {
+        constant_circuit: None,
+        circuits: vec![Circuit::new()],
+        input,
+    };
+    let mut bit_registers: HashMap<String, BitOutputRegister> = HashMap::new();
+    bit_registers.insert(
+        "ro".to_string(),
+        vec![vec![false], vec![false], vec![false], vec![true]],
+    );
+    let result = measurement
+        .evaluate_complex(bit_registers.clone(), HashMap::new(), HashMap::new())
+        .unwrap()
+        .unwrap();
+    assert!((result.expectation_values["iz"] - Complex64::new(0.0, 0.5)).norm() < 1e-10);
+    let vector: Array1<Complex64> = array![Complex64::new(0.0, 0.5), Complex64::new(0.0, 0.5)];
+    assert_eq!(result.vectors["vector"], vector);
+
+    let real = measurement
+        .evaluate(bit_registers, HashMap::new(), HashMap::new())
+        .unwrap()
+        .unwrap();
+    assert_eq!(real["iz"], 0.0);
+}
+
+#[test]
+fn test_cheated_non_hermitian_operator() {
+    let mut input = CheatedInput::new(1);
+    input
+        .add_operator_exp_val(
+            "sigma_plus".to_string(),
+            vec![(0, 1, Complex64::new(1.0, 0.0))],
+            "ro".to_string(),
+        )
+        .unwrap();
+    input
+        .add_operator_exp_val(
+            "i_sigma_plus".to_string(),
+            vec![(0, 1, Complex64::new(0.0, 1.0))],
+            "ro".to_string(),
+        )
+        .unwrap();
+    input
+        .add_exp_val_vector(
+            "sigma".to_string(),
+            vec!["sigma_plus".to_string(), "i_sigma_plus".to_string()],
+        )
+        .unwrap();
+    let measurement = Cheated {
+        constant_circuit: None,
+        circuits: vec![Circuit::new()],
+        input,
+    };
+    let amplitude = Complex64::new(std::f64::consts::FRAC_1_SQRT_2, 0.0);
+    let mut complex_registers: HashMap<String, ComplexOutputRegister> = HashMap::new();
+    complex_registers.insert("ro".to_string(), vec![vec![amplitude, amplitude]]);
+    let result = measurement
+        .evaluate_complex(HashMap::new(), HashMap::new(), complex_registers.clone())
+        .unwrap()
+        .unwrap();
+    assert!((result.expectation_values["sigma_plus"] - Complex64::new(0.5, 0.0)).norm() < 1e-10);
+    assert!((result.expectation_values["i_sigma_plus"] - Complex64::new(0.0, 0.5)).norm() < 1e-10);
+    assert_eq!(result.vectors["sigma"].len(), 2);
+
+    let real = measurement
+        .evaluate(HashMap::new(), HashMap::new(), complex_registers)
+        .unwrap()
+        .unwrap();
+    assert!((real["sigma_plus"] - 0.5).abs() < 1e-10);
+    assert!(real["i_sigma_plus"].abs() < 1e-10);
+}
+
+#[test]
+fn test_exp_val_matrix() {
+    let mut measurement = cheated_basis_rotation();
+    measurement
+        .input
+        .add_exp_val_matrix(
+            "matrix".to_string(),
+            vec![
+                vec!["x".to_string(), "sigma_plus".to_string()],
+                vec!["sigma_minus".to_string(), "x".to_string()],
+            ],
+        )
+        .unwrap();
+    let result = measurement
+        .evaluate_complex(HashMap::new(), float_registers(), HashMap::new())
+        .unwrap()
+        .unwrap();
+    let matrix: &Array2<Complex64> = &result.matrices["matrix"];
+    assert_eq!(matrix.shape(), &[2, 2]);
+    assert!((matrix[[0, 1]] - Complex64::new(0.2, 0.1)).norm() < 1e-10);
+    assert!((matrix[[1, 0]] - matrix[[0, 1]].conj()).norm() < 1e-10);
+    assert!((matrix[[1, 1]] - Complex64::new(0.4, 0.0)).norm() < 1e-10);
+}
+
+#[test]
+fn test_exp_val_group_errors() {
+    let mut input = CheatedBasisRotationInput::new();
+    input.add_pauli_product("ro".to_string());
+    let mut linear: HashMap<usize, f64> = HashMap::new();
+    linear.insert(0, 1.0);
+    input.add_linear_exp_val("z".to_string(), linear).unwrap();
+
+    let error = input.add_exp_val_vector("vector".to_string(), vec!["missing".to_string()]);
+    assert_eq!(
+        error,
+        Err(RoqoqoError::ExpValMissing {
+            name: "missing".to_string()
+        })
+    );
+    let error = input.add_exp_val_vector("vector".to_string(), vec![]);
+    assert!(matches!(error, Err(RoqoqoError::GenericError { .. })));
+    let error = input.add_exp_val_matrix(
+        "matrix".to_string(),
+        vec![
+            vec!["z".to_string(), "z".to_string()],
+            vec!["z".to_string()],
+        ],
+    );
+    assert!(matches!(error, Err(RoqoqoError::GenericError { .. })));
+
+    input
+        .add_exp_val_vector("vector".to_string(), vec!["z".to_string()])
+        .unwrap();
+    let error = input.add_exp_val_matrix("vector".to_string(), vec![vec!["z".to_string()]]);
+    assert_eq!(
+        error,
+        Err(RoqoqoError::ExpValUsedTwice {
+            name: "vector".to_string()
+        })
+    );
+    let error = input.add_linear_complex_exp_val("z".to_string(), HashMap::new());
+    assert_eq!(
+        error,
+        Err(RoqoqoError::ExpValUsedTwice {
+            name: "z".to_string()
+        })
+    );
+}
+
+#[test]
+fn test_from_groups_missing() {
+    let mut groups: HashMap<String, ExpValGroup> = HashMap::new();
+    groups.insert(
+        "vector".to_string(),
+        ExpValGroup::Vector(vec!["missing".to_string()]),
+    );
+    let error = ComplexExpectationValues::from_groups(HashMap::new(), &groups);
+    assert_eq!(
+        error,
+        Err(RoqoqoError::ExpValMissing {
+            name: "missing".to_string()
+        })
+    );
+}
+
+#[test]
+fn test_statistical_errors_linear_complex() {
+    let measurement = cheated_basis_rotation();
+    let mut float_registers: HashMap<String, FloatOutputRegister> = HashMap::new();
+    float_registers.insert("ro_x".to_string(), vec![vec![0.3], vec![0.5]]);
+    float_registers.insert("ro_y".to_string(), vec![vec![0.2]]);
+    let result = measurement
+        .evaluate_with_errors(HashMap::new(), float_registers, HashMap::new())
+        .unwrap()
+        .unwrap();
+    assert!((result.expectation_values["sigma_plus"].mean - 0.2).abs() < 1e-10);
+    assert!(result.expectation_values["sigma_plus"].standard_error > 0.0);
+}
@@ -0,0 +1,201 @@
+// Copyright © 2021 HQS Quantum Simulations GmbH. All Rights Reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except
+// in compliance with the License. You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the
+// License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either
+// express or implied. See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Integration test for public API of the histogram measurement
+
+use ndarray::array;
+use roqoqo::measurements::{Histogram, HistogramInput, ReadoutMitigation, ReadoutMitigationMethod};
+use roqoqo::operations::*;
+use roqoqo::prelude::*;
+use roqoqo::registers::BitOutputRegister;
+use roqoqo::{Circuit, RoqoqoError};
+use std::collections::HashMap;
+use test_case::test_case;
+
+/// Creates a register where readout bit 0 is true in number_true of the shots and bit 1 is always false
+fn register(number_false: usize, number_true: usize) -> BitOutputRegister {
+    let mut register = vec![vec![false, false]; number_false];
+    register.extend(vec![vec![true, false]; number_true]);
+    register
+}
+
+fn measurement(input: HistogramInput) -> Histogram {
+    Histogram {
+        constant_circuit: None,
+        circuits: vec![Circuit::new()],
+        input,
+    }
+}
+
+/// Test counting bitstrings and evaluating marginal frequencies
+#[test]
+fn evaluate_counts_and_marginals() {
+    let mut input = HistogramInput::new();
+    input
+        .add_marginal("first".to_string(), "ro".to_string(), vec![0])
+        .unwrap();
+    input
+        .add_marginal("reversed".to_string(), "ro".to_string(), vec![1, 0])
+        .unwrap();
+    let mut bit_registers: HashMap<String, BitOutputRegister> = HashMap::new();
+    bit_registers.insert("ro".to_string(), register(3, 5));
+    bit_registers.insert("other".to_string(), vec![vec![true]]);
+
+    let result = measurement(input)
+        .evaluate(bit_registers, HashMap::new(), HashMap::new())
+        .unwrap();
+
+    let mut counts: HashMap<String, usize> = HashMap::new();
+    counts.insert("00".to_string(), 3);
+    counts.insert("10".to_string(), 5);
+    assert_eq!(result.counts["ro"], counts);
+    assert_eq!(result.counts["other"]["1"], 1);
+
+    let first = &result.marginal_probabilities["first"];
+    assert_eq!(first.len(), 2);
+    assert!((first["0"] - 0.375).abs() < 1e-10);
+    assert!((first["1"] - 0.625).abs() < 1e-10);
+    let reversed = &result.marginal_probabilities["reversed"];
+    assert!((reversed["00"] - 0.375).abs() < 1e-10);
+    assert!((reversed["01"] - 0.625).abs() < 1e-10);
+}
+
+/// Test that readout errors are corrected in marginal probabilities
+///
+/// The qubit is in state 1 with probability 0.75 which is read out with probability 0.375 as 0.
+#[test_case(ReadoutMitigationMethod::TensoredInversion; "tensored_inversion")]
+#[test_case(ReadoutMitigationMethod::IterativeBayesianUnfolding{max_iterations: 1000, tolerance: 1e-12}; "iterative_bayesian_unfolding")]
+fn mitigated_marginals(method: ReadoutMitigationMethod) {
+    let mut mitigation = ReadoutMitigation::new(method);
+    mitigation
+        .add_single_qubit_confusion_matrix(0, array![[0.9, 0.2], [0.1, 0.8]])
+        .unwrap();
+    let mut input = HistogramInput::new();
+    input
+        .add_marginal("first".to_string(), "ro".to_string(), vec![0])
+        .unwrap();
+    input
+        .add_marginal("both".to_string(), "ro".to_string(), vec![0, 1])
+        .unwrap();
+    input.set_readout_mitigation(Some(mitigation));
+    let mut bit_registers: HashMap<String, BitOutputRegister> = HashMap::new();
+    bit_registers.insert("ro".to_string(), register(3, 5));
+
+    let result = measurement(input)
+        .evaluate(bit_registers, HashMap::new(), HashMap::new())
+        .unwrap();
+
+    // Counts are not mitigated
+    assert_eq!(result.counts["ro"]["10"], 5);
+    let first = &result.marginal_probabilities["first"];
+    assert!((first["0"] - 0.25).abs() < 1e-6);
+    assert!((first["1"] - 0.75).abs() < 1e-6);
+    let both = &result.marginal_probabilities["both"];
+    assert!((both["00"] - 0.25).abs() < 1e-6);
+    assert!((both["10"] - 0.75).abs() < 1e-6);
+    assert!(!both.contains_key("01"));
+}
+
+/// Test errors when adding marginals
+#[test]
+fn add_marginal_errors() {
+    let mut input = HistogramInput::new();
+    input
+        .add_marginal("first".to_string(), "ro".to_string(), vec![0])
+        .unwrap();
+    assert_eq!(
+        input.add_marginal("first".to_string(), "ro".to_string(), vec![1]),
+        Err(RoqoqoError::ExpValUsedTwice {
+            name: "first".to_string()
+        })
+    );
+    assert!(matches!(
+        input.add_marginal("empty".to_string(), "ro".to_string(), vec![]),
+        Err(RoqoqoError::HistogramMeasurementError { .. })
+    ));
+    assert!(matches!(
+        input.add_marginal("twice".to_string(), "ro".to_string(), vec![0, 1, 0]),
+        Err(RoqoqoError::HistogramMeasurementError { .. })
+    ));
+}
+
+/// Test errors when evaluating marginals from missing or invalid registers
+#[test]
+fn evaluate_errors() {
+    let mut input = HistogramInput::new();
+    input
+        .add_marginal("third".to_string(), "ro".to_string(), vec![2])
+        .unwrap();
+    let measurement = measurement(input);
+
+    let result = measurement.evaluate(HashMap::new(), HashMap::new(), HashMap::new());
+    assert_eq!(
+        result,
+        Err(RoqoqoError::MissingRegister {
+            name: "ro".to_string()
+        })
+    );
+
+    let mut bit_registers: HashMap<String, BitOutputRegister> = HashMap::new();
+    bit_registers.insert("ro".to_string(), register(1, 1));
+    let result = measurement.evaluate(bit_registers, HashMap::new(), HashMap::new());
+    assert!(matches!(
+        result,
+        Err(RoqoqoError::HistogramMeasurementError { .. })
+    ));
+
+    let mut bit_registers: HashMap<String, BitOutputRegister> = HashMap::new();
+    bit_registers.insert("ro".to_string(), vec![]);
+    let result = measurement.evaluate(bit_registers, HashMap::new(), HashMap::new());
+    assert!(matches!(
+        result,
+        Err(RoqoqoError::HistogramMeasurementError { .. })
+    ));
+}
+
+/// Test parameter substitution
+#[test]
+fn substitute_parameters() {
+    let mut input = HistogramInput::new();
+    input
+        .add_marginal("first".to_string(), "ro".to_string(), vec![0])
+        .unwrap();
+    let mut circuit = Circuit::new();
+    circuit += RotateX::new(0, "theta".into());
+    let measurement = Histogram {
+        constant_circuit: Some(circuit.clone()),
+        circuits: vec![circuit],
+        input: input.clone(),
+    };
+    let mut parameters: HashMap<String, f64> = HashMap::new();
+    parameters.insert("theta".to_string(), 0.5);
+    let substituted = measurement.substitute_parameters(parameters).unwrap();
+    let mut expected = Circuit::new();
+    expected += RotateX::new(0, 0.5.into());
+    assert_eq!(substituted.constant_circuit(), &Some(expected.clone()));
+    assert_eq!(substituted.circuits().next(), Some(&expected));
+    assert_eq!(substituted.input, input);
+}
+
+/// Test serialization roundtrip
+#[cfg(feature = "serialize")]
+#[test]
+fn serde_roundtrip() {
+    let mut input = HistogramInput::new();
+    input
+        .add_marginal("first".to_string(), "ro".to_string(), vec![0])
+        .unwrap();
+    let measurement = measurement(input);
+    let serialized = bincode::serialize(&measurement).unwrap();
+    let deserialized: Histogram = bincode::deserialize(&serialized).unwrap();
+    assert_eq!(measurement, deserialized);
+}
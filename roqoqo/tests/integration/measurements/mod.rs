@@ -20,3 +20,26 @@ mod cheated_measurement;
 pub use cheated_measurement::*;
 mod classical_register_measurement;
 pub use classical_register_measurement::*;
+mod readout_mitigation;
+pub use readout_mitigation::*;
+mod statistical_errors;
+pub use statistical_errors::*;
+mod basis_rotation_builder;
+pub use basis_rotation_builder::*;
+mod classical_shadows_measurement;
+pub use classical_shadows_measurement::*;
+
+mod zero_noise_extrapolation;
+pub use zero_noise_extrapolation::*;
+
+mod adaptive_shots;
+pub use adaptive_shots::*;
+
+mod histogram_measurement;
+pub use histogram_measurement::*;
+
+mod complex_expectation_values;
+pub use complex_expectation_values::*;
+
+mod sparse_operator;
+pub use sparse_operator::*;
@@ -0,0 +1,229 @@
+// Copyright © 2021 HQS Quantum Simulations GmbH. All Rights Reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except
+// in compliance with the License. You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the
+// License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either
+// express or implied. See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Integration test for public API of readout error mitigation
+
+use ndarray::{array, Array2};
+use roqoqo::devices::Device;
+use roqoqo::measurements::{
+    BasisRotation, BasisRotationInput, ReadoutMitigation, ReadoutMitigationMethod,
+};
+use roqoqo::prelude::*;
+use roqoqo::registers::BitOutputRegister;
+use roqoqo::{Circuit, RoqoqoError};
+use std::collections::HashMap;
+use test_case::test_case;
+
+/// Creates a register where readout bit 0 is true in number_true of the shots and bit 1 is always false
+fn register(number_false: usize, number_true: usize) -> BitOutputRegister {
+    let mut register = vec![vec![false, false]; number_false];
+    register.extend(vec![vec![true, false]; number_true]);
+    register
+}
+
+fn confusion_matrix() -> Array2<f64> {
+    array![[0.9, 0.2], [0.1, 0.8]]
+}
+
+fn measurement(
+    use_flipped_measurement: bool,
+    mitigation: Option<ReadoutMitigation>,
+) -> BasisRotation {
+    let mut bri = BasisRotationInput::new(2, use_flipped_measurement);
+    let z0 = bri.add_pauli_product("ro".to_string(), vec![0]).unwrap();
+    let z0z1 = bri.add_pauli_product("ro".to_string(), vec![0, 1]).unwrap();
+    let mut linear_map: HashMap<usize, f64> = HashMap::new();
+    linear_map.insert(z0, 1.0);
+    bri.add_linear_exp_val("z0".to_string(), linear_map)
+        .unwrap();
+    let mut linear_map: HashMap<usize, f64> = HashMap::new();
+    linear_map.insert(z0z1, 1.0);
+    bri.add_linear_exp_val("z0z1".to_string(), linear_map)
+        .unwrap();
+    bri.set_readout_mitigation(mitigation);
+    BasisRotation {
+        constant_circuit: None,
+        circuits: vec![Circuit::new()],
+        input: bri,
+    }
+}
+
+/// Test that readout errors of a single qubit are corrected
+///
+/// The qubit is in state 1 with probability 0.75 which is read out with probability 0.375 as 0.
+#[test_case(ReadoutMitigationMethod::TensoredInversion; "tensored_inversion")]
+#[test_case(ReadoutMitigationMethod::IterativeBayesianUnfolding{max_iterations: 1000, tolerance: 1e-12}; "iterative_bayesian_unfolding")]
+fn correct_single_qubit(method: ReadoutMitigationMethod) {
+    let mut measured_registers: HashMap<String, BitOutputRegister> = HashMap::new();
+    measured_registers.insert("ro".to_string(), register(3, 5));
+
+    let unmitigated = measurement(false, None)
+        .evaluate(measured_registers.clone(), HashMap::new(), HashMap::new())
+        .unwrap()
+        .unwrap();
+    assert!((unmitigated["z0"] + 0.25).abs() < 1e-10);
+
+    let mut mitigation = ReadoutMitigation::new(method);
+    mitigation
+        .add_single_qubit_confusion_matrix(0, confusion_matrix())
+        .unwrap();
+    let result = measurement(false, Some(mitigation))
+        .evaluate(measured_registers, HashMap::new(), HashMap::new())
+        .unwrap()
+        .unwrap();
+    assert!((result["z0"] + 0.5).abs() < 1e-6);
+    assert!((result["z0z1"] + 0.5).abs() < 1e-6);
+}
+
+/// Test that flipped readouts are corrected before the parity is flipped
+#[test]
+fn correct_flipped_measurement() {
+    let mut measured_registers: HashMap<String, BitOutputRegister> = HashMap::new();
+    measured_registers.insert("ro".to_string(), register(15, 25));
+    measured_registers.insert("ro_flipped".to_string(), register(29, 11));
+
+    let mut mitigation = ReadoutMitigation::new(ReadoutMitigationMethod::TensoredInversion);
+    mitigation
+        .add_single_qubit_confusion_matrix(0, confusion_matrix())
+        .unwrap();
+    let result = measurement(true, Some(mitigation))
+        .evaluate(measured_registers, HashMap::new(), HashMap::new())
+        .unwrap()
+        .unwrap();
+    assert!((result["z0"] + 0.5).abs() < 1e-10);
+}
+
+/// Test that a correlated confusion matrix without correlations equals the single qubit correction
+#[test]
+fn correct_correlated() {
+    let mut measured_registers: HashMap<String, BitOutputRegister> = HashMap::new();
+    measured_registers.insert("ro".to_string(), register(3, 5));
+
+    let single = confusion_matrix();
+    let correlated = Array2::from_shape_fn((4, 4), |(i, j)| {
+        if i / 2 == j / 2 {
+            single[[i % 2, j % 2]]
+        } else {
+            0.0
+        }
+    });
+    let mut mitigation = ReadoutMitigation::new(ReadoutMitigationMethod::TensoredInversion);
+    mitigation
+        .add_correlated_confusion_matrix(vec![0, 1], correlated)
+        .unwrap();
+    let result = measurement(false, Some(mitigation))
+        .evaluate(measured_registers, HashMap::new(), HashMap::new())
+        .unwrap()
+        .unwrap();
+    assert!((result["z0"] + 0.5).abs() < 1e-10);
+    assert!((result["z0z1"] + 0.5).abs() < 1e-10);
+}
+
+/// Test that invalid confusion matrices are rejected
+#[test]
+fn invalid_confusion_matrices() {
+    let mut mitigation = ReadoutMitigation::new(ReadoutMitigationMethod::TensoredInversion);
+    assert!(mitigation
+        .add_single_qubit_confusion_matrix(0, Array2::eye(4))
+        .is_err());
+    assert!(mitigation
+        .add_single_qubit_confusion_matrix(0, array![[0.9, 0.2], [0.2, 0.8]])
+        .is_err());
+    assert!(mitigation
+        .add_single_qubit_confusion_matrix(0, array![[1.1, 0.0], [-0.1, 1.0]])
+        .is_err());
+    assert!(mitigation
+        .add_correlated_confusion_matrix(vec![0, 0], Array2::eye(4))
+        .is_err());
+    mitigation
+        .add_correlated_confusion_matrix(vec![0, 1], Array2::eye(4))
+        .unwrap();
+    assert!(mitigation
+        .add_correlated_confusion_matrix(vec![1, 2], Array2::eye(4))
+        .is_err());
+}
+
+/// Test that singular confusion matrices return an error when using tensored inversion
+#[test]
+fn singular_confusion_matrix() {
+    let mut measured_registers: HashMap<String, BitOutputRegister> = HashMap::new();
+    measured_registers.insert("ro".to_string(), register(3, 5));
+    let mut mitigation = ReadoutMitigation::new(ReadoutMitigationMethod::TensoredInversion);
+    mitigation
+        .add_single_qubit_confusion_matrix(0, array![[0.5, 0.5], [0.5, 0.5]])
+        .unwrap();
+    let result = measurement(false, Some(mitigation)).evaluate(
+        measured_registers,
+        HashMap::new(),
+        HashMap::new(),
+    );
+    assert_eq!(
+        result,
+        Err(RoqoqoError::BasisRotationMeasurementError {
+            msg: "Confusion matrix is not invertible".to_string()
+        })
+    );
+}
+
+struct ReadoutDevice;
+
+impl Device for ReadoutDevice {
+    fn single_qubit_gate_time(&self, _hqslang: &str, _qubit: &usize) -> Option<f64> {
+        None
+    }
+
+    fn two_qubit_gate_time(
+        &self,
+        _hqslang: &str,
+        _control: &usize,
+        _target: &usize,
+    ) -> Option<f64> {
+        None
+    }
+
+    fn multi_qubit_gate_time(&self, _hqslang: &str, _qubits: &[usize]) -> Option<f64> {
+        None
+    }
+
+    fn qubit_decoherence_rates(&self, _qubit: &usize) -> Option<Array2<f64>> {
+        None
+    }
+
+    fn readout_confusion_matrix(&self, qubit: &usize) -> Option<Array2<f64>> {
+        match qubit {
+            0 => Some(confusion_matrix()),
+            _ => None,
+        }
+    }
+
+    fn number_qubits(&self) -> usize {
+        2
+    }
+
+    fn two_qubit_edges(&self) -> Vec<(usize, usize)> {
+        vec![(0, 1)]
+    }
+}
+
+/// Test that confusion matrices are derived from a device
+#[test]
+fn from_device() {
+    let mitigation =
+        ReadoutMitigation::from_device(&ReadoutDevice, ReadoutMitigationMethod::TensoredInversion)
+            .unwrap();
+    assert_eq!(mitigation.single_qubit_confusion_matrices.len(), 1);
+    assert_eq!(
+        mitigation.single_qubit_confusion_matrices.get(&0),
+        Some(&confusion_matrix())
+    );
+    assert!(mitigation.correlated_confusion_matrices.is_empty());
+}
@@ -0,0 +1,117 @@
+// Copyright © 2021 HQS Quantum Simulations GmbH. All Rights Reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except
+// in compliance with the License. You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the
+// License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either
+// express or implied. See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Integration test for public API of sparse operators in CSR format
+
+use num_complex::Complex64;
+use roqoqo::measurements::SparseOperatorCsr;
+use roqoqo::RoqoqoError;
+
+fn pauli_y() -> Vec<(usize, usize, Complex64)> {
+    vec![
+        (1, 0, Complex64::new(0.0, 1.0)),
+        (0, 1, Complex64::new(0.0, -1.0)),
+    ]
+}
+
+#[test]
+fn test_from_triplets() {
+    let operator = SparseOperatorCsr::from_triplets(&pauli_y(), 1).unwrap();
+    assert_eq!(operator.dimension(), 2);
+    assert_eq!(operator.number_nonzero(), 2);
+    let operator_cloned = operator.clone();
+    assert_eq!(operator, operator_cloned);
+
+    let empty = SparseOperatorCsr::from_triplets(&[], 2).unwrap();
+    assert_eq!(empty.dimension(), 4);
+    assert_eq!(empty.number_nonzero(), 0);
+    let state = vec![Complex64::new(0.5, 0.0); 4];
+    assert_eq!(
+        empty.vector_expectation_value(&state),
+        Complex64::new(0.0, 0.0)
+    );
+}
+
+#[test]
+fn test_duplicate_entries_summed() {
+    let triplets = vec![
+        (0, 0, Complex64::new(0.5, 0.0)),
+        (1, 1, Complex64::new(-1.0, 0.0)),
+        (0, 0, Complex64::new(0.5, 0.0)),
+    ];
+    let operator = SparseOperatorCsr::from_triplets(&triplets, 1).unwrap();
+    assert_eq!(operator.number_nonzero(), 2);
+    let state = vec![Complex64::new(1.0, 0.0), Complex64::new(0.0, 0.0)];
+    assert_eq!(
+        operator.vector_expectation_value(&state),
+        Complex64::new(1.0, 0.0)
+    );
+}
+
+#[test]
+fn test_from_triplets_error() {
+    let triplets = vec![(0, 2, Complex64::new(1.0, 0.0))];
+    let error = SparseOperatorCsr::from_triplets(&triplets, 1);
+    assert_eq!(
+        error,
+        Err(RoqoqoError::MismatchedOperatorDimension {
+            index: (0, 2),
+            number_qubits: 1
+        })
+    );
+}
+
+#[test]
+fn test_vector_expectation_value() {
+    let operator = SparseOperatorCsr::from_triplets(&pauli_y(), 1).unwrap();
+    let amplitude = std::f64::consts::FRAC_1_SQRT_2;
+    let state = vec![
+        Complex64::new(amplitude, 0.0),
+        Complex64::new(0.0, amplitude),
+    ];
+    let value = operator.vector_expectation_value(&state);
+    assert!((value - Complex64::new(1.0, 0.0)).norm() < 1e-10);
+
+    let sigma_plus =
+        SparseOperatorCsr::from_triplets(&[(0, 1, Complex64::new(1.0, 0.0))], 1).unwrap();
+    let value = sigma_plus.vector_expectation_value(&state);
+    assert!((value - Complex64::new(0.0, 0.5)).norm() < 1e-10);
+}
+
+#[test]
+fn test_density_matrix_expectation_value() {
+    // Pauli Z on the first qubit of two qubits
+    let triplets: Vec<(usize, usize, Complex64)> = (0..4)
+        .map(|index| {
+            let sign = if index & 1 == 1 { -1.0 } else { 1.0 };
+            (index, index, Complex64::new(sign, 0.0))
+        })
+        .collect();
+    let operator = SparseOperatorCsr::from_triplets(&triplets, 2).unwrap();
+    let mut density_matrix = vec![Complex64::new(0.0, 0.0); 16];
+    density_matrix[0] = Complex64::new(0.5, 0.0);
+    density_matrix[5] = Complex64::new(0.25, 0.0);
+    density_matrix[10] = Complex64::new(0.25, 0.0);
+    let value = operator.density_matrix_expectation_value(&density_matrix);
+    assert!((value - Complex64::new(0.5, 0.0)).norm() < 1e-10);
+
+    // Tr(Yρ) for the pure state (|0> + i|1>)/sqrt(2)
+    let operator = SparseOperatorCsr::from_triplets(&pauli_y(), 1).unwrap();
+    let density_matrix = vec![
+        Complex64::new(0.5, 0.0),
+        Complex64::new(0.0, -0.5),
+        Complex64::new(0.0, 0.5),
+        Complex64::new(0.5, 0.0),
+    ];
+    let value = operator.density_matrix_expectation_value(&density_matrix);
+    assert!((value - Complex64::new(1.0, 0.0)).norm() < 1e-10);
+}
@@ -0,0 +1,227 @@
+// Copyright © 2021 HQS Quantum Simulations GmbH. All Rights Reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except
+// in compliance with the License. You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the
+// License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either
+// express or implied. See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Integration test for public API of the evaluation of statistical errors
+
+use num_complex::Complex64;
+use qoqo_calculator::CalculatorFloat;
+use roqoqo::measurements::{
+    BasisRotation, BasisRotationInput, Cheated, CheatedBasisRotation, CheatedBasisRotationInput,
+    CheatedInput,
+};
+use roqoqo::prelude::*;
+use roqoqo::registers::{BitOutputRegister, ComplexOutputRegister, FloatOutputRegister};
+use roqoqo::Circuit;
+use std::collections::HashMap;
+
+fn basis_rotation(use_flipped_measurement: bool) -> BasisRotation {
+    let mut bri = BasisRotationInput::new(2, use_flipped_measurement);
+    let z0 = bri.add_pauli_product("ro".to_string(), vec![0]).unwrap();
+    let z1 = bri.add_pauli_product("ro".to_string(), vec![1]).unwrap();
+    let mut linear_map: HashMap<usize, f64> = HashMap::new();
+    linear_map.insert(z0, 2.0);
+    bri.add_linear_exp_val("linear".to_string(), linear_map)
+        .unwrap();
+    let mut linear_map: HashMap<usize, f64> = HashMap::new();
+    linear_map.insert(z0, 1.0);
+    linear_map.insert(z1, 1.0);
+    bri.add_linear_exp_val("sum".to_string(), linear_map)
+        .unwrap();
+    bri.add_symbolic_exp_val(
+        "symbolic".to_string(),
+        CalculatorFloat::from("2 * pauli_product_0 + 1"),
+    )
+    .unwrap();
+    BasisRotation {
+        constant_circuit: None,
+        circuits: vec![Circuit::new()],
+        input: bri,
+    }
+}
+
+/// Test mean, standard error and covariance of the BasisRotation measurement
+#[test]
+fn basis_rotation_errors() {
+    // Perfectly correlated qubits with mean zero
+    let register: BitOutputRegister = vec![
+        vec![true, true],
+        vec![true, true],
+        vec![false, false],
+        vec![false, false],
+    ];
+    let mut measured_registers: HashMap<String, BitOutputRegister> = HashMap::new();
+    measured_registers.insert("ro".to_string(), register);
+    let measurement = basis_rotation(false);
+    let result = measurement
+        .evaluate_with_errors(measured_registers.clone(), HashMap::new(), HashMap::new())
+        .unwrap()
+        .unwrap();
+    let means = measurement
+        .evaluate(measured_registers, HashMap::new(), HashMap::new())
+        .unwrap()
+        .unwrap();
+
+    // Sample variance of single shots is 4/3, variance of the mean 1/3
+    let variance = 1.0 / 3.0;
+    assert!((result.pauli_product_covariance[[0, 0]] - variance).abs() < 1e-10);
+    assert!((result.pauli_product_covariance[[0, 1]] - variance).abs() < 1e-10);
+    assert!((result.pauli_product_covariance[[1, 1]] - variance).abs() < 1e-10);
+
+    let linear = result.expectation_values["linear"];
+    assert!((linear.mean - means["linear"]).abs() < 1e-10);
+    assert!((linear.standard_error - 2.0 * variance.sqrt()).abs() < 1e-10);
+    let sum = result.expectation_values["sum"];
+    assert!((sum.standard_error - 2.0 * variance.sqrt()).abs() < 1e-10);
+    let symbolic = result.expectation_values["symbolic"];
+    assert!((symbolic.mean - 1.0).abs() < 1e-10);
+    assert!((symbolic.standard_error - 2.0 * variance.sqrt()).abs() < 1e-6);
+}
+
+/// Test that normal and flipped readouts are averaged as independent measurements
+#[test]
+fn basis_rotation_errors_flipped() {
+    let register: BitOutputRegister = vec![
+        vec![true, false],
+        vec![true, false],
+        vec![false, false],
+        vec![false, false],
+    ];
+    let mut measured_registers: HashMap<String, BitOutputRegister> = HashMap::new();
+    measured_registers.insert("ro".to_string(), register.clone());
+    measured_registers.insert("ro_flipped".to_string(), register);
+    let result = basis_rotation(true)
+        .evaluate_with_errors(measured_registers, HashMap::new(), HashMap::new())
+        .unwrap()
+        .unwrap();
+    assert!((result.pauli_product_covariance[[0, 0]] - 1.0 / 6.0).abs() < 1e-10);
+    assert_eq!(result.pauli_product_covariance[[1, 1]], 0.0);
+    assert_eq!(result.pauli_product_covariance[[0, 1]], 0.0);
+}
+
+/// Test that errors can not be estimated from a single shot
+#[test]
+fn basis_rotation_single_shot() {
+    let mut measured_registers: HashMap<String, BitOutputRegister> = HashMap::new();
+    measured_registers.insert("ro".to_string(), vec![vec![true, false]]);
+    let result = basis_rotation(false).evaluate_with_errors(
+        measured_registers,
+        HashMap::new(),
+        HashMap::new(),
+    );
+    assert!(result.is_err());
+}
+
+/// Test the trajectory errors of the CheatedBasisRotation measurement
+#[test]
+fn cheated_basis_rotation_errors() {
+    let mut cbri = CheatedBasisRotationInput::new();
+    let index = cbri.add_pauli_product("ro".to_string());
+    let mut linear_map: HashMap<usize, f64> = HashMap::new();
+    linear_map.insert(index, 3.0);
+    cbri.add_linear_exp_val("linear".to_string(), linear_map)
+        .unwrap();
+    let measurement = CheatedBasisRotation {
+        constant_circuit: None,
+        circuits: vec![Circuit::new()],
+        input: cbri,
+    };
+
+    let mut float_registers: HashMap<String, FloatOutputRegister> = HashMap::new();
+    float_registers.insert("ro".to_string(), vec![vec![0.5], vec![0.7]]);
+    let result = measurement
+        .evaluate_with_errors(HashMap::new(), float_registers, HashMap::new())
+        .unwrap()
+        .unwrap();
+    assert!((result.pauli_product_covariance[[0, 0]] - 0.01).abs() < 1e-10);
+    let linear = result.expectation_values["linear"];
+    assert!((linear.mean - 1.8).abs() < 1e-10);
+    assert!((linear.standard_error - 0.3).abs() < 1e-10);
+
+    // A single exact value has no statistical error
+    let mut float_registers: HashMap<String, FloatOutputRegister> = HashMap::new();
+    float_registers.insert("ro".to_string(), vec![vec![0.5]]);
+    let result = measurement
+        .evaluate_with_errors(HashMap::new(), float_registers, HashMap::new())
+        .unwrap()
+        .unwrap();
+    assert_eq!(result.expectation_values["linear"].standard_error, 0.0);
+}
+
+/// Test that evaluate returns the means of evaluate_with_errors for registers with several rows
+#[test]
+fn cheated_basis_rotation_errors_match_evaluate() {
+    let mut cbri = CheatedBasisRotationInput::new();
+    let first = cbri.add_pauli_product("ro_0".to_string());
+    let second = cbri.add_pauli_product("ro_1".to_string());
+    let mut linear_map: HashMap<usize, f64> = HashMap::new();
+    linear_map.insert(first, 2.0);
+    linear_map.insert(second, -1.0);
+    cbri.add_linear_exp_val("linear".to_string(), linear_map)
+        .unwrap();
+    let measurement = CheatedBasisRotation {
+        constant_circuit: None,
+        circuits: vec![Circuit::new()],
+        input: cbri,
+    };
+
+    let mut float_registers: HashMap<String, FloatOutputRegister> = HashMap::new();
+    float_registers.insert("ro_0".to_string(), vec![vec![0.2], vec![0.6], vec![0.7]]);
+    float_registers.insert("ro_1".to_string(), vec![vec![-0.4], vec![0.1]]);
+    let evaluated = measurement
+        .evaluate(HashMap::new(), float_registers.clone(), HashMap::new())
+        .unwrap()
+        .unwrap();
+    let with_errors = measurement
+        .evaluate_with_errors(HashMap::new(), float_registers, HashMap::new())
+        .unwrap()
+        .unwrap();
+    assert!((evaluated["linear"] - 1.15).abs() < 1e-10);
+    assert!((evaluated["linear"] - with_errors.expectation_values["linear"].mean).abs() < 1e-10);
+}
+
+/// Test the trajectory errors of the Cheated measurement
+#[test]
+fn cheated_errors() {
+    let mut cheated_input = CheatedInput::new(1);
+    cheated_input
+        .add_operator_exp_val(
+            "z".to_string(),
+            vec![
+                (0, 0, Complex64::new(1.0, 0.0)),
+                (1, 1, Complex64::new(-1.0, 0.0)),
+            ],
+            "ro".to_string(),
+        )
+        .unwrap();
+    let measurement = Cheated {
+        constant_circuit: None,
+        circuits: vec![Circuit::new()],
+        input: cheated_input,
+    };
+
+    let mut complex_registers: HashMap<String, ComplexOutputRegister> = HashMap::new();
+    complex_registers.insert(
+        "ro".to_string(),
+        vec![
+            vec![Complex64::new(1.0, 0.0), Complex64::new(0.0, 0.0)],
+            vec![Complex64::new(0.0, 0.0), Complex64::new(1.0, 0.0)],
+        ],
+    );
+    let result = measurement
+        .evaluate_with_errors(HashMap::new(), HashMap::new(), complex_registers)
+        .unwrap()
+        .unwrap();
+    let z = result.expectation_values["z"];
+    assert!(z.mean.abs() < 1e-10);
+    assert!((z.standard_error - 1.0).abs() < 1e-10);
+    assert_eq!(result.pauli_product_covariance.shape(), &[0, 0]);
+}
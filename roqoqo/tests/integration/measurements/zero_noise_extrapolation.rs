@@ -0,0 +1,292 @@
+// Copyright © 2021 HQS Quantum Simulations GmbH. All Rights Reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except
+// in compliance with the License. You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the
+// License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either
+// express or implied. See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Integration test for public API of zero-noise extrapolation
+
+use ndarray::Array2;
+use num_complex::Complex64;
+use roqoqo::backends::RegisterResult;
+use roqoqo::measurements::{
+    BasisRotation, BasisRotationInput, Extrapolation, NoiseScaling, ZeroNoiseExtrapolation,
+};
+use roqoqo::operations::*;
+use roqoqo::prelude::*;
+use roqoqo::registers::{BitOutputRegister, ComplexOutputRegister, FloatOutputRegister};
+use roqoqo::{Circuit, RoqoqoError};
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use test_case::test_case;
+
+/// Backend flipping the readout with a probability proportional to the number of gates and the noise boost
+struct NoisyBackend;
+
+impl EvaluatingBackend for NoisyBackend {
+    fn run_circuit_iterator<'a>(
+        &self,
+        circuit: impl Iterator<Item = &'a Operation>,
+    ) -> RegisterResult {
+        let mut number_gates = 0.0;
+        let mut boost = 1.0;
+        for operation in circuit {
+            if let Operation::PragmaBoostNoise(op) = operation {
+                boost = *op.noise_coefficient().float().unwrap();
+            } else if operation.tags().contains(&"GateOperation") {
+                number_gates += 1.0;
+            }
+        }
+        let number_flipped = (5.0 * number_gates * boost).round() as usize;
+        let mut register: BitOutputRegister = vec![vec![true]; number_flipped];
+        register.extend(vec![vec![false]; 100 - number_flipped]);
+        let mut bit_registers: HashMap<String, BitOutputRegister> = HashMap::new();
+        bit_registers.insert("ro".to_string(), register);
+        let float_registers: HashMap<String, FloatOutputRegister> = HashMap::new();
+        let complex_registers: HashMap<String, ComplexOutputRegister> = HashMap::new();
+        Ok((bit_registers, float_registers, complex_registers))
+    }
+}
+
+/// Creates a measurement of < Z0 > after a single gate
+fn measurement() -> BasisRotation {
+    let mut input = BasisRotationInput::new(1, false);
+    let index = input.add_pauli_product("ro".to_string(), vec![0]).unwrap();
+    let mut linear: HashMap<usize, f64> = HashMap::new();
+    linear.insert(index, 1.0);
+    input.add_linear_exp_val("z".to_string(), linear).unwrap();
+    let mut constant_circuit = Circuit::new();
+    constant_circuit += RotateX::new(0, 0.0.into());
+    let mut circuit = Circuit::new();
+    circuit += PragmaRepeatedMeasurement::new("ro".to_string(), 100, None);
+    BasisRotation {
+        constant_circuit: Some(constant_circuit),
+        circuits: vec![circuit],
+        input,
+    }
+}
+
+/// Test extrapolation of a noisy measurement run on a backend
+#[test_case(NoiseScaling::BoostNoise, Extrapolation::Linear, vec![1.0, 2.0, 3.0]; "boost linear")]
+#[test_case(NoiseScaling::BoostNoise, Extrapolation::Richardson, vec![1.0, 2.0]; "boost richardson")]
+#[test_case(NoiseScaling::UnitaryFolding, Extrapolation::Linear, vec![1.0, 3.0, 5.0]; "folding linear")]
+#[test_case(NoiseScaling::UnitaryFolding, Extrapolation::Richardson, vec![1.0, 3.0, 5.0]; "folding richardson")]
+fn run_zne(noise_scaling: NoiseScaling, extrapolation: Extrapolation, scale_factors: Vec<f64>) {
+    let zne = ZeroNoiseExtrapolation::new(noise_scaling, extrapolation, scale_factors).unwrap();
+    let unmitigated = NoisyBackend
+        .run_measurement(&measurement())
+        .unwrap()
+        .unwrap();
+    assert!((unmitigated["z"] - 0.9).abs() < 1e-10);
+    let result = zne.run(&NoisyBackend, &measurement()).unwrap().unwrap();
+    assert!((result["z"] - 1.0).abs() < 1e-10);
+}
+
+/// Test the noise scaled measurement circuits
+#[test]
+fn scale_measurement() {
+    let zne = ZeroNoiseExtrapolation::new(
+        NoiseScaling::BoostNoise,
+        Extrapolation::Linear,
+        vec![1.0, 2.0],
+    )
+    .unwrap();
+    let scaled = zne.scale_measurement(&measurement(), 2.0).unwrap();
+    let mut expected = Circuit::new();
+    expected += PragmaBoostNoise::new(2.0.into());
+    expected += RotateX::new(0, 0.0.into());
+    assert_eq!(scaled.constant_circuit(), &Some(expected));
+    assert_eq!(scaled.circuits, measurement().circuits);
+    assert_eq!(scaled.measurement, measurement());
+
+    let zne = ZeroNoiseExtrapolation::new(
+        NoiseScaling::UnitaryFolding,
+        Extrapolation::Linear,
+        vec![1.0, 3.0],
+    )
+    .unwrap();
+    let scaled = zne.scale_measurement(&measurement(), 3.0).unwrap();
+    let mut expected = Circuit::new();
+    expected += RotateX::new(0, 0.0.into());
+    expected += RotateX::new(0, (-0.0).into());
+    expected += RotateX::new(0, 0.0.into());
+    assert_eq!(scaled.constant_circuit(), &Some(expected));
+    assert_eq!(scaled.circuits, measurement().circuits);
+}
+
+/// Test folding of a fraction of the gates
+#[test]
+fn partial_folding() {
+    let zne = ZeroNoiseExtrapolation::new(
+        NoiseScaling::UnitaryFolding,
+        Extrapolation::Linear,
+        vec![1.0, 2.0],
+    )
+    .unwrap();
+    let mut circuit = Circuit::new();
+    circuit += DefinitionBit::new("ro".to_string(), 2, true);
+    circuit += Hadamard::new(0);
+    circuit += CNOT::new(0, 1);
+    circuit += MeasureQubit::new(0, "ro".to_string(), 0);
+    let folded = zne.scale_circuit(&circuit, 2.0).unwrap();
+    let mut expected = Circuit::new();
+    expected += DefinitionBit::new("ro".to_string(), 2, true);
+    expected += Hadamard::new(0);
+    expected += Hadamard::new(0);
+    expected += Hadamard::new(0);
+    expected += CNOT::new(0, 1);
+    expected += MeasureQubit::new(0, "ro".to_string(), 0);
+    assert_eq!(folded, expected);
+}
+
+/// Test that the folded gates are inverted correctly
+#[test_case(SGate::new(0).into(); "sgate")]
+#[test_case(TGate::new(0).into(); "tgate")]
+#[test_case(SqrtPauliX::new(0).into(); "sqrtpaulix")]
+#[test_case(RotateAroundSphericalAxis::new(0, 0.3.into(), 0.2.into(), 1.1.into()).into(); "spherical")]
+#[test_case(SingleQubitGate::new(0, 0.6.into(), 0.0.into(), 0.0.into(), 0.8.into(), 0.4.into()).into(); "singlequbitgate")]
+#[test_case(ISwap::new(0, 1).into(); "iswap")]
+#[test_case(SqrtISwap::new(0, 1).into(); "sqrtiswap")]
+#[test_case(MolmerSorensenXX::new(0, 1).into(); "molmersorensen")]
+#[test_case(ControlledPhaseShift::new(0, 1, 0.7.into()).into(); "controlledphaseshift")]
+#[test_case(FSwap::new(0, 1).into(); "fswap")]
+#[test_case(PauliY::new(0).into(); "pauliy")]
+#[test_case(InvSqrtPauliX::new(0).into(); "invsqrtpaulix")]
+#[test_case(InvSqrtISwap::new(0, 1).into(); "invsqrtiswap")]
+#[test_case(ControlledPauliY::new(0, 1).into(); "controlledpauliy")]
+fn inverse_gates(gate: Operation) {
+    let zne = ZeroNoiseExtrapolation::new(
+        NoiseScaling::UnitaryFolding,
+        Extrapolation::Linear,
+        vec![1.0, 3.0],
+    )
+    .unwrap();
+    let mut circuit = Circuit::new();
+    circuit += gate.clone();
+    let folded = zne.scale_circuit(&circuit, 3.0).unwrap();
+    assert_eq!(folded.len(), 3);
+    assert_eq!(folded[0], gate);
+    assert_eq!(folded[2], gate);
+    let matrix = GateOperation::try_from(gate)
+        .unwrap()
+        .unitary_matrix()
+        .unwrap();
+    let inverse = GateOperation::try_from(folded[1].clone())
+        .unwrap()
+        .unitary_matrix()
+        .unwrap();
+    let product = inverse.dot(&matrix);
+    let identity: Array2<Complex64> = Array2::eye(product.nrows());
+    for (value, expected) in product.iter().zip(identity.iter()) {
+        assert!((value - expected).norm() < 1e-10);
+    }
+}
+
+/// Test that gates without a known inverse cannot be folded
+#[test]
+fn fold_unsupported_gate() {
+    let zne = ZeroNoiseExtrapolation::new(
+        NoiseScaling::UnitaryFolding,
+        Extrapolation::Linear,
+        vec![1.0, 3.0],
+    )
+    .unwrap();
+    let mut circuit = Circuit::new();
+    circuit += GivensRotation::new(0, 1, 0.1.into(), 0.2.into());
+    assert!(zne.scale_circuit(&circuit, 3.0).is_err());
+    assert!(zne.scale_circuit(&circuit, 1.0).is_ok());
+}
+
+/// Test the extrapolation of single expectation values
+#[test_case(Extrapolation::Linear, vec![1.0, 2.0, 3.0], vec![0.8, 0.6, 0.4], 1.0; "linear")]
+#[test_case(Extrapolation::Linear, vec![1.0, 2.0, 3.0], vec![0.9, 0.6, 0.5], 3.2 / 3.0; "linear fit")]
+#[test_case(Extrapolation::Richardson, vec![1.0, 2.0, 3.0], vec![0.5, 0.0, -0.3], 1.2; "richardson")]
+#[test_case(Extrapolation::Exponential, vec![1.0, 2.0], vec![2.0 * (-0.5_f64).exp(), 2.0 * (-1.0_f64).exp()], 2.0; "exponential")]
+#[test_case(Extrapolation::Exponential, vec![1.0, 3.0], vec![-0.5, -0.125], -1.0; "exponential negative")]
+fn extrapolate_values(
+    extrapolation: Extrapolation,
+    scale_factors: Vec<f64>,
+    values: Vec<f64>,
+    expected: f64,
+) {
+    let zne = ZeroNoiseExtrapolation::new(NoiseScaling::BoostNoise, extrapolation, scale_factors)
+        .unwrap();
+    let extrapolated = zne.extrapolate_values(&values).unwrap();
+    assert!((extrapolated - expected).abs() < 1e-10);
+}
+
+/// Test errors of the zero-noise extrapolation
+#[test]
+fn zne_errors() {
+    assert!(ZeroNoiseExtrapolation::new(
+        NoiseScaling::BoostNoise,
+        Extrapolation::Linear,
+        vec![1.0]
+    )
+    .is_err());
+    assert!(ZeroNoiseExtrapolation::new(
+        NoiseScaling::BoostNoise,
+        Extrapolation::Linear,
+        vec![0.5, 1.0]
+    )
+    .is_err());
+    assert!(ZeroNoiseExtrapolation::new(
+        NoiseScaling::BoostNoise,
+        Extrapolation::Linear,
+        vec![2.0, 2.0]
+    )
+    .is_err());
+
+    let zne = ZeroNoiseExtrapolation::new(
+        NoiseScaling::BoostNoise,
+        Extrapolation::Exponential,
+        vec![1.0, 2.0],
+    )
+    .unwrap();
+    assert!(zne.extrapolate_values(&[0.5, -0.5]).is_err());
+    assert!(zne.extrapolate_values(&[0.5]).is_err());
+    assert_eq!(zne.extrapolate_values(&[0.0, 0.0]), Ok(0.0));
+
+    let mut first: HashMap<String, f64> = HashMap::new();
+    first.insert("z".to_string(), 0.5);
+    let second: HashMap<String, f64> = HashMap::new();
+    assert_eq!(
+        zne.extrapolate(&[first, second]),
+        Err(RoqoqoError::ZeroNoiseExtrapolationError {
+            msg: "Expectation value z is missing for a scale factor".to_string()
+        })
+    );
+}
+
+/// Test parameter substitution of the scaled measurement
+#[test]
+fn substitute_parameters() {
+    let zne = ZeroNoiseExtrapolation::new(
+        NoiseScaling::UnitaryFolding,
+        Extrapolation::Linear,
+        vec![1.0, 3.0],
+    )
+    .unwrap();
+    let mut measurement = measurement();
+    let mut constant_circuit = Circuit::new();
+    constant_circuit += RotateX::new(0, "theta".into());
+    measurement.constant_circuit = Some(constant_circuit);
+    let scaled = zne.scale_measurement(&measurement, 3.0).unwrap();
+    let mut parameters: HashMap<String, f64> = HashMap::new();
+    parameters.insert("theta".to_string(), 0.5);
+    let substituted = scaled.substitute_parameters(parameters).unwrap();
+    let mut expected = Circuit::new();
+    expected += RotateX::new(0, 0.5.into());
+    expected += RotateX::new(0, (-0.5).into());
+    expected += RotateX::new(0, 0.5.into());
+    assert_eq!(substituted.constant_circuit(), &Some(expected));
+    let mut expected = Circuit::new();
+    expected += RotateX::new(0, 0.5.into());
+    assert_eq!(substituted.measurement.constant_circuit(), &Some(expected));
+}
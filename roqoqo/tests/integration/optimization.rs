@@ -0,0 +1,446 @@
+// Copyright © 2021 HQS Quantum Simulations GmbH. All Rights Reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except
+// in compliance with the License. You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the
+// License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either
+// express or implied. See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Integration test for public API of the variational optimization
+
+use ndarray::array;
+use num_complex::Complex64;
+use roqoqo::measurements::{CheatedBasisRotation, CheatedBasisRotationInput, ClassicalRegister};
+use roqoqo::operations::*;
+use roqoqo::optimization::{
+    finite_difference_gradient, parameter_shift_gradient, CostFunction, GradientMethod, Objective,
+    OptimizationRecord, Optimizer, OptimizerMethod, QuantumProgramObjective, StoppingCriteria,
+    DEFAULT_FINITE_DIFFERENCE_STEP,
+};
+use roqoqo::prelude::*;
+use roqoqo::registers::{BitOutputRegister, ComplexOutputRegister, FloatOutputRegister};
+use roqoqo::{Circuit, QuantumProgram, RoqoqoBackendError};
+use std::collections::HashMap;
+use std::f64::consts::PI;
+use test_case::test_case;
+
+/// Quadratic objective with minimum 0 at (1, -0.5)
+struct Quadratic;
+
+impl Objective for Quadratic {
+    fn value(&self, parameters: &[f64]) -> Result<f64, RoqoqoBackendError> {
+        Ok((parameters[0] - 1.0).powi(2) + 2.0 * (parameters[1] + 0.5).powi(2))
+    }
+
+    fn gradient(&self, parameters: &[f64]) -> Result<Vec<f64>, RoqoqoBackendError> {
+        Ok(vec![
+            2.0 * (parameters[0] - 1.0),
+            4.0 * (parameters[1] + 0.5),
+        ])
+    }
+}
+
+/// Backend returning the cosine of the sum of the angles of the RotateX operations as float register "z"
+#[derive(Debug, Clone, Copy)]
+struct CosineBackend;
+
+impl EvaluatingBackend for CosineBackend {
+    fn run_circuit_iterator<'a>(
+        &self,
+        circuit: impl Iterator<Item = &'a Operation>,
+    ) -> roqoqo::backends::RegisterResult {
+        let mut angle = 0.0;
+        for operation in circuit {
+            if let Operation::RotateX(rotation) = operation {
+                angle += *rotation.theta().float()?;
+            }
+        }
+        let result_bit: HashMap<String, BitOutputRegister> = HashMap::new();
+        let mut result_float: HashMap<String, FloatOutputRegister> = HashMap::new();
+        result_float.insert("z".to_string(), vec![vec![angle.cos()]]);
+        let result_complex: HashMap<String, ComplexOutputRegister> = HashMap::new();
+        Ok((result_bit, result_float, result_complex))
+    }
+}
+
+fn cosine_program() -> QuantumProgram {
+    let mut input = CheatedBasisRotationInput::new();
+    let index = input.add_pauli_product("z".to_string());
+    let mut linear: HashMap<usize, f64> = HashMap::new();
+    linear.insert(index, 1.0);
+    input.add_linear_exp_val("z".to_string(), linear).unwrap();
+    let mut circuit = Circuit::new();
+    circuit += RotateX::new(0, "theta".into());
+    QuantumProgram::CheatedBasisRotation {
+        measurement: CheatedBasisRotation {
+            constant_circuit: None,
+            circuits: vec![circuit],
+            input,
+        },
+        input_parameter_names: vec!["theta".to_string()],
+    }
+}
+
+#[test_case(OptimizerMethod::nelder_mead(), 100; "nelder_mead")]
+#[test_case(OptimizerMethod::gradient_descent(0.1), 200; "gradient_descent")]
+#[test_case(OptimizerMethod::adam(0.05), 500; "adam")]
+fn test_minimize_quadratic(method: OptimizerMethod, max_iterations: usize) {
+    let mut optimizer = Optimizer::new(method, vec![0.0, 0.0]);
+    let criteria = StoppingCriteria {
+        max_iterations,
+        tolerance: 1e-10,
+    };
+    let best = optimizer.minimize(&Quadratic, &criteria).unwrap();
+    assert!((best.parameters[0] - 1.0).abs() < 1e-3);
+    assert!((best.parameters[1] + 0.5).abs() < 1e-3);
+    assert_eq!(optimizer.history()[0].iteration, 0);
+    assert_eq!(optimizer.history()[0].value, 1.5);
+    assert_eq!(optimizer.history().len(), optimizer.iteration() + 1);
+    assert!(optimizer.iteration() <= max_iterations);
+    assert_eq!(optimizer.best(), Some(&best));
+}
+
+#[test]
+fn test_minimize_spsa() {
+    let mut optimizer = Optimizer::new(OptimizerMethod::spsa(0.2, 0.1, 42), vec![0.0, 0.0]);
+    let criteria = StoppingCriteria {
+        max_iterations: 300,
+        tolerance: 0.0,
+    };
+    let best = optimizer.minimize(&Quadratic, &criteria).unwrap();
+    assert_eq!(optimizer.iteration(), 300);
+    assert!(best.value < 1e-4);
+}
+
+#[test]
+fn test_step_and_convergence() {
+    let mut optimizer = Optimizer::new(OptimizerMethod::gradient_descent(0.25), vec![0.0, -0.5]);
+    assert!(optimizer.best().is_none());
+    assert!(!optimizer.is_converged(1.0));
+    let record = optimizer.step(&Quadratic).unwrap().clone();
+    assert_eq!(
+        record,
+        OptimizationRecord {
+            iteration: 1,
+            parameters: vec![0.5, -0.5],
+            value: 0.25
+        }
+    );
+    assert_eq!(optimizer.parameters(), &[0.5, -0.5]);
+    assert_eq!(optimizer.history().len(), 2);
+    assert!(!optimizer.is_converged(0.5));
+    assert!(optimizer.is_converged(1.0));
+
+    let best = optimizer
+        .minimize(
+            &Quadratic,
+            &StoppingCriteria {
+                max_iterations: 1,
+                tolerance: 0.0,
+            },
+        )
+        .unwrap();
+    assert_eq!(best, record);
+}
+
+#[cfg(feature = "serialize")]
+#[test_case(OptimizerMethod::nelder_mead(); "nelder_mead")]
+#[test_case(OptimizerMethod::spsa(0.2, 0.1, 3); "spsa")]
+#[test_case(OptimizerMethod::adam(0.05); "adam")]
+fn test_resume_serialized(method: OptimizerMethod) {
+    let mut uninterrupted = Optimizer::new(method, vec![0.0, 0.0]);
+    let criteria = StoppingCriteria {
+        max_iterations: 20,
+        tolerance: 0.0,
+    };
+    uninterrupted.minimize(&Quadratic, &criteria).unwrap();
+
+    let mut interrupted = Optimizer::new(method, vec![0.0, 0.0]);
+    interrupted
+        .minimize(
+            &Quadratic,
+            &StoppingCriteria {
+                max_iterations: 7,
+                tolerance: 0.0,
+            },
+        )
+        .unwrap();
+    let serialized = bincode::serialize(&interrupted).unwrap();
+    let mut resumed: Optimizer = bincode::deserialize(&serialized).unwrap();
+    assert_eq!(resumed, interrupted);
+    resumed.minimize(&Quadratic, &criteria).unwrap();
+    assert_eq!(resumed, uninterrupted);
+}
+
+#[test]
+fn test_finite_difference_gradient() {
+    let gradient =
+        finite_difference_gradient(|parameters| Quadratic.value(parameters), &[0.0, 0.0], 1e-4)
+            .unwrap();
+    assert!((gradient[0] + 2.0).abs() < 1e-6);
+    assert!((gradient[1] - 2.0).abs() < 1e-6);
+}
+
+#[test]
+fn test_cost_function() {
+    let mut values: HashMap<String, f64> = HashMap::new();
+    values.insert("energy".to_string(), -1.0);
+    values.insert("penalty".to_string(), 2.0);
+    assert_eq!(
+        CostFunction::ExpectationValue("energy".to_string())
+            .evaluate(&values)
+            .unwrap(),
+        -1.0
+    );
+    assert_eq!(
+        CostFunction::Expression("energy + 0.5 * penalty^2".to_string())
+            .evaluate(&values)
+            .unwrap(),
+        1.0
+    );
+    assert!(CostFunction::ExpectationValue("missing".to_string())
+        .evaluate(&values)
+        .is_err());
+}
+
+#[test_case(GradientMethod::ParameterShift, 1e-9; "parameter_shift")]
+#[test_case(GradientMethod::FiniteDifference { step: 1e-5 }, 1e-6; "finite_difference")]
+fn test_quantum_program_objective(gradient_method: GradientMethod, accuracy: f64) {
+    let program = cosine_program();
+    let backend = CosineBackend;
+    let objective = QuantumProgramObjective::new(
+        &program,
+        &backend,
+        CostFunction::ExpectationValue("z".to_string()),
+        gradient_method,
+    );
+    assert_eq!(objective.gradient_method(), gradient_method);
+    assert!((objective.value(&[0.3]).unwrap() - 0.3_f64.cos()).abs() < 1e-12);
+    let gradient = objective.gradient(&[0.3]).unwrap();
+    assert!((gradient[0] + 0.3_f64.sin()).abs() < accuracy);
+
+    let expression = QuantumProgramObjective::new(
+        &program,
+        &backend,
+        CostFunction::Expression("2 * z + 1".to_string()),
+        gradient_method,
+    );
+    assert!((expression.value(&[0.3]).unwrap() - 2.0 * 0.3_f64.cos() - 1.0).abs() < 1e-12);
+    let gradient = expression.gradient(&[0.3]).unwrap();
+    assert!((gradient[0] + 2.0 * 0.3_f64.sin()).abs() < 1e-5);
+
+    assert!(objective.value(&[0.3, 0.1]).is_err());
+}
+
+#[test]
+fn test_minimize_quantum_program() {
+    let program = cosine_program();
+    let objective = QuantumProgramObjective::new(
+        &program,
+        &CosineBackend,
+        CostFunction::ExpectationValue("z".to_string()),
+        GradientMethod::ParameterShift,
+    );
+    let mut optimizer = Optimizer::new(OptimizerMethod::gradient_descent(0.5), vec![0.5]);
+    let best = optimizer
+        .minimize(&objective, &StoppingCriteria::default())
+        .unwrap();
+    assert!((best.parameters[0] - PI).abs() < 1e-3);
+    assert!((best.value + 1.0).abs() < 1e-6);
+}
+
+/// Backend simulating a single qubit and returning the expectation value of PauliZ as float register "z"
+#[derive(Debug, Clone, Copy)]
+struct SingleQubitBackend;
+
+impl EvaluatingBackend for SingleQubitBackend {
+    fn run_circuit_iterator<'a>(
+        &self,
+        circuit: impl Iterator<Item = &'a Operation>,
+    ) -> roqoqo::backends::RegisterResult {
+        let mut state = array![Complex64::new(1.0, 0.0), Complex64::new(0.0, 0.0)];
+        for operation in circuit {
+            // Bound input parameters are already substituted
+            if let Operation::InputSymbolic(_) = operation {
+                continue;
+            }
+            let gate = SingleQubitGateOperation::try_from(operation.clone())?;
+            state = gate.unitary_matrix()?.dot(&state);
+        }
+        let result_bit: HashMap<String, BitOutputRegister> = HashMap::new();
+        let mut result_float: HashMap<String, FloatOutputRegister> = HashMap::new();
+        result_float.insert(
+            "z".to_string(),
+            vec![vec![state[0].norm_sqr() - state[1].norm_sqr()]],
+        );
+        let result_complex: HashMap<String, ComplexOutputRegister> = HashMap::new();
+        Ok((result_bit, result_float, result_complex))
+    }
+}
+
+fn single_qubit_program(constant_circuit: Option<Circuit>, circuit: Circuit) -> QuantumProgram {
+    let mut input = CheatedBasisRotationInput::new();
+    let index = input.add_pauli_product("z".to_string());
+    let mut linear: HashMap<usize, f64> = HashMap::new();
+    linear.insert(index, 1.0);
+    input.add_linear_exp_val("z".to_string(), linear).unwrap();
+    QuantumProgram::CheatedBasisRotation {
+        measurement: CheatedBasisRotation {
+            constant_circuit,
+            circuits: vec![circuit],
+            input,
+        },
+        input_parameter_names: vec!["x".to_string(), "y".to_string()],
+    }
+}
+
+#[test]
+fn test_gradient_affine_angle() {
+    let mut circuit = Circuit::new();
+    circuit += RotateX::new(0, "2 * theta + 0.1".into());
+    circuit += RotateX::new(0, "theta".into());
+    let program = QuantumProgram::CheatedBasisRotation {
+        measurement: CheatedBasisRotation {
+            constant_circuit: None,
+            circuits: vec![circuit],
+            input: match cosine_program() {
+                QuantumProgram::CheatedBasisRotation { measurement, .. } => measurement.input,
+                _ => unreachable!(),
+            },
+        },
+        input_parameter_names: vec!["theta".to_string()],
+    };
+    let gradients = program.gradient(&CosineBackend, &[0.4]).unwrap();
+    let expected = -3.0 * (3.0 * 0.4_f64 + 0.1).sin();
+    assert!((gradients["z"][0] - expected).abs() < 1e-12);
+}
+
+#[test_case(None; "without_constant_circuit")]
+#[test_case(Some(vec![RotateY::new(0, "x - y".into()).into()]); "with_constant_circuit")]
+fn test_gradient_single_qubit(constant_operations: Option<Vec<Operation>>) {
+    let constant_circuit = constant_operations.map(|operations| {
+        let mut circuit = Circuit::new();
+        for operation in operations {
+            circuit.add_operation(operation);
+        }
+        circuit
+    });
+    let mut circuit = Circuit::new();
+    circuit += RotateY::new(0, "2 * x + 0.1".into());
+    circuit += RotateX::new(0, "x * y".into());
+    circuit += RotateZ::new(0, "sin(y)".into());
+    circuit += RotateAroundSphericalAxis::new(0, 0.7.into(), 1.2.into(), "y".into());
+    circuit += PauliX::new(0);
+    circuit += RotateY::new(0, "y".into());
+    let program = single_qubit_program(constant_circuit, circuit);
+    let parameters = [0.3, -0.8];
+
+    let gradients = program.gradient(&SingleQubitBackend, &parameters).unwrap();
+    let expected = finite_difference_gradient(
+        |parameters| Ok(program.run(&SingleQubitBackend, parameters)?.unwrap()["z"]),
+        &parameters,
+        1e-5,
+    )
+    .unwrap();
+    assert_eq!(gradients.len(), 1);
+    for (derivative, expected_derivative) in gradients["z"].iter().zip(expected.iter()) {
+        assert!((derivative - expected_derivative).abs() < 1e-6);
+    }
+}
+
+#[test]
+fn test_gradient_bound_parameters() {
+    let mut constant_circuit = Circuit::new();
+    constant_circuit += RotateY::new(0, "x - y".into());
+    let mut circuit = Circuit::new();
+    circuit += RotateY::new(0, "2 * x + y".into());
+    circuit += RotateX::new(0, "x * y".into());
+    circuit += RotateZ::new(0, "sin(y)".into());
+    let program = single_qubit_program(Some(constant_circuit), circuit);
+    let bound_program = program
+        .bind_parameters(&HashMap::from([("y".to_string(), -0.8)]))
+        .unwrap();
+
+    let gradients = bound_program.gradient(&SingleQubitBackend, &[0.3]).unwrap();
+    let expected = program.gradient(&SingleQubitBackend, &[0.3, -0.8]).unwrap();
+    assert_eq!(gradients["z"].len(), 1);
+    assert!((gradients["z"][0] - expected["z"][0]).abs() < 1e-10);
+}
+
+#[test]
+fn test_gradient_independent_and_errors() {
+    let mut circuit = Circuit::new();
+    circuit += RotateY::new(0, 0.5.into());
+    let program = single_qubit_program(None, circuit.clone());
+    let gradients = program.gradient(&SingleQubitBackend, &[0.1, 0.2]).unwrap();
+    assert_eq!(gradients["z"], vec![0.0, 0.0]);
+
+    assert!(program.gradient(&SingleQubitBackend, &[0.1]).is_err());
+
+    let measurement = match program {
+        QuantumProgram::CheatedBasisRotation { measurement, .. } => measurement,
+        _ => unreachable!(),
+    };
+    let gradients = parameter_shift_gradient(
+        &SingleQubitBackend,
+        &measurement,
+        &[],
+        &[],
+        DEFAULT_FINITE_DIFFERENCE_STEP,
+    )
+    .unwrap();
+    assert_eq!(gradients["z"], Vec::<f64>::new());
+
+    let register_program = QuantumProgram::ClassicalRegister {
+        measurement: ClassicalRegister {
+            constant_circuit: None,
+            circuits: vec![circuit],
+        },
+        input_parameter_names: vec![],
+    };
+    assert!(register_program.gradient(&SingleQubitBackend, &[]).is_err());
+}
+
+#[test]
+fn test_gradient_nonlinear_exp_val() {
+    let mut input = CheatedBasisRotationInput::new();
+    let index = input.add_pauli_product("z".to_string());
+    let mut linear: HashMap<usize, f64> = HashMap::new();
+    linear.insert(index, 1.0);
+    input.add_linear_exp_val("z".to_string(), linear).unwrap();
+    input
+        .add_symbolic_exp_val(
+            "z_squared".to_string(),
+            "pauli_product_0 * pauli_product_0".into(),
+        )
+        .unwrap();
+    let mut circuit = Circuit::new();
+    circuit += RotateX::new(0, "x".into());
+    circuit += RotateY::new(0, "2 * x".into());
+    let measurement = CheatedBasisRotation {
+        constant_circuit: None,
+        circuits: vec![circuit],
+        input,
+    };
+    assert!(!measurement.is_linear());
+    let program = QuantumProgram::CheatedBasisRotation {
+        measurement,
+        input_parameter_names: vec!["x".to_string()],
+    };
+    let parameters = [0.4];
+
+    let gradients = program.gradient(&SingleQubitBackend, &parameters).unwrap();
+    for name in ["z", "z_squared"] {
+        let expected = finite_difference_gradient(
+            |parameters| Ok(program.run(&SingleQubitBackend, parameters)?.unwrap()[name]),
+            &parameters,
+            1e-5,
+        )
+        .unwrap();
+        assert!((gradients[name][0] - expected[0]).abs() < 1e-5);
+    }
+}
@@ -0,0 +1,171 @@
+// Copyright © 2021 HQS Quantum Simulations GmbH. All Rights Reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except
+// in compliance with the License. You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the
+// License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either
+// express or implied. See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Integration test for public API of the QIR export
+
+use qoqo_calculator::{Calculator, CalculatorFloat};
+use roqoqo::operations::*;
+use roqoqo::qir::circuit_to_qir;
+use roqoqo::{Circuit, RoqoqoBackendError, RoqoqoError};
+use std::collections::HashMap;
+use test_case::test_case;
+
+/// Test export of a Bell state preparation
+#[test]
+fn export_bell_circuit() {
+    let mut circuit = Circuit::new();
+    circuit += DefinitionBit::new("ro".to_string(), 2, true);
+    circuit += Hadamard::new(0);
+    circuit += CNOT::new(0, 1);
+    circuit += RotateZ::new(1, CalculatorFloat::from(0.5));
+    circuit += MeasureQubit::new(0, "ro".to_string(), 0);
+    circuit += MeasureQubit::new(1, "ro".to_string(), 1);
+
+    let qir = circuit_to_qir(&circuit).unwrap();
+    let expected_body = "define void @main() #0 {
+entry:
+  call void @__quantum__rt__initialize(i8* null)
+  call void @__quantum__qis__h__body(%Qubit* null)
+  call void @__quantum__qis__cnot__body(%Qubit* null, %Qubit* inttoptr (i64 1 to %Qubit*))
+  call void @__quantum__qis__rz__body(double 0x3FE0000000000000, %Qubit* inttoptr (i64 1 to %Qubit*))
+  call void @__quantum__qis__mz__body(%Qubit* null, %Result* null)
+  call void @__quantum__qis__mz__body(%Qubit* inttoptr (i64 1 to %Qubit*), %Result* inttoptr (i64 1 to %Result*))
+  call void @__quantum__rt__array_record_output(i64 2, i8* null)
+  call void @__quantum__rt__result_record_output(%Result* null, i8* null)
+  call void @__quantum__rt__result_record_output(%Result* inttoptr (i64 1 to %Result*), i8* null)
+  ret void
+}";
+    assert!(qir.contains(expected_body));
+    assert!(qir.contains("declare void @__quantum__qis__mz__body(%Qubit*, %Result* writeonly) #1"));
+    assert!(qir.contains("declare void @__quantum__qis__rz__body(double, %Qubit*)"));
+    assert!(qir.contains("\"required_num_qubits\"=\"2\" \"required_num_results\"=\"2\""));
+    assert!(!qir.contains("__quantum__qis__x__body"));
+}
+
+/// Test that results of several registers are numbered consecutively and only outputs are recorded
+#[test]
+fn export_registers() {
+    let mut circuit = Circuit::new();
+    circuit += DefinitionBit::new("ancilla".to_string(), 1, false);
+    circuit += DefinitionBit::new("ro".to_string(), 2, true);
+    circuit += PauliX::new(2);
+    circuit += MeasureQubit::new(0, "ancilla".to_string(), 0);
+    let mut mapping: HashMap<usize, usize> = HashMap::new();
+    mapping.insert(2, 1);
+    mapping.insert(1, 0);
+    circuit += PragmaRepeatedMeasurement::new("ro".to_string(), 100, Some(mapping));
+
+    let qir = circuit_to_qir(&circuit).unwrap();
+    assert!(qir.contains("call void @__quantum__qis__mz__body(%Qubit* null, %Result* null)"));
+    assert!(qir.contains("call void @__quantum__qis__mz__body(%Qubit* inttoptr (i64 1 to %Qubit*), %Result* inttoptr (i64 1 to %Result*))"));
+    assert!(qir.contains("call void @__quantum__qis__mz__body(%Qubit* inttoptr (i64 2 to %Qubit*), %Result* inttoptr (i64 2 to %Result*))"));
+    assert_eq!(
+        qir.matches("call void @__quantum__rt__array_record_output")
+            .count(),
+        1
+    );
+    assert_eq!(
+        qir.matches("call void @__quantum__rt__result_record_output")
+            .count(),
+        2
+    );
+    assert!(qir.contains("\"required_num_qubits\"=\"3\" \"required_num_results\"=\"3\""));
+}
+
+/// Test that qubits only measured by the mapping of a repeated measurement are counted
+#[test]
+fn export_mapped_qubits() {
+    let mut circuit = Circuit::new();
+    circuit += DefinitionBit::new("ro".to_string(), 2, true);
+    circuit += Hadamard::new(0);
+    let mut mapping: HashMap<usize, usize> = HashMap::new();
+    mapping.insert(0, 0);
+    mapping.insert(4, 1);
+    circuit += PragmaRepeatedMeasurement::new("ro".to_string(), 100, Some(mapping));
+
+    let qir = circuit_to_qir(&circuit).unwrap();
+    assert!(qir.contains("call void @__quantum__qis__mz__body(%Qubit* inttoptr (i64 4 to %Qubit*), %Result* inttoptr (i64 1 to %Result*))"));
+    assert!(qir.contains("\"required_num_qubits\"=\"5\" \"required_num_results\"=\"2\""));
+}
+
+/// Test that the output of every register directly follows its array header
+#[test]
+fn export_output_registers() {
+    let mut circuit = Circuit::new();
+    circuit += DefinitionBit::new("ro".to_string(), 2, true);
+    circuit += DefinitionBit::new("ancilla".to_string(), 1, false);
+    circuit += DefinitionBit::new("flag".to_string(), 1, true);
+    circuit += MeasureQubit::new(0, "ro".to_string(), 0);
+    circuit += MeasureQubit::new(1, "ro".to_string(), 1);
+    circuit += MeasureQubit::new(2, "ancilla".to_string(), 0);
+    circuit += MeasureQubit::new(3, "flag".to_string(), 0);
+
+    let qir = circuit_to_qir(&circuit).unwrap();
+    let expected_output = "  call void @__quantum__rt__array_record_output(i64 2, i8* null)
+  call void @__quantum__rt__result_record_output(%Result* null, i8* null)
+  call void @__quantum__rt__result_record_output(%Result* inttoptr (i64 1 to %Result*), i8* null)
+  call void @__quantum__rt__array_record_output(i64 1, i8* null)
+  call void @__quantum__rt__result_record_output(%Result* inttoptr (i64 3 to %Result*), i8* null)
+  ret void";
+    assert!(qir.contains(expected_output));
+}
+
+/// Test that unsubstituted symbolic parameters return an error
+#[test]
+fn symbolic_parameters() {
+    let mut circuit = Circuit::new();
+    circuit += RotateX::new(0, CalculatorFloat::from("theta"));
+    let result = circuit_to_qir(&circuit);
+    match result {
+        Err(RoqoqoBackendError::GenericError { msg }) => {
+            assert!(msg.contains("theta"));
+            assert!(msg.contains("RotateX"));
+        }
+        _ => panic!("Expected error for symbolic parameter"),
+    }
+
+    let mut calculator = Calculator::new();
+    calculator.set_variable("theta", 1.0);
+    let substituted = circuit.substitute_parameters(&mut calculator).unwrap();
+    assert!(circuit_to_qir(&substituted).is_ok());
+}
+
+/// Test that unsupported operations and undefined registers return errors
+#[test_case(Operation::from(ISwap::new(0, 1)), "ISwap"; "ISwap")]
+#[test_case(Operation::from(PragmaDamping::new(0, 1.0.into(), 0.1.into())), "PragmaDamping"; "PragmaDamping")]
+#[test_case(Operation::from(PragmaActiveReset::new(0)), "PragmaActiveReset"; "PragmaActiveReset")]
+fn unsupported_operations(operation: Operation, hqslang: &'static str) {
+    let mut circuit = Circuit::new();
+    circuit += operation;
+    assert_eq!(
+        circuit_to_qir(&circuit),
+        Err(RoqoqoBackendError::OperationNotInBackend {
+            backend: "QIR",
+            hqslang
+        })
+    );
+}
+
+/// Test that measurements into undefined registers return an error
+#[test]
+fn missing_register() {
+    let mut circuit = Circuit::new();
+    circuit += MeasureQubit::new(0, "ro".to_string(), 0);
+    assert_eq!(
+        circuit_to_qir(&circuit),
+        Err(RoqoqoBackendError::RoqoqoError(
+            RoqoqoError::MissingRegister {
+                name: "ro".to_string()
+            }
+        ))
+    );
+}
@@ -10,15 +10,18 @@
 // express or implied. See the License for the specific language governing permissions and
 // limitations under the License.
 
+use ndarray::array;
 use roqoqo::measurements::{
-    BasisRotation, BasisRotationInput, Cheated, CheatedBasisRotation, CheatedBasisRotationInput,
-    CheatedInput, ClassicalRegister,
+    parse_pauli_product, BasisRotation, BasisRotationInput, Cheated, CheatedBasisRotation,
+    CheatedBasisRotationInput, CheatedInput, ClassicalRegister, ClassicalShadows,
+    ClassicalShadowsInput, Histogram, HistogramInput,
 };
 use roqoqo::operations;
 use roqoqo::prelude::*;
 use roqoqo::registers::{BitOutputRegister, ComplexOutputRegister, FloatOutputRegister};
 use roqoqo::Circuit;
 use roqoqo::QuantumProgram;
+use roqoqo::{parameter_grid, ParameterSweepResult, SymbolWarning};
 use std::collections::HashMap;
 
 #[derive(Debug, Clone, Copy)]
@@ -193,3 +196,439 @@ fn test_registers() {
     let result_fail = program.run_registers(backend, &[0.0, 1.0, 3.0]);
     assert!(result_fail.is_err());
 }
+
+#[derive(Debug, Clone, Copy)]
+struct ShadowsBackend;
+
+impl EvaluatingBackend for ShadowsBackend {
+    fn run_circuit_iterator<'a>(
+        &self,
+        circuit: impl Iterator<Item = &'a operations::Operation>,
+    ) -> roqoqo::backends::RegisterResult {
+        let mut circ_subs = Circuit::new();
+        circ_subs += operations::RotateX::new(0, 0.5.into());
+
+        for (op_test, op_created) in circuit.zip(circ_subs.iter()) {
+            assert_eq!(op_test, op_created)
+        }
+
+        let mut result_bit: HashMap<String, BitOutputRegister> = HashMap::new();
+        result_bit.insert("ro_0".to_string(), vec![vec![false]]);
+        let result_float: HashMap<String, FloatOutputRegister> = HashMap::new();
+        let result_complex: HashMap<String, ComplexOutputRegister> = HashMap::new();
+        Ok((result_bit, result_float, result_complex))
+    }
+}
+
+#[test]
+fn test_classical_shadows() {
+    let mut input = ClassicalShadowsInput::new(1, 1);
+    input
+        .measurement_bases
+        .insert("ro_0".to_string(), vec!['Z']);
+    let index = input
+        .add_pauli_product(parse_pauli_product("0Z").unwrap())
+        .unwrap();
+    let mut linear: HashMap<usize, f64> = HashMap::new();
+    linear.insert(index, 1.0);
+    input.add_linear_exp_val("z".to_string(), linear).unwrap();
+    let mut circ1 = Circuit::new();
+    circ1 += operations::RotateX::new(0, "theta".into());
+    let measurement = ClassicalShadows {
+        constant_circuit: None,
+        circuits: vec![circ1],
+        input,
+    };
+
+    let input_parameter_names = vec!["theta".to_string()];
+    let program = QuantumProgram::ClassicalShadows {
+        measurement,
+        input_parameter_names,
+    };
+
+    let backend = ShadowsBackend;
+
+    let result_fail = program.run_registers(backend, &[0.5]);
+    assert!(result_fail.is_err());
+    let result = program.run(backend, &[0.5]).unwrap().unwrap();
+    assert_eq!(result["z"], 3.0);
+    let result_fail = program.run(backend, &[]);
+    assert!(result_fail.is_err());
+    assert_eq!(format!("{}", program), "QuantumProgram::ClassicalShadows");
+}
+
+#[test]
+fn test_histogram() {
+    let mut input = HistogramInput::new();
+    input
+        .add_marginal("first".to_string(), "ro_0".to_string(), vec![0])
+        .unwrap();
+    let mut circ1 = Circuit::new();
+    circ1 += operations::RotateX::new(0, "theta".into());
+    let measurement = Histogram {
+        constant_circuit: None,
+        circuits: vec![circ1],
+        input,
+    };
+
+    let input_parameter_names = vec!["theta".to_string()];
+    let program = QuantumProgram::Histogram {
+        measurement,
+        input_parameter_names,
+    };
+
+    let backend = ShadowsBackend;
+
+    let result_fail = program.run(backend, &[0.5]);
+    assert!(result_fail.is_err());
+    let (bit_registers, _, _) = program.run_registers(backend, &[0.5]).unwrap();
+    assert_eq!(bit_registers["ro_0"], vec![vec![false]]);
+    let result = program.run_histogram(backend, &[0.5]).unwrap();
+    assert_eq!(result.counts["ro_0"]["0"], 1);
+    assert_eq!(result.marginal_probabilities["first"]["0"], 1.0);
+    let result_fail = program.run_histogram(backend, &[]);
+    assert!(result_fail.is_err());
+    assert_eq!(format!("{}", program), "QuantumProgram::Histogram");
+}
+
+/// Backend returning the sum of the angles of the RotateX operations as float register "angle"
+#[derive(Debug, Clone, Copy)]
+struct AngleBackend;
+
+impl EvaluatingBackend for AngleBackend {
+    fn run_circuit_iterator<'a>(
+        &self,
+        circuit: impl Iterator<Item = &'a operations::Operation>,
+    ) -> roqoqo::backends::RegisterResult {
+        let mut angle = 0.0;
+        for operation in circuit {
+            if let operations::Operation::RotateX(rotation) = operation {
+                angle += *rotation.theta().float()?;
+            }
+        }
+        let result_bit: HashMap<String, BitOutputRegister> = HashMap::new();
+        let mut result_float: HashMap<String, FloatOutputRegister> = HashMap::new();
+        result_float.insert("angle".to_string(), vec![vec![angle]]);
+        let result_complex: HashMap<String, ComplexOutputRegister> = HashMap::new();
+        Ok((result_bit, result_float, result_complex))
+    }
+
+    fn metadata(&self) -> HashMap<String, String> {
+        let mut metadata: HashMap<String, String> = HashMap::new();
+        metadata.insert("name".to_string(), "angle".to_string());
+        metadata
+    }
+}
+
+fn angle_program() -> QuantumProgram {
+    let mut input = CheatedBasisRotationInput::new();
+    let index = input.add_pauli_product("angle".to_string());
+    let mut linear: HashMap<usize, f64> = HashMap::new();
+    linear.insert(index, 1.0);
+    input
+        .add_linear_exp_val("angle".to_string(), linear.clone())
+        .unwrap();
+    linear.insert(index, 2.0);
+    input
+        .add_linear_exp_val("double".to_string(), linear)
+        .unwrap();
+    let mut circuit = Circuit::new();
+    circuit += operations::RotateX::new(0, "theta".into());
+    circuit += operations::RotateX::new(0, "phi".into());
+    QuantumProgram::CheatedBasisRotation {
+        measurement: CheatedBasisRotation {
+            constant_circuit: None,
+            circuits: vec![circuit],
+            input,
+        },
+        input_parameter_names: vec!["theta".to_string(), "phi".to_string()],
+    }
+}
+
+#[test]
+fn test_parameter_grid() {
+    assert_eq!(
+        parameter_grid(&[vec![0.0, 1.0], vec![2.0, 3.0, 4.0]]),
+        vec![
+            vec![0.0, 2.0],
+            vec![0.0, 3.0],
+            vec![0.0, 4.0],
+            vec![1.0, 2.0],
+            vec![1.0, 3.0],
+            vec![1.0, 4.0]
+        ]
+    );
+    assert_eq!(parameter_grid(&[]), vec![Vec::<f64>::new()]);
+    assert!(parameter_grid(&[vec![0.0], vec![]]).is_empty());
+}
+
+#[test]
+fn test_run_batch() {
+    let program = angle_program();
+    let backend = AngleBackend;
+    let result = program
+        .run_batch(&backend, &[vec![0.1, 0.2], vec![1.0, 2.0]])
+        .unwrap();
+    assert_eq!(
+        result.parameter_names,
+        vec!["theta".to_string(), "phi".to_string()]
+    );
+    assert_eq!(result.parameters, array![[0.1, 0.2], [1.0, 2.0]]);
+    assert_eq!(
+        result.expectation_value_names,
+        vec!["angle".to_string(), "double".to_string()]
+    );
+    assert_eq!(result.expectation_values.shape(), &[2, 2]);
+    assert!((result.expectation_values[[0, 0]] - 0.3).abs() < 1e-12);
+    assert!((result.expectation_values[[1, 1]] - 6.0).abs() < 1e-12);
+    assert_eq!(
+        result.expectation_value("double").unwrap(),
+        result.expectation_values.column(1)
+    );
+    assert!(result.expectation_value("missing").is_none());
+
+    assert!(program.run_batch(&backend, &[vec![0.1]]).is_err());
+    let empty = program.run_batch(&backend, &[]).unwrap();
+    assert_eq!(empty.parameters.shape(), &[0, 2]);
+    assert_eq!(empty.expectation_values.shape(), &[0, 0]);
+}
+
+#[test]
+fn test_run_grid() {
+    let program = angle_program();
+    let result = program
+        .run_grid(&AngleBackend, &[vec![0.0, 1.0], vec![0.5, 1.5]])
+        .unwrap();
+    assert_eq!(
+        result.parameters,
+        array![[0.0, 0.5], [0.0, 1.5], [1.0, 0.5], [1.0, 1.5]]
+    );
+    let angles = result.expectation_value("angle").unwrap();
+    for (angle, expected) in angles.iter().zip([0.5, 1.5, 1.5, 2.5]) {
+        assert!((angle - expected).abs() < 1e-12);
+    }
+}
+
+#[cfg(feature = "parallel")]
+#[test]
+fn test_run_batch_parallel() {
+    let program = angle_program();
+    let parameter_sets = parameter_grid(&[vec![0.0, 0.5, 1.0], vec![0.0, 2.0]]);
+    let serial = program.run_batch(&AngleBackend, &parameter_sets).unwrap();
+    let parallel = program
+        .run_batch_parallel(&AngleBackend, &parameter_sets)
+        .unwrap();
+    assert_eq!(serial, parallel);
+}
+
+#[test]
+fn test_run_with_metadata() {
+    let program = angle_program();
+    let backend = AngleBackend;
+    let result = program
+        .run_with_metadata(&backend, &[0.1, 0.2], false)
+        .unwrap();
+    let expectation_values = result.expectation_values.clone().unwrap();
+    assert!((expectation_values["angle"] - 0.3).abs() < 1e-12);
+    assert!(result.registers.is_none());
+    assert_eq!(
+        result.parameter_names,
+        vec!["theta".to_string(), "phi".to_string()]
+    );
+    assert_eq!(result.parameters, vec![0.1, 0.2]);
+    assert_eq!(result.number_circuits, 1);
+    assert_eq!(result.total_shots, 0);
+    assert!(result.elapsed_seconds >= 0.0);
+    assert_eq!(result.backend_metadata["name"], "angle");
+    assert_eq!(
+        Some(expectation_values),
+        program.run(&backend, &[0.1, 0.2]).unwrap()
+    );
+
+    let result = program
+        .run_with_metadata(&backend, &[1.0, 2.0], true)
+        .unwrap();
+    let registers = result.registers.unwrap();
+    assert_eq!(registers.float_registers["angle"], vec![vec![3.0]]);
+    assert!(registers.bit_registers.is_empty());
+
+    assert!(program.run_with_metadata(&backend, &[0.1], false).is_err());
+    let register_program = QuantumProgram::ClassicalRegister {
+        measurement: ClassicalRegister {
+            constant_circuit: None,
+            circuits: vec![Circuit::new()],
+        },
+        input_parameter_names: vec![],
+    };
+    assert!(register_program
+        .run_with_metadata(&backend, &[], false)
+        .is_err());
+}
+
+#[test]
+fn test_run_with_metadata_shots() {
+    let mut input = CheatedBasisRotationInput::new();
+    let index = input.add_pauli_product("angle".to_string());
+    let mut linear: HashMap<usize, f64> = HashMap::new();
+    linear.insert(index, 1.0);
+    input
+        .add_linear_exp_val("angle".to_string(), linear)
+        .unwrap();
+    let mut constant_circuit = Circuit::new();
+    constant_circuit += operations::MeasureQubit::new(0, "ro".to_string(), 0);
+    let mut circuit = Circuit::new();
+    circuit += operations::PragmaSetNumberOfMeasurements::new(100, "ro".to_string());
+    let program = QuantumProgram::CheatedBasisRotation {
+        measurement: CheatedBasisRotation {
+            constant_circuit: Some(constant_circuit),
+            circuits: vec![circuit],
+            input,
+        },
+        input_parameter_names: vec![],
+    };
+    let result = program
+        .run_with_metadata(&AngleBackend, &[], false)
+        .unwrap();
+    assert_eq!(result.total_shots, 100);
+    assert_eq!(result.number_circuits, 1);
+}
+
+#[cfg(feature = "serialize")]
+#[test]
+fn test_run_result_serde() {
+    let result = angle_program()
+        .run_with_metadata(&AngleBackend, &[0.1, 0.2], true)
+        .unwrap();
+    let serialized = bincode::serialize(&result).unwrap();
+    let deserialized: roqoqo::RunResult = bincode::deserialize(&serialized).unwrap();
+    assert_eq!(deserialized, result);
+}
+
+#[test]
+fn test_parameter_sweep_result_from_results() {
+    let mut first: HashMap<String, f64> = HashMap::new();
+    first.insert("b".to_string(), 1.0);
+    let mut second: HashMap<String, f64> = HashMap::new();
+    second.insert("a".to_string(), 2.0);
+    let result = ParameterSweepResult::from_results(
+        vec!["x".to_string()],
+        &[vec![0.0], vec![1.0], vec![2.0]],
+        vec![Some(first), Some(second), None],
+    )
+    .unwrap();
+    assert_eq!(
+        result.expectation_value_names,
+        vec!["a".to_string(), "b".to_string()]
+    );
+    assert!(result.expectation_values[[0, 0]].is_nan());
+    assert_eq!(result.expectation_values[[0, 1]], 1.0);
+    assert_eq!(result.expectation_values[[1, 0]], 2.0);
+    assert!(result
+        .expectation_values
+        .row(2)
+        .iter()
+        .all(|value| value.is_nan()));
+
+    assert!(
+        ParameterSweepResult::from_results(vec!["x".to_string()], &[vec![0.0]], vec![]).is_err()
+    );
+    assert!(ParameterSweepResult::from_results(
+        vec!["x".to_string()],
+        &[vec![0.0, 1.0]],
+        vec![None]
+    )
+    .is_err());
+}
+
+#[test]
+fn test_run_named() {
+    let program = angle_program();
+    let mut parameters: HashMap<String, f64> = HashMap::new();
+    parameters.insert("phi".to_string(), 0.2);
+    parameters.insert("theta".to_string(), 0.1);
+    assert_eq!(
+        program.parameter_values(&parameters).unwrap(),
+        vec![0.1, 0.2]
+    );
+    assert_eq!(
+        program.run_named(AngleBackend, &parameters).unwrap(),
+        program.run(AngleBackend, &[0.1, 0.2]).unwrap()
+    );
+
+    parameters.insert("unknown".to_string(), 0.3);
+    assert!(program.parameter_values(&parameters).is_err());
+    assert!(program.run_named(AngleBackend, &parameters).is_err());
+    parameters.remove("unknown");
+    parameters.remove("theta");
+    assert!(program.parameter_values(&parameters).is_err());
+    assert!(program.run_named(AngleBackend, &parameters).is_err());
+}
+
+#[test]
+fn test_bind_parameters() {
+    let program = angle_program();
+    let mut bound: HashMap<String, f64> = HashMap::new();
+    bound.insert("theta".to_string(), 0.1);
+    let partial = program.bind_parameters(&bound).unwrap();
+    match &partial {
+        QuantumProgram::CheatedBasisRotation {
+            input_parameter_names,
+            ..
+        } => assert_eq!(input_parameter_names, &vec!["phi".to_string()]),
+        _ => panic!("Binding changed the variant of the QuantumProgram"),
+    }
+    assert_eq!(
+        partial.run(AngleBackend, &[0.2]).unwrap(),
+        program.run(AngleBackend, &[0.1, 0.2]).unwrap()
+    );
+    assert_eq!(partial.free_symbols().unwrap().len(), 1);
+
+    let mut rest: HashMap<String, f64> = HashMap::new();
+    rest.insert("phi".to_string(), 0.2);
+    let fixed = partial.bind_parameters(&rest).unwrap();
+    assert_eq!(
+        fixed.run(AngleBackend, &[]).unwrap(),
+        program.run(AngleBackend, &[0.1, 0.2]).unwrap()
+    );
+    assert!(fixed.free_symbols().unwrap().is_empty());
+
+    // theta is no longer an input parameter of the partially bound program
+    assert!(partial.bind_parameters(&bound).is_err());
+}
+
+#[test]
+fn test_free_symbols() {
+    let program = angle_program();
+    let symbols: Vec<String> = program.free_symbols().unwrap().into_iter().collect();
+    assert_eq!(symbols, vec!["phi".to_string(), "theta".to_string()]);
+    assert!(program.check_symbols().unwrap().is_empty());
+
+    let mut constant_circuit = Circuit::new();
+    constant_circuit += operations::RotateX::new(0, "2 * theta + sin(gamma)".into());
+    let mut circuit = Circuit::new();
+    circuit += operations::RotateX::new(0, "theta".into());
+    circuit += operations::DefinitionFloat::new("ro".to_string(), 1, true);
+    circuit += operations::InputSymbolic::new("delta".to_string(), 1.0);
+    circuit += operations::RotateZ::new(0, "delta".into());
+    let program = QuantumProgram::ClassicalRegister {
+        measurement: ClassicalRegister {
+            constant_circuit: Some(constant_circuit),
+            circuits: vec![circuit],
+        },
+        input_parameter_names: vec!["theta".to_string(), "unused".to_string()],
+    };
+    let symbols: Vec<String> = program.free_symbols().unwrap().into_iter().collect();
+    assert_eq!(symbols, vec!["gamma".to_string(), "theta".to_string()]);
+    let warnings = program.check_symbols().unwrap();
+    assert_eq!(
+        warnings,
+        vec![
+            SymbolWarning::UnusedParameter("unused".to_string()),
+            SymbolWarning::UndeclaredSymbol("gamma".to_string())
+        ]
+    );
+    assert_eq!(
+        warnings[0].to_string(),
+        "Input parameter unused is not used in the circuits of the measurement"
+    );
+}
@@ -0,0 +1,195 @@
+// Copyright © 2021 HQS Quantum Simulations GmbH. All Rights Reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except
+// in compliance with the License. You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the
+// License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either
+// express or implied. See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Integration test for public API of the recording and replay backends
+
+use roqoqo::backends::{RecordingBackend, ReplayBackend};
+use roqoqo::measurements::{CheatedBasisRotation, CheatedBasisRotationInput};
+use roqoqo::operations::*;
+use roqoqo::prelude::*;
+use roqoqo::registers::{BitOutputRegister, ComplexOutputRegister, FloatOutputRegister};
+use roqoqo::{Circuit, QuantumProgram, RoqoqoBackendError};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Backend returning the angle of the RotateX operations and a run counter, failing on PauliX
+#[derive(Debug, Default)]
+struct CountingBackend {
+    runs: AtomicUsize,
+}
+
+impl EvaluatingBackend for CountingBackend {
+    fn run_circuit_iterator<'a>(
+        &self,
+        circuit: impl Iterator<Item = &'a Operation>,
+    ) -> roqoqo::backends::RegisterResult {
+        let run = self.runs.fetch_add(1, Ordering::SeqCst);
+        let mut angle = 0.0;
+        for operation in circuit {
+            match operation {
+                Operation::RotateX(rotation) => angle += *rotation.theta().float()?,
+                Operation::PauliX(_) => {
+                    return Err(RoqoqoBackendError::OperationNotInBackend {
+                        backend: "CountingBackend",
+                        hqslang: "PauliX",
+                    })
+                }
+                _ => (),
+            }
+        }
+        let result_bit: HashMap<String, BitOutputRegister> = HashMap::new();
+        let mut result_float: HashMap<String, FloatOutputRegister> = HashMap::new();
+        result_float.insert("angle".to_string(), vec![vec![angle]]);
+        result_float.insert("run".to_string(), vec![vec![run as f64]]);
+        let result_complex: HashMap<String, ComplexOutputRegister> = HashMap::new();
+        Ok((result_bit, result_float, result_complex))
+    }
+}
+
+fn rotation_circuit(theta: f64) -> Circuit {
+    let mut circuit = Circuit::new();
+    circuit += RotateX::new(0, theta.into());
+    circuit
+}
+
+fn quantum_program() -> QuantumProgram {
+    let mut input = CheatedBasisRotationInput::new();
+    let index = input.add_pauli_product("angle".to_string());
+    let mut linear: HashMap<usize, f64> = HashMap::new();
+    linear.insert(index, 1.0);
+    input
+        .add_linear_exp_val("angle".to_string(), linear)
+        .unwrap();
+    QuantumProgram::CheatedBasisRotation {
+        measurement: CheatedBasisRotation {
+            constant_circuit: Some(rotation_circuit(0.5)),
+            circuits: vec![{
+                let mut circuit = Circuit::new();
+                circuit += RotateX::new(0, "theta".into());
+                circuit
+            }],
+            input,
+        },
+        input_parameter_names: vec!["theta".to_string()],
+    }
+}
+
+#[test]
+fn test_record_and_replay_program() {
+    let program = quantum_program();
+    let recording_backend = RecordingBackend::new(CountingBackend::default());
+    let result = program.run(&recording_backend, &[0.25]).unwrap().unwrap();
+    assert_eq!(result["angle"], 0.75);
+    let recording = recording_backend.recording();
+    assert_eq!(recording.len(), 1);
+    let mut expected_circuit = rotation_circuit(0.5);
+    expected_circuit += RotateX::new(0, 0.25.into());
+    assert_eq!(recording[0].circuit, expected_circuit);
+    assert_eq!(recording[0].seed, None);
+
+    let mut buffer: Vec<u8> = Vec::new();
+    recording_backend.write_recording(&mut buffer).unwrap();
+    let replay_backend = ReplayBackend::from_reader(buffer.as_slice()).unwrap();
+    assert_eq!(replay_backend.recording(), recording.as_slice());
+    let replayed = program.run(&replay_backend, &[0.25]).unwrap().unwrap();
+    assert_eq!(replayed, result);
+
+    let error = program.run(&replay_backend, &[0.3]);
+    assert!(matches!(
+        error,
+        Err(RoqoqoBackendError::GenericError { .. })
+    ));
+}
+
+#[test]
+fn test_replay_repeated_circuits() {
+    let recording_backend = RecordingBackend::new(CountingBackend::default());
+    let circuit = rotation_circuit(0.1);
+    let first = recording_backend.run_circuit(&circuit).unwrap();
+    let second = recording_backend.run_circuit(&circuit).unwrap();
+    assert_ne!(first, second);
+
+    let replay_backend = ReplayBackend::new(recording_backend.recording());
+    assert_eq!(replay_backend.run_circuit(&circuit).unwrap(), first);
+    assert_eq!(replay_backend.run_circuit(&circuit).unwrap(), second);
+    // The last recorded result is repeated
+    assert_eq!(replay_backend.run_circuit(&circuit).unwrap(), second);
+}
+
+#[test]
+fn test_replay_seeded() {
+    let recording_backend = RecordingBackend::new(CountingBackend::default());
+    let circuit = rotation_circuit(0.1);
+    let seeded = recording_backend
+        .run_circuit_iterator_with_seed(circuit.iter(), 5)
+        .unwrap();
+    assert_eq!(recording_backend.recording()[0].seed, Some(5));
+
+    let replay_backend = ReplayBackend::new(recording_backend.recording());
+    assert_eq!(
+        replay_backend
+            .run_circuit_iterator_with_seed(circuit.iter(), 5)
+            .unwrap(),
+        seeded
+    );
+    assert!(replay_backend
+        .run_circuit_iterator_with_seed(circuit.iter(), 6)
+        .is_err());
+    assert!(replay_backend.run_circuit(&circuit).is_err());
+}
+
+#[test]
+fn test_failed_runs_not_recorded() {
+    let recording_backend = RecordingBackend::new(CountingBackend::default());
+    let mut circuit = rotation_circuit(0.1);
+    circuit += PauliX::new(0);
+    assert!(recording_backend.run_circuit(&circuit).is_err());
+    assert!(recording_backend.recording().is_empty());
+    assert_eq!(recording_backend.backend().runs.load(Ordering::SeqCst), 1);
+
+    assert!(ReplayBackend::from_reader([1_u8, 2].as_slice()).is_err());
+}
+
+#[test]
+fn test_replay_read_recording_mapping() {
+    let measured_circuit = |reversed: bool| {
+        let mut qubits: Vec<usize> = (0..16).collect();
+        if reversed {
+            qubits.reverse();
+        }
+        let mut qubit_mapping: HashMap<usize, usize> = HashMap::new();
+        for qubit in qubits {
+            let _ = qubit_mapping.insert(qubit, 15 - qubit);
+        }
+        let mut circuit = rotation_circuit(0.1);
+        circuit += PragmaRepeatedMeasurement::new("ro".to_string(), 10, Some(qubit_mapping));
+        circuit
+    };
+    let recording_backend = RecordingBackend::new(CountingBackend::default());
+    let recorded = recording_backend
+        .run_circuit(&measured_circuit(false))
+        .unwrap();
+    let mut buffer: Vec<u8> = Vec::new();
+    recording_backend.write_recording(&mut buffer).unwrap();
+
+    let replay_backend = ReplayBackend::from_reader(buffer.as_slice()).unwrap();
+    assert_eq!(
+        replay_backend.run_circuit(&measured_circuit(true)).unwrap(),
+        recorded
+    );
+    assert_eq!(
+        replay_backend
+            .run_circuit(&measured_circuit(false))
+            .unwrap(),
+        recorded
+    );
+}
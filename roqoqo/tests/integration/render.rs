@@ -0,0 +1,156 @@
+// Copyright © 2021 HQS Quantum Simulations GmbH. All Rights Reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except
+// in compliance with the License. You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the
+// License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either
+// express or implied. See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Integration test for public API of circuit rendering
+
+use qoqo_calculator::CalculatorFloat;
+use roqoqo::operations::*;
+use roqoqo::render::{circuit_to_quantikz, circuit_to_svg, RenderOptions};
+use roqoqo::{Circuit, RoqoqoError};
+use std::collections::HashMap;
+use test_case::test_case;
+
+/// Test rendering of a simple circuit to quantikz
+#[test]
+fn quantikz_simple_circuit() {
+    let mut circuit = Circuit::new();
+    circuit += DefinitionBit::new("ro".to_string(), 2, true);
+    circuit += Hadamard::new(0);
+    circuit += CNOT::new(0, 1);
+    circuit += MeasureQubit::new(0, "ro".to_string(), 0);
+    circuit += MeasureQubit::new(1, "ro".to_string(), 1);
+
+    let latex = circuit_to_quantikz(&circuit, &RenderOptions::new()).unwrap();
+    let expected = "\\begin{quantikz}\n\\lstick{$q_{0}$} & \\gate{H} & \\ctrl{1} & \\meter{} & \\qw \\\\\n\\lstick{$q_{1}$} & \\qw & \\targ{} & \\meter{} & \\qw\n\\end{quantikz}\n";
+    assert_eq!(latex, expected);
+}
+
+/// Test that parameters are rendered as floats or as symbolic expressions
+#[test_case(CalculatorFloat::from(0.5), "R_{x}(0.500)", "Rx(0.500)"; "float")]
+#[test_case(CalculatorFloat::from("theta_1"), "R_{x}(\\text{theta\\_1})", "Rx(theta_1)"; "symbolic")]
+fn render_parameters(theta: CalculatorFloat, latex_label: &str, text_label: &str) {
+    let mut circuit = Circuit::new();
+    circuit += RotateX::new(0, theta);
+
+    let options = RenderOptions::new();
+    let latex = circuit_to_quantikz(&circuit, &options).unwrap();
+    assert!(latex.contains(&format!("\\gate{{{}}}", latex_label)));
+    let svg = circuit_to_svg(&circuit, &options).unwrap();
+    assert!(svg.contains(text_label));
+}
+
+/// Test custom qubit labels and hiding qubit labels
+#[test]
+fn qubit_labels() {
+    let mut circuit = Circuit::new();
+    circuit += PauliX::new(0);
+    circuit += PauliZ::new(1);
+
+    let mut options = RenderOptions::new();
+    let mut labels: HashMap<usize, String> = HashMap::new();
+    labels.insert(1, "ancilla".to_string());
+    options.qubit_labels = labels;
+    let latex = circuit_to_quantikz(&circuit, &options).unwrap();
+    assert!(latex.contains("\\lstick{$q_{0}$}"));
+    assert!(latex.contains("\\lstick{ancilla}"));
+    let svg = circuit_to_svg(&circuit, &options).unwrap();
+    assert!(svg.contains(">ancilla</text>"));
+
+    options.show_qubit_labels = false;
+    let latex = circuit_to_quantikz(&circuit, &options).unwrap();
+    assert!(!latex.contains("\\lstick"));
+
+    options.number_qubits = Some(4);
+    let latex = circuit_to_quantikz(&circuit, &options).unwrap();
+    assert_eq!(
+        latex.matches("\\qw\n").count() + latex.matches("\\qw \\\\\n").count(),
+        4
+    );
+}
+
+/// Test grouping of decomposition blocks
+#[test]
+fn decomposition_blocks() {
+    let mut circuit = Circuit::new();
+    circuit += PauliX::new(2);
+    circuit += PragmaStartDecompositionBlock::new(vec![0, 1], HashMap::new());
+    circuit += Hadamard::new(0);
+    circuit += CNOT::new(0, 1);
+    circuit += PragmaStopDecompositionBlock::new(vec![0, 1]);
+
+    let mut options = RenderOptions::new();
+    options.decomposition_block_label = "Bell".to_string();
+    let latex = circuit_to_quantikz(&circuit, &options).unwrap();
+    assert!(latex.contains("\\gategroup[wires=2,steps=2,"));
+    assert!(latex.contains("{Bell}"));
+    let svg = circuit_to_svg(&circuit, &options).unwrap();
+    assert!(svg.contains("stroke-dasharray"));
+    assert!(svg.contains(">Bell</text>"));
+
+    options.group_decomposition_blocks = false;
+    let latex = circuit_to_quantikz(&circuit, &options).unwrap();
+    assert!(!latex.contains("\\gategroup"));
+}
+
+/// Test that unbalanced decomposition blocks return an error
+#[test]
+fn unbalanced_decomposition_blocks() {
+    let mut circuit = Circuit::new();
+    circuit += PragmaStartDecompositionBlock::new(vec![0], HashMap::new());
+    circuit += Hadamard::new(0);
+    let error = circuit_to_quantikz(&circuit, &RenderOptions::new());
+    assert!(matches!(error, Err(RoqoqoError::GenericError { .. })));
+
+    let mut circuit = Circuit::new();
+    circuit += PragmaStopDecompositionBlock::new(vec![0]);
+    let error = circuit_to_svg(&circuit, &RenderOptions::new());
+    assert!(matches!(error, Err(RoqoqoError::GenericError { .. })));
+}
+
+/// Test that gates on disjoint qubits share a column and that pragmas are only shown on request
+#[test]
+fn layout_columns() {
+    let mut circuit = Circuit::new();
+    circuit += Hadamard::new(0);
+    circuit += Hadamard::new(1);
+    circuit += SWAP::new(0, 2);
+    circuit += PragmaDamping::new(1, CalculatorFloat::from(0.1), CalculatorFloat::from(0.01));
+
+    let mut options = RenderOptions::new();
+    let latex = circuit_to_quantikz(&circuit, &options).unwrap();
+    assert!(latex.contains("\\lstick{$q_{0}$} & \\gate{H} & \\swap{2} & \\qw"));
+    assert!(latex.contains("\\lstick{$q_{1}$} & \\gate{H} & \\qw & \\qw"));
+    assert!(!latex.contains("PragmaDamping"));
+
+    options.show_pragmas = true;
+    let latex = circuit_to_quantikz(&circuit, &options).unwrap();
+    assert!(latex.contains("\\gate{\\text{PragmaDamping}}"));
+}
+
+/// Test that qubits only measured by the mapping of a repeated measurement are drawn
+#[test]
+fn repeated_measurement_mapping() {
+    let mut circuit = Circuit::new();
+    circuit += DefinitionBit::new("ro".to_string(), 2, true);
+    circuit += Hadamard::new(0);
+    let mut qubit_mapping: HashMap<usize, usize> = HashMap::new();
+    qubit_mapping.insert(0, 0);
+    qubit_mapping.insert(3, 1);
+    circuit += PragmaRepeatedMeasurement::new("ro".to_string(), 10, Some(qubit_mapping));
+
+    let options = RenderOptions::new();
+    let latex = circuit_to_quantikz(&circuit, &options).unwrap();
+    assert!(latex.contains("\\lstick{$q_{3}$}"));
+    assert_eq!(latex.matches("\\meter{}").count(), 2);
+    let svg = circuit_to_svg(&circuit, &options).unwrap();
+    assert!(svg.contains(">q_3</text>"));
+}
@@ -0,0 +1,117 @@
+// Copyright © 2021 HQS Quantum Simulations GmbH. All Rights Reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except
+// in compliance with the License. You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the
+// License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either
+// express or implied. See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Integration test for public API of JSON schema generation and validation
+
+use roqoqo::measurements::{BasisRotation, BasisRotationInput, ClassicalRegister};
+use roqoqo::operations::*;
+use roqoqo::schema::{from_json_validated, json_schema, validate_json};
+use roqoqo::{Circuit, QuantumProgram, RoqoqoError};
+
+fn create_circuit() -> Circuit {
+    let mut circuit = Circuit::new();
+    circuit += DefinitionBit::new("ro".to_string(), 1, true);
+    circuit += RotateX::new(0, "theta".into());
+    circuit += PauliX::new(1);
+    circuit += MeasureQubit::new(0, "ro".to_string(), 0);
+    circuit
+}
+
+/// Test that the schemas contain the version information and the measurement types
+#[test]
+fn schemas_contain_version() {
+    let schema = json_schema::<Circuit>();
+    assert!(schema.contains("_roqoqo_version"));
+    assert!(schema.contains("major_version"));
+    assert!(schema.contains("minor_version"));
+
+    let schema = json_schema::<QuantumProgram>();
+    assert!(schema.contains("BasisRotation"));
+    assert!(schema.contains("ClassicalRegister"));
+    assert!(schema.contains("_roqoqo_version"));
+
+    let schema = json_schema::<BasisRotation>();
+    assert!(schema.contains("pauli_product_qubit_masks"));
+}
+
+/// Test that serialized roqoqo objects are valid against their schema
+#[test]
+fn validate_serialized_objects() {
+    let circuit = create_circuit();
+    let json = serde_json::to_string(&circuit).unwrap();
+    assert_eq!(validate_json::<Circuit>(&json), Ok(()));
+    assert_eq!(from_json_validated::<Circuit>(&json).unwrap(), circuit);
+
+    let measurement = BasisRotation {
+        constant_circuit: None,
+        circuits: vec![circuit.clone()],
+        input: BasisRotationInput::new(2, false),
+    };
+    let json = serde_json::to_string(&measurement).unwrap();
+    assert_eq!(validate_json::<BasisRotation>(&json), Ok(()));
+
+    let program = QuantumProgram::ClassicalRegister {
+        measurement: ClassicalRegister {
+            constant_circuit: Some(circuit),
+            circuits: vec![],
+        },
+        input_parameter_names: vec!["theta".to_string()],
+    };
+    let json = serde_json::to_string(&program).unwrap();
+    assert_eq!(validate_json::<QuantumProgram>(&json), Ok(()));
+}
+
+/// Test that validation errors point to the offending value
+#[test]
+fn validation_error_paths() {
+    let circuit = create_circuit();
+    let mut value = serde_json::to_value(&circuit).unwrap();
+    value["operations"][1]["PauliX"]["qubit"] = serde_json::json!("one");
+    let result = validate_json::<Circuit>(&value.to_string());
+    match result {
+        Err(RoqoqoError::JsonValidationError { path, msg }) => {
+            assert_eq!(path, "/operations/1/PauliX/qubit");
+            assert!(msg.contains("string"));
+        }
+        _ => panic!("Expected JsonValidationError"),
+    }
+
+    let mut value = serde_json::to_value(&circuit).unwrap();
+    value["operations"]
+        .as_array_mut()
+        .unwrap()
+        .push(serde_json::json!({"NotAnOperation": {"qubit": 0}}));
+    let result = validate_json::<Circuit>(&value.to_string());
+    match result {
+        Err(RoqoqoError::JsonValidationError { path, .. }) => {
+            assert_eq!(path, "/operations/3");
+        }
+        _ => panic!("Expected JsonValidationError"),
+    }
+
+    let mut value = serde_json::to_value(&circuit).unwrap();
+    value.as_object_mut().unwrap().remove("definitions");
+    let result = from_json_validated::<Circuit>(&value.to_string());
+    match result {
+        Err(RoqoqoError::JsonValidationError { path, msg }) => {
+            assert_eq!(path, "/");
+            assert!(msg.contains("definitions"));
+        }
+        _ => panic!("Expected JsonValidationError"),
+    }
+
+    let result = validate_json::<Circuit>("{not json");
+    assert!(matches!(
+        result,
+        Err(RoqoqoError::SerializationError { .. })
+    ));
+}
@@ -0,0 +1,239 @@
+// Copyright © 2021 HQS Quantum Simulations GmbH. All Rights Reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except
+// in compliance with the License. You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the
+// License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either
+// express or implied. See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Integration test for public API of the versioned binary serialization
+
+use qoqo_calculator::CalculatorFloat;
+use roqoqo::measurements::{BasisRotation, BasisRotationInput, ClassicalRegister};
+use roqoqo::operations::*;
+use roqoqo::serialization::{
+    binary_header, from_binary, import_bincode, import_json, to_binary, upgrade_binary,
+};
+use roqoqo::{Circuit, QuantumProgram, RoqoqoError, ROQOQO_VERSION};
+use std::collections::HashMap;
+
+/// Returns the (major, minor) version of the library.
+fn library_version() -> (u32, u32) {
+    let mut split = ROQOQO_VERSION.split('.');
+    let major: u32 = split.next().unwrap().parse().unwrap();
+    let minor: u32 = split.next().unwrap().parse().unwrap();
+    (major, minor)
+}
+
+/// Replaces all encoded minor versions in the envelope and the payload.
+///
+/// Small unsigned integers are encoded in a single byte in CBOR,
+/// so the byte following the key "minor_version" is the version.
+fn patch_minor_version(bytes: &[u8], old: u32, new: u32) -> Vec<u8> {
+    assert!(old < 24 && new < 24);
+    let key = b"minor_version";
+    let mut patched = bytes.to_vec();
+    let mut index = 0;
+    while index + key.len() < patched.len() {
+        if &patched[index..index + key.len()] == key && patched[index + key.len()] == old as u8 {
+            patched[index + key.len()] = new as u8;
+        }
+        index += 1;
+    }
+    patched
+}
+
+fn create_circuit() -> Circuit {
+    let mut circuit = Circuit::new();
+    circuit += DefinitionBit::new("ro".to_string(), 2, true);
+    circuit += RotateX::new(0, CalculatorFloat::from("theta"));
+    circuit += CNOT::new(0, 1);
+    circuit += PragmaRepeatedMeasurement::new("ro".to_string(), 100, None);
+    circuit
+}
+
+/// Test roundtrip of a Circuit
+#[test]
+fn circuit_roundtrip() {
+    let circuit = create_circuit();
+    let bytes = to_binary(&circuit).unwrap();
+    let (kind, version) = binary_header(&bytes).unwrap();
+    assert_eq!(kind, "Circuit");
+    assert_eq!(version, library_version());
+    let deserialized: Circuit = from_binary(&bytes).unwrap();
+    assert_eq!(deserialized, circuit);
+}
+
+/// Test roundtrip of a measurement and a QuantumProgram
+#[test]
+fn program_roundtrip() {
+    let measurement = ClassicalRegister {
+        constant_circuit: Some(Circuit::new()),
+        circuits: vec![create_circuit()],
+    };
+    let bytes = to_binary(&measurement).unwrap();
+    let deserialized: ClassicalRegister = from_binary(&bytes).unwrap();
+    assert_eq!(deserialized, measurement);
+
+    let program = QuantumProgram::ClassicalRegister {
+        measurement,
+        input_parameter_names: vec!["theta".to_string()],
+    };
+    let bytes = to_binary(&program).unwrap();
+    let deserialized: QuantumProgram = from_binary(&bytes).unwrap();
+    assert_eq!(deserialized, program);
+}
+
+/// Test that data of the wrong kind is rejected
+#[test]
+fn wrong_kind() {
+    let bytes = to_binary(&create_circuit()).unwrap();
+    let result: Result<QuantumProgram, RoqoqoError> = from_binary(&bytes);
+    assert!(matches!(
+        result,
+        Err(RoqoqoError::SerializationError { .. })
+    ));
+    let result: Result<Circuit, RoqoqoError> = from_binary(&[0, 1, 2]);
+    assert!(matches!(
+        result,
+        Err(RoqoqoError::SerializationError { .. })
+    ));
+}
+
+/// Returns the BasisRotation stored in the fixtures written by roqoqo 0.10
+fn fixture_basis_rotation() -> BasisRotation {
+    let mut constant_circuit = Circuit::new();
+    constant_circuit += DefinitionBit::new("ro".to_string(), 2, true);
+    constant_circuit += Hadamard::new(0);
+    let mut circuit = Circuit::new();
+    circuit += CNOT::new(0, 1);
+    let mut mapping: HashMap<usize, usize> = HashMap::new();
+    mapping.insert(0, 1);
+    mapping.insert(1, 0);
+    circuit += PragmaRepeatedMeasurement::new("ro".to_string(), 100, Some(mapping));
+
+    let mut input = BasisRotationInput::new(2, false);
+    input.add_pauli_product("ro".to_string(), vec![0]).unwrap();
+    input
+        .add_pauli_product("ro".to_string(), vec![0, 1])
+        .unwrap();
+    let mut linear: HashMap<usize, f64> = HashMap::new();
+    linear.insert(0, 0.5);
+    linear.insert(1, -1.0);
+    input
+        .add_linear_exp_val("energy".to_string(), linear)
+        .unwrap();
+    BasisRotation {
+        constant_circuit: Some(constant_circuit),
+        circuits: vec![circuit],
+        input,
+    }
+}
+
+/// Test import of a QuantumProgram serialized to JSON by roqoqo 0.10
+#[test]
+fn import_json_0_10() {
+    let json = include_str!("../fixtures/quantum_program_0_10.json");
+    let bytes = import_json::<QuantumProgram>(json).unwrap();
+    assert_eq!(
+        binary_header(&bytes).unwrap(),
+        ("QuantumProgram".to_string(), library_version())
+    );
+    let program: QuantumProgram = from_binary(&bytes).unwrap();
+    assert_eq!(
+        program,
+        QuantumProgram::BasisRotation {
+            measurement: fixture_basis_rotation(),
+            input_parameter_names: vec![],
+        }
+    );
+
+    let result = import_json::<Circuit>(json);
+    assert!(matches!(
+        result,
+        Err(RoqoqoError::SerializationError { .. })
+    ));
+}
+
+/// Test import of a BasisRotation serialized with bincode by roqoqo 0.10
+#[test]
+fn import_bincode_0_10() {
+    let archived = include_bytes!("../fixtures/basis_rotation_0_10.bincode");
+    let bytes = import_bincode::<BasisRotation>(archived).unwrap();
+    assert_eq!(
+        binary_header(&bytes).unwrap(),
+        ("BasisRotation".to_string(), library_version())
+    );
+    let measurement: BasisRotation = from_binary(&bytes).unwrap();
+
+    let mut input = BasisRotationInput::new(1, false);
+    input.add_pauli_product("ro".to_string(), vec![0]).unwrap();
+    let mut linear: HashMap<usize, f64> = HashMap::new();
+    linear.insert(0, 1.0);
+    input.add_linear_exp_val("z".to_string(), linear).unwrap();
+    let expected = BasisRotation {
+        constant_circuit: None,
+        circuits: vec![Circuit::new()],
+        input,
+    };
+    assert_eq!(measurement, expected);
+    let upgraded: BasisRotation = from_binary(&upgrade_binary(&bytes).unwrap()).unwrap();
+    assert_eq!(upgraded, expected);
+
+    let result = import_bincode::<BasisRotation>(&archived[..archived.len() - 1]);
+    assert!(matches!(
+        result,
+        Err(RoqoqoError::SerializationError { .. })
+    ));
+}
+
+/// Test import of data serialized with bincode and JSON by the current version
+#[test]
+fn import_current_version() {
+    let mut measurement = fixture_basis_rotation();
+    measurement
+        .input
+        .add_exp_val_vector("energies".to_string(), vec!["energy".to_string()])
+        .unwrap();
+    let program = QuantumProgram::BasisRotation {
+        measurement,
+        input_parameter_names: vec!["theta".to_string()],
+    };
+
+    let json = serde_json::to_string(&program).unwrap();
+    let bytes = import_json::<QuantumProgram>(&json).unwrap();
+    let deserialized: QuantumProgram = from_binary(&bytes).unwrap();
+    assert_eq!(deserialized, program);
+
+    let archived = bincode::serialize(&program).unwrap();
+    let bytes = import_bincode::<QuantumProgram>(&archived).unwrap();
+    let deserialized: QuantumProgram = from_binary(&bytes).unwrap();
+    assert_eq!(deserialized, program);
+}
+
+/// Test that data without migration path or from a newer version is rejected
+#[test]
+fn reject_unsupported_versions() {
+    let (major, minor) = library_version();
+    let bytes = to_binary(&create_circuit()).unwrap();
+
+    let newer_bytes = patch_minor_version(&bytes, minor, minor + 1);
+    let result: Result<Circuit, RoqoqoError> = from_binary(&newer_bytes);
+    assert_eq!(
+        result,
+        Err(RoqoqoError::VersionMissmatch {
+            library_major_version: major,
+            library_minor_version: minor,
+            data_major_version: major,
+            data_minor_version: minor + 1,
+        })
+    );
+
+    let ancient_bytes = patch_minor_version(&bytes, minor, 1);
+    let result: Result<Circuit, RoqoqoError> = from_binary(&ancient_bytes);
+    assert!(matches!(result, Err(RoqoqoError::VersionMissmatch { .. })));
+}
@@ -0,0 +1,124 @@
+// Copyright © 2021 HQS Quantum Simulations GmbH. All Rights Reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except
+// in compliance with the License. You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the
+// License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either
+// express or implied. See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Integration test for public API of streaming serialization
+
+use roqoqo::operations::*;
+use roqoqo::prelude::*;
+use roqoqo::registers::{BitOutputRegister, ComplexOutputRegister, FloatOutputRegister};
+use roqoqo::streaming::{CircuitStreamReader, CircuitStreamWriter};
+use roqoqo::{Circuit, RoqoqoBackendError, RoqoqoError};
+use std::collections::HashMap;
+
+/// Backend counting the operations it runs in a float register
+#[derive(Debug, Clone, Copy)]
+struct CountingBackend;
+
+impl EvaluatingBackend for CountingBackend {
+    fn run_circuit_iterator<'a>(
+        &self,
+        circuit: impl Iterator<Item = &'a Operation>,
+    ) -> roqoqo::backends::RegisterResult {
+        let number_operations = circuit.count();
+        let result_bit: HashMap<String, BitOutputRegister> = HashMap::new();
+        let mut result_float: HashMap<String, FloatOutputRegister> = HashMap::new();
+        result_float.insert("count".to_string(), vec![vec![number_operations as f64]]);
+        let result_complex: HashMap<String, ComplexOutputRegister> = HashMap::new();
+        Ok((result_bit, result_float, result_complex))
+    }
+}
+
+fn create_circuit() -> Circuit {
+    let mut circuit = Circuit::new();
+    circuit += DefinitionBit::new("ro".to_string(), 2, true);
+    circuit += RotateX::new(0, "theta".into());
+    circuit += CNOT::new(0, 1);
+    circuit += PragmaConditional::new("ro".to_string(), 0, {
+        let mut inner = Circuit::new();
+        inner += PauliX::new(1);
+        inner
+    });
+    circuit += MeasureQubit::new(1, "ro".to_string(), 1);
+    circuit
+}
+
+/// Test writing and lazily reading a circuit
+#[test]
+fn stream_roundtrip() {
+    let circuit = create_circuit();
+    let mut writer = CircuitStreamWriter::new(Vec::new()).unwrap();
+    writer.write_circuit(&circuit).unwrap();
+    writer
+        .write_operation(&Operation::from(PauliZ::new(0)))
+        .unwrap();
+    assert_eq!(writer.number_operations(), circuit.len() + 1);
+    let bytes = writer.finish().unwrap();
+
+    let reader = CircuitStreamReader::new(&bytes[..]).unwrap();
+    let operations: Vec<Operation> = reader.map(|operation| operation.unwrap()).collect();
+    let mut expected: Vec<Operation> = circuit.iter().cloned().collect();
+    expected.push(Operation::from(PauliZ::new(0)));
+    assert_eq!(operations, expected);
+
+    let reader = CircuitStreamReader::new(&bytes[..]).unwrap();
+    let mut expected_circuit = circuit;
+    expected_circuit += PauliZ::new(0);
+    assert_eq!(reader.into_circuit().unwrap(), expected_circuit);
+}
+
+/// Test that truncated and foreign data is rejected
+#[test]
+fn stream_errors() {
+    let circuit = create_circuit();
+    let mut writer = CircuitStreamWriter::new(Vec::new()).unwrap();
+    writer.write_circuit(&circuit).unwrap();
+    let bytes = writer.finish().unwrap();
+
+    // Removing the end marker
+    let truncated = &bytes[..bytes.len() - 1];
+    let reader = CircuitStreamReader::new(truncated).unwrap();
+    let results: Vec<Result<Operation, RoqoqoError>> = reader.collect();
+    assert_eq!(results.len(), circuit.len() + 1);
+    assert!(results.last().unwrap().is_err());
+
+    let result = CircuitStreamReader::new(&b"not a stream"[..]);
+    assert!(matches!(
+        result,
+        Err(RoqoqoError::SerializationError { .. })
+    ));
+}
+
+/// Test running a lazily read stream on a backend
+#[test]
+fn run_stream() {
+    let circuit = create_circuit();
+    let mut writer = CircuitStreamWriter::new(Vec::new()).unwrap();
+    writer.write_circuit(&circuit).unwrap();
+    let bytes = writer.finish().unwrap();
+
+    let backend = CountingBackend;
+    let reader = CircuitStreamReader::new(&bytes[..]).unwrap();
+    let (_, float_registers, _) = backend.run_circuit_stream(reader).unwrap();
+    assert_eq!(
+        float_registers.get("count").unwrap(),
+        &vec![vec![circuit.len() as f64]]
+    );
+
+    let reader = CircuitStreamReader::new(&bytes[..bytes.len() - 1]).unwrap();
+    let result = backend.run_circuit_stream(reader);
+    assert!(matches!(
+        result,
+        Err(RoqoqoBackendError::RoqoqoError(
+            RoqoqoError::SerializationError { .. }
+        ))
+    ));
+}
@@ -0,0 +1,147 @@
+// Copyright © 2021 HQS Quantum Simulations GmbH. All Rights Reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except
+// in compliance with the License. You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the
+// License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either
+// express or implied. See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Test of the memory used when running streamed circuits
+//!
+//! Separate test binary, as the allocations of the whole process are counted by the global allocator.
+#![cfg(feature = "serialize")]
+
+use roqoqo::operations::*;
+use roqoqo::prelude::*;
+use roqoqo::registers::{BitOutputRegister, ComplexOutputRegister, FloatOutputRegister};
+use roqoqo::streaming::{CircuitStreamReader, CircuitStreamWriter};
+use roqoqo::RoqoqoError;
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Allocator keeping track of the allocated bytes and the peak of allocated bytes
+struct CountingAllocator {
+    allocated: AtomicUsize,
+    peak: AtomicUsize,
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let allocated = self.allocated.fetch_add(layout.size(), Ordering::SeqCst) + layout.size();
+        self.peak.fetch_max(allocated, Ordering::SeqCst);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        self.allocated.fetch_sub(layout.size(), Ordering::SeqCst);
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator {
+    allocated: AtomicUsize::new(0),
+    peak: AtomicUsize::new(0),
+};
+
+/// Returns the peak of additionally allocated bytes while running a function
+fn peak_allocation<T>(function: impl FnOnce() -> T) -> (T, usize) {
+    let start = ALLOCATOR.allocated.load(Ordering::SeqCst);
+    ALLOCATOR.peak.store(start, Ordering::SeqCst);
+    let result = function();
+    (result, ALLOCATOR.peak.load(Ordering::SeqCst) - start)
+}
+
+/// Backend summing the angles of all RotateX operations to a float register
+///
+/// Streamed operations are run one after another.
+#[derive(Debug, Clone, Copy)]
+struct AngleSumBackend;
+
+/// AngleSumBackend using the default implementation of run_circuit_stream
+#[derive(Debug, Clone, Copy)]
+struct CollectingBackend;
+
+impl AngleSumBackend {
+    fn add_angle(sum: &mut f64, operation: &Operation) -> Result<(), RoqoqoError> {
+        if let Operation::RotateX(op) = operation {
+            *sum += *op.theta().float()?;
+        }
+        Ok(())
+    }
+
+    fn registers(sum: f64) -> roqoqo::backends::RegisterResult {
+        let result_bit: HashMap<String, BitOutputRegister> = HashMap::new();
+        let mut result_float: HashMap<String, FloatOutputRegister> = HashMap::new();
+        result_float.insert("sum".to_string(), vec![vec![sum]]);
+        let result_complex: HashMap<String, ComplexOutputRegister> = HashMap::new();
+        Ok((result_bit, result_float, result_complex))
+    }
+}
+
+impl EvaluatingBackend for AngleSumBackend {
+    fn run_circuit_iterator<'a>(
+        &self,
+        circuit: impl Iterator<Item = &'a Operation>,
+    ) -> roqoqo::backends::RegisterResult {
+        let mut sum = 0.0;
+        for operation in circuit {
+            Self::add_angle(&mut sum, operation)?;
+        }
+        Self::registers(sum)
+    }
+
+    fn run_circuit_stream(
+        &self,
+        operations: impl Iterator<Item = Result<Operation, RoqoqoError>>,
+    ) -> roqoqo::backends::RegisterResult {
+        let mut sum = 0.0;
+        for operation in operations {
+            Self::add_angle(&mut sum, &operation?)?;
+        }
+        Self::registers(sum)
+    }
+}
+
+impl EvaluatingBackend for CollectingBackend {
+    fn run_circuit_iterator<'a>(
+        &self,
+        circuit: impl Iterator<Item = &'a Operation>,
+    ) -> roqoqo::backends::RegisterResult {
+        AngleSumBackend.run_circuit_iterator(circuit)
+    }
+}
+
+/// Test that a backend overriding run_circuit_stream runs a stream with bounded memory
+#[test]
+fn run_stream_bounded_memory() {
+    let number_operations: usize = 100_000;
+    let mut writer = CircuitStreamWriter::new(Vec::new()).unwrap();
+    for qubit in 0..number_operations {
+        writer
+            .write_operation(&Operation::from(RotateX::new(qubit % 10, 0.5.into())))
+            .unwrap();
+    }
+    let bytes = writer.finish().unwrap();
+    let expected_sum = 0.5 * number_operations as f64;
+
+    let (registers, streaming_peak) = peak_allocation(|| {
+        AngleSumBackend.run_circuit_stream(CircuitStreamReader::new(&bytes[..]).unwrap())
+    });
+    assert_eq!(registers.unwrap().1["sum"], vec![vec![expected_sum]]);
+
+    let (registers, collecting_peak) = peak_allocation(|| {
+        CollectingBackend.run_circuit_stream(CircuitStreamReader::new(&bytes[..]).unwrap())
+    });
+    assert_eq!(registers.unwrap().1["sum"], vec![vec![expected_sum]]);
+
+    // The collected operations alone take more memory than the whole streaming run
+    let collected_size = number_operations * std::mem::size_of::<Operation>();
+    assert!(collecting_peak >= collected_size);
+    assert!(streaming_peak < 64 * 1024);
+}